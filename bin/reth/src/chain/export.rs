@@ -0,0 +1,93 @@
+//! Command that exports canonical blocks to an RLP file.
+use crate::{
+    dirs::{DbPath, PlatformPath},
+    utils::init::{init_db, init_static_files},
+};
+use clap::Parser;
+use reth_primitives::BlockNumber;
+use reth_provider::{BlockProvider, ShareableDatabase};
+use reth_rlp::Encodable;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    sync::Arc,
+};
+use tracing::info;
+
+/// The number of blocks that are read from the database at once.
+const EXPORT_BATCH_SIZE: u64 = 1_000;
+
+/// Export canonical blocks to an RLP file.
+///
+/// The blocks are written one after another, which is the format `reth import` and `geth import`
+/// read.
+#[derive(Debug, Parser)]
+pub struct ExportCommand {
+    /// The path to the database folder.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/db` or `$HOME/.local/share/reth/db`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/db`
+    /// - macOS: `$HOME/Library/Application Support/reth/db`
+    #[arg(long, value_name = "PATH", verbatim_doc_comment, default_value_t)]
+    db: PlatformPath<DbPath>,
+
+    /// The first block to export.
+    #[arg(long, default_value_t = 0)]
+    from: BlockNumber,
+
+    /// The last block to export.
+    ///
+    /// Defaults to the tip of the canonical chain.
+    #[arg(long)]
+    to: Option<BlockNumber>,
+
+    /// The path to the file the blocks are written to.
+    #[arg(value_name = "EXPORT_PATH")]
+    path: PathBuf,
+}
+
+impl ExportCommand {
+    /// Execute `export` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        info!(target: "reth::cli", path = %self.db, "Opening database");
+        let db = Arc::new(init_db(&self.db)?);
+        let static_files = Arc::new(init_static_files(&self.db)?);
+        let client = ShareableDatabase::new(db).with_static_files(static_files);
+        info!(target: "reth::cli", "Database opened");
+
+        let to = match self.to {
+            Some(to) => to,
+            None => client.chain_info()?.best_number,
+        };
+        if self.from > to {
+            eyre::bail!("The first block #{} is above the last block #{to}", self.from)
+        }
+
+        info!(target: "reth::cli", from = self.from, to, path = %self.path.display(), "Exporting blocks");
+        let mut writer = BufWriter::new(File::create(&self.path)?);
+        let mut buf = Vec::new();
+        let mut next = self.from;
+        while next <= to {
+            let batch_end = to.min(next.saturating_add(EXPORT_BATCH_SIZE - 1));
+            let blocks = client.blocks_range(next..=batch_end)?;
+            for block in blocks.iter() {
+                buf.clear();
+                block.encode(&mut buf);
+                writer.write_all(&buf)?;
+            }
+
+            next += blocks.len() as u64;
+            if next <= batch_end {
+                eyre::bail!("Block #{next} is not part of the canonical chain")
+            }
+            info!(target: "reth::cli", block = next - 1, "Exported blocks");
+        }
+        writer.flush()?;
+
+        info!(target: "reth::cli", blocks = to - self.from + 1, "Finished exporting the blocks");
+        Ok(())
+    }
+}
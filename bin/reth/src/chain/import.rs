@@ -0,0 +1,216 @@
+//! Command that imports a chain from an RLP file.
+use crate::{
+    dirs::{ConfigPath, DbPath, PlatformPath},
+    utils::{
+        chainspec::chain_spec_value_parser,
+        init::{init_db, init_genesis},
+    },
+};
+use clap::{crate_version, Parser};
+use eyre::Context;
+use futures::StreamExt;
+use reth_consensus::beacon::BeaconConsensus;
+use reth_db::{
+    database::Database,
+    mdbx::{Env, WriteMap},
+    tables,
+    transaction::DbTx,
+};
+use reth_downloaders::{
+    bodies::bodies::BodiesDownloaderBuilder, file_client::FileClient,
+    headers::reverse_headers::ReverseHeadersDownloaderBuilder,
+};
+use reth_interfaces::consensus::{Consensus, ForkchoiceState};
+use reth_primitives::{BlockNumber, ChainSpec, Header, H256};
+use reth_staged_sync::Config;
+use reth_stages::{
+    prelude::*,
+    stages::{ExecutionStage, SenderRecoveryStage, TotalDifficultyStage, FINISH, HEADERS},
+};
+use std::{path::PathBuf, sync::Arc};
+use tracing::info;
+
+/// Import a chain from an RLP file.
+///
+/// The headers and bodies stages read the blocks from the file instead of the network, the other
+/// stages process them as during a regular sync.
+#[derive(Debug, Parser)]
+pub struct ImportCommand {
+    /// The path to the configuration file to use.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment, default_value_t)]
+    config: PlatformPath<ConfigPath>,
+
+    /// The path to the database folder.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/db` or `$HOME/.local/share/reth/db`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/db`
+    /// - macOS: `$HOME/Library/Application Support/reth/db`
+    #[arg(long, value_name = "PATH", verbatim_doc_comment, default_value_t)]
+    db: PlatformPath<DbPath>,
+
+    /// The chain of the imported blocks.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    ///
+    /// Built-in chains:
+    /// - mainnet
+    /// - goerli
+    /// - sepolia
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        verbatim_doc_comment,
+        default_value = "mainnet",
+        value_parser = chain_spec_value_parser
+    )]
+    chain: ChainSpec,
+
+    /// The path to the file with the RLP encoded blocks.
+    #[arg(value_name = "IMPORT_PATH")]
+    path: PathBuf,
+}
+
+impl ImportCommand {
+    /// Execute `import` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        info!(target: "reth::cli", "reth {} starting", crate_version!());
+
+        let config: Config = confy::load_path(&self.config).wrap_err("Could not load config")?;
+
+        info!(target: "reth::cli", path = %self.db, "Opening database");
+        let db = Arc::new(init_db(&self.db)?);
+        info!(target: "reth::cli", "Database opened");
+
+        init_genesis(db.clone(), self.chain.clone())?;
+
+        info!(target: "reth::cli", path = %self.path.display(), "Reading blocks");
+        let file_client = Arc::new(FileClient::new(&self.path).await?);
+        let (Some(tip), Some(max_block)) = (file_client.tip(), file_client.max_block()) else {
+            eyre::bail!("The file does not contain any blocks")
+        };
+        info!(target: "reth::cli", blocks = file_client.len(), %tip, "Blocks read");
+
+        if self.is_imported(&db, tip, max_block)? {
+            info!(target: "reth::cli", "The blocks of the file are already imported");
+            return Ok(())
+        }
+        if let Some(first) = file_client.first_header() {
+            self.ensure_connected(&db, first)?;
+        }
+
+        // The headers stage syncs to the tip of the file. The imported blocks are canonical, but
+        // they are not marked as safe or finalized.
+        let (consensus, forkchoice_state_tx) = BeaconConsensus::builder().build(self.chain.clone());
+        forkchoice_state_tx.send(ForkchoiceState {
+            head_block_hash: tip,
+            safe_block_hash: H256::zero(),
+            finalized_block_hash: H256::zero(),
+        })?;
+
+        let mut pipeline = self.build_pipeline(&config, consensus, file_client, &db, max_block);
+
+        let mut events = pipeline.events();
+        tokio::spawn(async move {
+            while let Some(event) = events.next().await {
+                if let PipelineEvent::Ran { stage_id, result } = event {
+                    info!(target: "reth::cli", stage = %stage_id, checkpoint = result.stage_progress, "Stage committed progress");
+                }
+            }
+        });
+
+        info!(target: "reth::cli", "Starting sync pipeline");
+        pipeline.run(db.clone()).await?;
+
+        info!(target: "reth::cli", "Finished importing the blocks");
+        Ok(())
+    }
+
+    /// Returns `true` if the tip of the file is part of the canonical chain and all stages have
+    /// processed it.
+    ///
+    /// Stages that already reached the tip of the file are skipped by the pipeline, so the
+    /// database can't contain a different block at the height of the tip.
+    fn is_imported(
+        &self,
+        db: &Env<WriteMap>,
+        tip: H256,
+        max_block: BlockNumber,
+    ) -> eyre::Result<bool> {
+        let tx = db.tx()?;
+        let is_canonical = tx.get::<tables::CanonicalHeaders>(max_block)? == Some(tip);
+        if !is_canonical && HEADERS.get_progress(&tx)?.unwrap_or_default() >= max_block {
+            eyre::bail!(
+                "The database contains a different block #{max_block} than the file, unwind it before importing"
+            )
+        }
+        Ok(is_canonical && FINISH.get_progress(&tx)?.unwrap_or_default() >= max_block)
+    }
+
+    /// Fails if the file does not connect to the local chain.
+    ///
+    /// The headers are downloaded from the tip of the file down to the local chain, so the parent
+    /// of the first block of the file must be canonical. Otherwise the download waits for the
+    /// missing blocks forever.
+    fn ensure_connected(&self, db: &Env<WriteMap>, first: &Header) -> eyre::Result<()> {
+        if first.number == 0 {
+            return Ok(())
+        }
+        let parent = db.tx()?.get::<tables::CanonicalHeaders>(first.number - 1)?;
+        if parent != Some(first.parent_hash) {
+            eyre::bail!(
+                "The parent of the first block #{} of the file is not part of the local chain",
+                first.number
+            )
+        }
+        Ok(())
+    }
+
+    fn build_pipeline(
+        &self,
+        config: &Config,
+        consensus: Arc<dyn Consensus>,
+        file_client: Arc<FileClient>,
+        db: &Arc<Env<WriteMap>>,
+        max_block: BlockNumber,
+    ) -> Pipeline<Env<WriteMap>, Arc<FileClient>> {
+        let stage_conf = &config.stages;
+
+        let header_downloader = ReverseHeadersDownloaderBuilder::default()
+            .request_limit(stage_conf.headers.downloader_batch_size)
+            .stream_batch_size(stage_conf.headers.commit_threshold as usize)
+            .build(consensus.clone(), file_client.clone());
+
+        let body_downloader = BodiesDownloaderBuilder::default()
+            .with_stream_batch_size(stage_conf.bodies.downloader_stream_batch_size)
+            .with_request_limit(stage_conf.bodies.downloader_request_limit)
+            .with_max_buffered_responses(stage_conf.bodies.downloader_max_buffered_responses)
+            .with_concurrent_requests_range(
+                stage_conf.bodies.downloader_min_concurrent_requests..=
+                    stage_conf.bodies.downloader_max_concurrent_requests,
+            )
+            .build(file_client.clone(), consensus.clone(), db.clone());
+
+        Pipeline::builder()
+            .with_sync_state_updater(file_client)
+            .add_stages(OnlineStages::new(consensus, header_downloader, body_downloader).set(
+                TotalDifficultyStage {
+                    commit_threshold: stage_conf.total_difficulty.commit_threshold,
+                },
+            ))
+            .add_stages(
+                OfflineStages::default()
+                    .set(SenderRecoveryStage {
+                        batch_size: stage_conf.sender_recovery.batch_size,
+                        commit_threshold: stage_conf.execution.commit_threshold,
+                    })
+                    .set(ExecutionStage::new(
+                        self.chain.clone(),
+                        stage_conf.execution.commit_threshold,
+                    )),
+            )
+            .with_max_block(max_block)
+            .build()
+    }
+}
@@ -0,0 +1,10 @@
+//! Commands that move chain segments between the database and RLP files.
+//!
+//! The files contain the blocks one after another as RLP, which is the format of the chain exports
+//! of geth.
+
+mod export;
+mod import;
+
+pub use export::ExportCommand;
+pub use import::ImportCommand;
@@ -1,6 +1,6 @@
 //! CLI definition and entrypoint to executable
 use crate::{
-    db,
+    chain, db,
     dirs::{LogsDir, PlatformPath},
     node, p2p, stage, test_eth_chain, test_vectors,
 };
@@ -21,6 +21,8 @@ pub async fn run() -> eyre::Result<()> {
 
     match opt.command {
         Commands::Node(command) => command.execute().await,
+        Commands::Import(command) => command.execute().await,
+        Commands::Export(command) => command.execute().await,
         Commands::TestEthChain(command) => command.execute().await,
         Commands::Db(command) => command.execute().await,
         Commands::Stage(command) => command.execute().await,
@@ -35,6 +37,12 @@ pub enum Commands {
     /// Start the node
     #[command(name = "node")]
     Node(node::Command),
+    /// Import a chain from an RLP file
+    #[command(name = "import")]
+    Import(chain::ImportCommand),
+    /// Export canonical blocks to an RLP file
+    #[command(name = "export")]
+    Export(chain::ExportCommand),
     /// Database debugging utilities
     #[command(name = "db")]
     Db(db::Command),
@@ -1,19 +1,24 @@
 //! Database debugging tool
-use crate::dirs::{DbPath, PlatformPath};
+use crate::{
+    dirs::{DbPath, PlatformPath},
+    utils::init::init_static_files,
+};
 use clap::{Parser, Subcommand};
 use comfy_table::{Cell, Row, Table as ComfyTable};
 use eyre::{Result, WrapErr};
 use reth_db::{
     cursor::{DbCursorRO, Walker},
     database::Database,
+    mdbx::{Env, WriteMap},
     table::Table,
     tables,
     transaction::DbTx,
 };
 use reth_interfaces::test_utils::generators::random_block_range;
 use reth_provider::insert_canonical_block;
-use std::collections::BTreeMap;
-use tracing::{error, info};
+use reth_stages::ConsistencyChecker;
+use std::{collections::BTreeMap, sync::Arc};
+use tracing::{error, info, warn};
 
 /// DB List TUI
 mod tui;
@@ -52,6 +57,9 @@ pub enum Subcommands {
     },
     /// Deletes all database entries
     Drop,
+    /// Checks that the tables written by the stages are consistent with each other and with the
+    /// stage checkpoints
+    Check(CheckArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -67,18 +75,29 @@ pub struct ListArgs {
     len: usize,
 }
 
+#[derive(Parser, Debug)]
+/// The arguments for the `reth db check` command
+pub struct CheckArgs {
+    /// Insert the missing entries that can be derived from other tables.
+    #[arg(long)]
+    repair: bool,
+    /// The maximum number of findings that are listed
+    #[arg(long, default_value_t = 100)]
+    max_findings: usize,
+}
+
 impl Command {
     /// Execute `db` command
     pub async fn execute(&self) -> eyre::Result<()> {
         std::fs::create_dir_all(&self.db)?;
 
         // TODO: Auto-impl for Database trait
-        let db = reth_db::mdbx::Env::<reth_db::mdbx::WriteMap>::open(
+        let db = Arc::new(reth_db::mdbx::Env::<reth_db::mdbx::WriteMap>::open(
             self.db.as_ref(),
             reth_db::mdbx::EnvKind::RW,
-        )?;
+        )?);
 
-        let mut tool = DbTool::new(&db)?;
+        let mut tool = DbTool::new(db.as_ref())?;
 
         match &self.command {
             // TODO: We'll need to add this on the DB trait.
@@ -175,16 +194,86 @@ impl Command {
                     BlockTransitionIndex,
                     TxTransitionIndex,
                     SyncStage,
-                    Transactions
+                    ForkchoiceMarkers,
+                    PruneCheckpoints,
+                    StaticFileCheckpoints,
+                    Transactions,
+                    Receipts,
+                    LogAddressIndex,
+                    LogTopicIndex
                 ]);
             }
             Subcommands::Drop => {
                 tool.drop(&self.db)?;
             }
+            Subcommands::Check(args) => {
+                self.check(db.clone(), args)?;
+            }
         }
 
         Ok(())
     }
+
+    /// Checks the database, prints the findings and repairs them if requested.
+    fn check(&self, db: Arc<Env<WriteMap>>, args: &CheckArgs) -> eyre::Result<()> {
+        let static_files = Arc::new(init_static_files(&self.db)?);
+        let checker = ConsistencyChecker::new(db).with_static_files(static_files);
+
+        info!(target: "reth::cli", "Checking the database");
+        let report = checker.check()?;
+
+        let mut stages_table = ComfyTable::new();
+        stages_table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+        stages_table.set_header(["Stage", "Checkpoint", "Rerun From"]);
+        for status in report.stages.iter() {
+            let mut row = Row::new();
+            row.add_cell(Cell::new(status.stage))
+                .add_cell(Cell::new(status.checkpoint.map(|cp| cp.to_string()).unwrap_or_default()))
+                .add_cell(Cell::new(
+                    status.rerun_from.map(|block| block.to_string()).unwrap_or_default(),
+                ));
+            stages_table.add_row(row);
+        }
+        println!("{stages_table}");
+
+        if report.is_consistent() {
+            info!(target: "reth::cli", "No inconsistencies found");
+            return Ok(())
+        }
+
+        let mut findings_table = ComfyTable::new();
+        findings_table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+        findings_table.set_header(["Stage", "Block", "Table", "Finding", "Repairable"]);
+        for finding in report.findings.iter().take(args.max_findings) {
+            let mut row = Row::new();
+            row.add_cell(Cell::new(finding.stage))
+                .add_cell(Cell::new(finding.block))
+                .add_cell(Cell::new(finding.table))
+                .add_cell(Cell::new(&finding.message))
+                .add_cell(Cell::new(finding.repair.is_some()));
+            findings_table.add_row(row);
+        }
+        println!("{findings_table}");
+        let listed = report.findings.len().min(args.max_findings);
+        info!(target: "reth::cli", findings = report.findings.len(), listed, "Inconsistencies found");
+
+        let repairable = report.findings.iter().filter(|finding| finding.repair.is_some()).count();
+        if args.repair {
+            let repaired = checker.repair(&report)?;
+            info!(target: "reth::cli", repaired, "Missing entries inserted");
+        } else if repairable > 0 {
+            info!(target: "reth::cli", repairable, "Run with --repair to insert the missing entries");
+        }
+
+        if let Some(block) = report.rerun_from() {
+            warn!(
+                target: "reth::cli",
+                unwind_to = block.saturating_sub(1),
+                "Some inconsistencies can't be repaired, unwind the affected stages and rerun them"
+            );
+        }
+        Ok(())
+    }
 }
 
 /// Wrapper over DB that implements many useful DB queries.
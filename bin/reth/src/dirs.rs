@@ -1,4 +1,5 @@
 //! reth data directories.
+use reth_rpc_builder::DEFAULT_IPC_ENDPOINT;
 use reth_staged_sync::utils::parse_path;
 use std::{
     env::VarError,
@@ -81,6 +82,37 @@ impl XdgPath for LogsDir {
     }
 }
 
+/// Returns the path to the JWT secret that authenticates the consensus layer.
+///
+/// Refer to [dirs_next::data_dir] for cross-platform behavior.
+#[derive(Default, Debug, Clone)]
+#[non_exhaustive]
+pub struct JwtSecretPath;
+
+impl XdgPath for JwtSecretPath {
+    fn resolve() -> Option<PathBuf> {
+        data_dir().map(|root| root.join("jwt.hex"))
+    }
+}
+
+/// Returns the path to the IPC socket of the RPC server.
+///
+/// On Windows this is the named pipe [DEFAULT_IPC_ENDPOINT], elsewhere the socket is placed in the
+/// data directory, see [dirs_next::data_dir].
+#[derive(Default, Debug, Clone)]
+#[non_exhaustive]
+pub struct IpcPath;
+
+impl XdgPath for IpcPath {
+    fn resolve() -> Option<PathBuf> {
+        if cfg!(windows) {
+            Some(PathBuf::from(DEFAULT_IPC_ENDPOINT))
+        } else {
+            data_dir().map(|root| root.join("reth.ipc"))
+        }
+    }
+}
+
 /// A small helper trait for unit structs that represent a standard path following the XDG
 /// path specification.
 trait XdgPath {
@@ -6,6 +6,7 @@
 ))]
 //! Rust Ethereum (reth) binary executable.
 
+pub mod chain;
 pub mod cli;
 pub mod db;
 pub mod dirs;
@@ -18,7 +19,13 @@ pub mod test_vectors;
 pub use reth_staged_sync::utils;
 
 use clap::Args;
+use dirs::{IpcPath, JwtSecretPath, PlatformPath};
 use reth_primitives::NodeRecord;
+use reth_rpc_builder::{
+    IpcServerBuilder, RethRpcModule, RpcModuleConfig, RpcServerBuilder, ServerBuilder,
+    TransportRpcModuleConfig, DEFAULT_AUTH_PORT, DEFAULT_HTTP_RPC_PORT, DEFAULT_WS_RPC_PORT,
+};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
 /// Parameters for configuring the network more granularly via CLI
 #[derive(Debug, Args)]
@@ -43,3 +50,159 @@ struct NetworkOpts {
     #[arg(long, value_delimiter = ',')]
     bootnodes: Option<Vec<NodeRecord>>,
 }
+
+/// Parameters for configuring the rpc servers more granularly via CLI
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Rpc")]
+struct RpcServerOpts {
+    /// Enable the HTTP-RPC server
+    #[arg(long)]
+    http: bool,
+
+    /// Http server address to listen on
+    #[arg(long = "http.addr", default_value_t = IpAddr::V4(Ipv4Addr::LOCALHOST))]
+    http_addr: IpAddr,
+
+    /// Http server port to listen on
+    #[arg(long = "http.port", default_value_t = DEFAULT_HTTP_RPC_PORT)]
+    http_port: u16,
+
+    /// Rpc Modules to be configured for the http server
+    ///
+    /// Defaults to `eth,net,web3`.
+    #[arg(long = "http.api", value_delimiter = ',')]
+    http_api: Option<Vec<RethRpcModule>>,
+
+    /// Http Corsdomain to allow requests from
+    #[arg(long = "http.corsdomain")]
+    http_corsdomain: Option<String>,
+
+    /// Enable the WS-RPC server
+    #[arg(long)]
+    ws: bool,
+
+    /// Ws server address to listen on
+    #[arg(long = "ws.addr", default_value_t = IpAddr::V4(Ipv4Addr::LOCALHOST))]
+    ws_addr: IpAddr,
+
+    /// Ws server port to listen on
+    #[arg(long = "ws.port", default_value_t = DEFAULT_WS_RPC_PORT)]
+    ws_port: u16,
+
+    /// Rpc Modules to be configured for the ws server
+    ///
+    /// Defaults to `eth,net,web3`.
+    #[arg(long = "ws.api", value_delimiter = ',')]
+    ws_api: Option<Vec<RethRpcModule>>,
+
+    /// Disable the IPC-RPC server
+    #[arg(long)]
+    ipcdisable: bool,
+
+    /// Filename for IPC socket/pipe
+    ///
+    /// The socket is only accessible by the user running the node. Defaults to the OS-specific
+    /// data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/reth.ipc` or `$HOME/.local/share/reth/reth.ipc`
+    /// - Windows: `\\.\pipe\reth.ipc`
+    /// - macOS: `$HOME/Library/Application Support/reth/reth.ipc`
+    #[arg(long, value_name = "PATH", verbatim_doc_comment, default_value_t)]
+    ipcpath: PlatformPath<IpcPath>,
+
+    /// Rpc Modules to be configured for the IPC server
+    ///
+    /// Defaults to `eth,net,web3`.
+    #[arg(long = "ipc.api", value_delimiter = ',')]
+    ipc_api: Option<Vec<RethRpcModule>>,
+
+    /// Set the maximum RPC request payload size for both http and ws in megabytes.
+    #[arg(long = "rpc.max-request-size", default_value_t = 15)]
+    rpc_max_request_size: u32,
+
+    /// Set the maximum RPC response payload size for both http and ws in megabytes.
+    #[arg(long = "rpc.max-response-size", default_value_t = 100)]
+    rpc_max_response_size: u32,
+
+    /// Set the maximum concurrent connections for each of the rpc servers.
+    #[arg(long = "rpc.max-connections", default_value_t = 100)]
+    rpc_max_connections: u32,
+
+    /// Auth server address to listen on
+    #[arg(long = "authrpc.addr", default_value_t = IpAddr::V4(Ipv4Addr::LOCALHOST))]
+    auth_addr: IpAddr,
+
+    /// Auth server port to listen on
+    #[arg(long = "authrpc.port", default_value_t = DEFAULT_AUTH_PORT)]
+    auth_port: u16,
+
+    /// Path to a JWT secret to use for the authenticated engine API server.
+    ///
+    /// A new secret is created at the path if the file does not exist.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/jwt.hex` or `$HOME/.local/share/reth/jwt.hex`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/jwt.hex`
+    /// - macOS: `$HOME/Library/Application Support/reth/jwt.hex`
+    #[arg(long = "authrpc.jwtsecret", value_name = "PATH", verbatim_doc_comment, default_value_t)]
+    auth_jwtsecret: PlatformPath<JwtSecretPath>,
+}
+
+impl RpcServerOpts {
+    /// Returns the modules that are served by every enabled transport.
+    fn transport_rpc_module_config(&self) -> TransportRpcModuleConfig {
+        let mut config = TransportRpcModuleConfig::default();
+        if self.http {
+            config = config.with_http(module_config(self.http_api.clone()));
+        }
+        if self.ws {
+            config = config.with_ws(module_config(self.ws_api.clone()));
+        }
+        if !self.ipcdisable {
+            config = config.with_ipc(module_config(self.ipc_api.clone()));
+        }
+        config
+    }
+
+    /// Returns the configuration of the enabled servers.
+    fn rpc_server_builder(&self) -> RpcServerBuilder {
+        let mut builder = RpcServerBuilder::default();
+        if self.http {
+            builder = builder
+                .with_http(self.http_ws_server_builder())
+                .with_http_address(SocketAddr::new(self.http_addr, self.http_port));
+            if let Some(cors) = &self.http_corsdomain {
+                builder = builder.with_cors(cors.clone());
+            }
+        }
+        if self.ws {
+            builder = builder
+                .with_ws(self.http_ws_server_builder())
+                .with_ws_address(SocketAddr::new(self.ws_addr, self.ws_port));
+        }
+        if !self.ipcdisable {
+            let ipc = IpcServerBuilder::default()
+                .max_request_body_size(self.rpc_max_request_size.saturating_mul(MEGABYTE))
+                .max_response_body_size(self.rpc_max_response_size.saturating_mul(MEGABYTE))
+                .max_connections(self.rpc_max_connections);
+            builder = builder.with_ipc(ipc).with_ipc_endpoint(self.ipcpath.to_string());
+        }
+        builder
+    }
+
+    fn http_ws_server_builder(&self) -> ServerBuilder {
+        ServerBuilder::default()
+            .max_request_body_size(self.rpc_max_request_size.saturating_mul(MEGABYTE))
+            .max_response_body_size(self.rpc_max_response_size.saturating_mul(MEGABYTE))
+            .max_connections(self.rpc_max_connections)
+    }
+}
+
+/// The number of bytes in a megabyte.
+const MEGABYTE: u32 = 1024 * 1024;
+
+/// Returns the configured selection or the standard modules if none were configured.
+fn module_config(modules: Option<Vec<RethRpcModule>>) -> RpcModuleConfig {
+    modules.map(RpcModuleConfig::Selection).unwrap_or(RpcModuleConfig::Standard)
+}
@@ -5,28 +5,52 @@ use crate::{
     dirs::{ConfigPath, DbPath, PlatformPath},
     prometheus_exporter,
     utils::{chainspec::chain_spec_value_parser, init::init_db, parse_socket_address},
-    NetworkOpts,
+    NetworkOpts, RpcServerOpts,
 };
 use clap::{crate_version, Parser};
 use eyre::Context;
 use fdlimit::raise_fd_limit;
 use futures::{stream::select as stream_select, Stream, StreamExt};
 use reth_consensus::beacon::BeaconConsensus;
-use reth_db::mdbx::{Env, WriteMap};
+use reth_db::{
+    mdbx::{Env, WriteMap},
+    static_file::{StaticFileSegment, StaticFiles},
+};
 use reth_downloaders::{bodies, headers};
-use reth_interfaces::consensus::{Consensus, ForkchoiceState};
+use reth_executor::block_tree::SharedBlockTree;
+use reth_interfaces::{
+    consensus::{Consensus, ForkchoiceState},
+    events::{ChainEventSender, ChainEventSubscriptions},
+};
 use reth_net_nat::NatResolver;
 use reth_network::{FetchClient, NetworkConfig, NetworkEvent, NetworkHandle};
 use reth_network_api::NetworkInfo;
+use reth_payload_builder::PayloadBuilderService;
 use reth_primitives::{BlockNumber, ChainSpec, H256};
 use reth_provider::ShareableDatabase;
-use reth_staged_sync::{utils::init::init_genesis, Config};
+use reth_rpc::{EngineApi, JwtSecret};
+use reth_rpc_builder::{launch_auth, AuthServerHandle, RpcModuleBuilder, RpcServerHandle};
+use reth_staged_sync::{
+    utils::init::{init_genesis, init_static_files},
+    Config,
+};
 use reth_stages::{
     prelude::*,
     stages::{ExecutionStage, SenderRecoveryStage, TotalDifficultyStage},
+    Pruner, StaticFileProducer,
+};
+use reth_tasks::{TaskExecutor, TaskManager};
+use reth_transaction_pool::{
+    journal::{journal_local_transactions, TransactionJournal, DEFAULT_JOURNAL_ROTATION_INTERVAL},
+    maintain::maintain_transaction_pool,
+    CostOrdering, EthTransactionPool, EthTransactionValidator, Pool, PoolConfig,
+    DEFAULT_MINIMAL_GAS_PRICE, DEFAULT_PRICE_BUMP,
+};
+use std::{net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
+use tokio::{
+    select,
+    sync::{mpsc::unbounded_channel, watch},
 };
-use std::{net::SocketAddr, sync::Arc, time::Duration};
-use tokio::select;
 use tracing::{debug, info, warn};
 
 /// Start the node
@@ -71,6 +95,8 @@ pub struct Command {
 
     /// Set the chain tip manually for testing purposes.
     ///
+    /// Otherwise the chain tip is set by the consensus layer via the engine API.
+    ///
     /// NOTE: This is a temporary flag
     #[arg(long = "debug.tip", help_heading = "Debug")]
     tip: Option<H256>,
@@ -78,13 +104,52 @@ pub struct Command {
     #[clap(flatten)]
     network: NetworkOpts,
 
+    #[clap(flatten)]
+    rpc: RpcServerOpts,
+
+    /// The minimum price bump (in %) a transaction needs to replace a transaction with the same
+    /// sender and nonce.
+    #[arg(
+        long = "txpool.pricebump",
+        value_name = "PERCENT",
+        default_value_t = DEFAULT_PRICE_BUMP,
+        help_heading = "TxPool"
+    )]
+    txpool_price_bump: u128,
+
+    /// The minimum gas price (in wei) of transactions received from the network.
+    ///
+    /// For EIP-1559 transactions this is the max priority fee.
+    #[arg(
+        long = "txpool.pricelimit",
+        value_name = "WEI",
+        default_value_t = DEFAULT_MINIMAL_GAS_PRICE,
+        help_heading = "TxPool"
+    )]
+    txpool_price_limit: u128,
+
+    /// Persist local transactions to this file and put them back into the pool on startup.
+    ///
+    /// Disabled by default.
+    #[arg(long = "txpool.journal", value_name = "FILE", help_heading = "TxPool")]
+    txpool_journal: Option<PathBuf>,
+
+    /// The interval in seconds at which the local transaction journal is rewritten with the
+    /// transactions that are still in the pool.
+    #[arg(
+        long = "txpool.journal-rotation",
+        value_name = "SECONDS",
+        default_value_t = DEFAULT_JOURNAL_ROTATION_INTERVAL.as_secs(),
+        help_heading = "TxPool"
+    )]
+    txpool_journal_rotation: u64,
+
     #[arg(long, default_value = "any")]
     nat: NatResolver,
 }
 
 impl Command {
     /// Execute `node` command
-    // TODO: RPC
     pub async fn execute(self) -> eyre::Result<()> {
         info!(target: "reth::cli", "reth {} starting", crate_version!());
 
@@ -99,30 +164,77 @@ impl Command {
 
         info!(target: "reth::cli", path = %self.db, "Opening database");
         let db = Arc::new(init_db(&self.db)?);
+        let static_files = Arc::new(init_static_files(&self.db)?);
         info!(target: "reth::cli", "Database opened");
 
         self.start_metrics_endpoint()?;
 
         init_genesis(db.clone(), self.chain.clone())?;
 
-        let consensus = self.init_consensus()?;
+        let (consensus, forkchoice_state_tx) = self.init_consensus()?;
         info!(target: "reth::cli", "Consensus engine initialized");
 
         info!(target: "reth::cli", "Connecting to P2P network");
-        let netconf = self.load_network_config(&config, &db);
+        let netconf = self.load_network_config(&config, &db, &static_files);
         let network = netconf.start_network().await?;
         info!(target: "reth::cli", peer_id = %network.peer_id(), local_addr = %network.local_addr(), "Connected to P2P network");
 
-        let mut pipeline = self.build_pipeline(&config, &network, &consensus, &db).await?;
+        // blocks executed by the engine API are committed by the pipeline
+        let block_tree = SharedBlockTree::default();
+
+        let mut pipeline =
+            self.build_pipeline(&config, &network, &consensus, &db, block_tree.clone()).await?;
+
+        let mut tasks = TaskManager::new(tokio::runtime::Handle::current());
+        let pool = self.start_pool(&db, &pipeline.chain_events(), tasks.executor());
+        let _rpc_server = self
+            .start_rpc(
+                &db,
+                &static_files,
+                &network,
+                pool.clone(),
+                pipeline.chain_events(),
+                tasks.executor(),
+            )
+            .await?;
+        let _auth_server = self
+            .start_auth(&db, &static_files, pool, forkchoice_state_tx, block_tree, tasks.executor())
+            .await?;
+
+        if !config.prune.is_empty() {
+            info!(target: "reth::cli", modes = ?config.prune, "Pruning enabled");
+            let pruner = Pruner::new(db.clone(), config.prune.clone())
+                .with_static_files(static_files.clone());
+            tasks.executor().spawn_critical(
+                "pruner",
+                pruner.run_on_chain_events(pipeline.chain_events().subscribe_chain_events()),
+            );
+        }
+
+        if config.static_files.enabled {
+            // Pruned receipts are not moved, readers fall back to the database for them.
+            let segments = StaticFileSegment::ALL.into_iter().filter(|segment| {
+                *segment != StaticFileSegment::Receipts || config.prune.receipts.is_none()
+            });
+            info!(target: "reth::cli", "Moving finalized blocks to static files");
+            let producer = StaticFileProducer::new(db.clone(), static_files.clone(), segments);
+            tasks.executor().spawn_critical(
+                "static file producer",
+                producer.run_on_chain_events(pipeline.chain_events().subscribe_chain_events()),
+            );
+        }
 
         tokio::spawn(handle_events(stream_select(
             network.event_listener().map(Into::into),
             pipeline.events().map(Into::into),
         )));
 
-        // Run pipeline
+        // Run pipeline, the node shuts down if one of the critical tasks panicked
         info!(target: "reth::cli", "Starting sync pipeline");
-        pipeline.run(db.clone()).await?;
+        select! {
+            res = pipeline.run(db.clone()) => res?,
+            Some(task) = tasks.next() => eyre::bail!("Critical task `{task}` panicked"),
+        }
 
         info!(target: "reth::cli", "Finishing up");
         Ok(())
@@ -152,7 +264,137 @@ impl Command {
         }
     }
 
-    fn init_consensus(&self) -> eyre::Result<Arc<dyn Consensus>> {
+    /// Creates the transaction pool and spawns the task that keeps it in sync with the canonical
+    /// chain, as well as the task that journals local transactions if enabled.
+    fn start_pool(
+        &self,
+        db: &Arc<Env<WriteMap>>,
+        chain_events: &ChainEventSender,
+        executor: TaskExecutor,
+    ) -> EthTransactionPool<Arc<ShareableDatabase<Arc<Env<WriteMap>>>>> {
+        let client = Arc::new(ShareableDatabase::new(db.clone()));
+        let validator = EthTransactionValidator::new(client, Arc::new(self.chain.clone()));
+        let config = PoolConfig {
+            price_bump: self.txpool_price_bump,
+            minimal_gas_price: self.txpool_price_limit,
+            ..Default::default()
+        };
+        let pool = Pool::new(Arc::new(validator), Arc::new(CostOrdering::default()), config);
+
+        executor.spawn_critical(
+            "txpool maintenance",
+            maintain_transaction_pool(
+                ShareableDatabase::new(db.clone()),
+                pool.clone(),
+                chain_events.subscribe_chain_events(),
+            ),
+        );
+
+        if let Some(path) = &self.txpool_journal {
+            info!(target: "reth::cli", path = %path.display(), "Journaling local transactions");
+            executor.spawn(journal_local_transactions(
+                pool.clone(),
+                TransactionJournal::new(path),
+                Duration::from_secs(self.txpool_journal_rotation),
+            ));
+        }
+
+        pool
+    }
+
+    /// Starts the enabled RPC servers.
+    ///
+    /// Returns `None` if all servers are disabled.
+    async fn start_rpc(
+        &self,
+        db: &Arc<Env<WriteMap>>,
+        static_files: &Arc<StaticFiles>,
+        network: &NetworkHandle,
+        pool: EthTransactionPool<Arc<ShareableDatabase<Arc<Env<WriteMap>>>>>,
+        chain_events: ChainEventSender,
+        executor: TaskExecutor,
+    ) -> eyre::Result<Option<RpcServerHandle>> {
+        let module_config = self.rpc.transport_rpc_module_config();
+        if module_config.is_empty() {
+            return Ok(None)
+        }
+
+        let modules = RpcModuleBuilder::new(
+            ShareableDatabase::new(db.clone()).with_static_files(static_files.clone()),
+            pool,
+            network.clone(),
+            chain_events,
+        )
+        .with_chain_spec(self.chain.clone())
+        .with_executor(executor)
+        .build(module_config);
+
+        #[cfg(unix)]
+        if !self.rpc.ipcdisable {
+            if let Some(parent) = self.rpc.ipcpath.as_ref().parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        let server = self.rpc.rpc_server_builder().build().await?;
+        if let Some(addr) = server.http_local_addr() {
+            info!(target: "reth::cli", %addr, "RPC HTTP server started");
+        }
+        if let Some(addr) = server.ws_local_addr() {
+            info!(target: "reth::cli", %addr, "RPC WS server started");
+        }
+        if let Some(endpoint) = server.ipc_endpoint() {
+            info!(target: "reth::cli", %endpoint, "RPC IPC server started");
+        }
+
+        Ok(Some(server.start(modules).await?))
+    }
+
+    /// Starts the authenticated server that serves the engine API to the consensus layer.
+    ///
+    /// The forkchoice states received from the consensus layer are forwarded to
+    /// `forkchoice_state_tx`, and the payloads requested by the consensus layer are built from
+    /// the transactions of the `pool`. New payloads are executed into the `block_tree`.
+    async fn start_auth(
+        &self,
+        db: &Arc<Env<WriteMap>>,
+        static_files: &Arc<StaticFiles>,
+        pool: EthTransactionPool<Arc<ShareableDatabase<Arc<Env<WriteMap>>>>>,
+        forkchoice_state_tx: watch::Sender<ForkchoiceState>,
+        block_tree: SharedBlockTree,
+        executor: TaskExecutor,
+    ) -> eyre::Result<AuthServerHandle> {
+        let secret = JwtSecret::load_or_create(self.rpc.auth_jwtsecret.as_ref())?;
+        info!(target: "reth::cli", path = %self.rpc.auth_jwtsecret, "JWT secret loaded");
+
+        let client =
+            Arc::new(ShareableDatabase::new(db.clone()).with_static_files(static_files.clone()));
+        let (payload_builder, payload_builder_handle) = PayloadBuilderService::new(
+            client.clone(),
+            pool,
+            Arc::new(self.chain.clone()),
+            Default::default(),
+        );
+        executor.spawn_critical("payload builder", payload_builder);
+
+        let (engine_tx, engine_rx) = unbounded_channel();
+        let engine = reth_rpc_engine_api::EngineApi::new(
+            client,
+            self.chain.clone(),
+            engine_rx,
+            forkchoice_state_tx,
+            payload_builder_handle,
+            block_tree,
+        );
+        executor.spawn_critical("engine api", engine);
+
+        let socket_addr = SocketAddr::new(self.rpc.auth_addr, self.rpc.auth_port);
+        let handle = launch_auth(EngineApi::new(engine_tx), socket_addr, secret).await?;
+        info!(target: "reth::cli", addr = %handle.local_addr(), "RPC auth server started");
+
+        Ok(handle)
+    }
+
+    fn init_consensus(&self) -> eyre::Result<(Arc<dyn Consensus>, watch::Sender<ForkchoiceState>)> {
         let (consensus, notifier) = BeaconConsensus::builder().build(self.chain.clone());
 
         if let Some(tip) = self.tip {
@@ -163,22 +405,20 @@ impl Command {
                 finalized_block_hash: tip,
             })?;
         } else {
-            let warn_msg = "No tip specified. \
-            reth cannot communicate with consensus clients, \
-            so a tip must manually be provided for the online stages with --debug.tip <HASH>.";
-            warn!(target: "reth::cli", warn_msg);
+            info!(target: "reth::cli", "Waiting for the consensus layer to set the chain tip");
         }
 
-        Ok(consensus)
+        Ok((consensus, notifier))
     }
 
     fn load_network_config(
         &self,
         config: &Config,
         db: &Arc<Env<WriteMap>>,
+        static_files: &Arc<StaticFiles>,
     ) -> NetworkConfig<ShareableDatabase<Env<WriteMap>>> {
         config.network_config(
-            db.clone(),
+            ShareableDatabase::new(db.clone()).with_static_files(static_files.clone()),
             self.chain.clone(),
             self.network.disable_discovery,
             self.network.bootnodes.clone(),
@@ -192,6 +432,7 @@ impl Command {
         network: &NetworkHandle,
         consensus: &Arc<dyn Consensus>,
         db: &Arc<Env<WriteMap>>,
+        block_tree: SharedBlockTree,
     ) -> eyre::Result<Pipeline<Env<WriteMap>, NetworkHandle>> {
         let fetch_client = Arc::new(network.fetch_client().await?);
 
@@ -214,10 +455,13 @@ impl Command {
                         batch_size: stage_conf.sender_recovery.batch_size,
                         commit_threshold: stage_conf.execution.commit_threshold,
                     })
-                    .set(ExecutionStage {
-                        chain_spec: self.chain.clone(),
-                        commit_threshold: stage_conf.execution.commit_threshold,
-                    }),
+                    .set(
+                        ExecutionStage::new(
+                            self.chain.clone(),
+                            stage_conf.execution.commit_threshold,
+                        )
+                        .with_block_tree(block_tree),
+                    ),
             )
             .build();
 
@@ -13,6 +13,7 @@ use reth_interfaces::p2p::{
 };
 use reth_network::FetchClient;
 use reth_primitives::{BlockHashOrNumber, ChainSpec, NodeRecord, SealedHeader};
+use reth_provider::ShareableDatabase;
 use reth_staged_sync::Config;
 use std::sync::Arc;
 
@@ -98,7 +99,13 @@ impl Command {
         config.peers.connect_trusted_nodes_only = self.trusted_only;
 
         let network = config
-            .network_config(noop_db, self.chain.clone(), self.disable_discovery, None, self.nat)
+            .network_config(
+                ShareableDatabase::new(noop_db),
+                self.chain.clone(),
+                self.disable_discovery,
+                None,
+                self.nat,
+            )
             .start_network()
             .await?;
 
@@ -12,6 +12,7 @@ use reth_downloaders::bodies::bodies::BodiesDownloaderBuilder;
 
 use reth_net_nat::NatResolver;
 use reth_primitives::ChainSpec;
+use reth_provider::ShareableDatabase;
 use reth_staged_sync::Config;
 use reth_stages::{
     stages::{BodyStage, ExecutionStage, SenderRecoveryStage},
@@ -138,7 +139,7 @@ impl Command {
 
                 let network = config
                     .network_config(
-                        db.clone(),
+                        ShareableDatabase::new(db.clone()),
                         self.chain.clone(),
                         self.network.disable_discovery,
                         None,
@@ -181,8 +182,7 @@ impl Command {
                 stage.execute(&mut tx, input).await?;
             }
             StageEnum::Execution => {
-                let mut stage =
-                    ExecutionStage { chain_spec: self.chain.clone(), commit_threshold: num_blocks };
+                let mut stage = ExecutionStage::new(self.chain.clone(), num_blocks);
                 if !self.skip_unwind {
                     stage.unwind(&mut tx, unwind).await?;
                 }
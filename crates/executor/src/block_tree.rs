@@ -0,0 +1,279 @@
+use crate::{executor::ExecutionResult, post_state::PostState};
+use parking_lot::Mutex;
+use reth_primitives::{BlockHash, BlockNumber, SealedBlock};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    sync::Arc,
+};
+
+/// The maximum number of executed blocks that are kept in the [BlockTree], not counting the
+/// ancestors of the most recently executed block.
+pub const MAX_EXECUTED_BLOCKS: usize = 256;
+
+/// The maximum number of blocks that are buffered until their parent can be executed.
+pub const MAX_BUFFERED_BLOCKS: usize = 256;
+
+/// A [BlockTree] that is shared between the engine API, which executes new blocks, and the
+/// execution stage, which commits them.
+pub type SharedBlockTree = Arc<Mutex<BlockTree>>;
+
+/// A block that was executed, but not written to the database.
+#[derive(Debug)]
+pub struct ExecutedBlock {
+    /// The executed block.
+    pub block: SealedBlock,
+    /// The result of the execution, as it is written to the database.
+    pub result: ExecutionResult,
+    /// The state changes of the block.
+    pub post_state: PostState,
+}
+
+/// Blocks that are not part of the canonical chain in the database yet.
+///
+/// Executed blocks extend the canonical chain or one of its sidechains. Their state changes are
+/// kept in memory, so their descendants can be executed on top of them, and they don't have to be
+/// executed again once they become canonical.
+///
+/// Blocks whose parent can't be executed yet are buffered, keyed by the hash of their parent.
+///
+/// Both are bounded: the oldest buffered blocks are evicted first. Executed blocks are evicted
+/// together with their executed descendants, whose state changes depend on them, starting with the
+/// lowest block. The most recently executed block and its ancestors are never evicted, they are
+/// removed once the execution stage commits them.
+#[derive(Debug, Default)]
+pub struct BlockTree {
+    /// The executed blocks by hash.
+    executed: HashMap<BlockHash, ExecutedBlock>,
+    /// The hashes of the executed blocks by number.
+    executed_by_number: BTreeMap<BlockNumber, HashSet<BlockHash>>,
+    /// The buffered blocks by parent hash and hash.
+    buffered: HashMap<BlockHash, HashMap<BlockHash, SealedBlock>>,
+    /// The parent hash and hash of the buffered blocks, the oldest first.
+    buffered_order: VecDeque<(BlockHash, BlockHash)>,
+}
+
+impl BlockTree {
+    /// Returns `true` if the block was executed.
+    pub fn is_executed(&self, hash: &BlockHash) -> bool {
+        self.executed.contains_key(hash)
+    }
+
+    /// Returns the executed block with the given hash.
+    pub fn executed_block(&self, hash: &BlockHash) -> Option<&ExecutedBlock> {
+        self.executed.get(hash)
+    }
+
+    /// Inserts an executed block.
+    pub fn insert_executed(&mut self, block: ExecutedBlock) {
+        let (hash, number) = (block.block.hash(), block.block.number);
+        if self.executed.insert(hash, block).is_none() {
+            self.executed_by_number.entry(number).or_default().insert(hash);
+        }
+
+        if self.executed.len() <= MAX_EXECUTED_BLOCKS {
+            return
+        }
+
+        let mut ancestors = HashSet::new();
+        let mut ancestor = hash;
+        while let Some(block) = self.executed.get(&ancestor) {
+            ancestors.insert(ancestor);
+            ancestor = block.block.parent_hash;
+        }
+
+        while self.executed.len() > MAX_EXECUTED_BLOCKS {
+            let lowest = self
+                .executed_by_number
+                .values()
+                .flatten()
+                .find(|hash| !ancestors.contains(*hash))
+                .copied();
+            let Some(lowest) = lowest else { break };
+            self.evict_executed(lowest);
+        }
+    }
+
+    /// Removes the executed block with the given hash and all of its executed descendants.
+    fn evict_executed(&mut self, hash: BlockHash) {
+        let Some(block) = self.take_executed(&hash) else { return };
+        let mut evicted = HashSet::from([hash]);
+        let descendants = self
+            .executed_by_number
+            .range(block.block.number + 1..)
+            .flat_map(|(_, hashes)| hashes.iter().copied())
+            .collect::<Vec<_>>();
+        for descendant in descendants {
+            if evicted.contains(&self.executed[&descendant].block.parent_hash) {
+                self.take_executed(&descendant);
+                evicted.insert(descendant);
+            }
+        }
+    }
+
+    /// Returns the number and hash of the executed blocks up to and including the given number.
+    pub fn executed_up_to(&self, number: BlockNumber) -> Vec<(BlockNumber, BlockHash)> {
+        self.executed_by_number
+            .range(..=number)
+            .flat_map(|(number, hashes)| hashes.iter().map(|hash| (*number, *hash)))
+            .collect()
+    }
+
+    /// Removes the executed block with the given hash.
+    ///
+    /// The descendants of the block stay in the tree and are executed on top of the block once it
+    /// is written to the database.
+    pub fn take_executed(&mut self, hash: &BlockHash) -> Option<ExecutedBlock> {
+        let block = self.executed.remove(hash)?;
+        if let Some(hashes) = self.executed_by_number.get_mut(&block.block.number) {
+            hashes.remove(hash);
+            if hashes.is_empty() {
+                self.executed_by_number.remove(&block.block.number);
+            }
+        }
+        Some(block)
+    }
+
+    /// Returns the combined state changes of the executed block with the given hash and its
+    /// executed ancestors.
+    ///
+    /// The changes apply to the state of the returned block, the first ancestor that was not
+    /// executed. If the given block was not executed, this is the block itself and there are no
+    /// changes.
+    pub fn pending_state(&self, hash: BlockHash) -> (BlockHash, PostState) {
+        let mut ancestors = Vec::new();
+        let mut fork = hash;
+        while let Some(block) = self.executed.get(&fork) {
+            ancestors.push(&block.post_state);
+            fork = block.block.parent_hash;
+        }
+
+        let mut post_state = PostState::default();
+        for ancestor in ancestors.into_iter().rev() {
+            post_state.extend(ancestor.clone());
+        }
+        (fork, post_state)
+    }
+
+    /// Buffers a block until its parent can be executed.
+    pub fn buffer_block(&mut self, block: SealedBlock) {
+        let (parent, hash) = (block.parent_hash, block.hash());
+        if self.buffered.entry(parent).or_default().insert(hash, block).is_none() {
+            self.buffered_order.push_back((parent, hash));
+        }
+
+        while self.buffered_order.len() > MAX_BUFFERED_BLOCKS {
+            let Some((parent, hash)) = self.buffered_order.pop_front() else { break };
+            if let Some(children) = self.buffered.get_mut(&parent) {
+                children.remove(&hash);
+                if children.is_empty() {
+                    self.buffered.remove(&parent);
+                }
+            }
+        }
+    }
+
+    /// Removes the buffered blocks with the given parent.
+    pub fn take_buffered_children(&mut self, parent: &BlockHash) -> Vec<SealedBlock> {
+        let Some(children) = self.buffered.remove(parent) else { return Vec::new() };
+        self.buffered_order.retain(|(block_parent, _)| block_parent != parent);
+        children.into_values().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::revm_wrap::{State, SubState};
+    use reth_primitives::{Header, H256};
+    use reth_provider::test_utils::MockEthProvider;
+
+    fn block(number: BlockNumber, parent_hash: BlockHash) -> SealedBlock {
+        SealedBlock {
+            header: Header { number, parent_hash, ..Default::default() }.seal(),
+            body: Vec::new(),
+            ommers: Vec::new(),
+        }
+    }
+
+    /// Returns the block as if it was executed without any state changes.
+    fn executed(block: SealedBlock) -> ExecutedBlock {
+        let result = ExecutionResult { changesets: Vec::new(), block_reward: None };
+        let db = SubState::new(State::new(MockEthProvider::default()));
+        let post_state = PostState::new(&block.header, &result, &db);
+        ExecutedBlock { block, result, post_state }
+    }
+
+    #[test]
+    fn pending_state_of_sidechain() {
+        let mut tree = BlockTree::default();
+        let fork = H256::random();
+        let first = block(1, fork);
+        let second = block(2, first.hash());
+        tree.insert_executed(executed(first.clone()));
+        tree.insert_executed(executed(second.clone()));
+
+        let mut expected = executed(first.clone()).post_state;
+        expected.extend(executed(second.clone()).post_state);
+        assert_eq!(tree.pending_state(second.hash()), (fork, expected));
+
+        // the remaining blocks are executed on top of the committed block
+        assert!(tree.take_executed(&first.hash()).is_some());
+        assert_eq!(tree.pending_state(second.hash()), (first.hash(), executed(second).post_state));
+
+        let unknown = H256::random();
+        assert_eq!(tree.pending_state(unknown), (unknown, PostState::default()));
+    }
+
+    #[test]
+    fn evicts_lowest_executed_blocks_with_descendants() {
+        let mut tree = BlockTree::default();
+        let fork = block(0, H256::random());
+        let fork_child = block(1, fork.hash());
+        tree.insert_executed(executed(fork.clone()));
+        tree.insert_executed(executed(fork_child.clone()));
+
+        let root = H256::random();
+        let mut parent = root;
+        let mut hashes = Vec::new();
+        for number in 0..MAX_EXECUTED_BLOCKS as u64 + 2 {
+            let block = block(number, parent);
+            parent = block.hash();
+            hashes.push(parent);
+            tree.insert_executed(executed(block));
+        }
+
+        // the fork is evicted with its descendant, so no partial state is returned for it
+        assert!(!tree.is_executed(&fork.hash()));
+        assert!(!tree.is_executed(&fork_child.hash()));
+        assert_eq!(
+            tree.pending_state(fork_child.hash()),
+            (fork_child.hash(), PostState::default())
+        );
+
+        // the ancestors of the most recently executed block are kept
+        assert!(hashes.iter().all(|hash| tree.is_executed(hash)));
+        assert_eq!(tree.pending_state(parent).0, root);
+    }
+
+    #[test]
+    fn buffers_blocks_by_parent() {
+        let mut tree = BlockTree::default();
+        let parent = H256::random();
+        let first = block(1, parent);
+        let sibling = block(1, parent);
+        let sibling = SealedBlock {
+            header: Header { timestamp: 1, ..sibling.header.unseal() }.seal(),
+            ..sibling
+        };
+        tree.buffer_block(first.clone());
+        tree.buffer_block(sibling.clone());
+        for number in 0..MAX_BUFFERED_BLOCKS as u64 - 1 {
+            tree.buffer_block(block(number, H256::random()));
+        }
+
+        // the oldest block was evicted
+        assert_eq!(tree.take_buffered_children(&parent), vec![sibling]);
+        assert!(tree.take_buffered_children(&parent).is_empty());
+        assert_eq!(tree.buffered_order.len(), MAX_BUFFERED_BLOCKS - 1);
+    }
+}
@@ -107,7 +107,7 @@ pub struct AccountChangeSet {
 
 /// Execution Result containing vector of transaction changesets
 /// and block reward if present
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ExecutionResult {
     /// Transaction changeset containing [Receipt], changed [Accounts][Account] and Storages.
     pub changesets: Vec<TransactionChangeSet>,
@@ -544,10 +544,10 @@ mod tests {
         transaction::DbTx,
     };
     use reth_primitives::{
-        hex_literal::hex, keccak256, Account, Address, Bytes, ChainSpecBuilder, SealedBlock,
-        StorageKey, H160, H256, MAINNET, U256,
+        hex_literal::hex, keccak256, proofs::genesis_state_root, Account, Address, Bytes,
+        ChainSpecBuilder, GenesisAccount, SealedBlock, StorageKey, H160, H256, MAINNET, U256,
     };
-    use reth_provider::{AccountProvider, BlockHashProvider, StateProvider};
+    use reth_provider::{trie::AccountChanges, AccountProvider, BlockHashProvider, StateProvider};
     use reth_rlp::Decodable;
 
     use super::*;
@@ -605,6 +605,54 @@ mod tests {
         fn bytecode_by_hash(&self, code_hash: H256) -> reth_interfaces::Result<Option<Bytes>> {
             Ok(self.contracts.get(&code_hash).cloned())
         }
+
+        fn proof(
+            &self,
+            _address: Address,
+            _keys: &[H256],
+        ) -> reth_interfaces::Result<(Vec<Bytes>, H256, Vec<Vec<Bytes>>)> {
+            Err(reth_interfaces::provider::Error::ProofUnavailable.into())
+        }
+
+        fn state_root_with_changes(
+            &self,
+            changes: &BTreeMap<Address, AccountChanges>,
+        ) -> reth_interfaces::Result<H256> {
+            let mut accounts = self.accounts.clone();
+            for (address, changes) in changes {
+                let Some(account) = changes.account else {
+                    accounts.remove(address);
+                    continue
+                };
+                let (storage, entry) = accounts.entry(*address).or_default();
+                *entry = account;
+                if changes.wipe_storage {
+                    storage.clear();
+                }
+                storage.extend(changes.storage.clone());
+            }
+
+            let alloc = accounts
+                .into_iter()
+                .map(|(address, (storage, account))| {
+                    let storage = storage
+                        .into_iter()
+                        .filter(|(_, value)| *value != U256::ZERO)
+                        .map(|(key, value)| (key, H256(value.to_be_bytes())))
+                        .collect();
+                    let account = GenesisAccount {
+                        nonce: Some(account.nonce),
+                        balance: account.balance,
+                        code: account
+                            .bytecode_hash
+                            .and_then(|hash| self.contracts.get(&hash).cloned()),
+                        storage: Some(storage),
+                    };
+                    (address, account)
+                })
+                .collect();
+            Ok(genesis_state_root(alloc))
+        }
     }
 
     #[test]
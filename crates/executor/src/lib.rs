@@ -7,9 +7,13 @@
 
 //! Reth executor executes transaction in block of data.
 
+/// In-memory tree of executed blocks
+pub mod block_tree;
 pub mod config;
 pub mod eth_dao_fork;
 /// Executor
 pub mod executor;
+/// In-memory state changes of executed blocks
+pub mod post_state;
 /// Wrapper around revm database and types
 pub mod revm_wrap;
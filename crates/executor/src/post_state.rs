@@ -0,0 +1,285 @@
+use crate::{
+    executor::ExecutionResult,
+    revm_wrap::{to_reth_acc, SubState},
+};
+use reth_interfaces::{provider::Error as ProviderError, Result};
+use reth_primitives::{
+    Account, Address, BlockNumber, Bytes, SealedHeader, StorageKey, StorageValue, H256,
+    KECCAK_EMPTY, U256,
+};
+use reth_provider::{trie::AccountChanges, AccountProvider, BlockHashProvider, StateProvider};
+use revm::{db::AccountState, AccountInfo, Bytecode};
+use std::collections::{BTreeMap, HashMap};
+
+/// The state changes of one or more executed blocks that are not written to the database.
+///
+/// The changes of consecutive blocks are combined with [PostState::extend].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PostState {
+    /// The changed accounts and their changed storage.
+    accounts: BTreeMap<Address, AccountChanges>,
+    /// The bytecodes of the created contracts.
+    bytecodes: HashMap<H256, Bytes>,
+    /// The hashes of the executed blocks.
+    block_hashes: BTreeMap<BlockNumber, H256>,
+}
+
+impl PostState {
+    /// Collects the changes of an executed block.
+    ///
+    /// The changesets of the [ExecutionResult] only contain the storage, the final accounts are
+    /// taken from the cache of the state the block was executed on.
+    pub fn new<DB: StateProvider>(
+        header: &SealedHeader,
+        result: &ExecutionResult,
+        db: &SubState<DB>,
+    ) -> Self {
+        let mut post_state = Self::default();
+        for changeset in result.changesets.iter() {
+            for (address, change) in changeset.changeset.iter() {
+                let account = post_state.accounts.entry(*address).or_default();
+                if change.wipe_storage {
+                    account.wipe_storage = true;
+                    account.storage.clear();
+                }
+                account.storage.extend(
+                    change
+                        .storage
+                        .iter()
+                        .map(|(slot, (_, value))| (H256(slot.to_be_bytes()), *value)),
+                );
+            }
+            post_state.bytecodes.extend(changeset.new_bytecodes.iter().map(|(hash, bytecode)| {
+                (*hash, Bytes::from(bytecode.bytes()[..bytecode.len()].to_vec()))
+            }));
+        }
+        for address in result.block_reward.iter().flat_map(|reward| reward.keys()) {
+            post_state.accounts.entry(*address).or_default();
+        }
+
+        for (address, change) in post_state.accounts.iter_mut() {
+            change.account = db
+                .accounts
+                .get(address)
+                .filter(|account| !matches!(account.account_state, AccountState::NotExisting))
+                .map(|account| to_reth_acc(&account.info))
+                .filter(|account| !account.is_empty());
+        }
+        post_state.block_hashes.insert(header.number, header.hash());
+        post_state
+    }
+
+    /// Returns the changed accounts.
+    pub fn accounts(&self) -> &BTreeMap<Address, AccountChanges> {
+        &self.accounts
+    }
+
+    /// Applies the changes of the following block on top of these changes.
+    pub fn extend(&mut self, other: PostState) {
+        for (address, change) in other.accounts {
+            let account = self.accounts.entry(address).or_default();
+            account.account = change.account;
+            if change.wipe_storage {
+                account.wipe_storage = true;
+                account.storage.clear();
+            }
+            account.storage.extend(change.storage);
+        }
+        self.bytecodes.extend(other.bytecodes);
+        self.block_hashes.extend(other.block_hashes);
+    }
+
+    /// Writes the changes into the cache of the state, as if the block was executed on it.
+    pub fn apply_to<DB: StateProvider>(&self, db: &mut SubState<DB>) {
+        for (hash, bytecode) in self.bytecodes.iter() {
+            db.contracts.insert(*hash, Bytecode::new_raw(bytecode.0.clone()));
+        }
+
+        for (address, change) in self.accounts.iter() {
+            let account = db.accounts.entry(*address).or_default();
+            let Some(new) = change.account else {
+                account.storage.clear();
+                account.account_state = AccountState::NotExisting;
+                account.info = AccountInfo::default();
+                continue
+            };
+
+            if change.wipe_storage {
+                account.storage.clear();
+            }
+            account.account_state = if change.wipe_storage ||
+                matches!(
+                    account.account_state,
+                    AccountState::NotExisting | AccountState::StorageCleared
+                ) {
+                AccountState::StorageCleared
+            } else {
+                AccountState::Touched
+            };
+            account.info = AccountInfo {
+                balance: new.balance,
+                nonce: new.nonce,
+                code_hash: new.bytecode_hash.unwrap_or(KECCAK_EMPTY),
+                code: None,
+            };
+            account.storage.extend(
+                change.storage.iter().map(|(slot, value)| (U256::from_be_bytes(slot.0), *value)),
+            );
+        }
+    }
+}
+
+/// A [StateProvider] that applies a [PostState] on top of another state.
+///
+/// This is used to execute blocks on top of blocks that were executed, but not written to the
+/// database.
+pub struct PostStateProvider<SP> {
+    /// The state the changes are applied to.
+    state: SP,
+    /// The changes on top of the state.
+    post_state: PostState,
+}
+
+impl<SP: StateProvider> PostStateProvider<SP> {
+    /// Create a new provider that applies the changes on top of the state.
+    pub fn new(state: SP, post_state: PostState) -> Self {
+        Self { state, post_state }
+    }
+}
+
+impl<SP: StateProvider> AccountProvider for PostStateProvider<SP> {
+    fn basic_account(&self, address: Address) -> Result<Option<Account>> {
+        match self.post_state.accounts.get(&address) {
+            Some(change) => Ok(change.account),
+            None => self.state.basic_account(address),
+        }
+    }
+}
+
+impl<SP: StateProvider> BlockHashProvider for PostStateProvider<SP> {
+    fn block_hash(&self, number: U256) -> Result<Option<H256>> {
+        match self.post_state.block_hashes.get(&number.to::<u64>()) {
+            Some(hash) => Ok(Some(*hash)),
+            None => self.state.block_hash(number),
+        }
+    }
+}
+
+impl<SP: StateProvider> StateProvider for PostStateProvider<SP> {
+    fn storage(&self, account: Address, storage_key: StorageKey) -> Result<Option<StorageValue>> {
+        if let Some(change) = self.post_state.accounts.get(&account) {
+            if let Some(value) = change.storage.get(&storage_key) {
+                return Ok(Some(*value))
+            }
+            if change.wipe_storage || change.account.is_none() {
+                return Ok(None)
+            }
+        }
+        self.state.storage(account, storage_key)
+    }
+
+    fn bytecode_by_hash(&self, code_hash: H256) -> Result<Option<Bytes>> {
+        match self.post_state.bytecodes.get(&code_hash) {
+            Some(bytecode) => Ok(Some(bytecode.clone())),
+            None => self.state.bytecode_by_hash(code_hash),
+        }
+    }
+
+    /// Proofs require the trie of the state, which is not built for the changes in memory.
+    fn proof(
+        &self,
+        _address: Address,
+        _keys: &[H256],
+    ) -> Result<(Vec<Bytes>, H256, Vec<Vec<Bytes>>)> {
+        Err(ProviderError::ProofUnavailable.into())
+    }
+
+    fn state_root_with_changes(&self, changes: &BTreeMap<Address, AccountChanges>) -> Result<H256> {
+        let mut post_state = self.post_state.clone();
+        post_state.extend(PostState { accounts: changes.clone(), ..Default::default() });
+        self.state.state_root_with_changes(&post_state.accounts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::revm_wrap::State;
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+
+    #[test]
+    fn reads_post_state() {
+        let provider = MockEthProvider::default();
+        let (changed, destroyed, untouched) =
+            (Address::random(), Address::random(), Address::random());
+        let slot = H256::from_low_u64_be(1);
+        for address in [changed, destroyed, untouched] {
+            provider.add_account(
+                address,
+                ExtendedAccount::new(1, U256::from(1)).extend_storage([(slot, U256::from(1))]),
+            );
+        }
+
+        let mut post_state = PostState::default();
+        post_state.accounts.insert(
+            changed,
+            AccountChanges {
+                account: Some(Account { nonce: 2, ..Default::default() }),
+                wipe_storage: true,
+                storage: BTreeMap::from([(H256::from_low_u64_be(2), U256::from(2))]),
+            },
+        );
+        post_state.accounts.insert(destroyed, AccountChanges::default());
+        post_state.block_hashes.insert(1, H256::random());
+
+        let state = PostStateProvider::new(&provider, post_state.clone());
+        assert_eq!(state.basic_account(changed).unwrap().map(|account| account.nonce), Some(2));
+        assert_eq!(state.storage(changed, slot).unwrap(), None);
+        assert_eq!(state.storage(changed, H256::from_low_u64_be(2)).unwrap(), Some(U256::from(2)));
+        assert_eq!(state.basic_account(destroyed).unwrap(), None);
+        assert_eq!(state.storage(destroyed, slot).unwrap(), None);
+        assert_eq!(state.storage(untouched, slot).unwrap(), Some(U256::from(1)));
+        assert_eq!(
+            state.block_hash(U256::from(1)).unwrap(),
+            post_state.block_hashes.get(&1).copied()
+        );
+
+        // the cache of a state reads the same values after the changes are applied
+        let mut db = SubState::new(State::new(&provider));
+        post_state.apply_to(&mut db);
+        let cached = db.accounts.get(&changed).unwrap();
+        assert_eq!(cached.info.nonce, 2);
+        assert!(matches!(cached.account_state, AccountState::StorageCleared));
+        assert_eq!(cached.storage.get(&U256::from(2)), Some(&U256::from(2)));
+        assert!(matches!(
+            db.accounts.get(&destroyed).unwrap().account_state,
+            AccountState::NotExisting
+        ));
+    }
+
+    #[test]
+    fn extend_post_state() {
+        let address = Address::random();
+        let mut post_state = PostState::default();
+        post_state.accounts.insert(
+            address,
+            AccountChanges {
+                account: Some(Account::default()),
+                wipe_storage: false,
+                storage: BTreeMap::from([(H256::from_low_u64_be(1), U256::from(1))]),
+            },
+        );
+
+        let mut next = PostState::default();
+        next.accounts.insert(
+            address,
+            AccountChanges {
+                account: Some(Account { nonce: 1, ..Default::default() }),
+                wipe_storage: true,
+                storage: BTreeMap::from([(H256::from_low_u64_be(2), U256::from(2))]),
+            },
+        );
+        post_state.extend(next.clone());
+        assert_eq!(post_state.accounts, next.accounts);
+    }
+}
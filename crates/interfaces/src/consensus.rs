@@ -47,6 +47,8 @@ pub enum Error {
     BodyTransactionRootDiff { got: H256, expected: H256 },
     #[error("Block receipts root ({got:?}) is different then expected: ({expected:?}).")]
     BodyReceiptsRootDiff { got: H256, expected: H256 },
+    #[error("Block state root ({got:?}) is different then expected: ({expected:?}).")]
+    BodyStateRootDiff { got: H256, expected: H256 },
     #[error("Block with [hash:{hash:?},number: {number:}] is already known.")]
     BlockKnown { hash: BlockHash, number: BlockNumber },
     #[error("Block parent [hash:{hash:?}] is not known.")]
@@ -28,4 +28,7 @@ pub enum Error {
     /// Failed to decode a key from a table..
     #[error("Error decoding value.")]
     DecodeError,
+    /// Failed to read from or write to the static files.
+    #[error("Static file error: {0}")]
+    StaticFile(String),
 }
@@ -0,0 +1,67 @@
+//! Events about changes of the canonical chain.
+
+use auto_impl::auto_impl;
+use reth_primitives::BlockNumber;
+use tokio::sync::broadcast;
+
+/// The default number of [ChainEvent]s a subscriber can fall behind before it misses events.
+pub const DEFAULT_CHAIN_EVENT_CAPACITY: usize = 256;
+
+/// A change of the canonical chain.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum ChainEvent {
+    /// New blocks were committed to the canonical chain.
+    Extended {
+        /// The first new block.
+        first: BlockNumber,
+        /// The new tip of the canonical chain.
+        tip: BlockNumber,
+    },
+    /// The canonical chain was unwound and all blocks above the `tip` were removed.
+    Unwound {
+        /// The new tip of the canonical chain.
+        tip: BlockNumber,
+    },
+}
+
+/// A type that allows to subscribe to [ChainEvent]s.
+#[auto_impl(&, Arc)]
+pub trait ChainEventSubscriptions: Send + Sync {
+    /// Returns a new receiver of all [ChainEvent]s emitted after this call.
+    ///
+    /// A receiver that falls behind by more than the channel capacity skips the oldest events,
+    /// see [broadcast::error::RecvError::Lagged].
+    fn subscribe_chain_events(&self) -> broadcast::Receiver<ChainEvent>;
+}
+
+/// Broadcasts [ChainEvent]s to all subscribers.
+#[derive(Debug, Clone)]
+pub struct ChainEventSender {
+    sender: broadcast::Sender<ChainEvent>,
+}
+
+impl ChainEventSender {
+    /// Creates a new sender that buffers up to `capacity` events per subscriber.
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Sends the event to all current subscribers.
+    pub fn notify(&self, event: ChainEvent) {
+        // an error only means that there are no subscribers
+        let _ = self.sender.send(event);
+    }
+}
+
+impl Default for ChainEventSender {
+    fn default() -> Self {
+        Self::new(DEFAULT_CHAIN_EVENT_CAPACITY)
+    }
+}
+
+impl ChainEventSubscriptions for ChainEventSender {
+    fn subscribe_chain_events(&self) -> broadcast::Receiver<ChainEvent> {
+        self.sender.subscribe()
+    }
+}
@@ -1,4 +1,6 @@
-use reth_primitives::{Address, BlockHash, BlockNumber, TransitionId, H256};
+use reth_primitives::{
+    Address, BlockHash, BlockNumber, PruneSegment, TransitionId, TxNumber, H256,
+};
 
 /// KV error type. They are using u32 to represent error code.
 #[allow(missing_docs)]
@@ -20,8 +22,24 @@ pub enum Error {
         expected_hash: BlockHash,
         received_hash: BlockHash,
     },
+    #[error("Transaction #{id} does not exist")]
+    Transaction { id: TxNumber },
+    #[error("Sender of transaction #{id} does not exist and could not be recovered")]
+    TransactionSender { id: TxNumber },
     #[error("Storage ChangeSet address: ({address:?} key: {storage_key:?}) for transition:#{transition_id} does not exist")]
     StorageChangeset { transition_id: TransitionId, address: Address, storage_key: H256 },
     #[error("Account {address:?} ChangeSet for transition #{transition_id} does not exist")]
     AccountChangeset { transition_id: TransitionId, address: Address },
+    #[error("Trie node {hash:?} does not exist")]
+    TrieNode { hash: H256 },
+    #[error("Trie node {hash:?} could not be decoded")]
+    TrieNodeDecode { hash: H256 },
+    #[error("Trie account {hashed_address:?} could not be decoded")]
+    TrieAccountDecode { hashed_address: H256 },
+    #[error("The state trie was not built yet")]
+    StateTrie,
+    #[error("Proofs are not available for state that is not stored in the database")]
+    ProofUnavailable,
+    #[error("The {segment} data of blocks up to #{pruned_to} was pruned")]
+    Pruned { segment: PruneSegment, pruned_to: BlockNumber },
 }
@@ -8,6 +8,11 @@ use reth_primitives::BlockNumber;
 pub trait SyncStateProvider: Send + Sync {
     /// Returns `true` if the network is undergoing sync.
     fn is_syncing(&self) -> bool;
+
+    /// Returns the block the node is syncing to, if it is syncing and the target is known.
+    fn sync_target(&self) -> Option<BlockNumber> {
+        None
+    }
 }
 
 /// An updater for updating the [SyncState] of the network.
@@ -59,6 +64,16 @@ impl SyncState {
     pub fn is_syncing(&self) -> bool {
         !matches!(self, SyncState::Idle)
     }
+
+    /// The block the node is syncing to, `None` if the node is idle.
+    pub fn target_block(&self) -> Option<BlockNumber> {
+        match self {
+            SyncState::Idle => None,
+            SyncState::Downloading { target_block } | SyncState::Executing { target_block } => {
+                Some(*target_block)
+            }
+        }
+    }
 }
 
 /// A [SyncStateUpdater] implementation that does nothing.
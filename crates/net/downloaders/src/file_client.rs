@@ -0,0 +1,305 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use reth_eth_wire::BlockBody;
+use reth_interfaces::{
+    p2p::{
+        bodies::client::{BodiesClient, BodiesFut},
+        download::DownloadClient,
+        error::RequestError,
+        headers::client::{HeadersClient, HeadersFut, HeadersRequest},
+        priority::Priority,
+    },
+    sync::{SyncState, SyncStateProvider, SyncStateUpdater},
+};
+use reth_primitives::{Block, BlockHash, BlockHashOrNumber, BlockNumber, Header, PeerId, H256};
+use reth_rlp::Decodable;
+use thiserror::Error;
+use tracing::warn;
+
+/// Front-end API for fetching chain data from a file.
+///
+/// Blocks are assumed to be written one after another in a file, as rlp bytes.
+///
+/// For example, if the file contains 3 blocks, the file is assumed to be encoded as follows:
+/// rlp(block1) || rlp(block2) || rlp(block3)
+///
+/// This is the format of the chain exports of geth. The whole file is decoded when the client is
+/// created and the blocks are held in memory, so the headers and bodies stages can be run from it
+/// without network access.
+#[derive(Debug, Default)]
+pub struct FileClient {
+    /// The buffered headers of the file.
+    headers: HashMap<BlockNumber, Header>,
+
+    /// The block numbers of the buffered headers.
+    hash_to_number: HashMap<BlockHash, BlockNumber>,
+
+    /// The buffered bodies of the file.
+    bodies: HashMap<BlockHash, BlockBody>,
+
+    /// The hash of the highest block in the file.
+    tip: Option<H256>,
+
+    /// Represents if we are currently syncing.
+    is_syncing: Arc<AtomicBool>,
+}
+
+/// An error that can occur when constructing and using a [`FileClient`](FileClient).
+#[derive(Debug, Error)]
+pub enum FileClientError {
+    /// An error occurred when opening or reading the file.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// An error occurred when decoding blocks, headers, or rlp headers from the file.
+    #[error(transparent)]
+    Rlp(#[from] reth_rlp::DecodeError),
+}
+
+impl FileClient {
+    /// Create a new file client from a file path.
+    pub async fn new<P: AsRef<Path>>(path: P) -> Result<Self, FileClientError> {
+        let bytes = tokio::fs::read(path).await?;
+        FileClient::from_bytes(&bytes)
+    }
+
+    /// Initialize the [`FileClient`](FileClient) with the rlp encoded blocks of a file.
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, FileClientError> {
+        let mut client = Self::default();
+        let mut tip_number = None;
+        while !bytes.is_empty() {
+            let block = Block::decode(&mut bytes)?;
+            let Block { header, body, ommers } = block;
+            let (number, hash) = (header.number, header.hash_slow());
+
+            if tip_number.map_or(true, |tip| number > tip) {
+                tip_number = Some(number);
+                client.tip = Some(hash);
+            }
+            client.headers.insert(number, header);
+            client.hash_to_number.insert(hash, number);
+            client.bodies.insert(hash, BlockBody { transactions: body, ommers });
+        }
+        Ok(client)
+    }
+
+    /// Use the provided bodies as the file client's block body buffer.
+    pub fn with_bodies(mut self, bodies: HashMap<BlockHash, BlockBody>) -> Self {
+        self.bodies = bodies;
+        self
+    }
+
+    /// Use the provided headers as the file client's header buffer.
+    pub fn with_headers(mut self, headers: HashMap<BlockNumber, Header>) -> Self {
+        self.hash_to_number =
+            headers.iter().map(|(number, header)| (header.hash_slow(), *number)).collect();
+        self.headers = headers;
+        self
+    }
+
+    /// Returns the hash of the highest block in the file.
+    ///
+    /// Returns `None` if the file contains no blocks.
+    pub fn tip(&self) -> Option<H256> {
+        self.tip
+    }
+
+    /// Returns the number of the highest block in the file.
+    ///
+    /// Returns `None` if the file contains no blocks.
+    pub fn max_block(&self) -> Option<BlockNumber> {
+        self.tip.and_then(|hash| self.hash_to_number.get(&hash).copied())
+    }
+
+    /// Returns the header of the lowest block in the file.
+    ///
+    /// Returns `None` if the file contains no blocks.
+    pub fn first_header(&self) -> Option<&Header> {
+        self.headers.keys().min().and_then(|number| self.headers.get(number))
+    }
+
+    /// Returns the number of blocks in the file.
+    pub fn len(&self) -> usize {
+        self.headers.len()
+    }
+
+    /// Returns `true` if the file contains no blocks.
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty()
+    }
+}
+
+impl HeadersClient for FileClient {
+    type Output = HeadersFut;
+
+    fn get_headers_with_priority(
+        &self,
+        request: HeadersRequest,
+        _priority: Priority,
+    ) -> Self::Output {
+        let start = match request.start {
+            BlockHashOrNumber::Hash(hash) => self.hash_to_number.get(&hash).copied(),
+            BlockHashOrNumber::Number(number) => Some(number),
+        };
+
+        // this just searches the buffer, the response ends at the first header that is missing
+        let headers = match start {
+            Some(start) => {
+                let numbers: Box<dyn Iterator<Item = BlockNumber>> =
+                    if request.direction.is_rising() {
+                        Box::new(start..)
+                    } else {
+                        Box::new((0..=start).rev())
+                    };
+                numbers
+                    .take(request.limit as usize)
+                    .map_while(|number| self.headers.get(&number).cloned())
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+
+        Box::pin(async move { Ok((PeerId::default(), headers).into()) })
+    }
+}
+
+impl BodiesClient for FileClient {
+    type Output = BodiesFut;
+
+    fn get_block_bodies_with_priority(
+        &self,
+        hashes: Vec<H256>,
+        _priority: Priority,
+    ) -> Self::Output {
+        // this just searches the buffer, and fails if it can't find the block
+        let mut bodies = Vec::new();
+
+        // check if any are an error
+        // could unwrap here
+        for hash in hashes {
+            match self.bodies.get(&hash).cloned() {
+                Some(body) => bodies.push(body),
+                None => return Box::pin(async move { Err(RequestError::BadResponse) }),
+            }
+        }
+
+        Box::pin(async move { Ok((PeerId::default(), bodies).into()) })
+    }
+}
+
+impl DownloadClient for FileClient {
+    fn report_bad_message(&self, _peer_id: PeerId) {
+        warn!("Reported a bad message on a file client, the file may be corrupted or invalid");
+        // noop
+    }
+
+    fn num_connected_peers(&self) -> usize {
+        // no such thing as connected peers when we are just using a file
+        1
+    }
+}
+
+impl SyncStateProvider for FileClient {
+    fn is_syncing(&self) -> bool {
+        self.is_syncing.load(Ordering::Relaxed)
+    }
+}
+
+impl SyncStateUpdater for FileClient {
+    fn update_sync_state(&self, state: SyncState) {
+        let is_syncing = state.is_syncing();
+        self.is_syncing.store(is_syncing, Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bodies::{
+            bodies::BodiesDownloaderBuilder,
+            test_utils::{insert_headers, zip_blocks},
+        },
+        test_utils::generate_bodies,
+    };
+    use assert_matches::assert_matches;
+    use futures_util::stream::StreamExt;
+    use reth_db::mdbx::{test_utils::create_test_db, EnvKind, WriteMap};
+    use reth_interfaces::{
+        p2p::bodies::downloader::BodyDownloader,
+        test_utils::{generators::random_block_range, TestConsensus},
+    };
+    use reth_primitives::HeadersDirection;
+    use reth_rlp::Encodable;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn streams_bodies_from_buffer() {
+        // Generate some random blocks
+        let db = create_test_db::<WriteMap>(EnvKind::RW);
+        let (headers, mut bodies) = generate_bodies(0..20);
+
+        insert_headers(&db, &headers);
+
+        // create an empty file
+        let file = tempfile::NamedTempFile::new().unwrap();
+
+        let client =
+            Arc::new(FileClient::new(file.path()).await.unwrap().with_bodies(bodies.clone()));
+        let mut downloader = BodiesDownloaderBuilder::default().build(
+            client.clone(),
+            Arc::new(TestConsensus::default()),
+            db,
+        );
+        downloader.set_download_range(0..20).expect("failed to set download range");
+
+        assert_matches!(
+            downloader.next().await,
+            Some(Ok(res)) => assert_eq!(res, zip_blocks(headers.iter(), &mut bodies))
+        );
+    }
+
+    #[tokio::test]
+    async fn serves_blocks_of_file() {
+        let blocks = random_block_range(0..10, H256::zero(), 0..2);
+        let mut bytes = Vec::new();
+        for block in blocks.iter() {
+            block.clone().unseal().encode(&mut bytes);
+        }
+
+        let client = FileClient::from_bytes(&bytes).unwrap();
+        assert_eq!(client.len(), 10);
+        assert_eq!(client.tip(), Some(blocks[9].hash()));
+        assert_eq!(client.max_block(), Some(9));
+        assert_eq!(client.first_header(), Some(&blocks[0].header.clone().unseal()));
+
+        let request = HeadersRequest {
+            start: BlockHashOrNumber::Hash(blocks[9].hash()),
+            limit: 5,
+            direction: HeadersDirection::Falling,
+        };
+        let (_, headers) = client.get_headers(request).await.unwrap().split();
+        assert_eq!(
+            headers,
+            blocks[5..].iter().rev().map(|block| block.header.clone().unseal()).collect::<Vec<_>>()
+        );
+
+        let request = HeadersRequest {
+            start: BlockHashOrNumber::Number(8),
+            limit: 5,
+            direction: HeadersDirection::Rising,
+        };
+        let (_, headers) = client.get_headers(request).await.unwrap().split();
+        assert_eq!(headers.len(), 2);
+
+        let (_, bodies) = client.get_block_bodies(vec![blocks[3].hash()]).await.unwrap().split();
+        assert_eq!(bodies[0].transactions, blocks[3].body);
+    }
+}
@@ -504,6 +504,10 @@ where
     H: HeadersClient + 'static,
 {
     fn update_local_head(&mut self, head: SealedHeader) {
+        // the local chain was unwound, so the headers above the new head have to be downloaded
+        // again
+        let unwound = self.local_block_number().map_or(false, |local| head.number < local);
+
         // ensure we're only yielding headers that are in range and follow the current local head.
         while self
             .queued_validated_headers
@@ -516,6 +520,14 @@ where
         }
         // update the local head
         self.local_head = Some(head);
+
+        if unwound {
+            if let Some(target) = self.sync_target.take() {
+                trace!(target: "downloaders::headers", head=?self.local_block_number(), "Local head was unwound, restarting download");
+                self.clear();
+                self.update_sync_target(SyncTarget::Tip(target.hash));
+            }
+        }
     }
 
     /// If the given target is different from the current target, we need to update the sync target
@@ -983,6 +995,29 @@ mod tests {
         assert!(downloader.queued_validated_headers.is_empty());
     }
 
+    /// Tests that the download is restarted if the local head is unwound
+    #[test]
+    fn test_head_unwind() {
+        let client = Arc::new(TestHeadersClient::default());
+
+        let tip = H256::random();
+        let mut downloader = ReverseHeadersDownloaderBuilder::default()
+            .build(Arc::new(TestConsensus::default()), Arc::clone(&client));
+        downloader.update_local_head(Header { number: 10, ..Default::default() }.seal());
+        downloader.update_sync_target(SyncTarget::Tip(tip));
+        downloader.sync_target_request.take();
+        downloader.lowest_validated_header =
+            Some(Header { number: 11, ..Default::default() }.seal());
+
+        downloader.update_local_head(Header { number: 7, ..Default::default() }.seal());
+        assert!(downloader.sync_target_request.is_some());
+        assert!(downloader.lowest_validated_header.is_none());
+        assert_matches!(
+            downloader.sync_target,
+            Some(target) => target.hash == tip && target.number.is_none()
+        );
+    }
+
     #[test]
     fn test_request_calc() {
         // request an entire batch
@@ -14,6 +14,9 @@ pub mod bodies;
 /// The collection of algorithms for downloading block headers.
 pub mod headers;
 
+/// A client that serves blocks read from a file instead of the network.
+pub mod file_client;
+
 /// Common downloader metrics.
 pub mod metrics;
 
@@ -31,8 +31,6 @@ pub(crate) fn generate_bodies(
     (headers, bodies)
 }
 
-mod file_client;
 mod test_client;
 
-pub use file_client::{FileClient, FileClientError};
 pub use test_client::TestBodiesClient;
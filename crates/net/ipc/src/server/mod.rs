@@ -27,7 +27,7 @@ use tower::{layer::util::Identity, Service};
 use tracing::{trace, warn};
 
 // re-export so can be used during builder setup
-pub use parity_tokio_ipc::Endpoint;
+pub use parity_tokio_ipc::{Endpoint, SecurityAttributes};
 
 mod connection;
 mod future;
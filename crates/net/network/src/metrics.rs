@@ -39,4 +39,6 @@ pub struct NetworkMetrics {
 pub struct TransactionsManagerMetrics {
     /// Total number of propagated transactions
     pub(crate) propagated_transactions: Counter,
+    /// Total number of transactions dropped because the peer exceeded its pool import quota
+    pub(crate) dropped_pool_imports: Counter,
 }
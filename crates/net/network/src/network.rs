@@ -13,7 +13,7 @@ use reth_net_common::bandwidth_meter::BandwidthMeter;
 use reth_network_api::{
     NetworkError, NetworkInfo, NetworkStatus, PeerKind, Peers, PeersInfo, ReputationChangeKind,
 };
-use reth_primitives::{NodeRecord, PeerId, TransactionSigned, TxHash, H256, U256};
+use reth_primitives::{BlockNumber, NodeRecord, PeerId, TransactionSigned, TxHash, H256, U256};
 use std::{
     net::SocketAddr,
     sync::{
@@ -57,6 +57,7 @@ impl NetworkHandle {
             network_mode,
             bandwidth_meter,
             is_syncing: Arc::new(Default::default()),
+            sync_target: Arc::new(Default::default()),
             chain_id,
         };
         Self { inner: Arc::new(inner) }
@@ -235,10 +236,17 @@ impl SyncStateProvider for NetworkHandle {
     fn is_syncing(&self) -> bool {
         self.inner.is_syncing.load(Ordering::Relaxed)
     }
+
+    fn sync_target(&self) -> Option<BlockNumber> {
+        self.is_syncing().then(|| self.inner.sync_target.load(Ordering::Relaxed))
+    }
 }
 
 impl SyncStateUpdater for NetworkHandle {
     fn update_sync_state(&self, state: SyncState) {
+        if let Some(target_block) = state.target_block() {
+            self.inner.sync_target.store(target_block, Ordering::Relaxed);
+        }
         let is_syncing = state.is_syncing();
         self.inner.is_syncing.store(is_syncing, Ordering::Relaxed)
     }
@@ -262,6 +270,8 @@ struct NetworkInner {
     bandwidth_meter: BandwidthMeter,
     /// Represents if the network is currently syncing.
     is_syncing: Arc<AtomicBool>,
+    /// The block the network is syncing to, only meaningful while syncing.
+    sync_target: Arc<AtomicU64>,
     /// The chain id
     chain_id: Arc<AtomicU64>,
 }
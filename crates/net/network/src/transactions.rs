@@ -18,7 +18,7 @@ use reth_primitives::{
     FromRecoveredTransaction, IntoRecoveredTransaction, PeerId, TransactionSigned, TxHash, H256,
 };
 use reth_transaction_pool::{
-    error::PoolResult, PropagateKind, PropagatedTransactions, TransactionPool,
+    error::PoolResult, PeerImportLimit, PropagateKind, PropagatedTransactions, TransactionPool,
 };
 use std::{
     collections::{hash_map::Entry, HashMap},
@@ -27,6 +27,7 @@ use std::{
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
+    time::Instant,
 };
 use tokio::sync::{mpsc, oneshot};
 use tokio_stream::wrappers::{ReceiverStream, UnboundedReceiverStream};
@@ -36,7 +37,10 @@ use tracing::trace;
 const PEER_TRANSACTION_CACHE_LIMIT: usize = 1024 * 10;
 
 /// The future for inserting a function into the pool
-pub type PoolImportFuture = Pin<Box<dyn Future<Output = PoolResult<TxHash>> + Send + 'static>>;
+///
+/// Resolves to the hash of the submitted transaction and the result of the import.
+pub type PoolImportFuture =
+    Pin<Box<dyn Future<Output = (TxHash, PoolResult<TxHash>)> + Send + 'static>>;
 
 /// Api to interact with [`TransactionsManager`] task.
 pub struct TransactionsHandle {
@@ -102,6 +106,8 @@ pub struct TransactionsManager<Pool> {
     pending_transactions: ReceiverStream<TxHash>,
     /// Incoming events from the [`NetworkManager`](crate::NetworkManager).
     transaction_events: UnboundedReceiverStream<NetworkTransactionEvent>,
+    /// The number of new transactions a single peer can submit to the pool per time window.
+    import_limit: PeerImportLimit,
     /// TransactionsManager metrics
     metrics: TransactionsManagerMetrics,
 }
@@ -120,6 +126,7 @@ impl<Pool: TransactionPool> TransactionsManager<Pool> {
 
         // install a listener for new transactions
         let pending = pool.pending_transactions_listener();
+        let import_limit = pool.config().peer_import_limit.clone();
 
         Self {
             pool,
@@ -133,6 +140,7 @@ impl<Pool: TransactionPool> TransactionsManager<Pool> {
             command_rx: UnboundedReceiverStream::new(command_rx),
             pending_transactions: ReceiverStream::new(pending),
             transaction_events: UnboundedReceiverStream::new(from_network),
+            import_limit,
             metrics: Default::default(),
         }
     }
@@ -332,6 +340,7 @@ where
                             NonZeroUsize::new(PEER_TRANSACTION_CACHE_LIMIT).unwrap(),
                         ),
                         request_tx: messages,
+                        pool_imports: PoolImports::new(Instant::now()),
                     },
                 );
 
@@ -365,6 +374,8 @@ where
         // tracks the quality of the given transactions
         let mut has_bad_transactions = false;
         let mut num_already_seen = 0;
+        let mut num_dropped = 0;
+        let now = Instant::now();
 
         if let Some(peer) = self.peers.get_mut(&peer_id) {
             for tx in transactions {
@@ -390,12 +401,19 @@ where
                         entry.get_mut().push(peer_id);
                     }
                     Entry::Vacant(entry) => {
+                        // enforce the import quota of the peer
+                        if !peer.pool_imports.try_count(&self.import_limit, now) {
+                            num_dropped += 1;
+                            continue
+                        }
+
                         // this is a new transaction that should be imported into the pool
+                        let hash = tx.hash;
                         let pool_transaction = <Pool::Transaction as FromRecoveredTransaction>::from_recovered_transaction(tx);
 
                         let pool = self.pool.clone();
                         let import = Box::pin(async move {
-                            pool.add_external_transaction(pool_transaction).await
+                            (hash, pool.add_external_transaction(pool_transaction).await)
                         });
 
                         self.pool_imports.push(import);
@@ -405,6 +423,11 @@ where
             }
         }
 
+        if num_dropped > 0 {
+            trace!(target: "net::tx", ?peer_id, num_dropped, "Peer exceeded its pool import quota");
+            self.metrics.dropped_pool_imports.increment(num_dropped);
+        }
+
         if has_bad_transactions || num_already_seen > 0 {
             self.report_bad_message(peer_id);
         }
@@ -414,12 +437,19 @@ where
         self.network.reputation_change(peer_id, ReputationChangeKind::BadTransactions);
     }
 
+    /// Stops tracking the finished import.
+    ///
+    /// Returns all peers that sent the transaction.
+    fn on_import_finished(&mut self, hash: TxHash) -> Option<Vec<PeerId>> {
+        self.transactions_by_peers.remove(&hash)
+    }
+
     fn on_good_import(&mut self, hash: TxHash) {
-        self.transactions_by_peers.remove(&hash);
+        self.on_import_finished(hash);
     }
 
     fn on_bad_import(&mut self, hash: TxHash) {
-        if let Some(peers) = self.transactions_by_peers.remove(&hash) {
+        if let Some(peers) = self.on_import_finished(hash) {
             for peer_id in peers {
                 self.report_bad_message(peer_id);
             }
@@ -476,13 +506,13 @@ where
         }
 
         // Advance all imports
-        while let Poll::Ready(Some(import_res)) = this.pool_imports.poll_next_unpin(cx) {
+        while let Poll::Ready(Some((hash, import_res))) = this.pool_imports.poll_next_unpin(cx) {
             match import_res {
-                Ok(hash) => {
+                Ok(_) => {
                     this.on_good_import(hash);
                 }
-                Err(err) => {
-                    this.on_bad_import(*err.hash());
+                Err(_) => {
+                    this.on_bad_import(hash);
                 }
             }
         }
@@ -532,6 +562,37 @@ struct Peer {
     transactions: LruCache<H256>,
     /// A communication channel directly to the session task.
     request_tx: PeerRequestSender,
+    /// The transactions of this peer that were submitted to the pool in the current window.
+    pool_imports: PoolImports,
+}
+
+/// Counts the new transactions a peer submits to the pool within a [PeerImportLimit::window].
+struct PoolImports {
+    /// When the current window started.
+    window_start: Instant,
+    /// Number of transactions submitted in the current window.
+    count: usize,
+}
+
+impl PoolImports {
+    fn new(now: Instant) -> Self {
+        Self { window_start: now, count: 0 }
+    }
+
+    /// Counts another transaction, unless the peer already reached the limit in the current
+    /// window.
+    ///
+    /// Returns `true` if the transaction was counted and can be imported.
+    fn try_count(&mut self, limit: &PeerImportLimit, now: Instant) -> bool {
+        if now.duration_since(self.window_start) >= limit.window {
+            *self = Self::new(now);
+        }
+        if self.count >= limit.max_txs {
+            return false
+        }
+        self.count += 1;
+        true
+    }
 }
 
 /// Commands to send to the [`TransactionsManager`](crate::transactions::TransactionsManager)
@@ -559,10 +620,16 @@ pub enum NetworkTransactionEvent {
 mod tests {
     use super::*;
     use crate::{NetworkConfigBuilder, NetworkManager};
-    use reth_interfaces::sync::{SyncState, SyncStateUpdater};
+    use futures::future::poll_fn;
+    use reth_interfaces::{
+        sync::{SyncState, SyncStateUpdater},
+        test_utils::generators::sign_message,
+    };
+    use reth_primitives::{Transaction, TransactionKind, TxLegacy};
     use reth_provider::test_utils::NoopProvider;
-    use reth_transaction_pool::test_utils::testing_pool;
+    use reth_transaction_pool::test_utils::{testing_pool, MockTransaction};
     use secp256k1::SecretKey;
+    use std::time::Duration;
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_ignored_tx_broadcasts_while_syncing() {
@@ -584,6 +651,7 @@ mod tests {
 
         handle.update_sync_state(SyncState::Downloading { target_block: 100 });
         assert!(handle.is_syncing());
+        assert_eq!(handle.sync_target(), Some(100));
 
         let peer_id = PeerId::random();
 
@@ -594,4 +662,75 @@ mod tests {
 
         assert!(pool.is_empty());
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_peer_import_limit_resets_after_window() {
+        reth_tracing::init_test_tracing();
+
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+
+        let client = Arc::new(NoopProvider::default());
+        let pool = testing_pool();
+        let config = NetworkConfigBuilder::new(secret_key).build(Arc::clone(&client));
+        let (_handle, network, mut transactions, _) = NetworkManager::new(config)
+            .await
+            .unwrap()
+            .into_builder()
+            .transactions(pool.clone())
+            .split_with_handle();
+
+        tokio::task::spawn(network);
+
+        let peer_id = PeerId::random();
+        let (to_session_tx, _session_rx) = mpsc::channel(1);
+        transactions.peers.insert(
+            peer_id,
+            Peer {
+                transactions: LruCache::new(
+                    NonZeroUsize::new(PEER_TRANSACTION_CACHE_LIMIT).unwrap(),
+                ),
+                request_tx: PeerRequestSender::new(peer_id, to_session_tx),
+                pool_imports: PoolImports::new(Instant::now()),
+            },
+        );
+        transactions.import_limit = PeerImportLimit { max_txs: 2, window: Duration::from_secs(60) };
+
+        let sender_key = H256::random();
+        let sign = |nonce: u64| {
+            let tx = Transaction::Legacy(TxLegacy {
+                nonce,
+                gas_price: 10,
+                gas_limit: 21_000,
+                to: TransactionKind::Call(Default::default()),
+                ..Default::default()
+            });
+            let signature = sign_message(sender_key, tx.signature_hash()).unwrap();
+            TransactionSigned::from_transaction_and_signature(tx, signature)
+        };
+
+        // the peer can only submit two transactions in the window
+        let batch = (0..3).map(sign).collect::<Vec<_>>();
+        transactions.import_transactions(peer_id, batch, TransactionSource::Broadcast);
+        assert_eq!(transactions.pool_imports.len(), 2);
+        assert_eq!(transactions.peers[&peer_id].pool_imports.count, 2);
+
+        // finished imports don't free the quota
+        while !transactions.pool_imports.is_empty() {
+            poll_fn(|cx| {
+                let _ = transactions.poll_unpin(cx);
+                Poll::Ready(())
+            })
+            .await;
+            tokio::task::yield_now().await;
+        }
+        transactions.import_transactions(peer_id, vec![sign(3)], TransactionSource::Broadcast);
+        assert!(transactions.pool_imports.is_empty());
+
+        // the quota is reset once the window ended
+        let peer = transactions.peers.get_mut(&peer_id).unwrap();
+        peer.pool_imports.window_start -= transactions.import_limit.window;
+        transactions.import_transactions(peer_id, vec![sign(4)], TransactionSource::Broadcast);
+        assert_eq!(transactions.pool_imports.len(), 1);
+        assert_eq!(transactions.peers[&peer_id].pool_imports.count, 1);
+    }
 }
@@ -17,10 +17,11 @@ mod eth_filter;
 mod eth_pubsub;
 mod net;
 mod trace;
+mod txpool;
 mod web3;
 
 pub use self::{
     admin::AdminApiServer, debug::DebugApiServer, engine::EngineApiServer, eth::EthApiServer,
     eth_filter::EthFilterApiServer, eth_pubsub::EthPubSubApiServer, net::NetApiServer,
-    trace::TraceApiServer, web3::Web3ApiServer,
+    trace::TraceApiServer, txpool::TxPoolApiServer, web3::Web3ApiServer,
 };
@@ -0,0 +1,83 @@
+//! Configure the authenticated server that serves the `engine_` namespace to the consensus layer.
+
+use jsonrpsee::{
+    core::Error as RpcError,
+    server::{ServerBuilder, ServerHandle},
+};
+use reth_rpc::{AuthLayer, EngineApi, JwtSecret};
+use reth_rpc_api::EngineApiServer;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+
+/// The default port for the authenticated server
+pub const DEFAULT_AUTH_PORT: u16 = 8551;
+
+/// Returns the default address of the authenticated server: [Ipv4Addr::LOCALHOST] and
+/// [DEFAULT_AUTH_PORT]
+pub fn default_auth_addr() -> SocketAddr {
+    SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, DEFAULT_AUTH_PORT))
+}
+
+/// Starts the authenticated http and ws server that serves the [EngineApi].
+///
+/// Every request must carry a token signed with the given secret, see [AuthLayer].
+pub async fn launch_auth(
+    engine_api: EngineApi,
+    socket_addr: SocketAddr,
+    secret: JwtSecret,
+) -> Result<AuthServerHandle, RpcError> {
+    let middleware = tower::ServiceBuilder::new().layer(AuthLayer::new(secret));
+    let server = ServerBuilder::default().set_middleware(middleware).build(socket_addr).await?;
+    let local_addr = server.local_addr()?;
+    let handle = server.start(engine_api.into_rpc())?;
+
+    Ok(AuthServerHandle { local_addr, handle })
+}
+
+/// A handle to the spawned authenticated server.
+///
+/// When stop has been called the server will be stopped.
+#[derive(Debug, Clone)]
+pub struct AuthServerHandle {
+    local_addr: SocketAddr,
+    handle: ServerHandle,
+}
+
+// === impl AuthServerHandle ===
+
+impl AuthServerHandle {
+    /// Returns the [`SocketAddr`] of the server.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Tell the server to stop without waiting for the server to stop.
+    pub fn stop(&self) -> Result<(), RpcError> {
+        self.handle.stop()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::{header::CONTENT_TYPE, Body, Client, Request, StatusCode};
+    use tokio::sync::mpsc::unbounded_channel;
+
+    #[tokio::test]
+    async fn rejects_unauthenticated_requests() {
+        let (engine_tx, _engine_rx) = unbounded_channel();
+        let localhost = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0));
+        let handle =
+            launch_auth(EngineApi::new(engine_tx), localhost, JwtSecret::random()).await.unwrap();
+
+        let req = Request::post(format!("http://{}", handle.local_addr()))
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                r#"{"jsonrpc":"2.0","method":"engine_getPayloadV1","params":["0x0"],"id":1}"#,
+            ))
+            .unwrap();
+        let res = Client::new().request(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+        handle.stop().unwrap();
+    }
+}
@@ -6,116 +6,341 @@
 ))]
 
 //! Configure reth RPC
+//!
+//! This crate contains several builder and config types that allow to configure the selection of
+//! [RethRpcModule] specific to transports (ws, http, ipc).
+//!
+//! The [RpcModuleBuilder] is the main entrypoint for configuring all reth modules. It takes
+//! instances of components required to start the servers, such as provider impls, network and
+//! transaction pool. [RpcModuleBuilder::build] returns a [TransportRpcModules] which contains the
+//! modules that are available via each transport.
+//!
+//! The [RpcServerBuilder] is used to configure the [RpcServer] type which contains all transport
+//! implementations (http server, ws server, ipc server). [RpcServer::start] requires the
+//! [TransportRpcModules] so it can start the servers with the configured modules.
+//!
+//! The `engine_` namespace is served separately by an authenticated server, see [launch_auth].
 
+use http::{HeaderValue, Method};
 use jsonrpsee::{
     core::{server::rpc_module::Methods, Error as RpcError},
-    server::ServerBuilder,
+    server::{Server, ServerHandle},
     RpcModule,
 };
-use reth_ipc::server::{Builder as IpcServerBuilder, Endpoint};
-use reth_network_api::{NetworkInfo, PeersInfo};
-use reth_provider::{BlockProvider, StateProviderFactory};
+use reth_interfaces::{events::ChainEventSubscriptions, sync::SyncStateProvider};
+use reth_ipc::server::{Endpoint, IpcServer, SecurityAttributes};
+use reth_network_api::{NetworkInfo, Peers, PeersInfo};
+use reth_primitives::{ChainSpec, MAINNET};
+use reth_provider::{
+    BlockProvider, HeaderProvider, LogIndexProvider, ReceiptProvider, StateProviderFactory,
+    TransactionsProvider,
+};
+use reth_rpc::{
+    AdminApi, DebugApi, EthApi, EthCallConfig, EthFilter, EthFilterConfig, EthPubSub, NetApi,
+    TraceApi, TxPoolApi, Web3Api,
+};
+use reth_rpc_api::{
+    AdminApiServer, DebugApiServer, EthApiServer, EthFilterApiServer, EthPubSubApiServer,
+    NetApiServer, TraceApiServer, TxPoolApiServer, Web3ApiServer,
+};
+use reth_tasks::TaskExecutor;
 use reth_transaction_pool::TransactionPool;
 use serde::{Deserialize, Serialize, Serializer};
-use std::{fmt, net::SocketAddr};
+use std::{
+    fmt,
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    sync::Arc,
+};
 use strum::{AsRefStr, EnumString, EnumVariantNames};
+use tower::layer::util::{Identity, Stack};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+
+// re-export for convenience
+pub use jsonrpsee::server::ServerBuilder;
+pub use reth_ipc::server::Builder as IpcServerBuilder;
+
+mod auth;
+pub use auth::{default_auth_addr, launch_auth, AuthServerHandle, DEFAULT_AUTH_PORT};
+
+/// The default port for the http server
+pub const DEFAULT_HTTP_RPC_PORT: u16 = 8545;
+
+/// The default port for the ws server
+pub const DEFAULT_WS_RPC_PORT: u16 = 8546;
+
+/// The default IPC endpoint
+#[cfg(windows)]
+pub const DEFAULT_IPC_ENDPOINT: &str = r"\\.\pipe\reth.ipc";
+
+/// The default IPC endpoint
+///
+/// Used if no endpoint is configured. The socket is only accessible by its owner, but it is
+/// recommended to place it in a directory that other users cannot write to.
+#[cfg(not(windows))]
+pub const DEFAULT_IPC_ENDPOINT: &str = "/tmp/reth.ipc";
 
 /// A builder type to configure the RPC module: See [RpcModule]
 ///
 /// This is the main entrypoint for up RPC servers.
 ///
 /// ```
-///  use reth_rpc_builder::{RethRpcModule, RpcModuleBuilder, RpcModuleConfig};
-/// let builder = RpcModuleBuilder::default()
-///     .with_config(RpcModuleConfig::Selection(vec![RethRpcModule::Eth]));
+///  use reth_rpc_builder::{RethRpcModule, RpcModuleConfig, TransportRpcModuleConfig};
+/// let config = TransportRpcModuleConfig::default()
+///     .with_http(RpcModuleConfig::Selection(vec![RethRpcModule::Eth]))
+///     .with_ipc(RpcModuleConfig::All);
 /// ```
 #[derive(Debug)]
-pub struct RpcModuleBuilder<Client, Pool, Network> {
+pub struct RpcModuleBuilder<Client, Pool, Network, Events> {
     /// The Client type to when creating all rpc handlers
     client: Client,
     /// The Pool type to when creating all rpc handlers
     pool: Pool,
     /// The Network type to when creating all rpc handlers
     network: Network,
-    /// What modules to configure
-    config: RpcModuleConfig,
+    /// The Events type to when creating all rpc handlers
+    events: Events,
+    /// The chain the node is running
+    chain_spec: ChainSpec,
+    /// Settings for the `eth_` handlers
+    eth_config: EthConfig,
+    /// Spawns the tasks of the rpc handlers
+    executor: Option<TaskExecutor>,
 }
 
 // === impl RpcBuilder ===
 
-impl<Client, Pool, Network> RpcModuleBuilder<Client, Pool, Network> {
+impl<Client, Pool, Network, Events> RpcModuleBuilder<Client, Pool, Network, Events> {
     /// Create a new instance of the builder
-    pub fn new(client: Client, pool: Pool, network: Network) -> Self {
-        Self { client, pool, network, config: Default::default() }
+    pub fn new(client: Client, pool: Pool, network: Network, events: Events) -> Self {
+        Self {
+            client,
+            pool,
+            network,
+            events,
+            chain_spec: MAINNET.clone(),
+            eth_config: Default::default(),
+            executor: None,
+        }
+    }
+
+    /// Configures the chain the node is running.
+    pub fn with_chain_spec(mut self, chain_spec: ChainSpec) -> Self {
+        self.chain_spec = chain_spec;
+        self
     }
 
-    /// Configures what RPC modules should be installed
-    pub fn with_config(mut self, config: RpcModuleConfig) -> Self {
-        self.config = config;
+    /// Configures the settings of the `eth_` handlers.
+    pub fn with_eth_config(mut self, eth_config: EthConfig) -> Self {
+        self.eth_config = eth_config;
+        self
+    }
+
+    /// Configures the executor that spawns the tasks of the rpc handlers.
+    ///
+    /// Without an executor `eth_subscribe` is not installed, since every subscription is served by
+    /// its own task.
+    pub fn with_executor(mut self, executor: TaskExecutor) -> Self {
+        self.executor = Some(executor);
         self
     }
 
     /// Configure the client instance.
-    pub fn with_client<C>(self, client: C) -> RpcModuleBuilder<C, Pool, Network>
+    pub fn with_client<C>(self, client: C) -> RpcModuleBuilder<C, Pool, Network, Events>
     where
         C: BlockProvider + StateProviderFactory + 'static,
     {
-        let Self { pool, config, network, .. } = self;
-        RpcModuleBuilder { client, config, network, pool }
+        let Self { pool, network, events, chain_spec, eth_config, executor, .. } = self;
+        RpcModuleBuilder { client, pool, network, events, chain_spec, eth_config, executor }
     }
 
     /// Configure the transaction pool instance.
-    pub fn with_pool<P>(self, pool: P) -> RpcModuleBuilder<Client, P, Network>
+    pub fn with_pool<P>(self, pool: P) -> RpcModuleBuilder<Client, P, Network, Events>
     where
         P: TransactionPool + 'static,
     {
-        let Self { client, config, network, .. } = self;
-        RpcModuleBuilder { client, config, network, pool }
+        let Self { client, network, events, chain_spec, eth_config, executor, .. } = self;
+        RpcModuleBuilder { client, pool, network, events, chain_spec, eth_config, executor }
     }
 
     /// Configure the network instance.
-    pub fn with_network<N>(self, network: N) -> RpcModuleBuilder<Client, Pool, N>
+    pub fn with_network<N>(self, network: N) -> RpcModuleBuilder<Client, Pool, N, Events>
     where
         N: NetworkInfo + PeersInfo + 'static,
     {
-        let Self { client, config, pool, .. } = self;
-        RpcModuleBuilder { client, config, network, pool }
+        let Self { client, pool, events, chain_spec, eth_config, executor, .. } = self;
+        RpcModuleBuilder { client, pool, network, events, chain_spec, eth_config, executor }
+    }
+
+    /// Configure the source of the canonical chain events.
+    pub fn with_events<E>(self, events: E) -> RpcModuleBuilder<Client, Pool, Network, E>
+    where
+        E: ChainEventSubscriptions + 'static,
+    {
+        let Self { client, pool, network, chain_spec, eth_config, executor, .. } = self;
+        RpcModuleBuilder { client, pool, network, events, chain_spec, eth_config, executor }
     }
 }
 
-impl<Client, Pool, Network> RpcModuleBuilder<Client, Pool, Network>
+impl<Client, Pool, Network, Events> RpcModuleBuilder<Client, Pool, Network, Events>
 where
-    Client: BlockProvider + StateProviderFactory + 'static,
-    Pool: TransactionPool + 'static,
-    Network: NetworkInfo + PeersInfo + 'static,
+    Client: BlockProvider
+        + HeaderProvider
+        + StateProviderFactory
+        + TransactionsProvider
+        + ReceiptProvider
+        + LogIndexProvider
+        + 'static,
+    Pool: TransactionPool + Clone + 'static,
+    Network: NetworkInfo + Peers + SyncStateProvider + Clone + 'static,
+    Events: ChainEventSubscriptions + 'static,
 {
-    /// Configures the [RpcModule] which can be used to start the server(s).
+    /// Configures the [RpcModule]s of all transports, which can be used to start the server(s).
+    ///
+    /// The handlers are created once and shared by all transports.
     ///
     /// See also [RpcServer::start]
-    pub fn build(self) -> RpcModule<()> {
-        let Self { client: _, pool: _, network: _, config: _ } = self;
-        let _io = RpcModule::new(());
+    pub fn build(self, module_config: TransportRpcModuleConfig) -> TransportRpcModules<()> {
+        let TransportRpcModuleConfig { http, ws, ipc } = module_config;
+        let registry = RethModuleRegistry::new(self);
 
-        todo!("merge all handlers")
+        TransportRpcModules {
+            http: http.map(|config| registry.module_for(&config)),
+            ws: ws.map(|config| registry.module_for(&config)),
+            ipc: ipc.map(|config| registry.module_for(&config)),
+        }
     }
 }
 
-impl Default for RpcModuleBuilder<(), (), ()> {
+impl Default for RpcModuleBuilder<(), (), (), ()> {
     fn default() -> Self {
-        RpcModuleBuilder::new((), (), ())
+        RpcModuleBuilder::new((), (), (), ())
+    }
+}
+
+/// Settings for the `eth_` handlers.
+#[derive(Debug, Clone, Default)]
+pub struct EthConfig {
+    /// Settings for `eth_call` and the methods that execute calls.
+    pub call: EthCallConfig,
+    /// Settings for the filter methods.
+    pub filter: EthFilterConfig,
+}
+
+/// Holds the handlers of all [RethRpcModule]s.
+struct RethModuleRegistry<Client, Pool, Network, Events> {
+    network: Network,
+    pool: Pool,
+    eth_api: EthApi<Pool, Client, Network>,
+    eth_filter: EthFilter<Pool, Client>,
+    /// Only available if an executor is configured.
+    eth_pubsub: Option<EthPubSub<Pool, Client, Events, Network>>,
+}
+
+// === impl RethModuleRegistry ===
+
+impl<Client, Pool, Network, Events> RethModuleRegistry<Client, Pool, Network, Events>
+where
+    Client: BlockProvider
+        + HeaderProvider
+        + StateProviderFactory
+        + TransactionsProvider
+        + ReceiptProvider
+        + LogIndexProvider
+        + 'static,
+    Pool: TransactionPool + Clone + 'static,
+    Network: NetworkInfo + Peers + SyncStateProvider + Clone + 'static,
+    Events: ChainEventSubscriptions + 'static,
+{
+    /// Creates the handlers shared by all transports.
+    fn new(builder: RpcModuleBuilder<Client, Pool, Network, Events>) -> Self {
+        let RpcModuleBuilder { client, pool, network, events, chain_spec, eth_config, executor } =
+            builder;
+        let client = Arc::new(client);
+
+        let eth_api = EthApi::new(
+            Arc::clone(&client),
+            pool.clone(),
+            network.clone(),
+            chain_spec,
+            eth_config.call,
+        );
+        let eth_filter = EthFilter::new(Arc::clone(&client), pool.clone(), eth_config.filter);
+        let eth_pubsub = executor.map(|executor| {
+            EthPubSub::new(client, pool.clone(), events, network.clone(), executor)
+        });
+
+        Self { network, pool, eth_api, eth_filter, eth_pubsub }
+    }
+
+    /// Merges the methods of all modules selected by the config.
+    fn module_for(&self, config: &RpcModuleConfig) -> RpcModule<()> {
+        let mut module = RpcModule::new(());
+        for reth_module in config.to_selection() {
+            module.merge(self.methods(reth_module)).expect("modules have no conflicting methods");
+        }
+        module
+    }
+
+    /// Returns the methods of the given module.
+    fn methods(&self, reth_module: RethRpcModule) -> Methods {
+        match reth_module {
+            RethRpcModule::Admin => AdminApi::new(self.network.clone()).into_rpc().into(),
+            RethRpcModule::Debug => DebugApi {}.into_rpc().into(),
+            RethRpcModule::Eth => {
+                let mut module = self.eth_api.clone().into_rpc();
+                module
+                    .merge(self.eth_filter.clone().into_rpc())
+                    .expect("eth filter methods do not conflict");
+                if let Some(pubsub) = &self.eth_pubsub {
+                    module.merge(pubsub.clone().into_rpc()).expect("eth pubsub does not conflict");
+                }
+                module.into()
+            }
+            RethRpcModule::Net => {
+                NetApi::new(self.network.clone(), self.eth_api.clone()).into_rpc().into()
+            }
+            RethRpcModule::Trace => TraceApi::default().into_rpc().into(),
+            RethRpcModule::Txpool => TxPoolApi::new(self.pool.clone()).into_rpc().into(),
+            RethRpcModule::Web3 => Web3Api::new(self.network.clone()).into_rpc().into(),
+        }
     }
 }
 
 /// Describes the modules that should be installed
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
 pub enum RpcModuleConfig {
     /// Use all available modules.
     #[default]
     All,
+    /// The modules that are commonly exposed: `eth`, `net` and `web3`.
+    Standard,
     /// Only use the configured modules.
     Selection(Vec<RethRpcModule>),
 }
 
+// === impl RpcModuleConfig ===
+
+impl RpcModuleConfig {
+    /// Returns the selected modules, without duplicates.
+    pub fn to_selection(&self) -> Vec<RethRpcModule> {
+        match self {
+            RpcModuleConfig::All => RethRpcModule::all_modules().to_vec(),
+            RpcModuleConfig::Standard => {
+                vec![RethRpcModule::Eth, RethRpcModule::Net, RethRpcModule::Web3]
+            }
+            RpcModuleConfig::Selection(modules) => {
+                let mut selection = Vec::with_capacity(modules.len());
+                for module in modules {
+                    if !selection.contains(module) {
+                        selection.push(*module);
+                    }
+                }
+                selection
+            }
+        }
+    }
+}
+
 /// Represents RPC modules that are supported by reth
 #[derive(
     Debug, Clone, Copy, Eq, PartialEq, AsRefStr, EnumVariantNames, EnumString, Deserialize,
@@ -133,10 +358,29 @@ pub enum RethRpcModule {
     Net,
     /// `trace_` module
     Trace,
+    /// `txpool_` module
+    Txpool,
     /// `web3_` module
     Web3,
 }
 
+// === impl RethRpcModule ===
+
+impl RethRpcModule {
+    /// Returns all variants of the enum
+    pub const fn all_modules() -> &'static [RethRpcModule] {
+        &[
+            RethRpcModule::Admin,
+            RethRpcModule::Debug,
+            RethRpcModule::Eth,
+            RethRpcModule::Net,
+            RethRpcModule::Trace,
+            RethRpcModule::Txpool,
+            RethRpcModule::Web3,
+        ]
+    }
+}
+
 impl fmt::Display for RethRpcModule {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.pad(self.as_ref())
@@ -152,6 +396,57 @@ impl Serialize for RethRpcModule {
     }
 }
 
+/// Holds the modules that should be installed per transport.
+///
+/// A transport without a config is not served any modules.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct TransportRpcModuleConfig {
+    /// http module configuration
+    http: Option<RpcModuleConfig>,
+    /// ws module configuration
+    ws: Option<RpcModuleConfig>,
+    /// ipc module configuration
+    ipc: Option<RpcModuleConfig>,
+}
+
+// === impl TransportRpcModuleConfig ===
+
+impl TransportRpcModuleConfig {
+    /// Sets the [RpcModuleConfig] for the http transport.
+    pub fn with_http(mut self, http: RpcModuleConfig) -> Self {
+        self.http = Some(http);
+        self
+    }
+
+    /// Sets the [RpcModuleConfig] for the ws transport.
+    pub fn with_ws(mut self, ws: RpcModuleConfig) -> Self {
+        self.ws = Some(ws);
+        self
+    }
+
+    /// Sets the [RpcModuleConfig] for the ipc transport.
+    pub fn with_ipc(mut self, ipc: RpcModuleConfig) -> Self {
+        self.ipc = Some(ipc);
+        self
+    }
+
+    /// Returns true if no transport is configured
+    pub fn is_empty(&self) -> bool {
+        self.http.is_none() && self.ws.is_none() && self.ipc.is_none()
+    }
+}
+
+/// Holds installed modules per transport type.
+#[derive(Debug, Default)]
+pub struct TransportRpcModules<Context> {
+    /// rpcs module for http
+    http: Option<RpcModule<Context>>,
+    /// rpcs module for ws
+    ws: Option<RpcModule<Context>>,
+    /// rpcs module for ipc
+    ipc: Option<RpcModule<Context>>,
+}
+
 /// A builder type for configuring and launching the servers that will handle RPC requests.
 ///
 /// Supported server transports are:
@@ -159,46 +454,205 @@ impl Serialize for RethRpcModule {
 ///    - ws
 ///    - ipc
 ///
-/// Http and WS share the same settings: [`ServerBuilder`].
+/// Http and WS are served by separate [`ServerBuilder`]s, so they can be bound to different
+/// addresses and serve different modules.
 ///
-/// Once the [RpcModule] is built via [RpcModuleBuilder] the servers can be started, See also
+/// Once the [RpcModule]s are built via [RpcModuleBuilder] the servers can be started, See also
 /// [ServerBuilder::build] and [Server::start](jsonrpsee::server::Server::start).
+#[derive(Default)]
 pub struct RpcServerBuilder {
-    /// Configs for JSON-RPC Http and WS server
-    pub http_ws_server_config: Option<ServerBuilder>,
-    /// Address where to bind the http and ws server to
-    pub http_ws_addr: Option<SocketAddr>,
+    /// Configs for JSON-RPC Http
+    http_server_config: Option<ServerBuilder>,
+    /// Allowed CORS Domains for http
+    http_cors_domains: Option<String>,
+    /// Address where to bind the http server to
+    http_addr: Option<SocketAddr>,
+    /// Configs for WS server
+    ws_server_config: Option<ServerBuilder>,
+    /// Address where to bind the ws server to
+    ws_addr: Option<SocketAddr>,
     /// Configs for JSON-RPC IPC server
-    pub ipc_server_config: Option<IpcServerBuilder>,
+    ipc_server_config: Option<IpcServerBuilder>,
     /// The Endpoint where to launch the ipc server
-    pub ipc_server_path: Option<Endpoint>,
+    ipc_endpoint: Option<String>,
 }
 
 /// === impl RpcServerBuilder ===
 
 impl RpcServerBuilder {
+    /// Configures the http server
+    pub fn with_http(mut self, config: ServerBuilder) -> Self {
+        self.http_server_config = Some(config.http_only());
+        self
+    }
+
+    /// Configure the cors domains for http
+    ///
+    /// Either `*` or a comma separated list of origins.
+    pub fn with_cors(mut self, cors_domain: String) -> Self {
+        self.http_cors_domains = Some(cors_domain);
+        self
+    }
+
+    /// Configures the ws server
+    pub fn with_ws(mut self, config: ServerBuilder) -> Self {
+        self.ws_server_config = Some(config.ws_only());
+        self
+    }
+
+    /// Configures the [SocketAddr] of the http server
+    ///
+    /// Default is [Ipv4Addr::LOCALHOST] and [DEFAULT_HTTP_RPC_PORT]
+    pub fn with_http_address(mut self, addr: SocketAddr) -> Self {
+        self.http_addr = Some(addr);
+        self
+    }
+
+    /// Configures the [SocketAddr] of the ws server
+    ///
+    /// Default is [Ipv4Addr::LOCALHOST] and [DEFAULT_WS_RPC_PORT]
+    pub fn with_ws_address(mut self, addr: SocketAddr) -> Self {
+        self.ws_addr = Some(addr);
+        self
+    }
+
+    /// Configures the ipc server
+    pub fn with_ipc(mut self, config: IpcServerBuilder) -> Self {
+        self.ipc_server_config = Some(config);
+        self
+    }
+
+    /// Configures the endpoint of the ipc server
+    ///
+    /// Default is [DEFAULT_IPC_ENDPOINT]. On unix, the socket is created with `0o600` permissions,
+    /// so only its owner can connect.
+    pub fn with_ipc_endpoint(mut self, path: impl Into<String>) -> Self {
+        self.ipc_endpoint = Some(path.into());
+        self
+    }
+
     /// Finalize the configuration of the server(s).
     ///
-    /// This consumes the builder and returns a server.
+    /// This consumes the builder and returns a server, the http and ws servers are already bound
+    /// to their addresses.
     ///
     /// Note: The server ist not started and does nothing unless polled, See also
+    /// [RpcServer::start]
     pub async fn build(self) -> Result<RpcServer, RpcError> {
-        todo!()
+        let mut server = RpcServer::default();
+
+        if let Some(builder) = self.http_server_config {
+            let socket_addr = self.http_addr.unwrap_or_else(|| {
+                SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, DEFAULT_HTTP_RPC_PORT))
+            });
+            let (http, addr) =
+                WsHttpServerKind::build(builder, socket_addr, self.http_cors_domains).await?;
+            server.http_local_addr = Some(addr);
+            server.http = Some(http);
+        }
+
+        if let Some(builder) = self.ws_server_config {
+            let socket_addr = self.ws_addr.unwrap_or_else(|| {
+                SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, DEFAULT_WS_RPC_PORT))
+            });
+            let (ws, addr) = WsHttpServerKind::build(builder, socket_addr, None).await?;
+            server.ws_local_addr = Some(addr);
+            server.ws = Some(ws);
+        }
+
+        if let Some(builder) = self.ipc_server_config {
+            let endpoint = self.ipc_endpoint.unwrap_or_else(|| DEFAULT_IPC_ENDPOINT.to_string());
+            let security = SecurityAttributes::empty()
+                .set_mode(0o600)
+                .map_err(|err| RpcError::Transport(err.into()))?;
+            let mut ipc_endpoint = Endpoint::new(endpoint.clone());
+            ipc_endpoint.set_security_attributes(security);
+            server.ipc = Some(builder.build_with_endpoint(ipc_endpoint)?);
+            server.ipc_endpoint = Some(endpoint);
+        }
+
+        Ok(server)
     }
 }
 
 /// Container type for the configured RPC server(s): http,ws,ipc
-pub struct RpcServer {}
+#[derive(Default)]
+pub struct RpcServer {
+    /// The address of the http server
+    http_local_addr: Option<SocketAddr>,
+    /// http server
+    http: Option<WsHttpServerKind>,
+    /// The address of the ws server
+    ws_local_addr: Option<SocketAddr>,
+    /// ws server
+    ws: Option<WsHttpServerKind>,
+    /// The endpoint of the ipc server
+    ipc_endpoint: Option<String>,
+    /// ipc server
+    ipc: Option<IpcServer>,
+}
 
 // === impl RpcServer ===
 
 impl RpcServer {
+    /// Returns the [`SocketAddr`] of the http server if started.
+    pub fn http_local_addr(&self) -> Option<SocketAddr> {
+        self.http_local_addr
+    }
+
+    /// Returns the [`SocketAddr`] of the ws server if started.
+    pub fn ws_local_addr(&self) -> Option<SocketAddr> {
+        self.ws_local_addr
+    }
+
+    /// Returns the endpoint of the ipc server if started.
+    pub fn ipc_endpoint(&self) -> Option<&str> {
+        self.ipc_endpoint.as_deref()
+    }
+
     /// Starts the configured server by spawning the servers on the tokio runtime.
     ///
+    /// A server without a module in the given [TransportRpcModules] serves no methods.
+    ///
     /// This returns an [RpcServerHandle] that's connected to the server task(s) until the server is
     /// stopped or the [RpcServerHandle] is dropped.
-    pub fn start(self, _methods: impl Into<Methods>) -> Result<RpcServerHandle, RpcError> {
-        todo!()
+    pub async fn start(
+        self,
+        modules: TransportRpcModules<()>,
+    ) -> Result<RpcServerHandle, RpcError> {
+        let TransportRpcModules { http, ws, ipc } = modules;
+        let mut handle = RpcServerHandle {
+            http_local_addr: self.http_local_addr,
+            ws_local_addr: self.ws_local_addr,
+            ipc_endpoint: self.ipc_endpoint,
+            http: None,
+            ws: None,
+            ipc: None,
+        };
+
+        if let Some(server) = self.http {
+            handle.http = Some(server.start(http.unwrap_or_else(|| RpcModule::new(())))?);
+        }
+
+        if let Some(server) = self.ws {
+            handle.ws = Some(server.start(ws.unwrap_or_else(|| RpcModule::new(())))?);
+        }
+
+        if let Some(server) = self.ipc {
+            handle.ipc = Some(server.start(ipc.unwrap_or_else(|| RpcModule::new(()))).await?);
+        }
+
+        Ok(handle)
+    }
+}
+
+impl fmt::Debug for RpcServer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RpcServer")
+            .field("http", &self.http_local_addr)
+            .field("ws", &self.ws_local_addr)
+            .field("ipc", &self.ipc_endpoint)
+            .finish()
     }
 }
 
@@ -206,17 +660,113 @@ impl RpcServer {
 ///
 /// When stop has been called the server will be stopped.
 #[derive(Debug, Clone)]
-pub struct RpcServerHandle {}
+pub struct RpcServerHandle {
+    /// The address of the http server
+    http_local_addr: Option<SocketAddr>,
+    /// The address of the ws server
+    ws_local_addr: Option<SocketAddr>,
+    /// The endpoint of the ipc server
+    ipc_endpoint: Option<String>,
+    http: Option<ServerHandle>,
+    ws: Option<ServerHandle>,
+    ipc: Option<ServerHandle>,
+}
 
 // === impl RpcServerHandle ===
 
 impl RpcServerHandle {
+    /// Returns the [`SocketAddr`] of the http server if started.
+    pub fn http_local_addr(&self) -> Option<SocketAddr> {
+        self.http_local_addr
+    }
+
+    /// Returns the [`SocketAddr`] of the ws server if started.
+    pub fn ws_local_addr(&self) -> Option<SocketAddr> {
+        self.ws_local_addr
+    }
+
+    /// Returns the endpoint of the ipc server if started.
+    pub fn ipc_endpoint(&self) -> Option<&str> {
+        self.ipc_endpoint.as_deref()
+    }
+
     /// Tell the server to stop without waiting for the server to stop.
     pub fn stop(&self) -> Result<(), RpcError> {
-        todo!()
+        for handle in [&self.http, &self.ws, &self.ipc].into_iter().flatten() {
+            handle.stop()?;
+        }
+        Ok(())
     }
 }
 
+/// The http or ws server, depending on whether cors is configured.
+enum WsHttpServerKind {
+    /// Server without additional middleware
+    Plain(Server),
+    /// Server that answers cors requests
+    WithCors(Server<Stack<CorsLayer, Identity>>),
+}
+
+// === impl WsHttpServerKind ===
+
+impl WsHttpServerKind {
+    /// Binds the server to the given address.
+    ///
+    /// Returns the server and its local address.
+    async fn build(
+        builder: ServerBuilder,
+        socket_addr: SocketAddr,
+        cors_domains: Option<String>,
+    ) -> Result<(Self, SocketAddr), RpcError> {
+        if let Some(cors_domains) = cors_domains {
+            let cors = create_cors_layer(&cors_domains)?;
+            let middleware = tower::ServiceBuilder::new().layer(cors);
+            let server = builder.set_middleware(middleware).build(socket_addr).await?;
+            let local_addr = server.local_addr()?;
+            Ok((WsHttpServerKind::WithCors(server), local_addr))
+        } else {
+            let server = builder.build(socket_addr).await?;
+            let local_addr = server.local_addr()?;
+            Ok((WsHttpServerKind::Plain(server), local_addr))
+        }
+    }
+
+    /// Starts serving the given module.
+    fn start(self, module: RpcModule<()>) -> Result<ServerHandle, RpcError> {
+        match self {
+            WsHttpServerKind::Plain(server) => server.start(module),
+            WsHttpServerKind::WithCors(server) => server.start(module),
+        }
+    }
+}
+
+/// Creates the [CorsLayer] for the given domains.
+///
+/// `*` allows any origin, otherwise the domains are a comma separated list of allowed origins.
+fn create_cors_layer(cors_domains: &str) -> Result<CorsLayer, RpcError> {
+    let origin = match cors_domains.trim() {
+        "*" => AllowOrigin::from(Any),
+        domains => {
+            let origins = domains
+                .split(',')
+                .map(str::trim)
+                .filter(|domain| !domain.is_empty())
+                .map(|domain| {
+                    domain
+                        .parse::<HeaderValue>()
+                        .map_err(|_| RpcError::Custom(format!("invalid cors domain: {domain}")))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            AllowOrigin::list(origins)
+        }
+    };
+
+    Ok(CorsLayer::new()
+        .allow_methods([Method::GET, Method::POST])
+        .allow_origin(origin)
+        .allow_headers(Any))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -239,7 +789,49 @@ mod tests {
                 "eth" =>  RethRpcModule::Eth,
                 "net" =>  RethRpcModule::Net,
                 "trace" =>  RethRpcModule::Trace,
+                "txpool" =>  RethRpcModule::Txpool,
                 "web3" =>  RethRpcModule::Web3,
             );
     }
+
+    #[test]
+    fn test_module_selection() {
+        assert_eq!(RpcModuleConfig::All.to_selection(), RethRpcModule::all_modules());
+        assert_eq!(
+            RpcModuleConfig::Selection(vec![
+                RethRpcModule::Eth,
+                RethRpcModule::Net,
+                RethRpcModule::Eth
+            ])
+            .to_selection(),
+            vec![RethRpcModule::Eth, RethRpcModule::Net]
+        );
+    }
+
+    #[test]
+    fn test_cors_domains() {
+        assert!(create_cors_layer("*").is_ok());
+        assert!(create_cors_layer("http://localhost:3000, https://example.com").is_ok());
+        assert!(create_cors_layer("http://localhost:3000,\u{7f}").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_start_stop_servers() {
+        let localhost = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0));
+        let server = RpcServerBuilder::default()
+            .with_http(ServerBuilder::default())
+            .with_http_address(localhost)
+            .with_cors("*".to_string())
+            .with_ws(ServerBuilder::default())
+            .with_ws_address(localhost)
+            .build()
+            .await
+            .unwrap();
+        assert!(server.http_local_addr().is_some());
+        assert!(server.ws_local_addr().is_some());
+        assert!(server.ipc_endpoint().is_none());
+
+        let handle = server.start(TransportRpcModules::default()).await.unwrap();
+        handle.stop().unwrap();
+    }
 }
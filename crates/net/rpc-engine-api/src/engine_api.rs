@@ -1,34 +1,72 @@
 use crate::{EngineApiError, EngineApiMessage, EngineApiResult};
-use futures::StreamExt;
+use futures::{stream::FuturesUnordered, StreamExt};
 use reth_executor::{
+    block_tree::{ExecutedBlock, SharedBlockTree},
     executor,
+    post_state::{PostState, PostStateProvider},
     revm_wrap::{State, SubState},
 };
-use reth_interfaces::consensus::ForkchoiceState;
+use reth_interfaces::{consensus::ForkchoiceState, executor::Error as ExecutorError};
+use reth_payload_builder::{PayloadBuilderAttributes, PayloadBuilderHandle};
 use reth_primitives::{
     proofs::{self, EMPTY_LIST_HASH},
-    rpc::{BlockId, H256 as EthersH256},
-    ChainSpec, Header, SealedBlock, TransactionSigned, H64, U256,
+    BlockNumber, ChainSpec, Hardfork, Header, SealedBlock, TransactionSigned, H256, H64, U256,
 };
-use reth_provider::{BlockProvider, HeaderProvider, StateProvider};
+use reth_provider::{BlockProvider, HeaderProvider, StateProvider, StateProviderFactory};
 use reth_rlp::Decodable;
 use reth_rpc_types::engine::{
     ExecutionPayload, ForkchoiceUpdated, PayloadAttributes, PayloadStatus, PayloadStatusEnum,
     TransitionConfiguration,
 };
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     future::Future,
     pin::Pin,
     sync::Arc,
-    task::{ready, Context, Poll},
+    task::{Context, Poll},
 };
-use tokio::sync::oneshot;
+use tokio::sync::{mpsc::UnboundedReceiver, oneshot, watch};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
 /// The Engine API response sender
 pub type EngineApiSender<Ok> = oneshot::Sender<EngineApiResult<Ok>>;
 
+/// A request that is resolved asynchronously.
+type PendingRequest = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// The maximum number of invalid blocks that are remembered.
+const MAX_INVALID_HEADERS: usize = 512;
+
+/// Blocks that failed validation, mapped to their latest valid ancestor.
+///
+/// The oldest entry is evicted once [MAX_INVALID_HEADERS] blocks are tracked.
+#[derive(Debug, Default)]
+struct InvalidHeaders {
+    latest_valid_hashes: HashMap<H256, H256>,
+    /// The tracked blocks, the oldest first.
+    order: VecDeque<H256>,
+}
+
+impl InvalidHeaders {
+    /// Returns the latest valid ancestor of the block if the block is invalid.
+    fn get(&self, hash: &H256) -> Option<H256> {
+        self.latest_valid_hashes.get(hash).copied()
+    }
+
+    /// Marks the block as invalid.
+    fn insert(&mut self, hash: H256, latest_valid_hash: H256) {
+        if self.latest_valid_hashes.insert(hash, latest_valid_hash).is_some() {
+            return
+        }
+        self.order.push_back(hash);
+        if self.order.len() > MAX_INVALID_HEADERS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.latest_valid_hashes.remove(&oldest);
+            }
+        }
+    }
+}
+
 /// The Engine API implementation that grants the Consensus layer access to data and
 /// functions in the Execution layer that are crucial for the consensus process.
 #[must_use = "EngineApi does nothing unless polled."]
@@ -37,19 +75,55 @@ pub struct EngineApi<Client> {
     /// Consensus configuration
     chain_spec: ChainSpec,
     rx: UnboundedReceiverStream<EngineApiMessage>,
-    // TODO: Placeholder for storing future blocks. Make cache bounded.
-    // Use [lru](https://crates.io/crates/lru) crate
-    local_store: HashMap<H64, ExecutionPayload>,
-    // remote_store: HashMap<H64, ExecutionPayload>,
+    /// Forwards the forkchoice state received from the consensus layer, which determines the
+    /// chain tip to sync to.
+    forkchoice_state_tx: watch::Sender<ForkchoiceState>,
+    /// Builds the payloads requested via `engine_forkchoiceUpdated`.
+    payload_builder: PayloadBuilderHandle,
+    /// `engine_getPayload` requests that wait for the payload builder.
+    pending_requests: FuturesUnordered<PendingRequest>,
+    /// Blocks that were rejected by `engine_newPayload`.
+    invalid_headers: InvalidHeaders,
+    /// Blocks that were executed by `engine_newPayload`, but are not canonical yet, and blocks
+    /// that can't be executed yet.
+    block_tree: SharedBlockTree,
 }
 
-impl<Client: HeaderProvider + BlockProvider + StateProvider> EngineApi<Client> {
+impl<Client> EngineApi<Client> {
+    /// Creates a new instance that serves the messages of the given receiver.
+    ///
+    /// Every forkchoice state received via `engine_forkchoiceUpdated` is forwarded to
+    /// `forkchoice_state_tx`, and payloads are built with the given `payload_builder`. The blocks
+    /// received via `engine_newPayload` are executed into the `block_tree`.
+    pub fn new(
+        client: Arc<Client>,
+        chain_spec: ChainSpec,
+        rx: UnboundedReceiver<EngineApiMessage>,
+        forkchoice_state_tx: watch::Sender<ForkchoiceState>,
+        payload_builder: PayloadBuilderHandle,
+        block_tree: SharedBlockTree,
+    ) -> Self {
+        Self {
+            client,
+            chain_spec,
+            rx: UnboundedReceiverStream::new(rx),
+            forkchoice_state_tx,
+            payload_builder,
+            pending_requests: Default::default(),
+            invalid_headers: Default::default(),
+            block_tree,
+        }
+    }
+}
+
+impl<Client: HeaderProvider + BlockProvider + StateProviderFactory> EngineApi<Client> {
     fn on_message(&mut self, msg: EngineApiMessage) {
         match msg {
             EngineApiMessage::GetPayload(payload_id, tx) => {
-                // NOTE: Will always result in `PayloadUnknown` since we don't support block
-                // building for now.
-                let _ = tx.send(self.get_payload(payload_id).ok_or(EngineApiError::PayloadUnknown));
+                let payload = self.get_payload(payload_id);
+                self.pending_requests.push(Box::pin(async move {
+                    let _ = tx.send(payload.await);
+                }));
             }
             EngineApiMessage::NewPayload(payload, tx) => {
                 let _ = tx.send(self.new_payload(payload));
@@ -119,8 +193,20 @@ impl<Client: HeaderProvider + BlockProvider + StateProvider> EngineApi<Client> {
 
     /// Called to retrieve the latest state of the network, validate new blocks, and maintain
     /// consistency between the Consensus and Execution layers.
-    pub fn get_payload(&self, payload_id: H64) -> Option<ExecutionPayload> {
-        self.local_store.get(&payload_id).cloned()
+    ///
+    /// Resolves to the best version of the payload built so far.
+    pub fn get_payload(
+        &self,
+        payload_id: H64,
+    ) -> impl Future<Output = EngineApiResult<ExecutionPayload>> + Send + 'static {
+        let payload_builder = self.payload_builder.clone();
+        async move {
+            let payload = payload_builder
+                .best_payload(payload_id)
+                .await
+                .ok_or(EngineApiError::PayloadUnknown)?;
+            Ok(payload.block.clone().into())
+        }
     }
 
     /// When the Consensus layer receives a new block via the consensus gossip protocol,
@@ -129,6 +215,9 @@ impl<Client: HeaderProvider + BlockProvider + StateProvider> EngineApi<Client> {
     /// state in the block header, then passes validation data back to Consensus layer, that
     /// adds the block to the head of its own blockchain and attests to it. The block is then
     /// broadcasted over the consensus p2p network in the form of a "Beacon block".
+    ///
+    /// Blocks are executed on top of the canonical chain or on top of blocks that were executed
+    /// before. Blocks whose parent can't be executed are buffered until the parent is executed.
     pub fn new_payload(&mut self, payload: ExecutionPayload) -> EngineApiResult<PayloadStatus> {
         let block = match self.try_construct_block(payload) {
             Ok(b) => b,
@@ -138,16 +227,71 @@ impl<Client: HeaderProvider + BlockProvider + StateProvider> EngineApi<Client> {
                 }))
             }
         };
+        let block_hash = block.hash();
+
+        // The block already exists in our database or was executed before
+        if self.client.is_known(&block_hash)? || self.block_tree.lock().is_executed(&block_hash) {
+            return Ok(PayloadStatus::new(PayloadStatusEnum::Valid, block_hash))
+        }
+
+        let status = self.insert_block(block)?;
+
+        // Execute the buffered descendants of the block
+        if status.status == PayloadStatusEnum::Valid {
+            let mut parents = vec![block_hash];
+            while let Some(parent) = parents.pop() {
+                let children = self.block_tree.lock().take_buffered_children(&parent);
+                for child in children {
+                    let hash = child.hash();
+                    if self.insert_block(child)?.status == PayloadStatusEnum::Valid {
+                        parents.push(hash);
+                    }
+                }
+            }
+        }
+
+        Ok(status)
+    }
 
-        // The block already exists in our database
-        if self.client.is_known(&block.hash())? {
-            return Ok(PayloadStatus::new(PayloadStatusEnum::Valid, block.hash()))
+    /// Validates and executes the block, and inserts it into the block tree if it is valid.
+    ///
+    /// The block is buffered if its parent can't be executed.
+    fn insert_block(&mut self, block: SealedBlock) -> EngineApiResult<PayloadStatus> {
+        // The parent was rejected before, so the block is invalid as well
+        if let Some(latest_valid_hash) = self.invalid_headers.get(&block.parent_hash) {
+            self.invalid_headers.insert(block.hash(), latest_valid_hash);
+            return Ok(PayloadStatus::new(
+                PayloadStatusEnum::Invalid {
+                    validation_error: EngineApiError::InvalidAncestor(block.parent_hash)
+                        .to_string(),
+                },
+                latest_valid_hash,
+            ))
         }
 
-        let Some(parent) = self.client.block(BlockId::Hash(EthersH256(block.parent_hash.0)))? else {
-             // TODO: cache block for storing later
-             return Ok(PayloadStatus::from_status(PayloadStatusEnum::Syncing))
+        // The state changes of the executed ancestors apply on top of the fork block, which has
+        // to be fully processed by the pipeline.
+        let (fork_hash, pending_state, executed_parent) = {
+            let block_tree = self.block_tree.lock();
+            let (fork_hash, pending_state) = block_tree.pending_state(block.parent_hash);
+            let executed_parent = block_tree
+                .executed_block(&block.parent_hash)
+                .map(|parent| parent.block.header.clone().unseal());
+            (fork_hash, pending_state, executed_parent)
         };
+        let fork = match self.client.header(&fork_hash)? {
+            Some(fork)
+                if fork.number <= self.client.chain_info()?.best_number &&
+                    self.is_canonical(fork_hash, fork.number)? =>
+            {
+                fork
+            }
+            _ => {
+                self.block_tree.lock().buffer_block(block);
+                return Ok(PayloadStatus::from_status(PayloadStatusEnum::Syncing))
+            }
+        };
+        let parent = executed_parent.unwrap_or(fork);
 
         if let Some(parent_td) = self.client.header_td(&block.parent_hash)? {
             if Some(parent_td) <= self.chain_spec.paris_status().terminal_total_difficulty() {
@@ -167,38 +311,89 @@ impl<Client: HeaderProvider + BlockProvider + StateProvider> EngineApi<Client> {
             }))
         }
 
-        let (header, body, _) = block.split();
-        let transactions = body
-            .into_iter()
+        let transactions = block
+            .body
+            .iter()
             .map(|tx| {
-                let tx_hash = tx.hash;
-                tx.into_ecrecovered().ok_or(EngineApiError::PayloadSignerRecovery { hash: tx_hash })
+                tx.clone()
+                    .into_ecrecovered()
+                    .ok_or(EngineApiError::PayloadSignerRecovery { hash: tx.hash })
             })
             .collect::<Result<Vec<_>, EngineApiError>>()?;
-        let mut state_provider = SubState::new(State::new(&*self.client));
-        match executor::execute_and_verify_receipt(
-            &header,
-            &transactions,
-            &[],
-            &self.chain_spec,
-            &mut state_provider,
-        ) {
-            Ok(_) => Ok(PayloadStatus::new(PayloadStatusEnum::Valid, header.hash())),
-            Err(err) => Ok(PayloadStatus::new(
-                PayloadStatusEnum::Invalid { validation_error: err.to_string() },
-                header.parent_hash, // The parent hash is already in our database hence it is valid
-            )),
+        let executed = {
+            let state = PostStateProvider::new(
+                self.client.history_by_block_hash(fork_hash)?,
+                pending_state,
+            );
+            let mut state_provider = SubState::new(State::new(state));
+            match executor::execute_and_verify_receipt(
+                &block.header,
+                &transactions,
+                &[],
+                &self.chain_spec,
+                &mut state_provider,
+            ) {
+                Ok(result) => {
+                    let post_state = PostState::new(&block.header, &result, &state_provider);
+                    let state_root =
+                        state_provider.db.state().state_root_with_changes(post_state.accounts())?;
+                    if state_root == block.state_root {
+                        Ok((result, post_state))
+                    } else {
+                        Err(EngineApiError::PayloadStateRoot {
+                            execution: state_root,
+                            consensus: block.state_root,
+                        }
+                        .to_string())
+                    }
+                }
+                // failing to read the state doesn't make the block invalid
+                Err(err @ (ExecutorError::ExecutionFatalError | ExecutorError::ProviderError)) => {
+                    return Err(reth_interfaces::Error::from(err).into())
+                }
+                Err(err) => Err(err.to_string()),
+            }
+        };
+
+        match executed {
+            Ok((result, post_state)) => {
+                let hash = block.hash();
+                self.block_tree.lock().insert_executed(ExecutedBlock { block, result, post_state });
+                Ok(PayloadStatus::new(PayloadStatusEnum::Valid, hash))
+            }
+            Err(validation_error) => {
+                // The parent was executed before, hence it is valid
+                self.invalid_headers.insert(block.hash(), block.parent_hash);
+                Ok(PayloadStatus::new(
+                    PayloadStatusEnum::Invalid { validation_error },
+                    block.parent_hash,
+                ))
+            }
         }
     }
 
+    /// Returns `true` if the block is part of the canonical chain.
+    fn is_canonical(&self, hash: H256, number: BlockNumber) -> EngineApiResult<bool> {
+        Ok(self.client.block_hash(U256::from(number))? == Some(hash))
+    }
+
     /// Called to resolve chain forks and ensure that the Execution layer is working with the latest
     /// valid chain.
+    ///
+    /// The forkchoice state is forwarded to the pipeline, which syncs to the head and unwinds the
+    /// local chain if the head is on a different branch. The head is only `VALID` once the
+    /// pipeline made it the tip of the canonical chain, until then the status is `SYNCING`.
+    ///
+    /// If payload attributes are provided, a payload is built on top of the head block. No payload
+    /// is built while the head is not the tip of the canonical chain. Attributes that require
+    /// withdrawals are rejected, since blocks with withdrawals can't be built yet.
     pub fn fork_choice_updated(
         &self,
         fork_choice_state: ForkchoiceState,
-        _payload_attributes: Option<PayloadAttributes>,
+        payload_attributes: Option<PayloadAttributes>,
     ) -> EngineApiResult<ForkchoiceUpdated> {
-        let ForkchoiceState { head_block_hash, finalized_block_hash, .. } = fork_choice_state;
+        let ForkchoiceState { head_block_hash, finalized_block_hash, safe_block_hash } =
+            fork_choice_state;
 
         if head_block_hash.is_zero() {
             return Ok(ForkchoiceUpdated::from_status(PayloadStatusEnum::Invalid {
@@ -206,21 +401,63 @@ impl<Client: HeaderProvider + BlockProvider + StateProvider> EngineApi<Client> {
             }))
         }
 
-        // Block is not known, nothing to do.
-        if !self.client.is_known(&head_block_hash)? {
-            return Ok(ForkchoiceUpdated::from_status(PayloadStatusEnum::Syncing))
+        // The head was rejected by `engine_newPayload` before
+        if let Some(latest_valid_hash) = self.invalid_headers.get(&head_block_hash) {
+            return Ok(ForkchoiceUpdated::from_status(PayloadStatusEnum::Invalid {
+                validation_error: EngineApiError::InvalidAncestor(head_block_hash).to_string(),
+            })
+            .with_latest_valid_hash(latest_valid_hash))
         }
 
-        // The finalized block hash is not known, we are still syncing
-        if !finalized_block_hash.is_zero() && !self.client.is_known(&finalized_block_hash)? {
+        // the pipeline syncs towards the head even if it is not known yet
+        let _ = self.forkchoice_state_tx.send(fork_choice_state);
+
+        let Some(head) = self.client.header(&head_block_hash)? else {
+            return Ok(ForkchoiceUpdated::from_status(PayloadStatusEnum::Syncing))
+        };
+
+        // The head is known, but was not processed by all stages or is on a different branch
+        if head.number > self.client.chain_info()?.best_number ||
+            !self.is_canonical(head_block_hash, head.number)?
+        {
             return Ok(ForkchoiceUpdated::from_status(PayloadStatusEnum::Syncing))
         }
 
-        // TODO: update tip
+        // The safe and finalized blocks have to be ancestors of the head
+        for hash in [finalized_block_hash, safe_block_hash] {
+            if hash.is_zero() {
+                continue
+            }
+            match self.client.header(&hash)? {
+                Some(header)
+                    if header.number <= head.number &&
+                        self.is_canonical(hash, header.number)? => {}
+                _ => return Err(EngineApiError::InvalidForkchoiceState),
+            }
+        }
 
-        let chain_info = self.client.chain_info()?;
-        Ok(ForkchoiceUpdated::from_status(PayloadStatusEnum::Valid)
-            .with_latest_valid_hash(chain_info.best_hash))
+        let mut updated = ForkchoiceUpdated::from_status(PayloadStatusEnum::Valid)
+            .with_latest_valid_hash(head_block_hash);
+
+        if let Some(attributes) = payload_attributes {
+            let timestamp = attributes.timestamp.as_u64();
+            if timestamp <= head.timestamp {
+                return Err(EngineApiError::PayloadAttributesTimestamp {
+                    invalid: timestamp,
+                    head: head.timestamp,
+                })
+            }
+            // blocks after Shanghai have to process withdrawals
+            if attributes.withdrawal.is_some() ||
+                self.chain_spec.fork_active(Hardfork::Shanghai, head.number + 1)
+            {
+                return Err(EngineApiError::PayloadAttributesWithdrawals)
+            }
+            let attributes = PayloadBuilderAttributes::new(head_block_hash, attributes);
+            updated = updated.with_payload_id(self.payload_builder.new_payload(attributes));
+        }
+
+        Ok(updated)
     }
 
     /// Called to verify network configuration parameters and ensure that Consensus and Execution
@@ -277,21 +514,27 @@ impl<Client: HeaderProvider + BlockProvider + StateProvider> EngineApi<Client> {
 
 impl<Client> Future for EngineApi<Client>
 where
-    Client: HeaderProvider + BlockProvider + StateProvider + Unpin,
+    Client: HeaderProvider + BlockProvider + StateProviderFactory + Unpin,
 {
     type Output = ();
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.get_mut();
         loop {
-            match ready!(this.rx.poll_next_unpin(cx)) {
-                Some(msg) => this.on_message(msg),
-                None => {
+            match this.rx.poll_next_unpin(cx) {
+                Poll::Ready(Some(msg)) => this.on_message(msg),
+                Poll::Ready(None) => {
                     // channel closed
                     return Poll::Ready(())
                 }
+                Poll::Pending => break,
             }
         }
+
+        // resolve the requests that wait for the payload builder
+        while let Poll::Ready(Some(())) = this.pending_requests.poll_next_unpin(cx) {}
+
+        Poll::Pending
     }
 }
 
@@ -300,15 +543,28 @@ mod tests {
     use super::*;
     use assert_matches::assert_matches;
     use reth_interfaces::test_utils::generators::random_block;
+    use reth_payload_builder::PayloadBuilderService;
     use reth_primitives::H256;
     use reth_provider::test_utils::MockEthProvider;
+    use reth_transaction_pool::test_utils::testing_pool;
     use tokio::sync::mpsc::unbounded_channel;
 
+    /// Returns a handle to a payload builder that is not running.
+    fn test_payload_builder() -> PayloadBuilderHandle {
+        let (_service, handle) = PayloadBuilderService::new(
+            Arc::new(MockEthProvider::default()),
+            testing_pool(),
+            Arc::new(reth_primitives::MAINNET.clone()),
+            Default::default(),
+        );
+        handle
+    }
+
     mod new_payload {
         use super::*;
         use bytes::{Bytes, BytesMut};
         use reth_interfaces::test_utils::generators::random_header;
-        use reth_primitives::{Block, MAINNET};
+        use reth_primitives::{Block, ChainSpecBuilder, Receipt, SealedHeader, MAINNET};
         use reth_rlp::DecodeError;
 
         fn transform_block<F: FnOnce(Block) -> Block>(src: SealedBlock, f: F) -> SealedBlock {
@@ -332,8 +588,12 @@ mod tests {
             let engine = EngineApi {
                 client: Arc::new(MockEthProvider::default()),
                 chain_spec: MAINNET.clone(),
-                local_store: Default::default(),
+                payload_builder: test_payload_builder(),
+                pending_requests: Default::default(),
                 rx: UnboundedReceiverStream::new(rx),
+                forkchoice_state_tx: watch::channel(ForkchoiceState::default()).0,
+                invalid_headers: Default::default(),
+                block_tree: Default::default(),
             };
 
             let block = random_block(100, Some(H256::random()), Some(3), Some(0));
@@ -421,8 +681,12 @@ mod tests {
             let engine = EngineApi {
                 client: client.clone(),
                 chain_spec: MAINNET.clone(),
-                local_store: Default::default(),
+                payload_builder: test_payload_builder(),
+                pending_requests: Default::default(),
                 rx: UnboundedReceiverStream::new(rx),
+                forkchoice_state_tx: watch::channel(ForkchoiceState::default()).0,
+                invalid_headers: Default::default(),
+                block_tree: Default::default(),
             };
 
             tokio::spawn(engine);
@@ -449,8 +713,12 @@ mod tests {
             let engine = EngineApi {
                 client: Arc::new(MockEthProvider::default()),
                 chain_spec: MAINNET.clone(),
-                local_store: Default::default(),
+                payload_builder: test_payload_builder(),
+                pending_requests: Default::default(),
                 rx: UnboundedReceiverStream::new(rx),
+                forkchoice_state_tx: watch::channel(ForkchoiceState::default()).0,
+                invalid_headers: Default::default(),
+                block_tree: Default::default(),
             };
 
             tokio::spawn(engine);
@@ -474,8 +742,12 @@ mod tests {
             let engine = EngineApi {
                 client: client.clone(),
                 chain_spec: chain_spec.clone(),
-                local_store: Default::default(),
+                payload_builder: test_payload_builder(),
+                pending_requests: Default::default(),
                 rx: UnboundedReceiverStream::new(rx),
+                forkchoice_state_tx: watch::channel(ForkchoiceState::default()).0,
+                invalid_headers: Default::default(),
+                block_tree: Default::default(),
             };
 
             tokio::spawn(engine);
@@ -509,8 +781,12 @@ mod tests {
             let engine = EngineApi {
                 client: client.clone(),
                 chain_spec: chain_spec.clone(),
-                local_store: Default::default(),
+                payload_builder: test_payload_builder(),
+                pending_requests: Default::default(),
                 rx: UnboundedReceiverStream::new(rx),
+                forkchoice_state_tx: watch::channel(ForkchoiceState::default()).0,
+                invalid_headers: Default::default(),
+                block_tree: Default::default(),
             };
 
             tokio::spawn(engine);
@@ -547,24 +823,373 @@ mod tests {
             assert_eq!(result.unwrap().unwrap(), expected_result);
         }
 
-        // TODO: add execution tests
+        /// Returns an empty block on top of the parent that does not change the state.
+        fn empty_block(parent: &SealedHeader, state_root: H256, timestamp: u64) -> SealedBlock {
+            let header = Header {
+                parent_hash: parent.hash(),
+                number: parent.number + 1,
+                timestamp,
+                gas_limit: 30_000_000,
+                base_fee_per_gas: Some(7),
+                state_root,
+                ommers_hash: EMPTY_LIST_HASH,
+                transactions_root: proofs::calculate_transaction_root(
+                    Vec::<TransactionSigned>::new().iter(),
+                ),
+                receipts_root: proofs::calculate_receipt_root(Vec::<Receipt>::new().iter()),
+                ..Default::default()
+            };
+            SealedBlock { header: header.seal(), body: Vec::new(), ommers: Vec::new() }
+        }
+
+        #[tokio::test]
+        async fn executes_sidechains() {
+            let chain_spec = ChainSpecBuilder::mainnet().paris_activated().build();
+            let client = Arc::new(MockEthProvider::default());
+            let block_tree = SharedBlockTree::default();
+            let mut engine = EngineApi::new(
+                client.clone(),
+                chain_spec.clone(),
+                unbounded_channel().1,
+                watch::channel(ForkchoiceState::default()).0,
+                test_payload_builder(),
+                block_tree.clone(),
+            );
+
+            let parent = Header {
+                number: 1,
+                timestamp: 1,
+                difficulty: chain_spec.paris_status().terminal_total_difficulty().unwrap() +
+                    U256::from(1),
+                ..Default::default()
+            }
+            .seal();
+            client.add_block(
+                parent.hash(),
+                Block { header: parent.clone().unseal(), ..Default::default() },
+            );
+            let state_root = client.state_root_with_changes(&Default::default()).unwrap();
+
+            let block = empty_block(&parent, state_root, 2);
+            let sibling = empty_block(&parent, state_root, 3);
+            let child = empty_block(&block.header, state_root, 4);
+
+            // the parent of the child is not known yet
+            assert_eq!(
+                engine.new_payload(child.clone().into()).unwrap(),
+                PayloadStatus::from_status(PayloadStatusEnum::Syncing)
+            );
+
+            // the child is executed on top of the block
+            assert_eq!(
+                engine.new_payload(block.clone().into()).unwrap(),
+                PayloadStatus::new(PayloadStatusEnum::Valid, block.hash())
+            );
+            assert!(block_tree.lock().is_executed(&child.hash()));
+
+            // the sibling forks off the same parent
+            assert_eq!(
+                engine.new_payload(sibling.clone().into()).unwrap(),
+                PayloadStatus::new(PayloadStatusEnum::Valid, sibling.hash())
+            );
+            assert_eq!(block_tree.lock().pending_state(child.hash()).0, parent.hash());
+
+            // blocks with an invalid state root are rejected
+            let invalid = empty_block(&sibling.header, H256::random(), 4);
+            assert_matches!(
+                engine.new_payload(invalid.into()).unwrap(),
+                PayloadStatus { status: PayloadStatusEnum::Invalid { .. }, latest_valid_hash }
+                    if latest_valid_hash == Some(sibling.hash())
+            );
+        }
+    }
+
+    // non exhaustive tests for engine_forkchoiceUpdated
+    mod fork_choice_updated {
+        use super::*;
+        use reth_primitives::{Address, Block, ChainSpecBuilder, MAINNET};
+        use reth_rpc_types::engine::Withdrawal;
+
+        #[tokio::test]
+        async fn forwards_forkchoice_state() {
+            let (tx, rx) = unbounded_channel();
+            let (forkchoice_state_tx, mut forkchoice_state_rx) =
+                watch::channel(ForkchoiceState::default());
+            let engine = EngineApi::new(
+                Arc::new(MockEthProvider::default()),
+                MAINNET.clone(),
+                rx,
+                forkchoice_state_tx,
+                test_payload_builder(),
+                Default::default(),
+            );
+
+            tokio::spawn(engine);
+
+            let state = ForkchoiceState {
+                head_block_hash: H256::random(),
+                safe_block_hash: H256::random(),
+                finalized_block_hash: H256::random(),
+            };
+            let attributes = PayloadAttributes {
+                timestamp: 1u64.into(),
+                prev_randao: H256::random(),
+                suggested_fee_recipient: Address::random(),
+                withdrawal: None,
+            };
+            let (result_tx, result_rx) = oneshot::channel();
+            tx.send(EngineApiMessage::ForkchoiceUpdated(
+                state.clone(),
+                Some(attributes),
+                result_tx,
+            ))
+            .expect("failed to send engine msg");
+
+            // the head is not known yet, so no payload is built on top of it
+            assert_matches!(
+                result_rx.await,
+                Ok(Ok(updated)) if updated.payload_status.status == PayloadStatusEnum::Syncing &&
+                    updated.payload_id.is_none()
+            );
+            assert!(forkchoice_state_rx.has_changed().unwrap());
+            assert_eq!(*forkchoice_state_rx.borrow_and_update(), state);
+        }
+
+        #[tokio::test]
+        async fn canonical_head() {
+            let client = Arc::new(MockEthProvider::default());
+            let parent = Header { number: 1, ..Default::default() };
+            let parent_hash = parent.hash_slow();
+            let head = Header { number: 2, parent_hash, ..Default::default() };
+            let head_hash = head.hash_slow();
+            client.add_block(parent_hash, Block { header: parent, ..Default::default() });
+            client.add_block(head_hash, Block { header: head, ..Default::default() });
+
+            let (tx, rx) = unbounded_channel();
+            let engine = EngineApi::new(
+                client,
+                MAINNET.clone(),
+                rx,
+                watch::channel(ForkchoiceState::default()).0,
+                test_payload_builder(),
+                Default::default(),
+            );
+
+            tokio::spawn(engine);
+
+            let state = ForkchoiceState {
+                head_block_hash: head_hash,
+                safe_block_hash: parent_hash,
+                finalized_block_hash: parent_hash,
+            };
+            let (result_tx, result_rx) = oneshot::channel();
+            tx.send(EngineApiMessage::ForkchoiceUpdated(state, None, result_tx))
+                .expect("failed to send engine msg");
+
+            let expected = ForkchoiceUpdated::from_status(PayloadStatusEnum::Valid)
+                .with_latest_valid_hash(head_hash);
+            assert_eq!(result_rx.await.unwrap().unwrap(), expected);
+        }
+
+        #[tokio::test]
+        async fn finalized_not_ancestor() {
+            let client = Arc::new(MockEthProvider::default());
+            let head = Header { number: 1, ..Default::default() };
+            let head_hash = head.hash_slow();
+            client.add_block(head_hash, Block { header: head, ..Default::default() });
+
+            let (tx, rx) = unbounded_channel();
+            let engine = EngineApi::new(
+                client,
+                MAINNET.clone(),
+                rx,
+                watch::channel(ForkchoiceState::default()).0,
+                test_payload_builder(),
+                Default::default(),
+            );
+
+            tokio::spawn(engine);
+
+            let state = ForkchoiceState {
+                head_block_hash: head_hash,
+                finalized_block_hash: H256::random(),
+                ..Default::default()
+            };
+            let (result_tx, result_rx) = oneshot::channel();
+            tx.send(EngineApiMessage::ForkchoiceUpdated(state, None, result_tx))
+                .expect("failed to send engine msg");
+
+            assert_matches!(result_rx.await, Ok(Err(EngineApiError::InvalidForkchoiceState)));
+        }
+
+        #[tokio::test]
+        async fn rejects_withdrawals() {
+            let client = Arc::new(MockEthProvider::default());
+            let head = Header { number: 1, timestamp: 1, ..Default::default() };
+            let head_hash = head.hash_slow();
+            let child =
+                Header { number: 2, parent_hash: head_hash, timestamp: 2, ..Default::default() };
+            let child_hash = child.hash_slow();
+            client.add_block(head_hash, Block { header: head, ..Default::default() });
+            client.add_block(child_hash, Block { header: child, ..Default::default() });
+
+            let (tx, rx) = unbounded_channel();
+            let engine = EngineApi::new(
+                client,
+                ChainSpecBuilder::mainnet().with_fork(Hardfork::Shanghai, 3).build(),
+                rx,
+                watch::channel(ForkchoiceState::default()).0,
+                test_payload_builder(),
+                Default::default(),
+            );
+
+            tokio::spawn(engine);
+
+            let state = ForkchoiceState { head_block_hash: head_hash, ..Default::default() };
+            let attributes = PayloadAttributes {
+                timestamp: 2u64.into(),
+                prev_randao: H256::random(),
+                suggested_fee_recipient: Address::random(),
+                withdrawal: None,
+            };
+
+            // attributes with withdrawals
+            let (result_tx, result_rx) = oneshot::channel();
+            let with_withdrawals =
+                PayloadAttributes { withdrawal: Some(Withdrawal::default()), ..attributes.clone() };
+            tx.send(EngineApiMessage::ForkchoiceUpdated(
+                state.clone(),
+                Some(with_withdrawals),
+                result_tx,
+            ))
+            .expect("failed to send engine msg");
+            assert_matches!(result_rx.await, Ok(Err(EngineApiError::PayloadAttributesWithdrawals)));
+
+            // the payload is built before Shanghai
+            let (result_tx, result_rx) = oneshot::channel();
+            tx.send(EngineApiMessage::ForkchoiceUpdated(
+                state,
+                Some(attributes.clone()),
+                result_tx,
+            ))
+            .expect("failed to send engine msg");
+            assert_matches!(result_rx.await, Ok(Ok(updated)) if updated.payload_id.is_some());
+
+            // but not once Shanghai is active
+            let state = ForkchoiceState { head_block_hash: child_hash, ..Default::default() };
+            let attributes = PayloadAttributes { timestamp: 3u64.into(), ..attributes };
+            let (result_tx, result_rx) = oneshot::channel();
+            tx.send(EngineApiMessage::ForkchoiceUpdated(state, Some(attributes), result_tx))
+                .expect("failed to send engine msg");
+            assert_matches!(result_rx.await, Ok(Err(EngineApiError::PayloadAttributesWithdrawals)));
+        }
+
+        #[tokio::test]
+        async fn invalid_head() {
+            let (tx, rx) = unbounded_channel();
+            let (forkchoice_state_tx, forkchoice_state_rx) =
+                watch::channel(ForkchoiceState::default());
+            let mut engine = EngineApi::new(
+                Arc::new(MockEthProvider::default()),
+                MAINNET.clone(),
+                rx,
+                forkchoice_state_tx,
+                test_payload_builder(),
+                Default::default(),
+            );
+
+            let head_hash = H256::random();
+            let latest_valid_hash = H256::random();
+            engine.invalid_headers.insert(head_hash, latest_valid_hash);
+
+            tokio::spawn(engine);
+
+            let state = ForkchoiceState { head_block_hash: head_hash, ..Default::default() };
+            let (result_tx, result_rx) = oneshot::channel();
+            tx.send(EngineApiMessage::ForkchoiceUpdated(state, None, result_tx))
+                .expect("failed to send engine msg");
+
+            let expected = ForkchoiceUpdated::from_status(PayloadStatusEnum::Invalid {
+                validation_error: EngineApiError::InvalidAncestor(head_hash).to_string(),
+            })
+            .with_latest_valid_hash(latest_valid_hash);
+            assert_eq!(result_rx.await.unwrap().unwrap(), expected);
+            // invalid heads are not forwarded to the pipeline
+            assert!(!forkchoice_state_rx.has_changed().unwrap());
+        }
     }
 
     // non exhaustive tests for engine_getPayload
-    // TODO: amend when block building is implemented
     mod get_payload {
-        use reth_primitives::MAINNET;
+        use reth_primitives::{proofs::EMPTY_ROOT, Address, Block, MAINNET};
 
         use super::*;
 
+        #[tokio::test]
+        async fn returns_built_payload() {
+            let client = Arc::new(MockEthProvider::default());
+            let head = Header {
+                number: 1,
+                gas_limit: 30_000_000,
+                timestamp: 1,
+                state_root: EMPTY_ROOT,
+                ..Default::default()
+            };
+            let head_hash = head.hash_slow();
+            client.add_block(head_hash, Block { header: head, ..Default::default() });
+
+            let (payload_builder, payload_builder_handle) = PayloadBuilderService::new(
+                client.clone(),
+                testing_pool(),
+                Arc::new(MAINNET.clone()),
+                Default::default(),
+            );
+            tokio::spawn(payload_builder);
+
+            let (tx, rx) = unbounded_channel();
+            let engine = EngineApi::new(
+                client,
+                MAINNET.clone(),
+                rx,
+                watch::channel(ForkchoiceState::default()).0,
+                payload_builder_handle,
+                Default::default(),
+            );
+            tokio::spawn(engine);
+
+            let state = ForkchoiceState { head_block_hash: head_hash, ..Default::default() };
+            let attributes = PayloadAttributes {
+                timestamp: 2u64.into(),
+                prev_randao: H256::random(),
+                suggested_fee_recipient: Address::random(),
+                withdrawal: None,
+            };
+            let (result_tx, result_rx) = oneshot::channel();
+            tx.send(EngineApiMessage::ForkchoiceUpdated(state, Some(attributes), result_tx))
+                .expect("failed to send engine msg");
+            let updated = result_rx.await.unwrap().unwrap();
+            let payload_id = updated.payload_id.expect("payload job started");
+
+            let (result_tx, result_rx) = oneshot::channel();
+            tx.send(EngineApiMessage::GetPayload(payload_id, result_tx))
+                .expect("failed to send engine msg");
+            let payload = result_rx.await.unwrap().unwrap();
+            assert_eq!(payload.parent_hash, head_hash);
+            assert_eq!(payload.block_number.as_u64(), 2);
+        }
+
         #[tokio::test]
         async fn payload_unknown() {
             let (tx, rx) = unbounded_channel();
             let engine = EngineApi {
                 client: Arc::new(MockEthProvider::default()),
                 chain_spec: MAINNET.clone(),
-                local_store: Default::default(),
+                payload_builder: test_payload_builder(),
+                pending_requests: Default::default(),
                 rx: UnboundedReceiverStream::new(rx),
+                forkchoice_state_tx: watch::channel(ForkchoiceState::default()).0,
+                invalid_headers: Default::default(),
+                block_tree: Default::default(),
             };
 
             tokio::spawn(engine);
@@ -592,8 +1217,12 @@ mod tests {
             let engine = EngineApi {
                 client: Arc::new(MockEthProvider::default()),
                 chain_spec: chain_spec.clone(),
-                local_store: Default::default(),
+                payload_builder: test_payload_builder(),
+                pending_requests: Default::default(),
                 rx: UnboundedReceiverStream::new(rx),
+                forkchoice_state_tx: watch::channel(ForkchoiceState::default()).0,
+                invalid_headers: Default::default(),
+                block_tree: Default::default(),
             };
 
             tokio::spawn(engine);
@@ -630,8 +1259,12 @@ mod tests {
             let engine = EngineApi {
                 client: client.clone(),
                 chain_spec: chain_spec.clone(),
-                local_store: Default::default(),
+                payload_builder: test_payload_builder(),
+                pending_requests: Default::default(),
                 rx: UnboundedReceiverStream::new(rx),
+                forkchoice_state_tx: watch::channel(ForkchoiceState::default()).0,
+                invalid_headers: Default::default(),
+                block_tree: Default::default(),
             };
 
             tokio::spawn(engine);
@@ -693,8 +1326,12 @@ mod tests {
             let engine = EngineApi {
                 client: client.clone(),
                 chain_spec: chain_spec.clone(),
-                local_store: Default::default(),
+                payload_builder: test_payload_builder(),
+                pending_requests: Default::default(),
                 rx: UnboundedReceiverStream::new(rx),
+                forkchoice_state_tx: watch::channel(ForkchoiceState::default()).0,
+                invalid_headers: Default::default(),
+                block_tree: Default::default(),
             };
 
             tokio::spawn(engine);
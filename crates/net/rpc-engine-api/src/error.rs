@@ -21,6 +21,14 @@ pub enum EngineApiError {
         /// The block hash provided with the payload.
         consensus: H256,
     },
+    /// The state root of the payload does not match the state after its execution.
+    #[error("Invalid payload state root. Execution: {execution:?}. Consensus: {consensus:?}")]
+    PayloadStateRoot {
+        /// The state root computed from the execution of the payload.
+        execution: H256,
+        /// The state root provided with the payload.
+        consensus: H256,
+    },
     /// Invalid payload block hash.
     #[error("Invalid payload timestamp: {invalid}. Latest: {latest}")]
     PayloadTimestamp {
@@ -41,6 +49,17 @@ pub enum EngineApiError {
     /// Unknown payload requested.
     #[error("Unknown payload")]
     PayloadUnknown,
+    /// The timestamp of the payload attributes is not greater than the timestamp of the head.
+    #[error("Invalid payload attributes timestamp: {invalid}. Head: {head}")]
+    PayloadAttributesTimestamp {
+        /// The payload attributes timestamp.
+        invalid: u64,
+        /// The timestamp of the head block.
+        head: u64,
+    },
+    /// The payload attributes require a block with withdrawals, which can't be built yet.
+    #[error("Payload attributes with withdrawals are not supported")]
+    PayloadAttributesWithdrawals,
     /// Terminal total difficulty mismatch during transition configuration exchange.
     #[error(
         "Invalid transition terminal total difficulty. Execution: {execution}. Consensus: {consensus}"
@@ -64,6 +83,12 @@ pub enum EngineApiError {
     /// Forkchoice zero hash head received.
     #[error("Received zero hash as forkchoice head")]
     ForkchoiceEmptyHead,
+    /// The safe or finalized block of the forkchoice state is not an ancestor of the head.
+    #[error("Invalid forkchoice state")]
+    InvalidForkchoiceState,
+    /// The block or one of its ancestors failed validation before.
+    #[error("Block {0:?} or one of its ancestors is invalid")]
+    InvalidAncestor(H256),
     /// Chain spec merge terminal total difficulty is not set
     #[error("The merge terminal total difficulty is not known")]
     UnknownMergeTerminalTotalDifficulty,
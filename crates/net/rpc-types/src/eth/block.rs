@@ -1,5 +1,9 @@
 use crate::Transaction;
-use reth_primitives::{Address, Bloom, Bytes, H256, H64, U256};
+use reth_primitives::{
+    Address, Block as PrimitiveBlock, Bloom, Bytes, Header as PrimitiveHeader, SealedHeader,
+    TransactionSignedEcRecovered, H256, H64, U256,
+};
+use reth_rlp::Encodable;
 use serde::{ser::Error, Deserialize, Serialize, Serializer};
 use std::{collections::BTreeMap, ops::Deref};
 
@@ -13,6 +17,33 @@ pub enum BlockTransactions {
     Full(Vec<Transaction>),
 }
 
+/// Determines how the `transactions` field of [Block] should be filled.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum BlockTransactionsKind {
+    /// Only include hashes: [BlockTransactions::Hashes]
+    Hashes,
+    /// Include full transaction objects: [BlockTransactions::Full]
+    Full,
+}
+
+impl From<bool> for BlockTransactionsKind {
+    fn from(is_full: bool) -> Self {
+        if is_full {
+            BlockTransactionsKind::Full
+        } else {
+            BlockTransactionsKind::Hashes
+        }
+    }
+}
+
+/// Error that can occur when converting other types to blocks
+#[derive(Debug, thiserror::Error)]
+pub enum BlockError {
+    /// A transaction failed sender recovery
+    #[error("transaction failed sender recovery")]
+    InvalidSignature,
+}
+
 /// Block representation
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -33,6 +64,72 @@ pub struct Block {
     pub base_fee_per_gas: Option<U256>,
 }
 
+impl Block {
+    /// Converts the given primitive block into a [Block] response with the given
+    /// [BlockTransactionsKind].
+    ///
+    /// Recovers the senders of the transactions if full transactions are requested.
+    pub fn from_block(
+        block: PrimitiveBlock,
+        total_difficulty: U256,
+        kind: BlockTransactionsKind,
+    ) -> Result<Self, BlockError> {
+        let block_hash = block.header.hash_slow();
+        let block_number = block.header.number;
+        let base_fee = block.header.base_fee_per_gas;
+        let block_length = block.length();
+
+        let transactions = match kind {
+            BlockTransactionsKind::Hashes => {
+                BlockTransactions::Hashes(block.body.iter().map(|tx| tx.hash()).collect())
+            }
+            BlockTransactionsKind::Full => {
+                let mut transactions = Vec::with_capacity(block.body.len());
+                for (idx, tx) in block.body.into_iter().enumerate() {
+                    let signer = tx.recover_signer().ok_or(BlockError::InvalidSignature)?;
+                    transactions.push(Transaction::from_recovered_with_block_context(
+                        TransactionSignedEcRecovered::from_signed_transaction(tx, signer),
+                        block_hash,
+                        block_number,
+                        base_fee,
+                        U256::from(idx),
+                    ));
+                }
+                BlockTransactions::Full(transactions)
+            }
+        };
+
+        let uncles = block.ommers.iter().map(|ommer| ommer.hash_slow()).collect();
+        let mut header =
+            Header::from_primitive_with_hash(SealedHeader::new(block.header, block_hash));
+        header.size = Some(U256::from(block_length));
+
+        Ok(Self {
+            header,
+            total_difficulty,
+            uncles,
+            transactions,
+            size: Some(U256::from(block_length)),
+            base_fee_per_gas: base_fee.map(U256::from),
+        })
+    }
+
+    /// Builds the response for an uncle (ommer) header. Uncles are returned without
+    /// transactions, uncles and total difficulty.
+    pub fn uncle_block_from_header(header: PrimitiveHeader) -> Self {
+        let base_fee_per_gas = header.base_fee_per_gas.map(U256::from);
+
+        Self {
+            header: Header::from_primitive_with_hash(header.seal()),
+            total_difficulty: U256::ZERO,
+            uncles: vec![],
+            transactions: BlockTransactions::Hashes(vec![]),
+            size: None,
+            base_fee_per_gas,
+        }
+    }
+}
+
 /// Block header representation.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -76,6 +173,55 @@ pub struct Header {
     pub size: Option<U256>,
 }
 
+impl Header {
+    /// Converts the primitive header into a [Header] response, the hash of the sealed header is
+    /// used as the block hash.
+    ///
+    /// The size is not known for a header alone and is left `None`.
+    pub fn from_primitive_with_hash(primitive_header: SealedHeader) -> Self {
+        let hash = primitive_header.hash();
+        let PrimitiveHeader {
+            parent_hash,
+            ommers_hash,
+            beneficiary,
+            state_root,
+            transactions_root,
+            receipts_root,
+            logs_bloom,
+            difficulty,
+            number,
+            gas_limit,
+            gas_used,
+            timestamp,
+            mix_hash,
+            nonce,
+            base_fee_per_gas: _,
+            extra_data,
+        } = primitive_header.unseal();
+
+        Self {
+            hash: Some(hash),
+            parent_hash,
+            uncles_hash: ommers_hash,
+            author: beneficiary,
+            miner: beneficiary,
+            state_root,
+            transactions_root,
+            receipts_root,
+            number: Some(U256::from(number)),
+            gas_used: U256::from(gas_used),
+            gas_limit: U256::from(gas_limit),
+            extra_data,
+            logs_bloom,
+            timestamp: U256::from(timestamp),
+            difficulty,
+            mix_hash,
+            nonce: Some(H64::from_low_u64_be(nonce)),
+            size: None,
+        }
+    }
+}
+
 /// A Block representation that allows to include additional fields
 pub type RichBlock = Rich<Block>;
 
@@ -92,6 +238,12 @@ pub struct Rich<T> {
     pub extra_info: BTreeMap<String, serde_json::Value>,
 }
 
+impl<T> From<T> for Rich<T> {
+    fn from(inner: T) -> Self {
+        Rich { inner, extra_info: Default::default() }
+    }
+}
+
 impl<T> Deref for Rich<T> {
     type Target = T;
     fn deref(&self) -> &Self::Target {
@@ -9,6 +9,7 @@ mod filter;
 mod index;
 mod log;
 pub mod pubsub;
+mod state;
 mod syncing;
 pub mod trace;
 mod transaction;
@@ -21,6 +22,7 @@ pub use fee::FeeHistory;
 pub use filter::*;
 pub use index::Index;
 pub use log::Log;
+pub use state::*;
 pub use syncing::*;
 pub use transaction::*;
 pub use work::Work;
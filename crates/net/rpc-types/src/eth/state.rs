@@ -0,0 +1,50 @@
+//! bindings for state overrides in eth_call
+
+use reth_primitives::{Address, Bytes, H256, U256, U64};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A set of account overrides
+pub type StateOverride = HashMap<Address, AccountOverride>;
+
+/// Custom account override used in call
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub struct AccountOverride {
+    /// Fake balance to set for the account before executing the call.
+    pub balance: Option<U256>,
+    /// Fake nonce to set for the account before executing the call.
+    pub nonce: Option<U64>,
+    /// Fake EVM bytecode to inject into the account before executing the call.
+    pub code: Option<Bytes>,
+    /// Fake key-value mapping to override all slots in the account storage before executing the
+    /// call.
+    pub state: Option<HashMap<H256, H256>>,
+    /// Fake key-value mapping to override individual slots in the account storage before
+    /// executing the call.
+    pub state_diff: Option<HashMap<H256, H256>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_override() {
+        let s = r#"{
+            "0x0000000000000000000000000000000000000124": {
+                "code": "0x1234",
+                "stateDiff": {
+                    "0x0000000000000000000000000000000000000000000000000000000000000001": "0x0000000000000000000000000000000000000000000000000000000000000002"
+                }
+            }
+        }"#;
+        let state_override: StateOverride = serde_json::from_str(s).unwrap();
+        let acc = state_override
+            .get(&"0x0000000000000000000000000000000000000124".parse().unwrap())
+            .unwrap();
+        assert!(acc.code.is_some());
+        assert!(acc.state.is_none());
+        assert_eq!(acc.state_diff.as_ref().unwrap().len(), 1);
+    }
+}
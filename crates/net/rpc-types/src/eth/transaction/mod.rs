@@ -7,7 +7,10 @@ pub use request::TransactionRequest;
 pub use typed::*;
 
 use reth_primitives::{
-    rpc::transaction::eip2930::AccessListItem, Address, Bytes, H256, H512, U128, U256, U64,
+    create_address,
+    rpc::{self, transaction::eip2930::AccessListItem},
+    Address, Bytes, Transaction as PrimitiveTransaction, TransactionKind,
+    TransactionSignedEcRecovered, TxType, H256, H512, U128, U256, U64,
 };
 use serde::{Deserialize, Serialize};
 
@@ -67,3 +70,90 @@ pub struct Transaction {
     #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
     pub transaction_type: Option<U256>,
 }
+
+impl Transaction {
+    /// Create a new rpc transaction result for a mined transaction, using the given block hash,
+    /// number, base fee and transaction index.
+    pub fn from_recovered_with_block_context(
+        tx: TransactionSignedEcRecovered,
+        block_hash: H256,
+        block_number: u64,
+        base_fee: Option<u64>,
+        tx_index: U256,
+    ) -> Self {
+        let mut tx = Self::fill(tx, base_fee);
+        tx.block_hash = Some(block_hash);
+        tx.block_number = Some(U256::from(block_number));
+        tx.transaction_index = Some(tx_index);
+        tx
+    }
+
+    /// Create a new rpc transaction result for a _pending_ signed transaction, setting block
+    /// environment related fields to `None`.
+    pub fn from_recovered(tx: TransactionSignedEcRecovered) -> Self {
+        Self::fill(tx, None)
+    }
+
+    fn fill(tx: TransactionSignedEcRecovered, base_fee: Option<u64>) -> Self {
+        let signer = tx.signer();
+        let signed_tx = tx.into_signed();
+
+        let (to, creates) = match signed_tx.kind() {
+            TransactionKind::Create => (None, Some(create_address(signer, signed_tx.nonce()))),
+            TransactionKind::Call(to) => (Some(*to), None),
+        };
+
+        let (gas_price, max_fee_per_gas) = match signed_tx.transaction {
+            PrimitiveTransaction::Legacy(_) | PrimitiveTransaction::Eip2930(_) => {
+                (Some(U128::from(signed_tx.max_fee_per_gas())), None)
+            }
+            PrimitiveTransaction::Eip1559(_) => (
+                base_fee.map(|base_fee| U128::from(signed_tx.effective_gas_price(Some(base_fee)))),
+                Some(U128::from(signed_tx.max_fee_per_gas())),
+            ),
+        };
+
+        let chain_id = signed_tx.chain_id().copied();
+        let signature = signed_tx.signature();
+        let (v, transaction_type) = match signed_tx.tx_type() {
+            TxType::Legacy => (U256::from(signature.v(chain_id)), None),
+            tx_type => (U256::from(signature.odd_y_parity as u8), Some(U256::from(tx_type as u8))),
+        };
+
+        let access_list = signed_tx.access_list().map(|list| {
+            list.0
+                .iter()
+                .map(|item| AccessListItem {
+                    address: rpc::H160(item.address.0),
+                    storage_keys: item.storage_keys.iter().map(|key| rpc::H256(key.0)).collect(),
+                })
+                .collect()
+        });
+
+        Self {
+            hash: signed_tx.hash(),
+            nonce: U256::from(signed_tx.nonce()),
+            block_hash: None,
+            block_number: None,
+            transaction_index: None,
+            from: signer,
+            to,
+            value: U256::from(*signed_tx.value()),
+            gas_price,
+            max_fee_per_gas,
+            max_priority_fee_per_gas: signed_tx.max_priority_fee_per_gas().map(U128::from),
+            gas: U256::from(signed_tx.gas_limit()),
+            input: signed_tx.input().clone(),
+            creates,
+            raw: signed_tx.envelope_encoded(),
+            public_key: None,
+            chain_id: chain_id.map(U64::from),
+            standard_v: U256::from(signature.odd_y_parity as u8),
+            v,
+            r: signature.r,
+            s: signature.s,
+            access_list,
+            transaction_type,
+        }
+    }
+}
@@ -11,6 +11,8 @@
 
 mod admin;
 mod eth;
+mod txpool;
 
 pub use admin::*;
 pub use eth::*;
+pub use txpool::*;
@@ -0,0 +1,152 @@
+//! Types for the `txpool` namespace.
+//!
+//! Note: this format is not standardized. Reth follows Geth's format,
+//! see: <https://geth.ethereum.org/docs/interacting-with-geth/rpc/ns-txpool>
+
+use crate::Transaction;
+use reth_primitives::{Address, U256, U64};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+/// Transactions of a single sender, keyed by their nonce in decimal.
+pub type TxpoolNonceMap<T> = BTreeMap<String, T>;
+
+/// The number of pending and queued transactions in the pool, returned by `txpool_status`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxpoolStatus {
+    /// Number of transactions that can be included in the next block.
+    pub pending: U64,
+    /// Number of transactions that can't be included in the next block yet.
+    pub queued: U64,
+}
+
+/// All transactions of the pool grouped by sender and nonce, returned by `txpool_content`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxpoolContent {
+    /// Transactions that can be included in the next block.
+    pub pending: BTreeMap<Address, TxpoolNonceMap<Transaction>>,
+    /// Transactions that can't be included in the next block yet.
+    pub queued: BTreeMap<Address, TxpoolNonceMap<Transaction>>,
+}
+
+/// The transactions of a single sender grouped by nonce, returned by `txpool_contentFrom`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxpoolContentFrom {
+    /// Transactions that can be included in the next block.
+    pub pending: TxpoolNonceMap<Transaction>,
+    /// Transactions that can't be included in the next block yet.
+    pub queued: TxpoolNonceMap<Transaction>,
+}
+
+/// A summary of all transactions of the pool grouped by sender and nonce, returned by
+/// `txpool_inspect`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxpoolInspect {
+    /// Transactions that can be included in the next block.
+    pub pending: BTreeMap<Address, TxpoolNonceMap<TxpoolInspectSummary>>,
+    /// Transactions that can't be included in the next block yet.
+    pub queued: BTreeMap<Address, TxpoolNonceMap<TxpoolInspectSummary>>,
+}
+
+/// A summary of a transaction in the pool.
+///
+/// This is serialized as `<to>: <value> wei + <gas> gas × <gas price> wei`, where `<to>` is
+/// `contract creation` for transactions that create a contract.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TxpoolInspectSummary {
+    /// Recipient of the transaction, `None` for contract creations.
+    pub to: Option<Address>,
+    /// Transferred value in wei.
+    pub value: U256,
+    /// Gas limit of the transaction.
+    pub gas: u64,
+    /// Gas price in wei, which is the max fee per gas for EIP-1559 transactions.
+    pub gas_price: u128,
+}
+
+impl fmt::Display for TxpoolInspectSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.to {
+            Some(to) => write!(f, "{to:?}")?,
+            None => f.write_str("contract creation")?,
+        }
+        write!(f, ": {} wei + {} gas × {} wei", self.value, self.gas, self.gas_price)
+    }
+}
+
+impl FromStr for TxpoolInspectSummary {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid txpool inspect summary: {s}");
+
+        let (to, rest) = s.split_once(": ").ok_or_else(invalid)?;
+        let (value, rest) = rest.split_once(" wei + ").ok_or_else(invalid)?;
+        let (gas, rest) = rest.split_once(" gas × ").ok_or_else(invalid)?;
+        let gas_price = rest.strip_suffix(" wei").ok_or_else(invalid)?;
+
+        let to = match to {
+            "contract creation" => None,
+            to => Some(to.parse().map_err(|_| invalid())?),
+        };
+        Ok(Self {
+            to,
+            value: U256::from_str_radix(value, 10).map_err(|_| invalid())?,
+            gas: gas.parse().map_err(|_| invalid())?,
+            gas_price: gas_price.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl Serialize for TxpoolInspectSummary {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        s.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for TxpoolInspectSummary {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_inspect_summary() {
+        let summary = TxpoolInspectSummary {
+            to: Some(Address::from_low_u64_be(1)),
+            value: U256::from(1_000u64),
+            gas: 21_000,
+            gas_price: 2,
+        };
+        let s = serde_json::to_string(&summary).unwrap();
+        assert_eq!(
+            s,
+            "\"0x0000000000000000000000000000000000000001: 1000 wei + 21000 gas × 2 wei\""
+        );
+        assert_eq!(serde_json::from_str::<TxpoolInspectSummary>(&s).unwrap(), summary);
+
+        let creation = TxpoolInspectSummary { to: None, ..summary };
+        let s = serde_json::to_string(&creation).unwrap();
+        assert_eq!(s, "\"contract creation: 1000 wei + 21000 gas × 2 wei\"");
+        assert_eq!(serde_json::from_str::<TxpoolInspectSummary>(&s).unwrap(), creation);
+    }
+
+    #[test]
+    fn serde_status() {
+        let status = TxpoolStatus { pending: U64::from(10), queued: U64::from(7) };
+        let s = serde_json::to_string(&status).unwrap();
+        assert_eq!(s, r#"{"pending":"0xa","queued":"0x7"}"#);
+        assert_eq!(serde_json::from_str::<TxpoolStatus>(&s).unwrap(), status);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::result::{internal_rpc_err, rpc_err};
+use crate::result::rpc_err;
 use async_trait::async_trait;
 use jsonrpsee::core::{Error, RpcResult as Result};
 use reth_interfaces::consensus::ForkchoiceState;
@@ -26,6 +26,12 @@ impl std::fmt::Debug for EngineApi {
 }
 
 impl EngineApi {
+    /// Creates a new instance that delegates all requests to the consensus engine behind
+    /// `engine_tx`.
+    pub fn new(engine_tx: UnboundedSender<EngineApiMessage>) -> Self {
+        Self { engine_tx }
+    }
+
     async fn delegate_request<T>(
         &self,
         msg: EngineApiMessage,
@@ -35,6 +41,8 @@ impl EngineApi {
         rx.await.map_err(|err| Error::Custom(err.to_string()))?.map_err(|err| {
             let code = match err {
                 EngineApiError::PayloadUnknown => -38001,
+                EngineApiError::InvalidForkchoiceState => -38002,
+                EngineApiError::PayloadAttributesTimestamp { .. } => -38003,
                 // Any other server error
                 _ => jsonrpsee::types::error::INTERNAL_ERROR_CODE,
             };
@@ -52,9 +60,12 @@ impl EngineApiServer for EngineApi {
         self.delegate_request(EngineApiMessage::NewPayload(payload, tx), rx).await
     }
 
-    /// See also <https://github.com/ethereum/execution-apis/blob/8db51dcd2f4bdfbd9ad6e4a7560aac97010ad063/src/engine/specification.md#engine_newpayloadv1>
-    async fn new_payload_v2(&self, _payload: ExecutionPayload) -> Result<PayloadStatus> {
-        Err(internal_rpc_err("unimplemented"))
+    /// See also <https://github.com/ethereum/execution-apis/blob/main/src/engine/shanghai.md#engine_newpayloadv2>
+    ///
+    /// Withdrawals are not supported yet, hence this is handled like [`Self::new_payload_v1`].
+    async fn new_payload_v2(&self, payload: ExecutionPayload) -> Result<PayloadStatus> {
+        let (tx, rx) = oneshot::channel();
+        self.delegate_request(EngineApiMessage::NewPayload(payload, tx), rx).await
     }
 
     /// See also <https://github.com/ethereum/execution-apis/blob/8db51dcd2f4bdfbd9ad6e4a7560aac97010ad063/src/engine/specification.md#engine_forkchoiceUpdatedV1>
@@ -74,12 +85,20 @@ impl EngineApiServer for EngineApi {
     }
 
     /// See also <https://github.com/ethereum/execution-apis/blob/main/src/engine/specification.md#engine_forkchoiceupdatedv2>
+    ///
+    /// Withdrawals are not supported yet, hence this is handled like
+    /// [`Self::fork_choice_updated_v1`] and payload attributes with withdrawals are rejected.
     async fn fork_choice_updated_v2(
         &self,
-        _fork_choice_state: ForkchoiceState,
-        _payload_attributes: Option<PayloadAttributes>,
+        fork_choice_state: ForkchoiceState,
+        payload_attributes: Option<PayloadAttributes>,
     ) -> Result<ForkchoiceUpdated> {
-        Err(internal_rpc_err("unimplemented"))
+        let (tx, rx) = oneshot::channel();
+        self.delegate_request(
+            EngineApiMessage::ForkchoiceUpdated(fork_choice_state, payload_attributes, tx),
+            rx,
+        )
+        .await
     }
 
     /// See also <https://github.com/ethereum/execution-apis/blob/8db51dcd2f4bdfbd9ad6e4a7560aac97010ad063/src/engine/specification.md#engine_getPayloadV1>
@@ -91,8 +110,11 @@ impl EngineApiServer for EngineApi {
     }
 
     /// See also <https://github.com/ethereum/execution-apis/blob/main/src/engine/specification.md#engine_getpayloadv2>
-    async fn get_payload_v2(&self, _payload_id: H64) -> Result<ExecutionPayload> {
-        Err(internal_rpc_err("unimplemented"))
+    ///
+    /// Withdrawals are not supported yet, hence this is handled like [`Self::get_payload_v1`].
+    async fn get_payload_v2(&self, payload_id: H64) -> Result<ExecutionPayload> {
+        let (tx, rx) = oneshot::channel();
+        self.delegate_request(EngineApiMessage::GetPayload(payload_id, tx), rx).await
     }
 
     /// See also <https://github.com/ethereum/execution-apis/blob/8db51dcd2f4bdfbd9ad6e4a7560aac97010ad063/src/engine/specification.md#engine_exchangeTransitionConfigurationV1>
@@ -0,0 +1,52 @@
+//! Contains RPC handler implementations specific to blocks.
+
+use crate::{eth::error::EthResult, EthApi};
+use reth_primitives::rpc::BlockId;
+use reth_provider::{BlockProvider, HeaderProvider};
+use reth_rpc_types::{Block, Index, RichBlock};
+
+impl<Pool, Client, Network> EthApi<Pool, Client, Network>
+where
+    Client: BlockProvider + HeaderProvider + 'static,
+{
+    /// Returns the block with the given id, with either full transactions or only their hashes.
+    ///
+    /// Returns `None` if the block does not exist.
+    pub(crate) fn rpc_block(&self, block_id: BlockId, full: bool) -> EthResult<Option<RichBlock>> {
+        let Some(block) = self.client().block(block_id)? else { return Ok(None) };
+        let block_hash = block.header.hash_slow();
+        let total_difficulty = self.client().header_td(&block_hash)?.unwrap_or_default();
+        let block = Block::from_block(block, total_difficulty, full.into())?;
+        Ok(Some(block.into()))
+    }
+
+    /// Returns the number of transactions in the block with the given id.
+    ///
+    /// Returns `None` if the block does not exist.
+    pub(crate) fn block_transaction_count(&self, block_id: BlockId) -> EthResult<Option<usize>> {
+        Ok(self.client().block(block_id)?.map(|block| block.body.len()))
+    }
+
+    /// Returns the number of ommers (uncles) of the block with the given id, or zero if the block
+    /// does not exist.
+    pub(crate) fn ommers_count(&self, block_id: BlockId) -> EthResult<usize> {
+        Ok(self.client().block(block_id)?.map(|block| block.ommers.len()).unwrap_or_default())
+    }
+
+    /// Returns the ommer (uncle) at the given index of the block with the given id.
+    ///
+    /// Returns `None` if the block or the ommer does not exist.
+    pub(crate) fn ommer_by_block_and_index(
+        &self,
+        block_id: BlockId,
+        index: Index,
+    ) -> EthResult<Option<RichBlock>> {
+        let Some(mut block) = self.client().block(block_id)? else { return Ok(None) };
+        let index = usize::from(index);
+        if index >= block.ommers.len() {
+            return Ok(None)
+        }
+        let ommer = block.ommers.swap_remove(index);
+        Ok(Some(Block::uncle_block_from_header(ommer).into()))
+    }
+}
@@ -0,0 +1,705 @@
+//! Contains RPC handler implementations for executing calls against the state: `eth_call`,
+//! `eth_estimateGas` and `eth_createAccessList`.
+
+use crate::{
+    eth::error::{EthApiError, EthResult},
+    EthApi,
+};
+use reth_executor::{
+    config::revm_spec,
+    revm_wrap::{fill_block_env, State, SubState},
+};
+use reth_primitives::{
+    keccak256,
+    rpc::{
+        self,
+        transaction::eip2930::{AccessList, AccessListItem, AccessListWithGasUsed},
+        BlockId, BlockNumber,
+    },
+    Address, Bytes, Header, H256, U256,
+};
+use reth_provider::{BlockProvider, HeaderProvider, StateProviderBox, StateProviderFactory};
+use reth_rpc_types::{CallRequest, StateOverride};
+use revm::{
+    db::{AccountState, CacheDB, DatabaseRef},
+    opcode, Bytecode, Database, EVMData, Env, ExecutionResult, Inspector, Interpreter, Return,
+    SpecId, TransactOut, TransactTo, EVM,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    time::{Duration, Instant},
+};
+
+/// Gas required by a plain value transfer, the lower bound of the gas estimation.
+const MIN_TRANSACTION_GAS: u64 = 21_000;
+
+/// Upper bound on the number of executions used to find a stable access list.
+const MAX_ACCESS_LIST_ITERATIONS: usize = 16;
+
+/// Number of EVM steps between two checks of the execution deadline.
+const DEADLINE_CHECK_INTERVAL: u64 = 1024;
+
+/// Limits applied when executing calls against the state, see [EthApi].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EthCallConfig {
+    /// The maximum gas a single call is allowed to use.
+    ///
+    /// Requests with a higher gas limit are capped to this value.
+    pub gas_cap: u64,
+    /// The maximum duration of a request.
+    ///
+    /// All EVM executions of a request share it, e.g. the steps of a gas estimation.
+    pub timeout: Duration,
+}
+
+impl Default for EthCallConfig {
+    fn default() -> Self {
+        Self { gas_cap: 50_000_000, timeout: Duration::from_secs(5) }
+    }
+}
+
+impl<Pool, Client, Network> EthApi<Pool, Client, Network>
+where
+    Client: BlockProvider + HeaderProvider + StateProviderFactory + 'static,
+{
+    /// Runs the request on the blocking thread pool.
+    ///
+    /// EVM executions run until the request times out, which must not block the async runtime
+    /// that serves the other requests.
+    pub(crate) async fn on_blocking_task<F, R>(&self, f: F) -> EthResult<R>
+    where
+        Self: Send + 'static,
+        F: FnOnce(Self) -> EthResult<R> + Send + 'static,
+        R: Send + 'static,
+    {
+        let this = self.clone();
+        tokio::task::spawn_blocking(move || f(this))
+            .await
+            .map_err(|_| EthApiError::ExecutionTaskFailed)?
+    }
+
+    /// Executes the call request against the state of the given block, with the state overrides
+    /// applied, and returns its output.
+    ///
+    /// Defaults to the latest block if no block is given.
+    pub(crate) fn call(
+        &self,
+        request: CallRequest,
+        block_id: Option<BlockId>,
+        state_overrides: Option<StateOverride>,
+    ) -> EthResult<Bytes> {
+        let deadline = self.deadline();
+        let (env, mut db) = self.prepare_call(request, block_id)?;
+        if let Some(state_overrides) = state_overrides {
+            apply_state_overrides(state_overrides, &mut db)?;
+        }
+        let result = self.transact(&mut db, env, deadline, None)?;
+        ensure_success(result)
+    }
+
+    /// Estimates the gas required by the call request, by searching for the lowest gas limit
+    /// the call succeeds with.
+    ///
+    /// The configured timeout applies to the whole search. Defaults to the latest block if no
+    /// block is given.
+    pub(crate) fn estimate_gas(
+        &self,
+        request: CallRequest,
+        block_id: Option<BlockId>,
+    ) -> EthResult<U256> {
+        let deadline = self.deadline();
+        let (mut env, mut db) = self.prepare_call(request, block_id)?;
+
+        // the highest gas limit the caller can afford
+        let mut highest_gas_limit = env.tx.gas_limit;
+        if env.tx.gas_price > U256::ZERO {
+            let balance =
+                Database::basic(&mut db, env.tx.caller)?.map(|acc| acc.balance).unwrap_or_default();
+            let available =
+                balance.checked_sub(env.tx.value).ok_or(EthApiError::InsufficientFunds)?;
+            let allowance = available / env.tx.gas_price;
+            if allowance < U256::from(highest_gas_limit) {
+                highest_gas_limit = allowance.to::<u64>();
+            }
+        }
+
+        // the call must succeed with the highest gas limit, otherwise it never does
+        env.tx.gas_limit = highest_gas_limit;
+        let result = self.transact(&mut db, env.clone(), deadline, None)?;
+        match result.exit_reason {
+            revm::return_ok!() => {}
+            Return::OutOfGas => {
+                return Err(EthApiError::GasRequiredExceedsAllowance(highest_gas_limit))
+            }
+            _ => {
+                ensure_success(result)?;
+            }
+        }
+
+        let mut lowest_gas_limit = MIN_TRANSACTION_GAS - 1;
+        while lowest_gas_limit + 1 < highest_gas_limit {
+            let mid_gas_limit = lowest_gas_limit + (highest_gas_limit - lowest_gas_limit) / 2;
+            env.tx.gas_limit = mid_gas_limit;
+            let result = self.transact(&mut db, env.clone(), deadline, None)?;
+            match result.exit_reason {
+                revm::return_ok!() => highest_gas_limit = mid_gas_limit,
+                Return::Revert | Return::OutOfGas | Return::CallTooDeep | Return::OutOfFund => {
+                    lowest_gas_limit = mid_gas_limit
+                }
+                _ => {
+                    // errors that don't depend on the gas limit
+                    ensure_success(result)?;
+                }
+            }
+        }
+
+        Ok(U256::from(highest_gas_limit))
+    }
+
+    /// Creates an [EIP-2930](https://eips.ethereum.org/EIPS/eip-2930) access list for the call
+    /// request, together with the gas used when the access list is attached to the call.
+    ///
+    /// The call is repeated with the last recorded access list until the list is stable, since
+    /// the accessed slots may depend on the gas that is available to the call.
+    pub(crate) fn create_access_list(
+        &self,
+        request: CallRequest,
+        block_id: Option<BlockId>,
+    ) -> EthResult<AccessListWithGasUsed> {
+        let deadline = self.deadline();
+        let (mut env, mut db) = self.prepare_call(request, block_id)?;
+
+        // the sender, the recipient and the precompiles are always warm
+        let mut excluded: HashSet<Address> = (1..=9).map(Address::from_low_u64_be).collect();
+        excluded.insert(env.tx.caller);
+        if let TransactTo::Call(to) = env.tx.transact_to {
+            excluded.insert(to);
+        }
+
+        let mut access_list = AccessListTracer::new(excluded, &env.tx.access_list);
+        for _ in 0..MAX_ACCESS_LIST_ITERATIONS {
+            env.tx.access_list = access_list.to_tx_access_list();
+            let mut tracer = access_list.clone();
+            let result = self.transact(&mut db, env.clone(), deadline, Some(&mut tracer))?;
+            let gas_used = result.gas_used;
+            ensure_success(result)?;
+
+            if tracer == access_list {
+                return Ok(AccessListWithGasUsed {
+                    access_list: access_list.into_access_list(),
+                    gas_used: rpc::U256::from(gas_used),
+                })
+            }
+            access_list = tracer;
+        }
+
+        Err(EthApiError::AccessListNotStable)
+    }
+
+    /// Builds the EVM environment for the call request and the database of the state it is
+    /// executed against.
+    fn prepare_call(
+        &self,
+        request: CallRequest,
+        block_id: Option<BlockId>,
+    ) -> EthResult<(Env, SubState<StateProviderBox<'_>>)> {
+        let block_id = match block_id.unwrap_or(BlockId::Number(BlockNumber::Latest)) {
+            // there is no pending block yet, execute on top of the latest one
+            BlockId::Number(BlockNumber::Pending) => BlockId::Number(BlockNumber::Latest),
+            block_id => block_id,
+        };
+        let header = self.header_by_block_id(block_id)?;
+        let env = self.prepare_env(&header, request)?;
+        let db = SubState::new(State::new(self.state_at_block_id(block_id)?));
+        Ok((env, db))
+    }
+
+    /// Returns the header of the block with the given id.
+    fn header_by_block_id(&self, block_id: BlockId) -> EthResult<Header> {
+        let hash =
+            self.client().block_hash_for_id(block_id)?.ok_or(EthApiError::UnknownBlockNumber)?;
+        let header = self.client().header(&hash)?.ok_or(EthApiError::UnknownBlockNumber)?;
+        Ok(header)
+    }
+
+    /// Fills the EVM environment for executing the call request in the block with the given
+    /// header.
+    ///
+    /// The gas limit of the request is capped by the block gas limit and the configured gas
+    /// cap.
+    fn prepare_env(&self, header: &Header, request: CallRequest) -> EthResult<Env> {
+        let CallRequest {
+            from,
+            to,
+            gas_price,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            gas,
+            value,
+            data,
+            nonce: _,
+            access_list,
+            transaction_type: _,
+        } = request;
+
+        let mut env = Env::default();
+
+        let spec_id = revm_spec(self.chain_spec(), header.number);
+        env.cfg.chain_id = U256::from(self.chain_spec().chain().id());
+        env.cfg.spec_id = spec_id;
+        fill_block_env(&mut env.block, header, spec_id >= SpecId::MERGE);
+
+        if let Some(gas_price) = gas_price {
+            if max_fee_per_gas.is_some() || max_priority_fee_per_gas.is_some() {
+                return Err(EthApiError::ConflictingFeeFieldsInRequest)
+            }
+            env.tx.gas_price = U256::from(gas_price.to::<u128>());
+        } else {
+            match max_fee_per_gas {
+                Some(max_fee_per_gas) => {
+                    env.tx.gas_price = U256::from(max_fee_per_gas.to::<u128>());
+                }
+                // calls without a fee are free, which the base fee check would reject
+                None => env.block.basefee = U256::ZERO,
+            }
+            env.tx.gas_priority_fee =
+                max_priority_fee_per_gas.map(|fee| U256::from(fee.to::<u128>()));
+        }
+
+        let gas_cap = header.gas_limit.min(self.call_config().gas_cap);
+        env.tx.gas_limit = match gas {
+            Some(gas) if gas < U256::from(gas_cap) => gas.to::<u64>(),
+            _ => gas_cap,
+        };
+
+        env.tx.caller = from.unwrap_or_default();
+        env.tx.transact_to = to.map(TransactTo::Call).unwrap_or_else(TransactTo::create);
+        env.tx.value = value.unwrap_or_default();
+        env.tx.data = data.map(|data| data.0).unwrap_or_default();
+        // nonce checks are skipped for calls
+        env.tx.nonce = None;
+        env.tx.chain_id = None;
+        env.tx.access_list = access_list
+            .unwrap_or_default()
+            .into_iter()
+            .map(|item| {
+                (
+                    Address(item.address.0),
+                    item.storage_keys.into_iter().map(|key| U256::from_be_bytes(key.0)).collect(),
+                )
+            })
+            .collect();
+
+        Ok(env)
+    }
+
+    /// Returns the deadline of a request that starts now.
+    fn deadline(&self) -> Instant {
+        Instant::now() + self.call_config().timeout
+    }
+
+    /// Executes the environment against the database without committing any changes.
+    ///
+    /// Fails if the execution does not finish before the deadline of the request.
+    fn transact(
+        &self,
+        db: &mut SubState<StateProviderBox<'_>>,
+        env: Env,
+        deadline: Instant,
+        access_list: Option<&mut AccessListTracer>,
+    ) -> EthResult<ExecutionResult> {
+        let timeout = self.call_config().timeout;
+        let mut evm = EVM::new();
+        evm.env = env;
+        evm.database(db);
+
+        // the deadline may have passed during a previous execution of the request
+        if Instant::now() >= deadline {
+            return Err(EthApiError::ExecutionTimedOut(timeout))
+        }
+
+        let mut timed_out = false;
+        let inspector =
+            CallInspector { deadline, steps: 0, timed_out: &mut timed_out, access_list };
+        let (result, _) = evm.inspect(inspector);
+
+        if timed_out {
+            return Err(EthApiError::ExecutionTimedOut(timeout))
+        }
+        if result.exit_reason == Return::FatalExternalError {
+            return Err(EthApiError::EvmFatal)
+        }
+        Ok(result)
+    }
+}
+
+/// Returns the output of a successful execution or the error the execution failed with.
+fn ensure_success(result: ExecutionResult) -> EthResult<Bytes> {
+    let output = match result.out {
+        TransactOut::None => Bytes::default(),
+        TransactOut::Call(output) | TransactOut::Create(output, _) => output.into(),
+    };
+    match result.exit_reason {
+        revm::return_ok!() => Ok(output),
+        Return::Revert => Err(EthApiError::Revert(output)),
+        Return::LackOfFundForGasLimit | Return::OutOfFund => Err(EthApiError::InsufficientFunds),
+        Return::GasPriceLessThenBasefee => Err(EthApiError::FeeCapTooLow),
+        reason => Err(EthApiError::EvmHalt(reason)),
+    }
+}
+
+/// Applies the state overrides to the database.
+fn apply_state_overrides<DB>(overrides: StateOverride, db: &mut CacheDB<DB>) -> EthResult<()>
+where
+    DB: DatabaseRef,
+    EthApiError: From<<DB as DatabaseRef>::Error>,
+{
+    for (address, account_override) in overrides {
+        let account = db.load_account(address)?;
+
+        if let Some(balance) = account_override.balance {
+            account.info.balance = balance;
+        }
+        if let Some(nonce) = account_override.nonce {
+            account.info.nonce = nonce.as_u64();
+        }
+        if let Some(code) = account_override.code {
+            account.info.code_hash = keccak256(&code);
+            account.info.code = Some(Bytecode::new_raw(code.0));
+        }
+
+        match (account_override.state, account_override.state_diff) {
+            (Some(_), Some(_)) => return Err(EthApiError::BothStateAndStateDiffInOverride(address)),
+            (Some(state), None) => {
+                account.account_state = AccountState::StorageCleared;
+                account.storage = state
+                    .into_iter()
+                    .map(|(slot, value)| {
+                        (U256::from_be_bytes(slot.0), U256::from_be_bytes(value.0))
+                    })
+                    .collect();
+            }
+            (None, Some(state_diff)) => {
+                account.storage.extend(state_diff.into_iter().map(|(slot, value)| {
+                    (U256::from_be_bytes(slot.0), U256::from_be_bytes(value.0))
+                }));
+            }
+            (None, None) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Records the addresses and storage slots accessed during an execution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AccessListTracer {
+    /// Addresses that are warm regardless of the access list.
+    excluded: HashSet<Address>,
+    /// The accessed storage slots per address.
+    access_list: BTreeMap<Address, BTreeSet<H256>>,
+}
+
+impl AccessListTracer {
+    /// Creates a new tracer that starts with the given access list of the transaction.
+    fn new(excluded: HashSet<Address>, access_list: &[(Address, Vec<U256>)]) -> Self {
+        let mut tracer = Self { excluded, access_list: Default::default() };
+        for (address, slots) in access_list {
+            tracer.add_address(*address);
+            for slot in slots {
+                tracer.add_slot(*address, H256(slot.to_be_bytes()));
+            }
+        }
+        tracer
+    }
+
+    /// Records the access of an address.
+    fn add_address(&mut self, address: Address) {
+        if !self.excluded.contains(&address) {
+            self.access_list.entry(address).or_default();
+        }
+    }
+
+    /// Records the access of a storage slot.
+    ///
+    /// Slots of excluded addresses are recorded as well, since they are not warm by default.
+    fn add_slot(&mut self, address: Address, slot: H256) {
+        self.access_list.entry(address).or_default().insert(slot);
+    }
+
+    /// Returns the recorded access list in the form the EVM environment expects.
+    fn to_tx_access_list(&self) -> Vec<(Address, Vec<U256>)> {
+        self.access_list
+            .iter()
+            .map(|(address, slots)| {
+                (*address, slots.iter().map(|slot| U256::from_be_bytes(slot.0)).collect())
+            })
+            .collect()
+    }
+
+    /// Converts the recorded access list into its RPC representation.
+    fn into_access_list(self) -> AccessList {
+        AccessList(
+            self.access_list
+                .into_iter()
+                .map(|(address, slots)| AccessListItem {
+                    address: rpc::H160(address.0),
+                    storage_keys: slots.into_iter().map(|slot| rpc::H256(slot.0)).collect(),
+                })
+                .collect(),
+        )
+    }
+}
+
+/// An [Inspector] that aborts the execution once the deadline passed and optionally records the
+/// accessed addresses and storage slots.
+struct CallInspector<'a> {
+    /// The instant at which the execution is aborted.
+    deadline: Instant,
+    /// Number of executed steps.
+    steps: u64,
+    /// Set if the execution was aborted because of the deadline.
+    timed_out: &'a mut bool,
+    /// Records the access list if set.
+    access_list: Option<&'a mut AccessListTracer>,
+}
+
+impl<DB: Database> Inspector<DB> for CallInspector<'_> {
+    fn step(
+        &mut self,
+        interp: &mut Interpreter,
+        _data: &mut EVMData<'_, DB>,
+        _is_static: bool,
+    ) -> Return {
+        self.steps += 1;
+        if self.steps % DEADLINE_CHECK_INTERVAL == 0 && Instant::now() >= self.deadline {
+            *self.timed_out = true;
+            return Return::OutOfGas
+        }
+
+        let Some(access_list) = self.access_list.as_deref_mut() else { return Return::Continue };
+        match interp.current_opcode() {
+            opcode::SLOAD | opcode::SSTORE => {
+                if let Ok(slot) = interp.stack.peek(0) {
+                    access_list.add_slot(interp.contract.address, H256(slot.to_be_bytes()));
+                }
+            }
+            opcode::EXTCODECOPY |
+            opcode::EXTCODEHASH |
+            opcode::EXTCODESIZE |
+            opcode::BALANCE |
+            opcode::SELFDESTRUCT => {
+                if let Ok(address) = interp.stack.peek(0) {
+                    access_list.add_address(address_from_word(address));
+                }
+            }
+            opcode::DELEGATECALL | opcode::CALL | opcode::STATICCALL | opcode::CALLCODE => {
+                if let Ok(address) = interp.stack.peek(1) {
+                    access_list.add_address(address_from_word(address));
+                }
+            }
+            _ => {}
+        }
+
+        Return::Continue
+    }
+}
+
+/// Returns the address stored in the lower 20 bytes of a stack word.
+fn address_from_word(word: U256) -> Address {
+    Address::from_slice(&word.to_be_bytes::<32>()[12..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use reth_primitives::MAINNET;
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_rpc_types::AccountOverride;
+    use std::{collections::HashMap, sync::Arc};
+
+    /// Returns storage slot 0: `SLOAD(0)`.
+    const SLOAD_CODE: [u8; 11] = [0x60, 0x00, 0x54, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3];
+    /// Stores 1 in storage slot 0: `SSTORE(0, 1)`.
+    const SSTORE_CODE: [u8; 5] = [0x60, 0x01, 0x60, 0x00, 0x55];
+    /// Returns the remaining gas: `GAS`.
+    const GAS_CODE: [u8; 9] = [0x5a, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3];
+    /// Loops forever.
+    const LOOP_CODE: [u8; 4] = [0x5b, 0x60, 0x00, 0x56];
+
+    const BLOCK_GAS_LIMIT: u64 = 30_000_000;
+
+    fn contract(n: u64) -> Address {
+        Address::from_low_u64_be(0x1000 + n)
+    }
+
+    /// Creates an [EthApi] over a mock chain with a single block and the test contracts.
+    fn eth_api(call_config: EthCallConfig) -> EthApi<(), MockEthProvider, ()> {
+        let client = MockEthProvider::default();
+        client.add_header(
+            H256::random(),
+            Header { gas_limit: BLOCK_GAS_LIMIT, ..Default::default() },
+        );
+        for (n, code) in
+            [&SLOAD_CODE[..], &SSTORE_CODE, &GAS_CODE, &LOOP_CODE].into_iter().enumerate()
+        {
+            let account = ExtendedAccount::new(0, U256::ZERO)
+                .with_bytecode(Bytes::from(code))
+                .extend_storage([(H256::zero(), U256::from(1))]);
+            client.add_account(contract(n as u64), account);
+        }
+        EthApi::new(Arc::new(client), (), (), MAINNET.clone(), call_config)
+    }
+
+    fn call_to(to: Address) -> CallRequest {
+        CallRequest { to: Some(to), ..Default::default() }
+    }
+
+    #[test]
+    fn call_returns_output() {
+        let api = eth_api(EthCallConfig::default());
+        let output = api.call(call_to(contract(0)), None, None).unwrap();
+        assert_eq!(U256::try_from_be_slice(&output), Some(U256::from(1)));
+
+        let unknown = BlockId::Number(BlockNumber::Number(1u64.into()));
+        assert_matches!(
+            api.call(call_to(contract(0)), Some(unknown), None),
+            Err(EthApiError::UnknownBlockNumber)
+        );
+    }
+
+    #[test]
+    fn call_gas_is_capped() {
+        let gas_cap = 100_000;
+        let api = eth_api(EthCallConfig { gas_cap, ..Default::default() });
+
+        let request =
+            CallRequest { gas: Some(U256::from(BLOCK_GAS_LIMIT)), ..call_to(contract(2)) };
+        let remaining = U256::try_from_be_slice(&api.call(request, None, None).unwrap()).unwrap();
+        assert!(remaining < U256::from(gas_cap - MIN_TRANSACTION_GAS));
+
+        // the cap is not applied to lower gas limits
+        let request = CallRequest { gas: Some(U256::from(50_000)), ..call_to(contract(2)) };
+        let remaining = U256::try_from_be_slice(&api.call(request, None, None).unwrap()).unwrap();
+        assert!(remaining < U256::from(50_000 - MIN_TRANSACTION_GAS));
+        assert!(remaining > U256::from(50_000 - MIN_TRANSACTION_GAS - 10));
+    }
+
+    #[test]
+    fn call_times_out() {
+        let timeout = Duration::ZERO;
+        let api = eth_api(EthCallConfig { timeout, ..Default::default() });
+        assert_matches!(
+            api.call(call_to(contract(3)), None, None),
+            Err(EthApiError::ExecutionTimedOut(t)) if t == timeout
+        );
+    }
+
+    #[test]
+    fn call_with_state_overrides() {
+        let api = eth_api(EthCallConfig::default());
+        let slot = |value: u64| HashMap::from([(H256::zero(), H256::from_low_u64_be(value))]);
+        let call = |account_override: AccountOverride| {
+            let overrides = HashMap::from([(contract(0), account_override)]);
+            api.call(call_to(contract(0)), None, Some(overrides))
+                .map(|output| U256::try_from_be_slice(&output).unwrap())
+        };
+
+        let state_diff = AccountOverride { state_diff: Some(slot(2)), ..Default::default() };
+        assert_eq!(call(state_diff).unwrap(), U256::from(2));
+
+        // the full storage override clears all other slots
+        let state = AccountOverride {
+            state: Some(HashMap::from([(H256::from_low_u64_be(1), H256::from_low_u64_be(1))])),
+            ..Default::default()
+        };
+        assert_eq!(call(state).unwrap(), U256::ZERO);
+
+        let code = AccountOverride { code: Some(Bytes::from(GAS_CODE)), ..Default::default() };
+        assert!(call(code).unwrap() > U256::from(1));
+
+        let both = AccountOverride {
+            state: Some(slot(2)),
+            state_diff: Some(slot(2)),
+            ..Default::default()
+        };
+        assert_matches!(
+            call(both),
+            Err(EthApiError::BothStateAndStateDiffInOverride(address)) if address == contract(0)
+        );
+    }
+
+    #[test]
+    fn estimate_gas_finds_lowest_limit() {
+        let api = eth_api(EthCallConfig::default());
+
+        let transfer = call_to(Address::from_low_u64_be(0xdead));
+        assert_eq!(api.estimate_gas(transfer, None).unwrap(), U256::from(MIN_TRANSACTION_GAS));
+
+        let estimate = api.estimate_gas(call_to(contract(1)), None).unwrap();
+        assert!(estimate > U256::from(MIN_TRANSACTION_GAS));
+        let request = CallRequest { gas: Some(estimate), ..call_to(contract(1)) };
+        assert!(api.call(request, None, None).is_ok());
+        let request = CallRequest { gas: Some(estimate - U256::from(1)), ..call_to(contract(1)) };
+        assert!(api.call(request, None, None).is_err());
+    }
+
+    #[test]
+    fn estimate_gas_times_out() {
+        let timeout = Duration::ZERO;
+        let api = eth_api(EthCallConfig { timeout, ..Default::default() });
+        let transfer = call_to(Address::from_low_u64_be(0xdead));
+        assert_matches!(
+            api.estimate_gas(transfer, None),
+            Err(EthApiError::ExecutionTimedOut(t)) if t == timeout
+        );
+    }
+
+    #[tokio::test]
+    async fn estimate_gas_on_blocking_task() {
+        let api = eth_api(EthCallConfig::default());
+        let transfer = call_to(Address::from_low_u64_be(0xdead));
+        let estimate = api.on_blocking_task(move |api| api.estimate_gas(transfer, None)).await;
+        assert_eq!(estimate.unwrap(), U256::from(MIN_TRANSACTION_GAS));
+    }
+
+    #[test]
+    fn estimate_gas_fails_above_cap() {
+        let gas_cap = 100_000;
+        let api = eth_api(EthCallConfig { gas_cap, ..Default::default() });
+        assert_matches!(
+            api.estimate_gas(call_to(contract(3)), None),
+            Err(EthApiError::GasRequiredExceedsAllowance(gas)) if gas == gas_cap
+        );
+    }
+
+    #[test]
+    fn access_list_tracer_excludes_addresses() {
+        let excluded = Address::from_low_u64_be(1);
+        let contract = Address::from_low_u64_be(100);
+        let mut tracer = AccessListTracer::new(HashSet::from([excluded]), &[]);
+
+        tracer.add_address(excluded);
+        tracer.add_address(contract);
+        tracer.add_slot(contract, H256::from_low_u64_be(1));
+        tracer.add_slot(excluded, H256::from_low_u64_be(2));
+
+        let tx_access_list = tracer.to_tx_access_list();
+        assert_eq!(
+            tx_access_list,
+            vec![(excluded, vec![U256::from(2)]), (contract, vec![U256::from(1)])]
+        );
+
+        // the tracer is stable when restarted from its own access list
+        let restarted = AccessListTracer::new(HashSet::from([excluded]), &tx_access_list);
+        assert_eq!(restarted, tracer);
+    }
+
+    #[test]
+    fn address_from_stack_word() {
+        let address = Address::from_low_u64_be(0xdead);
+        let mut word = [0u8; 32];
+        word[12..].copy_from_slice(address.as_bytes());
+        assert_eq!(address_from_word(U256::from_be_bytes(word)), address);
+    }
+}
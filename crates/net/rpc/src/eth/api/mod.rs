@@ -7,15 +7,19 @@ use crate::eth::signer::EthSigner;
 use async_trait::async_trait;
 use reth_interfaces::Result;
 use reth_network_api::NetworkInfo;
-use reth_primitives::{ChainInfo, U64};
+use reth_primitives::{ChainInfo, ChainSpec, U64};
 use reth_provider::{BlockProvider, StateProviderFactory};
-use reth_rpc_types::Transaction;
 use reth_transaction_pool::TransactionPool;
 use std::sync::Arc;
 
+mod block;
+mod call;
 mod server;
+mod state;
 mod transactions;
 
+pub use call::EthCallConfig;
+
 /// `Eth` API trait.
 ///
 /// Defines core functionality of the `eth` API implementation.
@@ -39,17 +43,35 @@ pub trait EthApiSpec: Send + Sync {
 /// are implemented separately in submodules. The rpc handler implementation can then delegate to
 /// the main impls. This way [`EthApi`] is not limited to [`jsonrpsee`] and can be used standalone
 /// or in other network handlers (for example ipc).
-#[derive(Clone)]
 #[allow(missing_debug_implementations)]
 pub struct EthApi<Pool, Client, Network> {
     /// All nested fields bundled together.
     inner: Arc<EthApiInner<Pool, Client, Network>>,
 }
 
+impl<Pool, Client, Network> Clone for EthApi<Pool, Client, Network> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
 impl<Pool, Client, Network> EthApi<Pool, Client, Network> {
     /// Creates a new, shareable instance.
-    pub fn new(client: Arc<Client>, pool: Pool, network: Network) -> Self {
-        let inner = EthApiInner { client, pool, network, signers: Default::default() };
+    pub fn new(
+        client: Arc<Client>,
+        pool: Pool,
+        network: Network,
+        chain_spec: ChainSpec,
+        call_config: EthCallConfig,
+    ) -> Self {
+        let inner = EthApiInner {
+            client,
+            pool,
+            network,
+            chain_spec,
+            call_config,
+            signers: Default::default(),
+        };
         Self { inner: Arc::new(inner) }
     }
 
@@ -67,12 +89,22 @@ impl<Pool, Client, Network> EthApi<Pool, Client, Network> {
     pub(crate) fn pool(&self) -> &Pool {
         &self.inner.pool
     }
+
+    /// Returns the configured [ChainSpec]
+    pub(crate) fn chain_spec(&self) -> &ChainSpec {
+        &self.inner.chain_spec
+    }
+
+    /// Returns the configured [EthCallConfig]
+    pub(crate) fn call_config(&self) -> &EthCallConfig {
+        &self.inner.call_config
+    }
 }
 
 #[async_trait]
 impl<Pool, Client, Network> EthApiSpec for EthApi<Pool, Client, Network>
 where
-    Pool: TransactionPool<Transaction = Transaction> + Clone + 'static,
+    Pool: TransactionPool + Clone + 'static,
     Client: BlockProvider + StateProviderFactory + 'static,
     Network: NetworkInfo + 'static,
 {
@@ -103,6 +135,10 @@ struct EthApiInner<Pool, Client, Network> {
     client: Arc<Client>,
     /// An interface to interact with the network
     network: Network,
+    /// The chain the client is running on.
+    chain_spec: ChainSpec,
+    /// Limits applied when executing calls against the state.
+    call_config: EthCallConfig,
     /// All configured Signers
     signers: Vec<Box<dyn EthSigner>>,
 }
@@ -7,14 +7,16 @@ use crate::{
 };
 use jsonrpsee::core::RpcResult as Result;
 use reth_primitives::{
-    rpc::{transaction::eip2930::AccessListWithGasUsed, BlockId},
-    Address, BlockNumber, Bytes, H256, H64, U256, U64,
+    rpc::{transaction::eip2930::AccessListWithGasUsed, BlockId, BlockNumber},
+    Address, Bytes, H256, H64, U256, U64,
+};
+use reth_provider::{
+    BlockProvider, HeaderProvider, ReceiptProvider, StateProviderFactory, TransactionsProvider,
 };
-use reth_provider::{BlockProvider, StateProviderFactory};
 use reth_rpc_api::EthApiServer;
 use reth_rpc_types::{
-    CallRequest, EIP1186AccountProofResponse, FeeHistory, Index, RichBlock, SyncStatus,
-    TransactionReceipt, TransactionRequest, Work,
+    CallRequest, EIP1186AccountProofResponse, FeeHistory, Index, RichBlock, StateOverride,
+    SyncStatus, TransactionReceipt, TransactionRequest, Work,
 };
 use reth_transaction_pool::TransactionPool;
 use serde_json::Value;
@@ -26,7 +28,12 @@ impl<Pool, Client, Network> EthApiServer for EthApi<Pool, Client, Network>
 where
     Self: EthApiSpec,
     Pool: TransactionPool + 'static,
-    Client: BlockProvider + StateProviderFactory + 'static,
+    Client: BlockProvider
+        + HeaderProvider
+        + StateProviderFactory
+        + TransactionsProvider
+        + ReceiptProvider
+        + 'static,
     Network: 'static,
 {
     async fn protocol_version(&self) -> Result<U64> {
@@ -55,123 +62,131 @@ where
         Ok(Some(EthApiSpec::chain_id(self)))
     }
 
-    async fn block_by_hash(&self, _hash: H256, _full: bool) -> Result<Option<RichBlock>> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn block_by_hash(&self, hash: H256, full: bool) -> Result<Option<RichBlock>> {
+        EthApi::rpc_block(self, BlockId::Hash(hash.0.into()), full).to_rpc_result()
     }
 
-    async fn block_by_number(
-        &self,
-        _number: BlockNumber,
-        _full: bool,
-    ) -> Result<Option<RichBlock>> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn block_by_number(&self, number: BlockNumber, full: bool) -> Result<Option<RichBlock>> {
+        EthApi::rpc_block(self, BlockId::Number(number), full).to_rpc_result()
     }
 
-    async fn block_transaction_count_by_hash(&self, _hash: H256) -> Result<Option<U256>> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn block_transaction_count_by_hash(&self, hash: H256) -> Result<Option<U256>> {
+        Ok(EthApi::block_transaction_count(self, BlockId::Hash(hash.0.into()))
+            .to_rpc_result()?
+            .map(U256::from))
     }
 
-    async fn block_transaction_count_by_number(
-        &self,
-        _number: BlockNumber,
-    ) -> Result<Option<U256>> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn block_transaction_count_by_number(&self, number: BlockNumber) -> Result<Option<U256>> {
+        Ok(EthApi::block_transaction_count(self, BlockId::Number(number))
+            .to_rpc_result()?
+            .map(U256::from))
     }
 
-    async fn block_uncles_count_by_hash(&self, _hash: H256) -> Result<U256> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn block_uncles_count_by_hash(&self, hash: H256) -> Result<U256> {
+        Ok(U256::from(EthApi::ommers_count(self, BlockId::Hash(hash.0.into())).to_rpc_result()?))
     }
 
-    async fn block_uncles_count_by_number(&self, _number: BlockNumber) -> Result<U256> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn block_uncles_count_by_number(&self, number: BlockNumber) -> Result<U256> {
+        Ok(U256::from(EthApi::ommers_count(self, BlockId::Number(number)).to_rpc_result()?))
     }
 
     async fn uncle_by_block_hash_and_index(
         &self,
-        _hash: H256,
-        _index: Index,
+        hash: H256,
+        index: Index,
     ) -> Result<Option<RichBlock>> {
-        Err(internal_rpc_err("unimplemented"))
+        EthApi::ommer_by_block_and_index(self, BlockId::Hash(hash.0.into()), index).to_rpc_result()
     }
 
     async fn uncle_by_block_number_and_index(
         &self,
-        _number: BlockNumber,
-        _index: Index,
+        number: BlockNumber,
+        index: Index,
     ) -> Result<Option<RichBlock>> {
-        Err(internal_rpc_err("unimplemented"))
+        EthApi::ommer_by_block_and_index(self, BlockId::Number(number), index).to_rpc_result()
     }
 
-    async fn transaction_by_hash(
-        &self,
-        _hash: H256,
-    ) -> Result<Option<reth_rpc_types::Transaction>> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn transaction_by_hash(&self, hash: H256) -> Result<Option<reth_rpc_types::Transaction>> {
+        EthApi::transaction_by_hash(self, hash).to_rpc_result()
     }
 
     async fn transaction_by_block_hash_and_index(
         &self,
-        _hash: H256,
-        _index: Index,
+        hash: H256,
+        index: Index,
     ) -> Result<Option<reth_rpc_types::Transaction>> {
-        Err(internal_rpc_err("unimplemented"))
+        EthApi::transaction_by_block_and_index(self, BlockId::Hash(hash.0.into()), index)
+            .to_rpc_result()
     }
 
     async fn transaction_by_block_number_and_index(
         &self,
-        _number: BlockNumber,
-        _index: Index,
+        number: BlockNumber,
+        index: Index,
     ) -> Result<Option<reth_rpc_types::Transaction>> {
-        Err(internal_rpc_err("unimplemented"))
+        EthApi::transaction_by_block_and_index(self, BlockId::Number(number), index).to_rpc_result()
     }
 
-    async fn transaction_receipt(&self, _hash: H256) -> Result<Option<TransactionReceipt>> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn transaction_receipt(&self, hash: H256) -> Result<Option<TransactionReceipt>> {
+        EthApi::transaction_receipt(self, hash).to_rpc_result()
     }
 
-    async fn balance(&self, _address: Address, _block_number: Option<BlockId>) -> Result<U256> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn balance(&self, address: Address, block_number: Option<BlockId>) -> Result<U256> {
+        EthApi::balance(self, address, block_number).to_rpc_result()
     }
 
     async fn storage_at(
         &self,
-        _address: Address,
-        _index: U256,
-        _block_number: Option<BlockId>,
+        address: Address,
+        index: U256,
+        block_number: Option<BlockId>,
     ) -> Result<H256> {
-        Err(internal_rpc_err("unimplemented"))
+        EthApi::storage_at(self, address, index, block_number).to_rpc_result()
     }
 
     async fn transaction_count(
         &self,
-        _address: Address,
-        _block_number: Option<BlockId>,
+        address: Address,
+        block_number: Option<BlockId>,
     ) -> Result<U256> {
-        Err(internal_rpc_err("unimplemented"))
+        EthApi::get_transaction_count(self, address, block_number).to_rpc_result()
     }
 
-    async fn get_code(&self, _address: Address, _block_number: Option<BlockId>) -> Result<Bytes> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn get_code(&self, address: Address, block_number: Option<BlockId>) -> Result<Bytes> {
+        EthApi::get_code(self, address, block_number).to_rpc_result()
     }
 
-    async fn call(&self, _request: CallRequest, _block_number: Option<BlockId>) -> Result<Bytes> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn call(
+        &self,
+        request: CallRequest,
+        block_number: Option<BlockId>,
+        state_overrides: Option<StateOverride>,
+    ) -> Result<Bytes> {
+        self.on_blocking_task(move |this| {
+            EthApi::call(&this, request, block_number, state_overrides)
+        })
+        .await
+        .to_rpc_result()
     }
 
     async fn create_access_list(
         &self,
-        _request: CallRequest,
-        _block_number: Option<BlockId>,
+        request: CallRequest,
+        block_number: Option<BlockId>,
     ) -> Result<AccessListWithGasUsed> {
-        Err(internal_rpc_err("unimplemented"))
+        self.on_blocking_task(move |this| EthApi::create_access_list(&this, request, block_number))
+            .await
+            .to_rpc_result()
     }
 
     async fn estimate_gas(
         &self,
-        _request: CallRequest,
-        _block_number: Option<BlockId>,
+        request: CallRequest,
+        block_number: Option<BlockId>,
     ) -> Result<U256> {
-        Err(internal_rpc_err("unimplemented"))
+        self.on_blocking_task(move |this| EthApi::estimate_gas(&this, request, block_number))
+            .await
+            .to_rpc_result()
     }
 
     async fn gas_price(&self) -> Result<U256> {
@@ -233,10 +248,10 @@ where
 
     async fn get_proof(
         &self,
-        _address: Address,
-        _keys: Vec<H256>,
-        _block_number: Option<BlockId>,
+        address: Address,
+        keys: Vec<H256>,
+        block_number: Option<BlockId>,
     ) -> Result<EIP1186AccountProofResponse> {
-        Err(internal_rpc_err("unimplemented"))
+        EthApi::get_proof(self, address, keys, block_number).to_rpc_result()
     }
 }
@@ -0,0 +1,193 @@
+//! Contains RPC handler implementations specific to state.
+
+use crate::{
+    eth::error::{EthApiError, EthResult},
+    EthApi,
+};
+use reth_primitives::{
+    rpc::{BlockId, BlockNumber},
+    Address, Bytes, H256, KECCAK_EMPTY, U256, U64,
+};
+use reth_provider::{
+    AccountProvider, BlockProvider, StateProvider, StateProviderBox, StateProviderFactory,
+};
+use reth_rpc_types::{EIP1186AccountProofResponse, StorageProof};
+
+impl<Pool, Client, Network> EthApi<Pool, Client, Network>
+where
+    Client: BlockProvider + StateProviderFactory + 'static,
+{
+    /// Returns the balance of `address` at the given block.
+    pub(crate) fn balance(&self, address: Address, block_id: Option<BlockId>) -> EthResult<U256> {
+        let state = self.state_at_block_id_or_latest(block_id)?;
+        let balance = state.basic_account(address)?.map(|acc| acc.balance).unwrap_or_default();
+        Ok(balance)
+    }
+
+    /// Returns the nonce of `address` at the given block.
+    pub(crate) fn get_transaction_count(
+        &self,
+        address: Address,
+        block_id: Option<BlockId>,
+    ) -> EthResult<U256> {
+        let state = self.state_at_block_id_or_latest(block_id)?;
+        let nonce = state.basic_account(address)?.map(|acc| acc.nonce).unwrap_or_default();
+        Ok(U256::from(nonce))
+    }
+
+    /// Returns the code deployed at `address` at the given block.
+    pub(crate) fn get_code(&self, address: Address, block_id: Option<BlockId>) -> EthResult<Bytes> {
+        let state = self.state_at_block_id_or_latest(block_id)?;
+        let Some(code_hash) = state.basic_account(address)?.and_then(|acc| acc.bytecode_hash)
+        else {
+            return Ok(Bytes::default())
+        };
+        Ok(state.bytecode_by_hash(code_hash)?.unwrap_or_default())
+    }
+
+    /// Returns the value of the storage slot `index` of `address` at the given block.
+    pub(crate) fn storage_at(
+        &self,
+        address: Address,
+        index: U256,
+        block_id: Option<BlockId>,
+    ) -> EthResult<H256> {
+        let state = self.state_at_block_id_or_latest(block_id)?;
+        let value = state.storage(address, H256(index.to_be_bytes()))?.unwrap_or_default();
+        Ok(H256(value.to_be_bytes()))
+    }
+
+    /// Returns the account and storage values of `address` at the given block, together with
+    /// their Merkle proofs.
+    ///
+    /// Defaults to the latest block if no block is given.
+    pub(crate) fn get_proof(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        block_id: Option<BlockId>,
+    ) -> EthResult<EIP1186AccountProofResponse> {
+        let state = self.state_at_block_id_or_latest(block_id)?;
+        proof_from_state(state, address, keys)
+    }
+
+    /// Returns the state at the given block, or the latest state if no block is given.
+    pub(crate) fn state_at_block_id_or_latest(
+        &self,
+        block_id: Option<BlockId>,
+    ) -> EthResult<StateProviderBox<'_>> {
+        self.state_at_block_id(block_id.unwrap_or(BlockId::Number(BlockNumber::Latest)))
+    }
+
+    /// Returns the state at the given block.
+    ///
+    /// `latest` and `pending` resolve to the latest state, since there is no pending state yet.
+    /// Blocks given by hash must be canonical.
+    pub(crate) fn state_at_block_id(&self, block_id: BlockId) -> EthResult<StateProviderBox<'_>> {
+        match block_id {
+            BlockId::Number(BlockNumber::Latest | BlockNumber::Pending) => {
+                Ok(Box::new(self.client().latest()?))
+            }
+            BlockId::Hash(hash) => Ok(Box::new(self.client().history_by_block_hash(H256(hash.0))?)),
+            BlockId::Number(number) => {
+                let block_number = self
+                    .client()
+                    .convert_block_number(number)?
+                    .ok_or(EthApiError::UnknownBlockNumber)?;
+                if block_number == self.client().chain_info()?.best_number {
+                    return Ok(Box::new(self.client().latest()?))
+                }
+                Ok(Box::new(self.client().history_by_block_number(block_number)?))
+            }
+        }
+    }
+}
+
+/// Builds the [EIP1186AccountProofResponse] of `address` and its storage `keys` from the given
+/// state.
+fn proof_from_state<SP: StateProvider>(
+    state: SP,
+    address: Address,
+    keys: Vec<H256>,
+) -> EthResult<EIP1186AccountProofResponse> {
+    let (account_proof, storage_hash, storage_proofs) = state.proof(address, &keys)?;
+    let account = state.basic_account(address)?.unwrap_or_default();
+
+    let storage_proof = keys
+        .into_iter()
+        .zip(storage_proofs)
+        .map(|(key, proof)| {
+            let value = state.storage(address, key)?.unwrap_or_default();
+            Ok(StorageProof { key: U256::from_be_bytes(key.to_fixed_bytes()), value, proof })
+        })
+        .collect::<EthResult<_>>()?;
+
+    Ok(EIP1186AccountProofResponse {
+        address,
+        balance: account.balance,
+        code_hash: account.bytecode_hash.unwrap_or(KECCAK_EMPTY),
+        nonce: U64::from(account.nonce),
+        storage_hash,
+        account_proof,
+        storage_proof,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::api::EthCallConfig;
+    use reth_db::{
+        database::Database,
+        mdbx::test_utils::create_test_rw_db,
+        models::{AccountBeforeTx, ShardedKey},
+        tables,
+        transaction::{DbTx, DbTxMut},
+        TransitionList,
+    };
+    use reth_interfaces::test_utils::generators::random_block_range;
+    use reth_primitives::{Account, MAINNET};
+    use reth_provider::{insert_canonical_block, ShareableDatabase, FINISH};
+    use std::sync::Arc;
+
+    #[test]
+    fn resolves_state_at_block_id() {
+        let db = create_test_rw_db();
+        let address = Address::from_low_u64_be(1);
+        let account = |nonce| Account { nonce, ..Default::default() };
+
+        // Empty blocks with a block reward, so the state at the end of block `n` is at
+        // transition `n + 1`. The block reward of block 2 bumps the nonce of the account.
+        let blocks = random_block_range(0..3, H256::zero(), 0..1);
+        let tx = db.tx_mut().unwrap();
+        for block in blocks.iter() {
+            insert_canonical_block(&tx, block, true).unwrap();
+        }
+        FINISH.save_progress(&tx, 2).unwrap();
+        tx.put::<tables::AccountChangeSet>(2, AccountBeforeTx { address, info: Some(account(1)) })
+            .unwrap();
+        tx.put::<tables::AccountHistory>(
+            ShardedKey::new(address, u64::MAX),
+            TransitionList::new([2]).unwrap(),
+        )
+        .unwrap();
+        tx.put::<tables::PlainAccountState>(address, account(2)).unwrap();
+        tx.commit().unwrap();
+
+        let api = EthApi::new(
+            Arc::new(ShareableDatabase::new(db)),
+            (),
+            (),
+            MAINNET.clone(),
+            EthCallConfig::default(),
+        );
+        let nonce_at = |block_id| {
+            api.state_at_block_id(block_id).unwrap().basic_account(address).unwrap().unwrap().nonce
+        };
+
+        assert_eq!(nonce_at(BlockId::Number(BlockNumber::Latest)), 2);
+        assert_eq!(nonce_at(BlockId::Number(BlockNumber::Number(2u64.into()))), 2);
+        assert_eq!(nonce_at(BlockId::Number(BlockNumber::Number(1u64.into()))), 1);
+        assert_eq!(nonce_at(BlockId::Hash(blocks[1].hash().0.into())), 1);
+    }
+}
@@ -4,20 +4,76 @@ use crate::{
     eth::error::{EthApiError, EthResult},
     EthApi,
 };
-use reth_primitives::{Bytes, FromRecoveredTransaction, TransactionSigned, H256};
-use reth_provider::{BlockProvider, StateProviderFactory};
+use reth_primitives::{
+    create_address, rpc::BlockId, Bytes, FromRecoveredTransaction, IntoRecoveredTransaction,
+    Receipt, TransactionKind, TransactionSigned, TransactionSignedEcRecovered, H256, U128, U256,
+    U64,
+};
+use reth_provider::{BlockProvider, ReceiptProvider, TransactionMeta, TransactionsProvider};
 use reth_rlp::Decodable;
-use reth_rpc_types::TransactionRequest;
+use reth_rpc_types::{Index, Log, Transaction, TransactionReceipt};
 use reth_transaction_pool::{TransactionOrigin, TransactionPool};
 
 impl<Pool, Client, Network> EthApi<Pool, Client, Network>
 where
     Pool: TransactionPool + 'static,
-    Client: BlockProvider + StateProviderFactory + 'static,
+    Client: BlockProvider + TransactionsProvider + ReceiptProvider + 'static,
     Network: 'static,
 {
-    pub(crate) async fn send_transaction(&self, _request: TransactionRequest) -> EthResult<H256> {
-        unimplemented!()
+    /// Returns the transaction with the given hash.
+    ///
+    /// Pending transactions are looked up in the pool first, then the database is searched for a
+    /// mined transaction.
+    pub(crate) fn transaction_by_hash(&self, hash: H256) -> EthResult<Option<Transaction>> {
+        if let Some(tx) = self.pool().get(&hash) {
+            return Ok(Some(Transaction::from_recovered(tx.transaction.to_recovered_transaction())))
+        }
+
+        let Some((tx, meta)) = self.client().transaction_by_hash_with_meta(hash)? else {
+            return Ok(None)
+        };
+        let tx = TransactionSignedEcRecovered::from_signed_transaction(tx, meta.sender);
+        Ok(Some(Transaction::from_recovered_with_block_context(
+            tx,
+            meta.block_hash,
+            meta.block_number,
+            meta.base_fee,
+            U256::from(meta.index),
+        )))
+    }
+
+    /// Returns the transaction at the given index of the block with the given id.
+    ///
+    /// Returns `None` if the block or the transaction does not exist.
+    pub(crate) fn transaction_by_block_and_index(
+        &self,
+        block_id: BlockId,
+        index: Index,
+    ) -> EthResult<Option<Transaction>> {
+        let Some(block) = self.client().block(block_id)? else { return Ok(None) };
+        let index = usize::from(index);
+        let block_hash = block.header.hash_slow();
+        let Some(tx) = block.body.into_iter().nth(index) else { return Ok(None) };
+        let tx = tx.into_ecrecovered().ok_or(EthApiError::InvalidTransactionSignature)?;
+        Ok(Some(Transaction::from_recovered_with_block_context(
+            tx,
+            block_hash,
+            block.header.number,
+            block.header.base_fee_per_gas,
+            U256::from(index),
+        )))
+    }
+
+    /// Returns the receipt of the mined transaction with the given hash.
+    ///
+    /// Returns `None` if the transaction is unknown or still pending.
+    pub(crate) fn transaction_receipt(&self, hash: H256) -> EthResult<Option<TransactionReceipt>> {
+        let Some((tx, meta)) = self.client().transaction_by_hash_with_meta(hash)? else {
+            return Ok(None)
+        };
+        let block_id = BlockId::Hash(meta.block_hash.0.into());
+        let Some(receipts) = self.client().receipts_by_block(block_id)? else { return Ok(None) };
+        Ok(build_transaction_receipt(tx, meta, &receipts))
     }
 
     /// Decodes and recovers the transaction and submits it to the pool.
@@ -43,3 +99,126 @@ where
         Ok(hash)
     }
 }
+
+/// Builds the RPC receipt of the transaction from the receipts of the block it was mined in.
+///
+/// The receipts of the preceding transactions are required to compute the gas used by this
+/// transaction and the block-wide index of its logs.
+fn build_transaction_receipt(
+    tx: TransactionSigned,
+    meta: TransactionMeta,
+    receipts: &[Receipt],
+) -> Option<TransactionReceipt> {
+    let index = meta.index as usize;
+    let receipt = receipts.get(index)?;
+    let prev_cumulative_gas_used =
+        receipts[..index].last().map(|prev| prev.cumulative_gas_used).unwrap_or_default();
+    let first_log_index: usize = receipts[..index].iter().map(|prev| prev.logs.len()).sum();
+
+    let (to, contract_address) = match tx.kind() {
+        TransactionKind::Create => (None, Some(create_address(meta.sender, tx.nonce()))),
+        TransactionKind::Call(to) => (Some(*to), None),
+    };
+
+    let transaction_hash = tx.hash();
+    let transaction_index = U256::from(meta.index);
+    let block_hash = Some(meta.block_hash);
+    let block_number = Some(U256::from(meta.block_number));
+
+    let logs = receipt
+        .logs
+        .iter()
+        .enumerate()
+        .map(|(tx_log_index, log)| Log {
+            address: log.address,
+            topics: log.topics.clone(),
+            data: log.data.clone(),
+            block_hash,
+            block_number,
+            transaction_hash: Some(transaction_hash),
+            transaction_index: Some(transaction_index),
+            log_index: Some(U256::from(first_log_index + tx_log_index)),
+            transaction_log_index: Some(U256::from(tx_log_index)),
+            removed: false,
+        })
+        .collect();
+
+    Some(TransactionReceipt {
+        transaction_hash: Some(transaction_hash),
+        transaction_index: Some(transaction_index),
+        block_hash,
+        block_number,
+        from: meta.sender,
+        to,
+        cumulative_gas_used: U256::from(receipt.cumulative_gas_used),
+        gas_used: Some(U256::from(receipt.cumulative_gas_used - prev_cumulative_gas_used)),
+        contract_address,
+        logs,
+        state_root: None,
+        logs_bloom: receipt.bloom,
+        status_code: Some(U64::from(receipt.success as u64)),
+        effective_gas_price: U128::from(tx.effective_gas_price(meta.base_fee)),
+        transaction_type: U256::from(receipt.tx_type as u8),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Address, Log as PrimitiveLog, Signature, Transaction as Tx, TxLegacy};
+
+    #[test]
+    fn builds_receipt_from_block_receipts() {
+        let to = Address::from_low_u64_be(1);
+        let tx = TransactionSigned::from_transaction_and_signature(
+            Tx::Legacy(TxLegacy { to: TransactionKind::Call(to), ..Default::default() }),
+            Signature::default(),
+        );
+        let log = |address| PrimitiveLog { address, ..Default::default() };
+        let receipts = vec![
+            Receipt {
+                cumulative_gas_used: 21_000,
+                logs: vec![log(to), log(to)],
+                ..Default::default()
+            },
+            Receipt { cumulative_gas_used: 50_000, logs: vec![log(to)], ..Default::default() },
+            Receipt {
+                success: true,
+                cumulative_gas_used: 80_000,
+                logs: vec![log(to), log(to)],
+                ..Default::default()
+            },
+        ];
+        let meta = TransactionMeta {
+            tx_id: 12,
+            sender: Address::from_low_u64_be(2),
+            index: 1,
+            block_hash: H256::from_low_u64_be(3),
+            block_number: 7,
+            base_fee: None,
+        };
+
+        let receipt = build_transaction_receipt(tx.clone(), meta, &receipts).unwrap();
+        assert_eq!(receipt.transaction_hash, Some(tx.hash()));
+        assert_eq!(receipt.to, Some(to));
+        assert_eq!(receipt.contract_address, None);
+        assert_eq!(receipt.cumulative_gas_used, U256::from(50_000));
+        assert_eq!(receipt.gas_used, Some(U256::from(29_000)));
+        assert_eq!(receipt.status_code, Some(U64::zero()));
+        assert_eq!(receipt.logs.len(), 1);
+        assert_eq!(receipt.logs[0].log_index, Some(U256::from(2)));
+        assert_eq!(receipt.logs[0].transaction_log_index, Some(U256::from(0)));
+        assert_eq!(receipt.logs[0].block_number, Some(U256::from(7)));
+
+        let meta = TransactionMeta { index: 2, ..meta };
+        let receipt = build_transaction_receipt(tx.clone(), meta, &receipts).unwrap();
+        assert_eq!(receipt.gas_used, Some(U256::from(30_000)));
+        assert_eq!(receipt.status_code, Some(U64::from(1)));
+        let log_indexes = receipt.logs.iter().map(|log| log.log_index).collect::<Vec<_>>();
+        assert_eq!(log_indexes, vec![Some(U256::from(3)), Some(U256::from(4))]);
+
+        // the receipt of the transaction is missing
+        assert!(build_transaction_receipt(tx, TransactionMeta { index: 3, ..meta }, &receipts)
+            .is_none());
+    }
+}
@@ -1,7 +1,11 @@
 //! Error variants for the `eth_` namespace.
 
 use crate::{impl_to_rpc_result, result::ToRpcResult};
+use reth_primitives::{Address, Bytes};
+use reth_rpc_types::BlockError;
 use reth_transaction_pool::error::PoolError;
+use revm::Return;
+use std::time::Duration;
 
 /// Result alias
 pub(crate) type EthResult<T> = Result<T, EthApiError>;
@@ -19,6 +23,50 @@ pub(crate) enum EthApiError {
     InvalidTransactionSignature,
     #[error(transparent)]
     PoolError(GethCompatPoolError),
+    #[error("Unknown block number")]
+    UnknownBlockNumber,
+    /// Thrown when a call request sets both the legacy and the EIP-1559 fee fields
+    #[error("both gasPrice and (maxFeePerGas or maxPriorityFeePerGas) specified")]
+    ConflictingFeeFieldsInRequest,
+    /// Thrown when a state override sets both the full storage and individual slots
+    #[error("account {0:?} has both 'state' and 'stateDiff'")]
+    BothStateAndStateDiffInOverride(Address),
+    #[error("insufficient funds for gas * price + value")]
+    InsufficientFunds,
+    #[error("max fee per gas less than block base fee")]
+    FeeCapTooLow,
+    #[error("gas required exceeds allowance ({0})")]
+    GasRequiredExceedsAllowance(u64),
+    /// Thrown when a call reverted, contains the revert output
+    #[error("execution reverted: {0}")]
+    Revert(Bytes),
+    /// Thrown when a call halted with an EVM error
+    #[error("EVM error {0:?}")]
+    EvmHalt(Return),
+    #[error("fatal error in the EVM database")]
+    EvmFatal,
+    /// Thrown when a call exceeded the configured timeout
+    #[error("execution aborted (timeout = {0:?})")]
+    ExecutionTimedOut(Duration),
+    /// Thrown when the task executing a call panicked or was cancelled
+    #[error("call execution failed")]
+    ExecutionTaskFailed,
+    /// Thrown when the access list of a call does not converge
+    #[error("access list did not stabilize")]
+    AccessListNotStable,
+    #[error("filter not found")]
+    FilterNotFound,
+    /// Thrown when the start of a log query is after its end
+    #[error("invalid block range params")]
+    InvalidBlockRange,
+    #[error("query exceeds max block range {0}")]
+    QueryExceedsBlockRange(u64),
+    #[error("query exceeds max results {0}")]
+    QueryExceedsMaxLogs(usize),
+    #[error(transparent)]
+    InvalidBlockData(#[from] BlockError),
+    #[error(transparent)]
+    Internal(#[from] reth_interfaces::Error),
 }
 
 impl_to_rpc_result!(EthApiError);
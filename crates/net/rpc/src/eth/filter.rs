@@ -0,0 +1,558 @@
+//! `eth_` filter RPC handler implementation
+
+use crate::{
+    eth::error::{EthApiError, EthResult},
+    result::ToRpcResult,
+};
+use jsonrpsee::core::RpcResult;
+use parking_lot::{Mutex, MutexGuard};
+use reth_primitives::{
+    rpc::{BlockId, BlockNumber, Filter, FilterBlockOption, ValueOrArray},
+    H160, H256, U256,
+};
+use reth_provider::{BlockProvider, LogIndexProvider, ReceiptProvider, TransactionsProvider};
+use reth_rpc_api::EthFilterApiServer;
+use reth_rpc_types::{FilterChanges, Index, Log};
+use reth_transaction_pool::TransactionPool;
+use std::{
+    collections::{BTreeSet, HashMap},
+    ops::RangeInclusive,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use tokio::sync::mpsc::Receiver;
+
+/// Settings for the `eth_` filter handlers.
+#[derive(Debug, Clone)]
+pub struct EthFilterConfig {
+    /// Maximum number of blocks a single log query can span.
+    pub max_block_range: u64,
+    /// Maximum number of logs a single log query can return.
+    pub max_logs: usize,
+    /// Filters that were not polled for this long are uninstalled.
+    pub stale_filter_ttl: Duration,
+}
+
+impl Default for EthFilterConfig {
+    fn default() -> Self {
+        Self {
+            max_block_range: 10_000,
+            max_logs: 20_000,
+            stale_filter_ttl: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+/// `Eth` filter RPC implementation.
+///
+/// Installed filters are kept in memory and removed once they were not polled for
+/// [`EthFilterConfig::stale_filter_ttl`]. Stale filters are removed whenever a filter is
+/// installed, polled or uninstalled.
+#[derive(Debug)]
+pub struct EthFilter<Pool, Client> {
+    /// All nested fields bundled together.
+    inner: Arc<EthFilterInner<Pool, Client>>,
+}
+
+impl<Pool, Client> Clone for EthFilter<Pool, Client> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+// === impl EthFilter ===
+
+impl<Pool, Client> EthFilter<Pool, Client> {
+    /// Creates a new, shareable instance.
+    pub fn new(client: Arc<Client>, pool: Pool, config: EthFilterConfig) -> Self {
+        let inner = EthFilterInner {
+            client,
+            pool,
+            config,
+            active_filters: Default::default(),
+            id_counter: AtomicUsize::new(1),
+        };
+        Self { inner: Arc::new(inner) }
+    }
+}
+
+impl<Pool, Client> EthFilter<Pool, Client>
+where
+    Pool: TransactionPool + 'static,
+    Client: BlockProvider + ReceiptProvider + TransactionsProvider + LogIndexProvider + 'static,
+{
+    /// Installs the filter and returns its id.
+    ///
+    /// The filter only reports changes after the current best block.
+    fn install_filter(&self, kind: FilterKind) -> EthResult<U256> {
+        let block = self.inner.client.chain_info()?.best_number;
+        let id = self.inner.id_counter.fetch_add(1, Ordering::Relaxed);
+
+        self.active_filters().insert(id, ActiveFilter { kind, block, last_poll: Instant::now() });
+        Ok(U256::from(id))
+    }
+
+    /// Locks the installed filters and uninstalls the filters that were not polled for
+    /// [`EthFilterConfig::stale_filter_ttl`].
+    fn active_filters(&self) -> MutexGuard<'_, HashMap<usize, ActiveFilter>> {
+        let mut filters = self.inner.active_filters.lock();
+        let ttl = self.inner.config.stale_filter_ttl;
+        filters.retain(|_, filter| filter.last_poll.elapsed() < ttl);
+        filters
+    }
+
+    /// Returns the changes of the filter since the last poll.
+    ///
+    /// A poll covers at most [`EthFilterConfig::max_block_range`] blocks, the remaining blocks are
+    /// reported by the next polls.
+    fn filter_changes(&self, id: usize) -> EthResult<FilterChanges> {
+        let best_number = self.inner.client.chain_info()?.best_number;
+
+        let (filter, from_block) = {
+            let mut filters = self.active_filters();
+            let active = filters.get_mut(&id).ok_or(EthApiError::FilterNotFound)?;
+            active.last_poll = Instant::now();
+            let from_block = active.block + 1;
+            let filter = match &mut active.kind {
+                FilterKind::PendingTransaction(receiver) => {
+                    let mut hashes = Vec::new();
+                    while let Ok(hash) = receiver.try_recv() {
+                        hashes.push(hash);
+                    }
+                    return Ok(FilterChanges::Hashes(hashes))
+                }
+                FilterKind::Block => None,
+                FilterKind::Log(filter) => Some(filter.clone()),
+            };
+            (filter, from_block)
+        };
+        let to_block = best_number
+            .min(from_block.saturating_add(self.inner.config.max_block_range.saturating_sub(1)));
+
+        let changes = match filter {
+            None => {
+                let mut hashes = Vec::new();
+                for number in from_block..=to_block {
+                    if let Some(hash) = self.inner.client.block_hash(U256::from(number))? {
+                        hashes.push(hash);
+                    }
+                }
+                FilterChanges::Hashes(hashes)
+            }
+            Some(filter) => self.log_changes(&filter, from_block, to_block, best_number)?,
+        };
+
+        // only mark the blocks as reported once the changes were collected, so a failed poll can
+        // be retried
+        if let Some(active) = self.inner.active_filters.lock().get_mut(&id) {
+            active.block = active.block.max(to_block);
+        }
+        Ok(changes)
+    }
+
+    /// Returns the logs matching the filter in the blocks `from_block..=to_block`.
+    fn log_changes(
+        &self,
+        filter: &Filter,
+        from_block: u64,
+        to_block: u64,
+        best_number: u64,
+    ) -> EthResult<FilterChanges> {
+        let FilterBlockOption::Range { from_block: start, to_block: end } = filter.block_option
+        else {
+            // a filter for a single block has no changes
+            return Ok(FilterChanges::Empty)
+        };
+        let start = self.resolve_block_number(start, best_number)?.max(from_block);
+        let end = self.resolve_block_number(end, best_number)?.min(to_block);
+        if start > end {
+            return Ok(FilterChanges::Empty)
+        }
+        let logs = self.logs_in_range(&LogMatcher::new(filter), start..=end)?;
+        Ok(FilterChanges::Logs(logs))
+    }
+
+    /// Returns all logs that match the log filter with the given id.
+    fn filter_logs(&self, id: usize) -> EthResult<Vec<Log>> {
+        let filter = {
+            let mut filters = self.active_filters();
+            let active = filters.get_mut(&id).ok_or(EthApiError::FilterNotFound)?;
+            active.last_poll = Instant::now();
+            match &active.kind {
+                FilterKind::Log(filter) => filter.clone(),
+                _ => return Err(EthApiError::FilterNotFound),
+            }
+        };
+        self.logs(&filter)
+    }
+
+    /// Returns all logs that match the filter.
+    fn logs(&self, filter: &Filter) -> EthResult<Vec<Log>> {
+        let matcher = LogMatcher::new(filter);
+        match filter.block_option {
+            FilterBlockOption::AtBlockHash(hash) => {
+                let hash = H256(hash.0);
+                let number =
+                    self.inner.client.block_number(hash)?.ok_or(EthApiError::UnknownBlockNumber)?;
+                let mut logs = Vec::new();
+                append_block_logs(&*self.inner.client, &matcher, number, hash, &mut logs)?;
+                Ok(logs)
+            }
+            FilterBlockOption::Range { from_block, to_block } => {
+                let best_number = self.inner.client.chain_info()?.best_number;
+                let start = self.resolve_block_number(from_block, best_number)?;
+                let end = self.resolve_block_number(to_block, best_number)?;
+                if start > end {
+                    return Err(EthApiError::InvalidBlockRange)
+                }
+                self.logs_in_range(&matcher, start..=end)
+            }
+        }
+    }
+
+    /// Resolves a block number of a filter, which defaults to the best block.
+    fn resolve_block_number(
+        &self,
+        number: Option<BlockNumber>,
+        best_number: u64,
+    ) -> EthResult<u64> {
+        match number {
+            None | Some(BlockNumber::Latest | BlockNumber::Pending) => Ok(best_number),
+            Some(number) => Ok(self
+                .inner
+                .client
+                .convert_block_number(number)?
+                .ok_or(EthApiError::UnknownBlockNumber)?),
+        }
+    }
+
+    /// Returns all logs in the canonical blocks of the range that match.
+    ///
+    /// If the filter restricts addresses or topics, only the blocks found in the log indices are
+    /// scanned.
+    fn logs_in_range(
+        &self,
+        matcher: &LogMatcher,
+        range: RangeInclusive<u64>,
+    ) -> EthResult<Vec<Log>> {
+        let max_block_range = self.inner.config.max_block_range;
+        if range.end() - range.start() >= max_block_range {
+            return Err(EthApiError::QueryExceedsBlockRange(max_block_range))
+        }
+
+        let blocks = match matcher.indexed_blocks(&*self.inner.client, range.clone())? {
+            Some(blocks) => blocks.into_iter().collect(),
+            None => range.collect::<Vec<_>>(),
+        };
+
+        let mut logs = Vec::new();
+        for number in blocks {
+            let Some(hash) = self.inner.client.block_hash(U256::from(number))? else { continue };
+            append_block_logs(&*self.inner.client, matcher, number, hash, &mut logs)?;
+            if logs.len() > self.inner.config.max_logs {
+                return Err(EthApiError::QueryExceedsMaxLogs(self.inner.config.max_logs))
+            }
+        }
+        Ok(logs)
+    }
+}
+
+#[async_trait::async_trait]
+impl<Pool, Client> EthFilterApiServer for EthFilter<Pool, Client>
+where
+    Pool: TransactionPool + 'static,
+    Client: BlockProvider + ReceiptProvider + TransactionsProvider + LogIndexProvider + 'static,
+{
+    fn new_filter(&self, filter: Filter) -> RpcResult<U256> {
+        self.install_filter(FilterKind::Log(Box::new(filter))).to_rpc_result()
+    }
+
+    fn new_block_filter(&self) -> RpcResult<U256> {
+        self.install_filter(FilterKind::Block).to_rpc_result()
+    }
+
+    fn new_pending_transaction_filter(&self) -> RpcResult<U256> {
+        let receiver = self.inner.pool.pending_transactions_listener();
+        self.install_filter(FilterKind::PendingTransaction(receiver)).to_rpc_result()
+    }
+
+    async fn filter_changes(&self, index: Index) -> RpcResult<FilterChanges> {
+        EthFilter::filter_changes(self, index.into()).to_rpc_result()
+    }
+
+    async fn filter_logs(&self, index: Index) -> RpcResult<Vec<Log>> {
+        EthFilter::filter_logs(self, index.into()).to_rpc_result()
+    }
+
+    fn uninstall_filter(&self, index: Index) -> RpcResult<bool> {
+        Ok(self.active_filters().remove(&usize::from(index)).is_some())
+    }
+
+    async fn logs(&self, filter: Filter) -> RpcResult<Vec<Log>> {
+        EthFilter::logs(self, &filter).to_rpc_result()
+    }
+}
+
+/// Container type `EthFilter`
+#[derive(Debug)]
+struct EthFilterInner<Pool, Client> {
+    /// The transaction pool.
+    pool: Pool,
+    /// The client that can interact with the chain.
+    client: Arc<Client>,
+    /// Settings for the filter handlers.
+    config: EthFilterConfig,
+    /// All currently installed filters.
+    active_filters: Mutex<HashMap<usize, ActiveFilter>>,
+    /// The id of the next filter.
+    id_counter: AtomicUsize,
+}
+
+/// An installed filter.
+#[derive(Debug)]
+struct ActiveFilter {
+    /// What the filter reports.
+    kind: FilterKind,
+    /// The highest block that was already reported.
+    block: u64,
+    /// The last time the filter was polled.
+    last_poll: Instant,
+}
+
+/// The kind of an installed filter.
+#[derive(Debug)]
+enum FilterKind {
+    /// Reports logs of new blocks.
+    Log(Box<Filter>),
+    /// Reports the hashes of new blocks.
+    Block,
+    /// Reports the hashes of new pending transactions.
+    PendingTransaction(Receiver<H256>),
+}
+
+/// Appends the logs of the canonical block that match.
+///
+/// Does nothing if the block is not canonical.
+pub(crate) fn append_block_logs<Client>(
+    client: &Client,
+    matcher: &LogMatcher,
+    number: u64,
+    hash: H256,
+    logs: &mut Vec<Log>,
+) -> EthResult<()>
+where
+    Client: ReceiptProvider + TransactionsProvider,
+{
+    let block_id = BlockId::Number(BlockNumber::Number(number.into()));
+    let (Some(receipts), Some(transactions)) =
+        (client.receipts_by_block(block_id)?, client.transactions_by_block(block_id)?)
+    else {
+        return Ok(())
+    };
+
+    let mut log_index = 0;
+    for (tx_index, (receipt, transaction)) in receipts.iter().zip(transactions).enumerate() {
+        for (tx_log_index, log) in receipt.logs.iter().enumerate() {
+            if matcher.matches(log) {
+                logs.push(Log {
+                    address: log.address,
+                    topics: log.topics.clone(),
+                    data: log.data.clone(),
+                    block_hash: Some(hash),
+                    block_number: Some(U256::from(number)),
+                    transaction_hash: Some(transaction.hash()),
+                    transaction_index: Some(U256::from(tx_index)),
+                    log_index: Some(U256::from(log_index)),
+                    transaction_log_index: Some(U256::from(tx_log_index)),
+                    removed: false,
+                });
+            }
+            log_index += 1;
+        }
+    }
+    Ok(())
+}
+
+/// The address and topic criteria of a [Filter].
+///
+/// A criterion set to `None` matches any value.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LogMatcher {
+    /// The log must be emitted by one of the addresses.
+    addresses: Option<Vec<H160>>,
+    /// The topic at each position must be one of the given topics.
+    topics: [Option<Vec<H256>>; 4],
+}
+
+impl LogMatcher {
+    /// Converts the criteria of the filter. Empty lists match any value.
+    pub(crate) fn new(filter: &Filter) -> Self {
+        let addresses = filter
+            .address
+            .as_ref()
+            .map(|address| match address {
+                ValueOrArray::Value(address) => vec![H160(address.0)],
+                ValueOrArray::Array(addresses) => {
+                    addresses.iter().map(|address| H160(address.0)).collect()
+                }
+            })
+            .filter(|addresses| !addresses.is_empty());
+
+        let topics = filter.topics.clone().map(|topic| {
+            match topic? {
+                ValueOrArray::Value(topic) => topic.map(|topic| vec![H256(topic.0)]),
+                // a wildcard in the list matches any topic
+                ValueOrArray::Array(topics) => topics
+                    .into_iter()
+                    .map(|topic| topic.map(|topic| H256(topic.0)))
+                    .collect::<Option<Vec<_>>>(),
+            }
+            .filter(|topics| !topics.is_empty())
+        });
+
+        Self { addresses, topics }
+    }
+
+    /// Returns true if the log matches all criteria.
+    pub(crate) fn matches(&self, log: &reth_primitives::Log) -> bool {
+        if let Some(addresses) = &self.addresses {
+            if !addresses.contains(&log.address) {
+                return false
+            }
+        }
+        self.topics.iter().enumerate().all(|(position, topics)| match topics {
+            Some(topics) => log.topics.get(position).map_or(false, |topic| topics.contains(topic)),
+            None => true,
+        })
+    }
+
+    /// Returns the blocks of the range that may contain matching logs according to the log
+    /// indices, or `None` if the matcher has no criteria to look up.
+    fn indexed_blocks(
+        &self,
+        client: &impl LogIndexProvider,
+        range: RangeInclusive<u64>,
+    ) -> EthResult<Option<BTreeSet<u64>>> {
+        let mut candidates: Option<BTreeSet<u64>> = None;
+        let mut intersect = |blocks: BTreeSet<u64>| {
+            candidates = Some(match candidates.take() {
+                Some(candidates) => candidates.intersection(&blocks).copied().collect(),
+                None => blocks,
+            });
+        };
+
+        if let Some(addresses) = &self.addresses {
+            let mut blocks = BTreeSet::new();
+            for address in addresses {
+                blocks.extend(client.blocks_with_log_address(*address, range.clone())?);
+            }
+            intersect(blocks);
+        }
+        for topics in self.topics.iter().flatten() {
+            let mut blocks = BTreeSet::new();
+            for topic in topics {
+                blocks.extend(client.blocks_with_log_topic(*topic, range.clone())?);
+            }
+            intersect(blocks);
+        }
+
+        Ok(candidates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{
+        rpc::{self, Filter},
+        Block, Header,
+    };
+    use reth_provider::test_utils::MockEthProvider;
+    use reth_transaction_pool::test_utils::testing_pool;
+
+    /// Adds empty blocks with the given numbers and returns their hashes.
+    fn add_blocks(client: &MockEthProvider, numbers: RangeInclusive<u64>) -> Vec<H256> {
+        numbers
+            .map(|number| {
+                let hash = H256::random();
+                let header = Header { number, ..Default::default() };
+                client.add_block(hash, Block { header, ..Default::default() });
+                hash
+            })
+            .collect()
+    }
+
+    fn log(address: u64, topics: &[u64]) -> reth_primitives::Log {
+        reth_primitives::Log {
+            address: H160::from_low_u64_be(address),
+            topics: topics.iter().map(|topic| H256::from_low_u64_be(*topic)).collect(),
+            data: Default::default(),
+        }
+    }
+
+    #[test]
+    fn match_logs() {
+        let any = LogMatcher::new(&Filter::new());
+        assert!(any.matches(&log(1, &[])));
+        assert!(any.matches(&log(2, &[1, 2])));
+
+        let filter = Filter::new()
+            .address(vec![rpc::H160::from_low_u64_be(1), rpc::H160::from_low_u64_be(2)])
+            .topic1(rpc::H256::from_low_u64_be(5));
+        let matcher = LogMatcher::new(&filter);
+        assert!(matcher.matches(&log(1, &[9, 5])));
+        assert!(matcher.matches(&log(2, &[8, 5, 7])));
+        assert!(!matcher.matches(&log(3, &[9, 5])));
+        assert!(!matcher.matches(&log(1, &[5])));
+        assert!(!matcher.matches(&log(1, &[5, 9])));
+    }
+
+    #[test]
+    fn poll_backlog_larger_than_block_range() {
+        let client = MockEthProvider::default();
+        add_blocks(&client, 0..=0);
+        let config = EthFilterConfig { max_block_range: 2, ..Default::default() };
+        let filter = EthFilter::new(Arc::new(client.clone()), testing_pool(), config);
+        let blocks = filter.install_filter(FilterKind::Block).unwrap().to::<usize>();
+        let log_filter = Filter::new().from_block(0);
+        let logs =
+            filter.install_filter(FilterKind::Log(Box::new(log_filter))).unwrap().to::<usize>();
+
+        // the chain advanced by more blocks than a single query may span
+        let hashes = add_blocks(&client, 1..=5);
+        for expected in [&hashes[..2], &hashes[2..4], &hashes[4..], &[]] {
+            assert_eq!(
+                filter.filter_changes(blocks).unwrap(),
+                FilterChanges::Hashes(expected.to_vec())
+            );
+        }
+        for _ in 0..3 {
+            assert_eq!(filter.filter_changes(logs).unwrap(), FilterChanges::Logs(Vec::new()));
+        }
+        assert_eq!(filter.inner.active_filters.lock()[&logs].block, 5);
+    }
+
+    #[test]
+    fn stale_filters_are_removed_on_poll() {
+        let client = MockEthProvider::default();
+        add_blocks(&client, 0..=0);
+        let config = EthFilterConfig { stale_filter_ttl: Duration::ZERO, ..Default::default() };
+        let filter = EthFilter::new(Arc::new(client), testing_pool(), config);
+        let id = filter.install_filter(FilterKind::Block).unwrap().to::<usize>();
+        assert_eq!(filter.inner.active_filters.lock().len(), 1);
+
+        // polling another filter removes the abandoned one
+        assert!(filter.filter_changes(id + 1).is_err());
+        assert!(filter.inner.active_filters.lock().is_empty());
+    }
+
+    #[test]
+    fn wildcards_are_not_indexed() {
+        let matcher = LogMatcher::new(&Filter::new().address(Vec::<rpc::H160>::new()));
+        assert!(matcher.addresses.is_none());
+        assert!(matcher.topics.iter().all(Option::is_none));
+    }
+}
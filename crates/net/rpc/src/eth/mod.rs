@@ -2,8 +2,10 @@
 
 mod api;
 pub(crate) mod error;
+mod filter;
 mod pubsub;
 mod signer;
 
-pub use api::{EthApi, EthApiSpec};
+pub use api::{EthApi, EthApiSpec, EthCallConfig};
+pub use filter::{EthFilter, EthFilterConfig};
 pub use pubsub::EthPubSub;
@@ -1,63 +1,517 @@
 //! `eth_` PubSub RPC handler implementation
 
-use jsonrpsee::{types::SubscriptionResult, SubscriptionSink};
-use reth_provider::BlockProvider;
+use crate::eth::filter::{append_block_logs, LogMatcher};
+use jsonrpsee::{
+    core::to_json_raw_value,
+    types::{error::INTERNAL_ERROR_CODE, ErrorObject, SubscriptionResult},
+    SubscriptionSink,
+};
+use reth_interfaces::{
+    events::{ChainEvent, ChainEventSubscriptions},
+    sync::SyncStateProvider,
+};
+use reth_primitives::{BlockNumber, U256};
+use reth_provider::{BlockProvider, HeaderProvider, ReceiptProvider, TransactionsProvider};
 use reth_rpc_api::EthPubSubApiServer;
-use reth_rpc_types::pubsub::{Kind, Params};
+use reth_rpc_types::{
+    pubsub::{self, Kind, Params, PubSubSyncStatus, SyncStatusMetadata},
+    Header, Log,
+};
+use reth_tasks::TaskExecutor;
 use reth_transaction_pool::TransactionPool;
-use std::sync::Arc;
+use std::{
+    collections::VecDeque,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::warn;
+
+/// How often the sync status is checked for `syncing` subscriptions.
+const SYNC_STATUS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The maximum number of notification bytes a subscription may have queued, see [Subscriber].
+const SUBSCRIPTION_BUFFER_SIZE: usize = 16 * 1024 * 1024;
+
+/// The number of bytes per second a subscriber is expected to consume, see [Subscriber].
+const SUBSCRIPTION_DRAIN_RATE: usize = 1024 * 1024;
+
+/// The number of most recent blocks whose logs are reported as removed if the blocks are unwound.
+const REMOVED_LOGS_DEPTH: BlockNumber = 64;
 
 /// `Eth` pubsub RPC implementation.
 ///
-/// This handles
-#[derive(Debug, Clone)]
-pub struct EthPubSub<Pool, Client> {
+/// This handles `eth_subscribe` calls. Every accepted subscription is served by its own task.
+///
+/// Header and log notifications are driven by the [ChainEvent]s of the canonical chain. A
+/// subscription task that can not keep up with the chain skips the events it missed instead of
+/// holding back the sender, see [ChainEventSubscriptions::subscribe_chain_events].
+///
+/// The notifications sent to the [SubscriptionSink] are queued by the server until the connection
+/// consumes them. A subscription that queues more than [SUBSCRIPTION_BUFFER_SIZE] bytes is closed,
+/// see [Subscriber].
+///
+/// Logs of blocks that are unwound are sent again with `removed: true`, if they were sent for one
+/// of the last [REMOVED_LOGS_DEPTH] blocks.
+#[derive(Debug)]
+pub struct EthPubSub<Pool, Client, Events, Network> {
     /// All nested fields bundled together.
-    inner: Arc<EthPubSubInner<Pool, Client>>,
+    inner: Arc<EthPubSubInner<Pool, Client, Events, Network>>,
+}
+
+impl<Pool, Client, Events, Network> Clone for EthPubSub<Pool, Client, Events, Network> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
 }
 
 // === impl EthPubSub ===
 
-impl<Pool, Client> EthPubSub<Pool, Client> {
+impl<Pool, Client, Events, Network> EthPubSub<Pool, Client, Events, Network> {
     /// Creates a new, shareable instance.
-    pub fn new(client: Arc<Client>, pool: Pool) -> Self {
-        let inner = EthPubSubInner { client, pool };
+    pub fn new(
+        client: Arc<Client>,
+        pool: Pool,
+        chain_events: Events,
+        network: Network,
+        executor: TaskExecutor,
+    ) -> Self {
+        let inner = EthPubSubInner { pool, client, chain_events, network, executor };
         Self { inner: Arc::new(inner) }
     }
 }
 
-impl<Pool, Client> EthPubSubApiServer for EthPubSub<Pool, Client>
+impl<Pool, Client, Events, Network> EthPubSubApiServer for EthPubSub<Pool, Client, Events, Network>
 where
     Pool: TransactionPool + 'static,
-    Client: BlockProvider + 'static,
+    Client: BlockProvider + HeaderProvider + ReceiptProvider + TransactionsProvider + 'static,
+    Events: ChainEventSubscriptions + 'static,
+    Network: SyncStateProvider + 'static,
 {
     fn subscribe(
         &self,
         mut sink: SubscriptionSink,
-        _kind: Kind,
-        _params: Option<Params>,
+        kind: Kind,
+        params: Option<Params>,
     ) -> SubscriptionResult {
         sink.accept()?;
-        todo!()
+
+        let pubsub = Arc::clone(&self.inner);
+        self.inner.executor.spawn(async move {
+            handle_accepted(pubsub, sink, kind, params).await;
+        });
+
+        Ok(())
     }
 }
 
 /// The actual handler for and accepted [`EthPubSub::subscribe`] call.
-async fn handle_accepted<Pool, Client>(
-    _pool: Pool,
-    _client: Arc<Client>,
-    _accepted_sink: SubscriptionSink,
-    _kind: Kind,
-    _params: Option<Params>,
-) {
+///
+/// Returns once the subscription is closed. A subscription that exceeds its buffer is closed with
+/// an error.
+async fn handle_accepted<Pool, Client, Events, Network>(
+    pubsub: Arc<EthPubSubInner<Pool, Client, Events, Network>>,
+    accepted_sink: SubscriptionSink,
+    kind: Kind,
+    params: Option<Params>,
+) where
+    Pool: TransactionPool + 'static,
+    Client: BlockProvider + HeaderProvider + ReceiptProvider + TransactionsProvider + 'static,
+    Events: ChainEventSubscriptions + 'static,
+    Network: SyncStateProvider + 'static,
+{
+    let mut subscriber = Subscriber::new(accepted_sink);
+    if let Err(SubscriptionStopped::BufferExceeded) =
+        serve_subscription(&pubsub, &mut subscriber, kind, params).await
+    {
+        warn!(target: "rpc::eth::pubsub", "Closing subscription that exceeded its buffer");
+        subscriber.sink.close(ErrorObject::owned(
+            INTERNAL_ERROR_CODE,
+            "subscription buffer exceeded",
+            None::<()>,
+        ));
+    }
+}
+
+/// Sends the notifications of the subscription until it is stopped.
+async fn serve_subscription<Pool, Client, Events, Network>(
+    pubsub: &EthPubSubInner<Pool, Client, Events, Network>,
+    subscriber: &mut Subscriber,
+    kind: Kind,
+    params: Option<Params>,
+) -> Result<(), SubscriptionStopped>
+where
+    Pool: TransactionPool + 'static,
+    Client: BlockProvider + HeaderProvider + ReceiptProvider + TransactionsProvider + 'static,
+    Events: ChainEventSubscriptions + 'static,
+    Network: SyncStateProvider + 'static,
+{
+    match kind {
+        Kind::NewHeads => {
+            let mut events = pubsub.chain_events.subscribe_chain_events();
+            while let Some(event) = next_chain_event(&mut events).await {
+                // unwound blocks are replaced by the heads of the new chain
+                let ChainEvent::Extended { first, tip } = event else { continue };
+                for number in first..=tip {
+                    let Ok(Some(header)) = pubsub.client.header_by_number(number) else { continue };
+                    let header = Header::from_primitive_with_hash(header.seal());
+                    let item = pubsub::SubscriptionResult::Header(Box::new(header.into()));
+                    subscriber.send(&item)?;
+                }
+            }
+        }
+        Kind::Logs => {
+            let matcher = match params {
+                Some(Params::Logs(filter)) => LogMatcher::new(&filter),
+                _ => LogMatcher::default(),
+            };
+            // the logs sent for the most recent blocks, to report them as removed on an unwind
+            let mut sent_logs = VecDeque::<(BlockNumber, Vec<Log>)>::new();
+            let mut events = pubsub.chain_events.subscribe_chain_events();
+            while let Some(event) = next_chain_event(&mut events).await {
+                match event {
+                    ChainEvent::Extended { first, tip } => {
+                        // blocks that are replaced without an unwind event the subscriber received
+                        send_removed_logs(subscriber, &mut sent_logs, first.saturating_sub(1))?;
+                        for number in first..=tip {
+                            let Ok(Some(hash)) = pubsub.client.block_hash(U256::from(number))
+                            else {
+                                continue
+                            };
+                            let mut logs = Vec::new();
+                            if append_block_logs(&*pubsub.client, &matcher, number, hash, &mut logs)
+                                .is_err()
+                            {
+                                continue
+                            }
+                            for log in logs.iter() {
+                                let item = pubsub::SubscriptionResult::Log(Box::new(log.clone()));
+                                subscriber.send(&item)?;
+                            }
+                            sent_logs.push_back((number, logs));
+                        }
+                        while sent_logs
+                            .front()
+                            .map_or(false, |(number, _)| number + REMOVED_LOGS_DEPTH <= tip)
+                        {
+                            sent_logs.pop_front();
+                        }
+                    }
+                    ChainEvent::Unwound { tip } => {
+                        send_removed_logs(subscriber, &mut sent_logs, tip)?
+                    }
+                }
+            }
+        }
+        Kind::NewPendingTransactions => {
+            let mut hashes = pubsub.pool.pending_transactions_listener();
+            while let Some(hash) = hashes.recv().await {
+                let item = pubsub::SubscriptionResult::TransactionHash(hash);
+                subscriber.send(&item)?;
+            }
+        }
+        Kind::Syncing => {
+            let mut interval = tokio::time::interval(SYNC_STATUS_POLL_INTERVAL);
+            let mut last_status = None;
+            loop {
+                interval.tick().await;
+                if subscriber.sink.is_closed() {
+                    return Err(SubscriptionStopped::Closed)
+                }
+
+                let status = sync_status(pubsub, last_status.as_ref());
+                // only changes of the sync status are announced
+                if last_status.as_ref() == Some(&status) {
+                    continue
+                }
+                let item = pubsub::SubscriptionResult::SyncState(status.clone());
+                subscriber.send(&item)?;
+                last_status = Some(status);
+            }
+        }
+    }
+    Ok(())
 }
 
-/// Container type `EthApi`
+/// Sends the logs that were sent for the blocks above `tip` again with `removed: true`, latest
+/// first, and forgets them.
+fn send_removed_logs(
+    subscriber: &mut Subscriber,
+    sent_logs: &mut VecDeque<(BlockNumber, Vec<Log>)>,
+    tip: BlockNumber,
+) -> Result<(), SubscriptionStopped> {
+    while let Some((number, logs)) = sent_logs.pop_back() {
+        if number <= tip {
+            sent_logs.push_back((number, logs));
+            break
+        }
+        for mut log in logs.into_iter().rev() {
+            log.removed = true;
+            let item = pubsub::SubscriptionResult::Log(Box::new(log));
+            subscriber.send(&item)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the current sync status.
+///
+/// The starting block is the best block at the time the sync started, which is taken from the
+/// previously announced status.
+fn sync_status<Pool, Client, Events, Network>(
+    pubsub: &EthPubSubInner<Pool, Client, Events, Network>,
+    last_status: Option<&PubSubSyncStatus>,
+) -> PubSubSyncStatus
+where
+    Client: BlockProvider,
+    Network: SyncStateProvider,
+{
+    if !pubsub.network.is_syncing() {
+        return PubSubSyncStatus::Simple(false)
+    }
+    let current_block = pubsub.client.chain_info().map(|info| info.best_number).unwrap_or_default();
+    let starting_block = match last_status {
+        Some(PubSubSyncStatus::Detailed(status)) => status.starting_block,
+        _ => current_block,
+    };
+    PubSubSyncStatus::Detailed(SyncStatusMetadata {
+        syncing: true,
+        starting_block,
+        current_block,
+        highest_block: pubsub.network.sync_target(),
+    })
+}
+
+/// Waits for the next change of the canonical chain.
+///
+/// The events a lagging subscriber missed are skipped. Returns `None` once no more events can be
+/// received.
+async fn next_chain_event(events: &mut broadcast::Receiver<ChainEvent>) -> Option<ChainEvent> {
+    loop {
+        match events.recv().await {
+            Ok(event) => return Some(event),
+            Err(RecvError::Lagged(skipped)) => {
+                warn!(target: "rpc::eth::pubsub", skipped, "Subscriber lagged behind the chain");
+            }
+            Err(RecvError::Closed) => return None,
+        }
+    }
+}
+
+/// Why a subscription task stopped sending notifications.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SubscriptionStopped {
+    /// The subscription was closed by the subscriber or the server.
+    Closed,
+    /// The subscription queued more notifications than its buffer allows.
+    BufferExceeded,
+}
+
+/// The sink of an accepted subscription that limits how much the subscription may buffer.
+///
+/// The server queues the notifications of a subscription until the connection consumes them,
+/// without reporting how much is queued. The queue is therefore estimated, assuming that the
+/// connection consumes [SUBSCRIPTION_DRAIN_RATE] bytes per second.
+struct Subscriber {
+    /// The sink of the subscription.
+    sink: SubscriptionSink,
+    /// The estimated number of bytes that were sent but not consumed yet.
+    queued: usize,
+    /// When `queued` was last updated.
+    updated_at: Instant,
+}
+
+impl Subscriber {
+    /// Creates a new subscriber with an empty buffer.
+    fn new(sink: SubscriptionSink) -> Self {
+        Self { sink, queued: 0, updated_at: Instant::now() }
+    }
+
+    /// Sends the item to the subscriber.
+    ///
+    /// Fails if the subscription is closed, or if the item does not fit into the buffer of
+    /// [SUBSCRIPTION_BUFFER_SIZE] bytes.
+    fn send(&mut self, item: &pubsub::SubscriptionResult) -> Result<(), SubscriptionStopped> {
+        let item = match to_json_raw_value(item) {
+            Ok(item) => item,
+            Err(err) => {
+                warn!(target: "rpc::eth::pubsub", ?err, "Failed to serialize subscription item");
+                return Err(SubscriptionStopped::Closed)
+            }
+        };
+
+        let now = Instant::now();
+        let drained =
+            now.duration_since(self.updated_at).as_secs_f64() * SUBSCRIPTION_DRAIN_RATE as f64;
+        self.queued = self.queued.saturating_sub(drained as usize) + item.get().len();
+        self.updated_at = now;
+        if self.queued > SUBSCRIPTION_BUFFER_SIZE {
+            return Err(SubscriptionStopped::BufferExceeded)
+        }
+
+        match self.sink.send(&item) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(SubscriptionStopped::Closed),
+            Err(err) => {
+                warn!(target: "rpc::eth::pubsub", ?err, "Failed to serialize subscription item");
+                Err(SubscriptionStopped::Closed)
+            }
+        }
+    }
+}
+
+/// Container type `EthPubSub`
 #[derive(Debug)]
-struct EthPubSubInner<Pool, Client> {
+struct EthPubSubInner<Pool, Client, Events, Network> {
     /// The transaction pool.
     pool: Pool,
     /// The client that can interact with the chain.
     client: Arc<Client>,
-    // TODO needs spawn access
+    /// Subscriptions to changes of the canonical chain.
+    chain_events: Events,
+    /// The network, used to determine the sync status.
+    network: Network,
+    /// The type that spawns the subscription tasks.
+    executor: TaskExecutor,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use reth_interfaces::events::ChainEventSender;
+    use reth_primitives::{
+        Address, Block, Header as PrimitiveHeader, Log as PrimitiveLog, Receipt, TransactionSigned,
+        H256,
+    };
+    use reth_provider::test_utils::MockEthProvider;
+    use reth_tasks::TaskManager;
+    use reth_transaction_pool::test_utils::testing_pool;
+    use serde::de::DeserializeOwned;
+    use tokio::sync::{mpsc::UnboundedReceiver, Notify};
+
+    /// Reports the configured sync target, syncing if there is one.
+    #[derive(Debug, Default)]
+    struct TestSyncState {
+        target: Mutex<Option<BlockNumber>>,
+    }
+
+    impl SyncStateProvider for TestSyncState {
+        fn is_syncing(&self) -> bool {
+            self.target.lock().is_some()
+        }
+
+        fn sync_target(&self) -> Option<BlockNumber> {
+            *self.target.lock()
+        }
+    }
+
+    /// Chain events that announce when the subscription task subscribed to them.
+    #[derive(Debug, Default)]
+    struct TestChainEvents {
+        sender: ChainEventSender,
+        subscribed: Notify,
+    }
+
+    impl ChainEventSubscriptions for TestChainEvents {
+        fn subscribe_chain_events(&self) -> broadcast::Receiver<ChainEvent> {
+            let events = self.sender.subscribe_chain_events();
+            self.subscribed.notify_one();
+            events
+        }
+    }
+
+    async fn next_notification<T: DeserializeOwned>(
+        notifications: &mut UnboundedReceiver<String>,
+    ) -> T {
+        let notification = notifications.recv().await.expect("subscription is open");
+        let notification: serde_json::Value = serde_json::from_str(&notification).unwrap();
+        serde_json::from_value(notification["params"]["result"].clone()).unwrap()
+    }
+
+    async fn next_sync_status(notifications: &mut UnboundedReceiver<String>) -> PubSubSyncStatus {
+        next_notification(notifications).await
+    }
+
+    #[tokio::test]
+    async fn logs_subscription_reports_removed_logs() {
+        let manager = TaskManager::new(tokio::runtime::Handle::current());
+        let client = MockEthProvider::default();
+        let hash = H256::random();
+        let block = Block {
+            header: PrimitiveHeader { number: 1, ..Default::default() },
+            body: vec![TransactionSigned::default()],
+            ..Default::default()
+        };
+        client.add_block(hash, block);
+        let log = PrimitiveLog { address: Address::random(), ..Default::default() };
+        client.add_receipts(hash, vec![Receipt { logs: vec![log.clone()], ..Default::default() }]);
+        let events = Arc::new(TestChainEvents::default());
+
+        let pubsub = EthPubSub::new(
+            Arc::new(client),
+            testing_pool(),
+            Arc::clone(&events),
+            Arc::new(TestSyncState::default()),
+            manager.executor(),
+        );
+        let module = pubsub.into_rpc();
+        let (_, mut notifications) = module
+            .raw_json_request(
+                r#"{"jsonrpc":"2.0","id":1,"method":"eth_subscribe","params":["logs"]}"#,
+            )
+            .await
+            .unwrap();
+        events.subscribed.notified().await;
+
+        events.sender.notify(ChainEvent::Extended { first: 1, tip: 1 });
+        let sent: Log = next_notification(&mut notifications).await;
+        assert_eq!((sent.address, sent.block_hash, sent.removed), (log.address, Some(hash), false));
+
+        // the unwound log is sent again as removed
+        events.sender.notify(ChainEvent::Unwound { tip: 0 });
+        let removed: Log = next_notification(&mut notifications).await;
+        assert_eq!(removed, Log { removed: true, ..sent });
+    }
+
+    #[tokio::test]
+    async fn syncing_subscription_reports_progress() {
+        let manager = TaskManager::new(tokio::runtime::Handle::current());
+        let client = MockEthProvider::default();
+        client.add_header(H256::random(), PrimitiveHeader { number: 1, ..Default::default() });
+        let network = Arc::new(TestSyncState::default());
+        *network.target.lock() = Some(10);
+
+        let pubsub = EthPubSub::new(
+            Arc::new(client.clone()),
+            testing_pool(),
+            ChainEventSender::default(),
+            Arc::clone(&network),
+            manager.executor(),
+        );
+        let module = pubsub.into_rpc();
+        let (_, mut notifications) = module
+            .raw_json_request(
+                r#"{"jsonrpc":"2.0","id":1,"method":"eth_subscribe","params":["syncing"]}"#,
+            )
+            .await
+            .unwrap();
+
+        let status = |starting_block, current_block| {
+            PubSubSyncStatus::Detailed(SyncStatusMetadata {
+                syncing: true,
+                starting_block,
+                current_block,
+                highest_block: Some(10),
+            })
+        };
+        assert_eq!(next_sync_status(&mut notifications).await, status(1, 1));
+
+        // the starting block is kept while the sync progresses
+        client.add_header(H256::random(), PrimitiveHeader { number: 5, ..Default::default() });
+        assert_eq!(next_sync_status(&mut notifications).await, status(1, 5));
+
+        *network.target.lock() = None;
+        assert_eq!(next_sync_status(&mut notifications).await, PubSubSyncStatus::Simple(false));
+    }
 }
@@ -0,0 +1,112 @@
+use super::{JwtError, JwtSecret};
+use futures::future::{ready, Either, Ready};
+use hyper::{header::AUTHORIZATION, Body, HeaderMap, Request, Response, StatusCode};
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// A [Layer] that authenticates every http request with a [JwtSecret].
+///
+/// The request must carry a token signed with the secret in its `Authorization: Bearer <token>`
+/// header, otherwise it is rejected with `401 Unauthorized`. This also applies to the upgrade
+/// requests of websocket connections.
+#[derive(Debug, Clone)]
+pub struct AuthLayer {
+    secret: JwtSecret,
+}
+
+impl AuthLayer {
+    /// Creates a new layer that validates tokens with the given secret.
+    pub fn new(secret: JwtSecret) -> Self {
+        Self { secret }
+    }
+}
+
+impl<S> Layer<S> for AuthLayer {
+    type Service = AuthService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuthService { secret: self.secret.clone(), inner }
+    }
+}
+
+/// The service created by [AuthLayer] that only forwards authenticated requests to the inner
+/// service.
+#[derive(Debug, Clone)]
+pub struct AuthService<S> {
+    secret: JwtSecret,
+    inner: S,
+}
+
+impl<S> Service<Request<Body>> for AuthService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>>,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = Either<Ready<Result<Response<Body>, S::Error>>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        match authenticate(&self.secret, req.headers()) {
+            Ok(()) => Either::Right(self.inner.call(req)),
+            Err(err) => Either::Left(ready(Ok(unauthorized(err)))),
+        }
+    }
+}
+
+/// Validates the bearer token of the request.
+fn authenticate(secret: &JwtSecret, headers: &HeaderMap) -> Result<(), JwtError> {
+    let jwt = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or(JwtError::MissingOrInvalidAuthorizationHeader)?;
+    secret.validate(jwt)
+}
+
+/// The response to a request that failed authentication.
+fn unauthorized(err: JwtError) -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::UNAUTHORIZED)
+        .body(Body::from(err.to_string()))
+        .expect("valid response")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layers::jwt_secret::{tests::now, Claims};
+    use std::convert::Infallible;
+    use tower::{service_fn, ServiceExt};
+
+    async fn status(secret: &JwtSecret, authorization: Option<String>) -> StatusCode {
+        let service = AuthLayer::new(secret.clone())
+            .layer(service_fn(|_req| async { Ok::<_, Infallible>(Response::new(Body::empty())) }));
+
+        let mut req = Request::builder();
+        if let Some(authorization) = authorization {
+            req = req.header(AUTHORIZATION, authorization);
+        }
+        let res = service.oneshot(req.body(Body::empty()).unwrap()).await.unwrap();
+        res.status()
+    }
+
+    #[tokio::test]
+    async fn authenticates_requests() {
+        let secret = JwtSecret::random();
+        let jwt = secret.encode(&Claims { iat: now(), exp: None });
+
+        assert_eq!(status(&secret, Some(format!("Bearer {jwt}"))).await, StatusCode::OK);
+
+        assert_eq!(status(&secret, None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status(&secret, Some(jwt)).await, StatusCode::UNAUTHORIZED);
+        let other = JwtSecret::random().encode(&Claims { iat: now(), exp: None });
+        assert_eq!(
+            status(&secret, Some(format!("Bearer {other}"))).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+}
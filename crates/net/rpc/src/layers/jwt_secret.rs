@@ -0,0 +1,252 @@
+use jsonwebtoken::{decode, errors::ErrorKind, Algorithm, DecodingKey, Validation};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use thiserror::Error;
+
+/// The length of the hex encoded secret.
+const JWT_SECRET_LEN: usize = 64;
+
+/// The maximum difference between the `iat` (issued-at) claim and the local time.
+const JWT_MAX_IAT_DIFF: Duration = Duration::from_secs(60);
+
+/// The only signature algorithm the execution layer must support: HMAC + SHA256 (HS256).
+const JWT_SIGNATURE_ALGO: Algorithm = Algorithm::HS256;
+
+/// Errors returned by the [`JwtSecret`]
+#[derive(Error, Debug)]
+pub enum JwtError {
+    /// The secret is not valid hex.
+    #[error(transparent)]
+    JwtSecretHexDecodeError(#[from] hex::FromHexError),
+    /// The secret has the wrong length.
+    #[error("JWT key is expected to have a length of {0} digits. {1} digits key provided.")]
+    InvalidLength(usize, usize),
+    /// The token is not signed with HS256.
+    #[error("Unsupported signature algorithm. Only HS256 is supported")]
+    UnsupportedSignatureAlgorithm,
+    /// The token is not signed with the secret.
+    #[error("The provided signature is invalid.")]
+    InvalidSignature,
+    /// The token was issued too far from the local time.
+    #[error("The iat (issued-at) claim is not within +-60 seconds from the current time")]
+    InvalidIssuanceTimestamp,
+    /// The request does not carry a `Bearer` token.
+    #[error("Authorization header is missing or invalid")]
+    MissingOrInvalidAuthorizationHeader,
+    /// The token could not be decoded.
+    #[error("JWT decoding error: {0}")]
+    JwtDecodingError(String),
+    /// The secret file could not be read or written.
+    #[error("Failed to access the JWT secret at {path:?}: {err}")]
+    Io {
+        /// The path of the secret file.
+        path: PathBuf,
+        /// The underlying error.
+        err: std::io::Error,
+    },
+}
+
+/// A 256-bit secret key that is shared between the execution and the consensus layer, and used
+/// to authenticate the requests of the consensus layer.
+///
+/// The secret is stored as hex in a file that both clients read.
+///
+/// See also: <https://github.com/ethereum/execution-apis/blob/main/src/engine/authentication.md>
+#[derive(Clone, PartialEq, Eq)]
+pub struct JwtSecret([u8; 32]);
+
+impl JwtSecret {
+    /// Creates the secret from its hex encoding, with or without `0x` prefix.
+    ///
+    /// Surrounding whitespace is ignored.
+    pub fn from_hex<S: AsRef<str>>(hex: S) -> Result<Self, JwtError> {
+        let hex = hex.as_ref().trim();
+        let hex = hex.strip_prefix("0x").unwrap_or(hex);
+        if hex.len() != JWT_SECRET_LEN {
+            return Err(JwtError::InvalidLength(JWT_SECRET_LEN, hex.len()))
+        }
+
+        let mut secret = [0u8; 32];
+        hex::decode_to_slice(hex, &mut secret)?;
+        Ok(Self(secret))
+    }
+
+    /// Creates a new random secret.
+    pub fn random() -> Self {
+        Self(rand::thread_rng().gen())
+    }
+
+    /// Reads the hex encoded secret from the file.
+    pub fn from_file(path: &Path) -> Result<Self, JwtError> {
+        let hex = std::fs::read_to_string(path)
+            .map_err(|err| JwtError::Io { path: path.to_path_buf(), err })?;
+        Self::from_hex(hex)
+    }
+
+    /// Creates a new random secret and writes it hex encoded to the file.
+    ///
+    /// Missing parent directories are created. The file must not exist yet, and on unix it is only
+    /// readable and writable by the owner, since anyone who can read the secret can authenticate.
+    pub fn try_create(path: &Path) -> Result<Self, JwtError> {
+        let io_err = |err| JwtError::Io { path: path.to_path_buf(), err };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(io_err)?;
+        }
+
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        let secret = Self::random();
+        let mut file = options.open(path).map_err(io_err)?;
+        file.write_all(hex::encode(secret.0).as_bytes()).map_err(io_err)?;
+        Ok(secret)
+    }
+
+    /// Reads the secret from the file, or creates it if the file does not exist.
+    pub fn load_or_create(path: &Path) -> Result<Self, JwtError> {
+        if path.exists() {
+            Self::from_file(path)
+        } else {
+            Self::try_create(path)
+        }
+    }
+
+    /// Validates the given token.
+    ///
+    /// The token must be signed with this secret using HS256, and its `iat` claim must be within
+    /// 60 seconds of the local time. The optional `exp` claim is checked if present.
+    pub fn validate(&self, jwt: &str) -> Result<(), JwtError> {
+        let mut validation = Validation::new(JWT_SIGNATURE_ALGO);
+        // `exp` is optional, `iat` is required by `Claims`
+        validation.required_spec_claims.clear();
+
+        let key = DecodingKey::from_secret(&self.0);
+        let claims = decode::<Claims>(jwt, &key, &validation)
+            .map_err(|err| match err.kind() {
+                ErrorKind::InvalidSignature => JwtError::InvalidSignature,
+                ErrorKind::InvalidAlgorithm => JwtError::UnsupportedSignatureAlgorithm,
+                _ => JwtError::JwtDecodingError(err.to_string()),
+            })?
+            .claims;
+
+        if !claims.is_within_time_window() {
+            return Err(JwtError::InvalidIssuanceTimestamp)
+        }
+        Ok(())
+    }
+
+    /// Encodes the claims into a token signed with this secret.
+    #[cfg(test)]
+    pub(crate) fn encode(&self, claims: &Claims) -> String {
+        use jsonwebtoken::{encode, EncodingKey, Header};
+        let key = EncodingKey::from_secret(&self.0);
+        encode(&Header::new(JWT_SIGNATURE_ALGO), claims, &key).expect("valid claims")
+    }
+}
+
+impl std::fmt::Debug for JwtSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // never print the secret
+        f.debug_tuple("JwtSecret").field(&"...").finish()
+    }
+}
+
+/// The claims of a token issued by the consensus layer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Claims {
+    /// The time the token was issued at, in seconds since the unix epoch.
+    pub(crate) iat: u64,
+    /// The optional expiration time, in seconds since the unix epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) exp: Option<u64>,
+}
+
+impl Claims {
+    /// Returns true if the token was issued within [JWT_MAX_IAT_DIFF] of the local time.
+    fn is_within_time_window(&self) -> bool {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        now.abs_diff(self.iat) <= JWT_MAX_IAT_DIFF.as_secs()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+
+    pub(crate) fn now() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+    }
+
+    #[test]
+    fn secret_from_hex() {
+        let hex = "f79ae8046bc11c9927afe911db7143c51a806c4a537cc08e0d37140b0192f430";
+        assert!(JwtSecret::from_hex(hex).is_ok());
+        assert_eq!(
+            JwtSecret::from_hex(hex).unwrap(),
+            JwtSecret::from_hex(format!("0x{hex}\n")).unwrap()
+        );
+
+        assert_matches!(JwtSecret::from_hex(&hex[1..]), Err(JwtError::InvalidLength(64, 63)));
+        assert_matches!(
+            JwtSecret::from_hex(hex.replace('f', "x")),
+            Err(JwtError::JwtSecretHexDecodeError(_))
+        );
+    }
+
+    #[test]
+    fn secret_file_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secret").join("jwt.hex");
+
+        let created = JwtSecret::load_or_create(&path).unwrap();
+        assert_eq!(JwtSecret::load_or_create(&path).unwrap(), created);
+        assert_eq!(JwtSecret::from_file(&path).unwrap(), created);
+        // an existing secret is never overwritten
+        assert_matches!(JwtSecret::try_create(&path), Err(JwtError::Io { .. }));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn created_secret_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jwt.hex");
+        JwtSecret::try_create(&path).unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn validate_token() {
+        let secret = JwtSecret::random();
+
+        let jwt = secret.encode(&Claims { iat: now(), exp: None });
+        assert_matches!(secret.validate(&jwt), Ok(()));
+
+        let jwt = secret.encode(&Claims { iat: now(), exp: Some(now() + 60) });
+        assert_matches!(secret.validate(&jwt), Ok(()));
+
+        // signed with another secret
+        let jwt = JwtSecret::random().encode(&Claims { iat: now(), exp: None });
+        assert_matches!(secret.validate(&jwt), Err(JwtError::InvalidSignature));
+
+        // issued too early or too late
+        let jwt = secret.encode(&Claims { iat: now() - 120, exp: None });
+        assert_matches!(secret.validate(&jwt), Err(JwtError::InvalidIssuanceTimestamp));
+        let jwt = secret.encode(&Claims { iat: now() + 120, exp: None });
+        assert_matches!(secret.validate(&jwt), Err(JwtError::InvalidIssuanceTimestamp));
+
+        assert_matches!(secret.validate("not a token"), Err(JwtError::JwtDecodingError(_)));
+    }
+}
@@ -0,0 +1,7 @@
+//! Tower layers for the RPC servers.
+
+mod auth_layer;
+mod jwt_secret;
+
+pub use auth_layer::{AuthLayer, AuthService};
+pub use jwt_secret::{JwtError, JwtSecret};
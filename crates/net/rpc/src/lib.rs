@@ -15,16 +15,20 @@ mod admin;
 mod debug;
 mod engine;
 mod eth;
+mod layers;
 mod net;
 mod trace;
+mod txpool;
 mod web3;
 
 pub use admin::AdminApi;
 pub use debug::DebugApi;
 pub use engine::EngineApi;
-pub use eth::{EthApi, EthApiSpec, EthPubSub};
+pub use eth::{EthApi, EthApiSpec, EthCallConfig, EthFilter, EthFilterConfig, EthPubSub};
+pub use layers::{AuthLayer, AuthService, JwtError, JwtSecret};
 pub use net::NetApi;
 pub use trace::TraceApi;
+pub use txpool::TxPoolApi;
 pub use web3::Web3Api;
 
 pub(crate) mod result;
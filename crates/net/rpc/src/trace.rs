@@ -12,6 +12,7 @@ use std::collections::HashSet;
 /// `trace` API implementation.
 ///
 /// This type provides the functionality for handling `trace` related requests.
+#[derive(Default)]
 #[non_exhaustive]
 pub struct TraceApi {}
 
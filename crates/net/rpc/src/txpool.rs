@@ -0,0 +1,157 @@
+use jsonrpsee::core::RpcResult as Result;
+use reth_primitives::{Address, IntoRecoveredTransaction, TransactionKind, U256};
+use reth_rpc_api::TxPoolApiServer;
+use reth_rpc_types::{
+    Transaction, TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolInspectSummary,
+    TxpoolNonceMap, TxpoolStatus,
+};
+use reth_transaction_pool::{PoolTransaction, TransactionPool, ValidPoolTransaction};
+use std::{collections::BTreeMap, sync::Arc};
+
+/// `txpool` API implementation.
+///
+/// This type provides the functionality for handling `txpool` related requests.
+///
+/// Geth does not distinguish the _basefee_ and _queued_ sub-pools, so transactions of both are
+/// reported as queued.
+#[derive(Clone)]
+pub struct TxPoolApi<Pool> {
+    /// An interface to interact with the pool
+    pool: Pool,
+}
+
+// === impl TxPoolApi ===
+
+impl<Pool> TxPoolApi<Pool> {
+    /// Creates a new instance of `TxPoolApi`.
+    pub fn new(pool: Pool) -> Self {
+        TxPoolApi { pool }
+    }
+}
+
+/// Groups the transactions by sender and nonce, converting them with the given function.
+fn group_by_sender<T, R>(
+    transactions: Vec<Arc<ValidPoolTransaction<T>>>,
+    convert: impl Fn(&ValidPoolTransaction<T>) -> R,
+) -> BTreeMap<Address, TxpoolNonceMap<R>>
+where
+    T: PoolTransaction,
+{
+    let mut content = BTreeMap::<Address, TxpoolNonceMap<R>>::new();
+    for tx in transactions {
+        content.entry(tx.sender()).or_default().insert(tx.nonce().to_string(), convert(&tx));
+    }
+    content
+}
+
+/// Returns the transactions of the given sender keyed by nonce, converting them with the given
+/// function.
+fn by_nonce<T, R>(
+    transactions: Vec<Arc<ValidPoolTransaction<T>>>,
+    sender: Address,
+    convert: impl Fn(&ValidPoolTransaction<T>) -> R,
+) -> TxpoolNonceMap<R>
+where
+    T: PoolTransaction,
+{
+    transactions
+        .into_iter()
+        .filter(|tx| tx.sender() == sender)
+        .map(|tx| (tx.nonce().to_string(), convert(&tx)))
+        .collect()
+}
+
+/// Converts the pooled transaction into its RPC representation.
+fn rpc_transaction<T: PoolTransaction>(tx: &ValidPoolTransaction<T>) -> Transaction {
+    Transaction::from_recovered(tx.transaction.to_recovered_transaction())
+}
+
+/// Returns the `txpool_inspect` summary of the pooled transaction.
+fn inspect_summary<T: PoolTransaction>(tx: &ValidPoolTransaction<T>) -> TxpoolInspectSummary {
+    let tx = tx.transaction.to_recovered_transaction();
+    TxpoolInspectSummary {
+        to: match tx.kind() {
+            TransactionKind::Create => None,
+            TransactionKind::Call(to) => Some(*to),
+        },
+        value: U256::from(*tx.value()),
+        gas: tx.gas_limit(),
+        gas_price: tx.max_fee_per_gas(),
+    }
+}
+
+impl<Pool> TxPoolApiServer for TxPoolApi<Pool>
+where
+    Pool: TransactionPool + 'static,
+{
+    fn txpool_status(&self) -> Result<TxpoolStatus> {
+        let size = self.pool.status();
+        Ok(TxpoolStatus {
+            pending: (size.pending as u64).into(),
+            queued: ((size.basefee + size.queued) as u64).into(),
+        })
+    }
+
+    fn txpool_inspect(&self) -> Result<TxpoolInspect> {
+        Ok(TxpoolInspect {
+            pending: group_by_sender(self.pool.pending_transactions(), inspect_summary),
+            queued: group_by_sender(self.pool.queued_transactions(), inspect_summary),
+        })
+    }
+
+    fn txpool_content_from(&self, from: Address) -> Result<TxpoolContentFrom> {
+        Ok(TxpoolContentFrom {
+            pending: by_nonce(self.pool.pending_transactions(), from, rpc_transaction),
+            queued: by_nonce(self.pool.queued_transactions(), from, rpc_transaction),
+        })
+    }
+
+    fn txpool_content(&self) -> Result<TxpoolContent> {
+        Ok(TxpoolContent {
+            pending: group_by_sender(self.pool.pending_transactions(), rpc_transaction),
+            queued: group_by_sender(self.pool.queued_transactions(), rpc_transaction),
+        })
+    }
+}
+
+impl<Pool> std::fmt::Debug for TxPoolApi<Pool> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TxPoolApi").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_interfaces::test_utils::generators::random_signed_tx;
+    use reth_primitives::FromRecoveredTransaction;
+    use reth_transaction_pool::{
+        test_utils::NoopTransactionValidator, CostOrdering, Pool, PooledTransaction,
+    };
+
+    #[tokio::test]
+    async fn groups_by_sender_and_nonce() {
+        let pool = Pool::new(
+            Arc::new(NoopTransactionValidator::<PooledTransaction>::default()),
+            Arc::new(CostOrdering::default()),
+            Default::default(),
+        );
+        let tx = random_signed_tx().into_ecrecovered().unwrap();
+        let (sender, nonce, gas_limit) = (tx.signer(), tx.nonce(), tx.gas_limit());
+        // the sender can't afford the transaction, so it is queued
+        pool.add_external_transaction(PooledTransaction::from_recovered_transaction(tx))
+            .await
+            .unwrap();
+        let api = TxPoolApi::new(pool);
+
+        let status = api.txpool_status().unwrap();
+        assert_eq!(status, TxpoolStatus { pending: 0u64.into(), queued: 1u64.into() });
+
+        let content = api.txpool_content_from(sender).unwrap();
+        assert!(content.pending.is_empty());
+        assert_eq!(content.queued[&nonce.to_string()].from, sender);
+
+        let inspect = api.txpool_inspect().unwrap();
+        assert_eq!(inspect.queued[&sender][&nonce.to_string()].gas, gas_limit);
+    }
+}
@@ -0,0 +1,286 @@
+use crate::{BuiltPayload, PayloadBuilderAttributes, PayloadBuilderError};
+use reth_executor::{
+    config::revm_spec,
+    executor::commit_changes,
+    revm_wrap::{self, to_reth_acc, State, SubState},
+};
+use reth_interfaces::executor::Error as ExecutorError;
+use reth_primitives::{
+    bloom::logs_bloom,
+    proofs::{self, EMPTY_LIST_HASH},
+    Address, Bytes, ChainSpec, Header, IntoRecoveredTransaction, Log, Receipt, SealedBlock, H160,
+    H256, U256,
+};
+use reth_provider::{trie::AccountChanges, HeaderProvider, StateProvider, StateProviderFactory};
+use reth_transaction_pool::TransactionPool;
+use revm::{db::AccountState, AnalysisKind, SpecId, EVM};
+use std::collections::BTreeMap;
+use tracing::trace;
+
+/// Builds a block on top of the parent of the given attributes.
+///
+/// The block is filled with the best transactions of the pool until the gas limit of the parent
+/// is reached. Transactions that can't be executed, for example because their nonce is outdated,
+/// are skipped together with all transactions that depend on them.
+pub fn build_payload<Client, Pool>(
+    client: &Client,
+    pool: &Pool,
+    chain_spec: &ChainSpec,
+    attributes: &PayloadBuilderAttributes,
+    extra_data: Bytes,
+) -> Result<BuiltPayload, PayloadBuilderError>
+where
+    Client: HeaderProvider + StateProviderFactory,
+    Pool: TransactionPool,
+{
+    let parent = client
+        .header(&attributes.parent)?
+        .ok_or(PayloadBuilderError::MissingParentBlock(attributes.parent))?;
+    let state = client.history_by_block_hash(attributes.parent)?;
+    // the state of the parent is only available once the parent was executed
+    if state.state_root_with_changes(&BTreeMap::new())? != parent.state_root {
+        return Err(PayloadBuilderError::MissingParentState(attributes.parent))
+    }
+    let mut db = SubState::new(State::new(state));
+
+    let mut header = Header {
+        parent_hash: attributes.parent,
+        ommers_hash: EMPTY_LIST_HASH,
+        beneficiary: attributes.suggested_fee_recipient,
+        number: parent.number + 1,
+        gas_limit: parent.gas_limit,
+        timestamp: attributes.timestamp,
+        mix_hash: attributes.prev_randao,
+        base_fee_per_gas: parent.next_block_base_fee(),
+        extra_data,
+        ..Default::default()
+    };
+
+    let mut evm = EVM::new();
+    evm.database(&mut db);
+
+    let spec_id = revm_spec(chain_spec, header.number);
+    evm.env.cfg.chain_id = U256::from(chain_spec.chain().id());
+    evm.env.cfg.spec_id = spec_id;
+    evm.env.cfg.perf_all_precompiles_have_balance = false;
+    evm.env.cfg.perf_analyse_created_bytecodes = AnalysisKind::Raw;
+    revm_wrap::fill_block_env(&mut evm.env.block, &header, spec_id >= SpecId::MERGE);
+
+    let mut cumulative_gas_used = 0;
+    let mut fees = U256::ZERO;
+    let mut transactions = Vec::new();
+    let mut receipts = Vec::new();
+    let mut changes = BTreeMap::<Address, AccountChanges>::new();
+
+    let mut best_transactions = pool.best_transactions();
+    while let Some(pool_transaction) = best_transactions.next() {
+        // skip transactions that don't fit into the block anymore
+        if cumulative_gas_used + pool_transaction.gas_limit() > header.gas_limit {
+            best_transactions.mark_invalid(&pool_transaction);
+            continue
+        }
+
+        let transaction = pool_transaction.transaction.to_recovered_transaction();
+        revm_wrap::fill_tx_env(&mut evm.env.tx, &transaction);
+
+        let (revm::ExecutionResult { exit_reason, gas_used, logs, .. }, state) = evm.transact();
+
+        if exit_reason == revm::Return::FatalExternalError {
+            return Err(reth_interfaces::Error::from(ExecutorError::ExecutionFatalError).into())
+        }
+
+        // transactions that fail the checks before execution, for example because of an invalid
+        // nonce or an insufficient balance, don't consume any gas and can't be included
+        if gas_used == 0 {
+            trace!(target: "payload_builder", hash = ?transaction.hash(), ?exit_reason, "Skipping invalid transaction");
+            best_transactions.mark_invalid(&pool_transaction);
+            continue
+        }
+
+        let success = matches!(exit_reason, revm::return_ok!());
+        cumulative_gas_used += gas_used;
+
+        let logs: Vec<Log> = logs
+            .into_iter()
+            .map(|l| Log {
+                address: H160(l.address.0),
+                topics: l.topics.into_iter().map(|h| H256(h.0)).collect(),
+                data: l.data.into(),
+            })
+            .collect();
+
+        let (changeset, _) = commit_changes(evm.db().expect("Db to not be moved."), state);
+        for (address, change) in changeset {
+            let account = changes.entry(address).or_default();
+            if change.wipe_storage {
+                account.wipe_storage = true;
+                account.storage.clear();
+            }
+            account.storage.extend(
+                change
+                    .storage
+                    .into_iter()
+                    .map(|(slot, (_, value))| (H256(slot.to_be_bytes()), value)),
+            );
+        }
+
+        let base_fee = header.base_fee_per_gas;
+        let tip = transaction.effective_gas_price(base_fee) - base_fee.unwrap_or_default() as u128;
+        fees += U256::from(tip) * U256::from(gas_used);
+
+        receipts.push(Receipt {
+            tx_type: transaction.tx_type(),
+            success,
+            cumulative_gas_used,
+            bloom: logs_bloom(logs.iter()),
+            logs,
+        });
+        transactions.push(transaction.into_signed());
+    }
+    drop(evm);
+
+    // the changesets only contain the storage, the final accounts are taken from the cache
+    for (address, change) in changes.iter_mut() {
+        change.account = db
+            .accounts
+            .get(address)
+            .filter(|account| !matches!(account.account_state, AccountState::NotExisting))
+            .map(|account| to_reth_acc(&account.info))
+            .filter(|account| !account.is_empty());
+    }
+
+    header.state_root = db.db.state().state_root_with_changes(&changes)?;
+    header.transactions_root = proofs::calculate_transaction_root(transactions.iter());
+    header.receipts_root = proofs::calculate_receipt_root(receipts.iter());
+    header.logs_bloom = logs_bloom(receipts.iter().flat_map(|receipt| receipt.logs.iter()));
+    header.gas_used = cumulative_gas_used;
+
+    let block = SealedBlock { header: header.seal(), body: transactions, ommers: Vec::new() };
+    Ok(BuiltPayload { id: attributes.id, block, fees })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PayloadId;
+    use reth_interfaces::test_utils::generators::sign_message;
+    use reth_primitives::{
+        proofs::EMPTY_ROOT, FromRecoveredTransaction, Transaction, TransactionKind,
+        TransactionSigned, TxLegacy, MAINNET,
+    };
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_transaction_pool::{
+        test_utils::NoopTransactionValidator, CostOrdering, Pool, PooledTransaction,
+        TransactionOrigin,
+    };
+    use std::sync::Arc;
+
+    fn attributes(parent: H256) -> PayloadBuilderAttributes {
+        PayloadBuilderAttributes {
+            id: PayloadId::random(),
+            parent,
+            timestamp: 1000,
+            suggested_fee_recipient: Address::random(),
+            prev_randao: H256::random(),
+        }
+    }
+
+    /// Returns a provider with the given accounts and a parent block of their state.
+    fn provider_with_parent(
+        accounts: impl IntoIterator<Item = (Address, ExtendedAccount)>,
+    ) -> (MockEthProvider, H256) {
+        let client = MockEthProvider::default();
+        client.extend_accounts(accounts);
+        let parent = Header {
+            number: 1,
+            gas_limit: 30_000_000,
+            state_root: client.state_root_with_changes(&BTreeMap::new()).unwrap(),
+            ..Default::default()
+        };
+        let parent_hash = parent.hash_slow();
+        client.add_header(parent_hash, parent);
+        (client, parent_hash)
+    }
+
+    #[test]
+    fn builds_empty_payload() {
+        let (client, parent) = provider_with_parent([]);
+        let pool = reth_transaction_pool::test_utils::testing_pool();
+        let attributes = attributes(parent);
+
+        let payload =
+            build_payload(&client, &pool, &MAINNET, &attributes, Bytes::default()).unwrap();
+
+        assert_eq!(payload.id, attributes.id);
+        assert_eq!(payload.fees, U256::ZERO);
+        let block = payload.block;
+        assert_eq!(block.parent_hash, parent);
+        assert_eq!(block.number, 2);
+        assert_eq!(block.timestamp, attributes.timestamp);
+        assert_eq!(block.beneficiary, attributes.suggested_fee_recipient);
+        assert_eq!(block.gas_used, 0);
+        assert!(block.body.is_empty());
+        assert_eq!(block.transactions_root, EMPTY_ROOT);
+        assert_eq!(block.receipts_root, EMPTY_ROOT);
+        assert_eq!(block.state_root, client.state_root_with_changes(&BTreeMap::new()).unwrap());
+    }
+
+    #[test]
+    fn rejects_missing_parent_state() {
+        let (client, parent) = provider_with_parent([]);
+        client.add_account(Address::random(), ExtendedAccount::new(0, U256::from(1)));
+        let pool = reth_transaction_pool::test_utils::testing_pool();
+
+        assert!(matches!(
+            build_payload(&client, &pool, &MAINNET, &attributes(parent), Bytes::default()),
+            Err(PayloadBuilderError::MissingParentState(hash)) if hash == parent
+        ));
+    }
+
+    #[tokio::test]
+    async fn includes_pool_transactions() {
+        let secret = H256::random();
+        let tx = Transaction::Legacy(TxLegacy {
+            chain_id: None,
+            nonce: 0,
+            gas_price: 0,
+            gas_limit: 21_000,
+            to: TransactionKind::Call(Address::random()),
+            value: 0,
+            input: Default::default(),
+        });
+        let signature = sign_message(secret, tx.signature_hash()).unwrap();
+        let tx = TransactionSigned::from_transaction_and_signature(tx, signature)
+            .into_ecrecovered()
+            .unwrap();
+        let sender = tx.signer();
+        let balance = U256::from(1_000_000_000u64);
+        let (client, parent) = provider_with_parent([(sender, ExtendedAccount::new(0, balance))]);
+
+        let pool = Pool::new(
+            Arc::new(NoopTransactionValidator::<PooledTransaction>::default()),
+            Arc::new(CostOrdering::default()),
+            Default::default(),
+        );
+        // free transactions are only accepted from local senders
+        pool.add_transaction(
+            TransactionOrigin::Local,
+            PooledTransaction::from_recovered_transaction(tx.clone()),
+        )
+        .await
+        .unwrap();
+        let attributes = attributes(parent);
+
+        let payload =
+            build_payload(&client, &pool, &MAINNET, &attributes, Bytes::default()).unwrap();
+
+        let block = payload.block;
+        assert_eq!(block.body, vec![tx.into_signed()]);
+        assert_eq!(block.gas_used, 21_000);
+        assert_ne!(block.receipts_root, EMPTY_ROOT);
+
+        let expected = MockEthProvider::default();
+        expected.add_account(sender, ExtendedAccount::new(1, balance));
+        assert_eq!(block.state_root, expected.state_root_with_changes(&BTreeMap::new()).unwrap());
+    }
+}
@@ -0,0 +1,16 @@
+use reth_primitives::H256;
+
+/// Possible error variants during payload building.
+#[derive(Debug, thiserror::Error)]
+pub enum PayloadBuilderError {
+    /// The block to build on top of is not known.
+    #[error("missing parent block {0:?}")]
+    MissingParentBlock(H256),
+    /// The state of the block to build on top of is not available, for example because the
+    /// block was not executed yet.
+    #[error("missing state of parent block {0:?}")]
+    MissingParentState(H256),
+    /// Reading the state or executing a transaction failed.
+    #[error(transparent)]
+    Internal(#[from] reth_interfaces::Error),
+}
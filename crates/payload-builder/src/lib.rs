@@ -0,0 +1,26 @@
+#![warn(missing_docs, unreachable_pub)]
+#![deny(unused_must_use, rust_2018_idioms)]
+#![doc(test(
+    no_crate_inject,
+    attr(deny(warnings, rust_2018_idioms), allow(dead_code, unused_variables))
+))]
+
+//! Builds execution payloads for the Engine API.
+//!
+//! When the consensus layer sends `engine_forkchoiceUpdated` with payload attributes, a new
+//! payload job is started on top of the head block. The job repeatedly builds a block from the
+//! best transactions of the pool and keeps the most profitable one, which is returned by
+//! `engine_getPayload`.
+//!
+//! The [PayloadBuilderService] drives all jobs and is controlled via its
+//! [PayloadBuilderHandle].
+
+mod builder;
+mod error;
+mod payload;
+mod service;
+
+pub use builder::build_payload;
+pub use error::PayloadBuilderError;
+pub use payload::{BuiltPayload, PayloadBuilderAttributes, PayloadId};
+pub use service::{PayloadBuilderConfig, PayloadBuilderHandle, PayloadBuilderService};
@@ -0,0 +1,93 @@
+use reth_primitives::{keccak256, Address, SealedBlock, H256, H64, U256};
+use reth_rpc_types::engine::{ExecutionPayload, PayloadAttributes};
+
+/// The identifier of a payload, as returned by `engine_forkchoiceUpdated`.
+pub type PayloadId = H64;
+
+/// The attributes of a payload that is built on top of a parent block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayloadBuilderAttributes {
+    /// Identifier of the payload
+    pub id: PayloadId,
+    /// Hash of the parent block
+    pub parent: H256,
+    /// Timestamp of the payload
+    pub timestamp: u64,
+    /// Address that receives the priority fees of the payload
+    pub suggested_fee_recipient: Address,
+    /// Randomness value of the payload
+    pub prev_randao: H256,
+}
+
+impl PayloadBuilderAttributes {
+    /// Creates the attributes of a payload that is built on top of the given parent block.
+    ///
+    /// The payload id is derived from the parent and the attributes, so the same request always
+    /// results in the same id.
+    pub fn new(parent: H256, attributes: PayloadAttributes) -> Self {
+        let PayloadAttributes { timestamp, prev_randao, suggested_fee_recipient, .. } = attributes;
+        let timestamp = timestamp.as_u64();
+        Self {
+            id: payload_id(parent, timestamp, prev_randao, suggested_fee_recipient),
+            parent,
+            timestamp,
+            suggested_fee_recipient,
+            prev_randao,
+        }
+    }
+}
+
+/// Derives the payload id from the first 8 bytes of the hash of the payload attributes.
+fn payload_id(
+    parent: H256,
+    timestamp: u64,
+    prev_randao: H256,
+    suggested_fee_recipient: Address,
+) -> PayloadId {
+    let mut buf = Vec::with_capacity(32 + 8 + 32 + 20);
+    buf.extend_from_slice(parent.as_bytes());
+    buf.extend_from_slice(&timestamp.to_be_bytes());
+    buf.extend_from_slice(prev_randao.as_bytes());
+    buf.extend_from_slice(suggested_fee_recipient.as_bytes());
+    PayloadId::from_slice(&keccak256(buf)[..8])
+}
+
+/// A payload that was built by a payload job.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuiltPayload {
+    /// Identifier of the payload
+    pub id: PayloadId,
+    /// The built block
+    pub block: SealedBlock,
+    /// The priority fees that the fee recipient receives
+    pub fees: U256,
+}
+
+impl From<BuiltPayload> for ExecutionPayload {
+    fn from(payload: BuiltPayload) -> Self {
+        payload.block.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_id_is_deterministic() {
+        let parent = H256::random();
+        let attributes = PayloadAttributes {
+            timestamp: 1u64.into(),
+            prev_randao: H256::random(),
+            suggested_fee_recipient: Address::random(),
+            withdrawal: None,
+        };
+
+        let id = PayloadBuilderAttributes::new(parent, attributes.clone()).id;
+        assert_eq!(PayloadBuilderAttributes::new(parent, attributes.clone()).id, id);
+
+        let later = PayloadAttributes { timestamp: 2u64.into(), ..attributes.clone() };
+        assert_ne!(PayloadBuilderAttributes::new(parent, later).id, id);
+        assert_ne!(PayloadBuilderAttributes::new(H256::random(), attributes).id, id);
+    }
+}
@@ -0,0 +1,334 @@
+use crate::{
+    build_payload, BuiltPayload, PayloadBuilderAttributes, PayloadBuilderError, PayloadId,
+};
+use futures::{FutureExt, StreamExt};
+use reth_primitives::{Bytes, ChainSpec};
+use reth_provider::{HeaderProvider, StateProviderFactory};
+use reth_transaction_pool::TransactionPool;
+use std::{
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::{JoinError, JoinHandle},
+    time::{interval_at, Instant, Interval, MissedTickBehavior, Sleep},
+};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::{debug, trace, warn};
+
+/// Settings of the [PayloadBuilderService].
+#[derive(Debug, Clone)]
+pub struct PayloadBuilderConfig {
+    /// Extra data of the built blocks.
+    pub extra_data: Bytes,
+    /// The interval at which a payload is rebuilt with the current best transactions.
+    pub interval: Duration,
+    /// How long a payload is improved after it was requested.
+    pub deadline: Duration,
+    /// The maximum number of payloads that are kept, the oldest is dropped first.
+    pub max_payload_jobs: usize,
+}
+
+impl Default for PayloadBuilderConfig {
+    fn default() -> Self {
+        Self {
+            extra_data: Bytes::default(),
+            interval: Duration::from_secs(1),
+            // one slot
+            deadline: Duration::from_secs(12),
+            max_payload_jobs: 16,
+        }
+    }
+}
+
+/// Commands that are sent to the [PayloadBuilderService].
+#[derive(Debug)]
+enum PayloadServiceCommand {
+    /// Start building a new payload.
+    BuildNewPayload(PayloadBuilderAttributes),
+    /// Return the best payload built so far.
+    BestPayload(PayloadId, oneshot::Sender<Option<Arc<BuiltPayload>>>),
+}
+
+/// A handle to the [PayloadBuilderService].
+#[derive(Debug, Clone)]
+pub struct PayloadBuilderHandle {
+    to_service: mpsc::UnboundedSender<PayloadServiceCommand>,
+}
+
+// === impl PayloadBuilderHandle ===
+
+impl PayloadBuilderHandle {
+    /// Starts building a payload with the given attributes and returns its id.
+    ///
+    /// Does nothing if a payload with the same id is already being built.
+    pub fn new_payload(&self, attributes: PayloadBuilderAttributes) -> PayloadId {
+        let id = attributes.id;
+        let _ = self.to_service.send(PayloadServiceCommand::BuildNewPayload(attributes));
+        id
+    }
+
+    /// Returns the best payload built so far for the given id.
+    ///
+    /// If the first version of the payload is still being built, this waits until it is done.
+    /// Returns `None` if the payload is unknown.
+    pub async fn best_payload(&self, id: PayloadId) -> Option<Arc<BuiltPayload>> {
+        let (tx, rx) = oneshot::channel();
+        self.to_service.send(PayloadServiceCommand::BestPayload(id, tx)).ok()?;
+        rx.await.ok()?
+    }
+}
+
+/// A service that builds payloads in the background.
+///
+/// Every payload is rebuilt on a blocking thread each [PayloadBuilderConfig::interval] until its
+/// deadline is reached, and the version with the highest fees is kept.
+///
+/// The service runs until all [PayloadBuilderHandle]s are dropped.
+#[must_use = "PayloadBuilderService does nothing unless polled."]
+pub struct PayloadBuilderService<Client, Pool> {
+    client: Arc<Client>,
+    pool: Pool,
+    chain_spec: Arc<ChainSpec>,
+    config: PayloadBuilderConfig,
+    /// All payload jobs, the oldest first.
+    jobs: VecDeque<PayloadJob>,
+    command_rx: UnboundedReceiverStream<PayloadServiceCommand>,
+}
+
+// === impl PayloadBuilderService ===
+
+impl<Client, Pool> PayloadBuilderService<Client, Pool>
+where
+    Client: HeaderProvider + StateProviderFactory + 'static,
+    Pool: TransactionPool + 'static,
+{
+    /// Creates a new service and the handle to control it.
+    pub fn new(
+        client: Arc<Client>,
+        pool: Pool,
+        chain_spec: Arc<ChainSpec>,
+        config: PayloadBuilderConfig,
+    ) -> (Self, PayloadBuilderHandle) {
+        let (to_service, command_rx) = mpsc::unbounded_channel();
+        let service = Self {
+            client,
+            pool,
+            chain_spec,
+            config,
+            jobs: VecDeque::new(),
+            command_rx: UnboundedReceiverStream::new(command_rx),
+        };
+        (service, PayloadBuilderHandle { to_service })
+    }
+
+    fn on_command(&mut self, command: PayloadServiceCommand) {
+        match command {
+            PayloadServiceCommand::BuildNewPayload(attributes) => {
+                if self.jobs.iter().any(|job| job.attributes.id == attributes.id) {
+                    return
+                }
+                debug!(target: "payload_builder", id = ?attributes.id, parent = ?attributes.parent, "Starting payload job");
+
+                let client = Arc::clone(&self.client);
+                let pool = self.pool.clone();
+                let chain_spec = Arc::clone(&self.chain_spec);
+                let extra_data = self.config.extra_data.clone();
+                let build = Arc::new(move |attributes: &PayloadBuilderAttributes| {
+                    build_payload(&*client, &pool, &chain_spec, attributes, extra_data.clone())
+                });
+
+                if self.jobs.len() >= self.config.max_payload_jobs {
+                    self.jobs.pop_front();
+                }
+                self.jobs.push_back(PayloadJob::new(attributes, build, &self.config));
+            }
+            PayloadServiceCommand::BestPayload(id, tx) => {
+                match self.jobs.iter_mut().find(|job| job.attributes.id == id) {
+                    Some(job) if job.is_building_first_payload() => job.waiting.push(tx),
+                    Some(job) => {
+                        let _ = tx.send(job.best_payload.clone());
+                    }
+                    None => {
+                        let _ = tx.send(None);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<Client, Pool> Future for PayloadBuilderService<Client, Pool>
+where
+    Client: HeaderProvider + StateProviderFactory + Unpin + 'static,
+    Pool: TransactionPool + Unpin + 'static,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        loop {
+            match this.command_rx.poll_next_unpin(cx) {
+                Poll::Ready(Some(command)) => this.on_command(command),
+                // all handles were dropped
+                Poll::Ready(None) => return Poll::Ready(()),
+                Poll::Pending => break,
+            }
+        }
+
+        for job in this.jobs.iter_mut() {
+            let _ = job.poll(cx);
+        }
+
+        Poll::Pending
+    }
+}
+
+/// Builds a payload with the given attributes.
+type BuildFn = Arc<
+    dyn Fn(&PayloadBuilderAttributes) -> Result<BuiltPayload, PayloadBuilderError> + Send + Sync,
+>;
+
+/// A payload that is improved until its deadline.
+struct PayloadJob {
+    attributes: Arc<PayloadBuilderAttributes>,
+    build: BuildFn,
+    /// The version with the highest fees built so far.
+    best_payload: Option<Arc<BuiltPayload>>,
+    /// Requests that wait for the first version of the payload.
+    waiting: Vec<oneshot::Sender<Option<Arc<BuiltPayload>>>>,
+    pending_build: Option<JoinHandle<Result<BuiltPayload, PayloadBuilderError>>>,
+    /// Whether at least one build finished.
+    has_built: bool,
+    interval: Interval,
+    deadline: Pin<Box<Sleep>>,
+}
+
+// === impl PayloadJob ===
+
+impl PayloadJob {
+    /// Creates a new job and immediately starts the first build.
+    fn new(
+        attributes: PayloadBuilderAttributes,
+        build: BuildFn,
+        config: &PayloadBuilderConfig,
+    ) -> Self {
+        let now = Instant::now();
+        let mut interval = interval_at(now + config.interval, config.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut job = Self {
+            attributes: Arc::new(attributes),
+            build,
+            best_payload: None,
+            waiting: Vec::new(),
+            pending_build: None,
+            has_built: false,
+            interval,
+            deadline: Box::pin(tokio::time::sleep_until(now + config.deadline)),
+        };
+        job.spawn_build();
+        job
+    }
+
+    fn is_building_first_payload(&self) -> bool {
+        !self.has_built && self.pending_build.is_some()
+    }
+
+    fn spawn_build(&mut self) {
+        let attributes = Arc::clone(&self.attributes);
+        let build = Arc::clone(&self.build);
+        self.pending_build = Some(tokio::task::spawn_blocking(move || build(&attributes)));
+    }
+
+    fn on_build_finished(
+        &mut self,
+        result: Result<Result<BuiltPayload, PayloadBuilderError>, JoinError>,
+    ) {
+        self.has_built = true;
+        match result {
+            Ok(Ok(payload)) => {
+                if self.best_payload.as_ref().map_or(true, |best| payload.fees > best.fees) {
+                    trace!(target: "payload_builder", id = ?payload.id, fees = %payload.fees, txs = payload.block.body.len(), "Improved payload");
+                    self.best_payload = Some(Arc::new(payload));
+                }
+            }
+            Ok(Err(err)) => {
+                warn!(target: "payload_builder", id = ?self.attributes.id, ?err, "Failed to build payload")
+            }
+            Err(err) => {
+                warn!(target: "payload_builder", id = ?self.attributes.id, ?err, "Payload build task failed")
+            }
+        }
+        for tx in self.waiting.drain(..) {
+            let _ = tx.send(self.best_payload.clone());
+        }
+    }
+
+    /// Advances the job, returns `Ready` once the deadline is reached and no build is pending.
+    fn poll(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        loop {
+            if let Some(build) = self.pending_build.as_mut() {
+                let result = ready!(build.poll_unpin(cx));
+                self.pending_build = None;
+                self.on_build_finished(result);
+            }
+
+            if self.deadline.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(())
+            }
+
+            ready!(self.interval.poll_tick(cx));
+            self.spawn_build();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{proofs::EMPTY_ROOT, Address, Header, H256, MAINNET};
+    use reth_provider::test_utils::MockEthProvider;
+    use reth_transaction_pool::test_utils::testing_pool;
+
+    #[tokio::test]
+    async fn serves_best_payload() {
+        let client = MockEthProvider::default();
+        let parent = Header {
+            number: 1,
+            gas_limit: 30_000_000,
+            state_root: EMPTY_ROOT,
+            ..Default::default()
+        };
+        let parent_hash = parent.hash_slow();
+        client.add_header(parent_hash, parent);
+
+        let (service, handle) = PayloadBuilderService::new(
+            Arc::new(client),
+            testing_pool(),
+            Arc::new(MAINNET.clone()),
+            Default::default(),
+        );
+        tokio::spawn(service);
+
+        let attributes = PayloadBuilderAttributes {
+            id: PayloadId::random(),
+            parent: parent_hash,
+            timestamp: 1000,
+            suggested_fee_recipient: Address::random(),
+            prev_randao: H256::random(),
+        };
+        let id = handle.new_payload(attributes);
+
+        let payload = handle.best_payload(id).await.unwrap();
+        assert_eq!(payload.id, id);
+        assert_eq!(payload.block.parent_hash, parent_hash);
+
+        assert!(handle.best_payload(PayloadId::random()).await.is_none());
+    }
+}
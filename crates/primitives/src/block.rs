@@ -1,4 +1,4 @@
-use crate::{Header, SealedHeader, TransactionSigned, H256};
+use crate::{Address, Header, SealedHeader, TransactionSigned, H256};
 use reth_codecs::derive_arbitrary;
 use reth_rlp::{Decodable, DecodeError, Encodable, RlpDecodable, RlpEncodable};
 use serde::{Deserialize, Serialize};
@@ -22,6 +22,22 @@ impl Deref for Block {
     }
 }
 
+/// Ethereum full block with the senders of its transactions.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BlockWithSenders {
+    /// The block.
+    pub block: Block,
+    /// The senders of the transactions, in the order of the transactions.
+    pub senders: Vec<Address>,
+}
+
+impl Deref for BlockWithSenders {
+    type Target = Block;
+    fn deref(&self) -> &Self::Target {
+        &self.block
+    }
+}
+
 /// Sealed Ethereum full block.
 #[derive(Debug, Clone, PartialEq, Eq, Default, RlpEncodable, RlpDecodable)]
 pub struct SealedBlock {
@@ -1,4 +1,5 @@
 use crate::{
+    basefee::calculate_next_block_base_fee,
     keccak256,
     proofs::{EMPTY_LIST_HASH, EMPTY_ROOT},
     BlockHash, BlockNumber, Bloom, Bytes, H160, H256, U256,
@@ -106,6 +107,13 @@ impl Header {
         self.ommers_hash == EMPTY_LIST_HASH && self.transactions_root == EMPTY_ROOT
     }
 
+    /// Calculates the base fee of the next block according to EIP-1559.
+    ///
+    /// Returns `None` if this header has no base fee, i.e. London is not active yet.
+    pub fn next_block_base_fee(&self) -> Option<u64> {
+        Some(calculate_next_block_base_fee(self.gas_used, self.gas_limit, self.base_fee_per_gas?))
+    }
+
     /// Calculate hash and seal the Header so that it can't be changed.
     pub fn seal(self) -> SealedHeader {
         let hash = self.hash_slow();
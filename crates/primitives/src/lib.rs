@@ -10,6 +10,7 @@
 //! This crate contains Ethereum primitive types and helper functions.
 
 mod account;
+pub mod basefee;
 mod bits;
 mod block;
 pub mod bloom;
@@ -26,6 +27,7 @@ mod jsonu256;
 mod log;
 mod net;
 mod peer;
+mod prune;
 mod receipt;
 mod storage;
 mod transaction;
@@ -35,7 +37,7 @@ pub mod proofs;
 
 pub use account::Account;
 pub use bits::H512;
-pub use block::{Block, BlockHashOrNumber, SealedBlock};
+pub use block::{Block, BlockHashOrNumber, BlockWithSenders, SealedBlock};
 pub use bloom::Bloom;
 pub use chain::{
     Chain, ChainInfo, ChainSpec, ChainSpecBuilder, ParisStatus, GOERLI, MAINNET, SEPOLIA,
@@ -53,6 +55,7 @@ pub use jsonu256::JsonU256;
 pub use log::Log;
 pub use net::NodeRecord;
 pub use peer::{PeerId, WithPeerId};
+pub use prune::{PruneMode, PruneModes, PruneSegment};
 pub use receipt::Receipt;
 pub use storage::StorageEntry;
 pub use transaction::{
@@ -115,3 +118,34 @@ pub fn keccak256(data: impl AsRef<[u8]>) -> H256 {
     hasher.finalize(&mut buf);
     buf.into()
 }
+
+/// Returns the address of the contract created by `sender` with a `CREATE` at the given `nonce`.
+pub fn create_address(sender: Address, nonce: u64) -> Address {
+    use reth_rlp::Encodable;
+
+    let mut out = Vec::new();
+    reth_rlp::Header { list: true, payload_length: sender.length() + nonce.length() }
+        .encode(&mut out);
+    sender.encode(&mut out);
+    nonce.encode(&mut out);
+    Address::from_slice(&keccak256(out)[12..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn contract_create_address() {
+        let sender = Address::from(hex!("6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0"));
+        assert_eq!(
+            create_address(sender, 0),
+            Address::from(hex!("cd234a471b72ba2f1ccf0a70fcaba648a5eecd8d"))
+        );
+        assert_eq!(
+            create_address(sender, 1),
+            Address::from(hex!("343c43a37d37dff08ae8c4a11544c718abb4fcf8"))
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use crate::BlockNumber;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// A part of the database that can be pruned independently.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PruneSegment {
+    /// The receipts and logs of transactions.
+    Receipts,
+    /// The account changesets and the account history index.
+    AccountHistory,
+    /// The storage changesets and the storage history index.
+    StorageHistory,
+}
+
+impl PruneSegment {
+    /// All segments, in the order they are pruned.
+    pub const ALL: [PruneSegment; 3] =
+        [PruneSegment::Receipts, PruneSegment::AccountHistory, PruneSegment::StorageHistory];
+
+    /// The name of the segment, which is also the key of its pruning progress.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PruneSegment::Receipts => "Receipts",
+            PruneSegment::AccountHistory => "AccountHistory",
+            PruneSegment::StorageHistory => "StorageHistory",
+        }
+    }
+}
+
+impl Display for PruneSegment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How much of a [PruneSegment] is kept.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PruneMode {
+    /// Prune all blocks.
+    Full,
+    /// Keep the given number of the most recent blocks.
+    Distance(u64),
+    /// Keep all blocks starting at the given block.
+    Before(BlockNumber),
+}
+
+impl PruneMode {
+    /// Returns the highest block that should be pruned if the chain tip is at `tip`.
+    ///
+    /// Returns `None` if no block should be pruned.
+    pub fn prune_target(&self, tip: BlockNumber) -> Option<BlockNumber> {
+        match self {
+            PruneMode::Full => Some(tip),
+            PruneMode::Distance(distance) => tip.checked_sub(*distance),
+            PruneMode::Before(block) => block.checked_sub(1).map(|block| block.min(tip)),
+        }
+    }
+}
+
+/// The [PruneMode] of each [PruneSegment]. Segments without a mode are never pruned.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PruneModes {
+    /// The pruning mode of [PruneSegment::Receipts].
+    pub receipts: Option<PruneMode>,
+    /// The pruning mode of [PruneSegment::AccountHistory].
+    pub account_history: Option<PruneMode>,
+    /// The pruning mode of [PruneSegment::StorageHistory].
+    pub storage_history: Option<PruneMode>,
+}
+
+impl PruneModes {
+    /// Returns the pruning mode of the segment.
+    pub fn mode(&self, segment: PruneSegment) -> Option<PruneMode> {
+        match segment {
+            PruneSegment::Receipts => self.receipts,
+            PruneSegment::AccountHistory => self.account_history,
+            PruneSegment::StorageHistory => self.storage_history,
+        }
+    }
+
+    /// Returns `true` if no segment is pruned.
+    pub fn is_empty(&self) -> bool {
+        PruneSegment::ALL.iter().all(|segment| self.mode(*segment).is_none())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prune_targets() {
+        assert_eq!(PruneMode::Full.prune_target(100), Some(100));
+        assert_eq!(PruneMode::Distance(10).prune_target(100), Some(90));
+        assert_eq!(PruneMode::Distance(200).prune_target(100), None);
+        assert_eq!(PruneMode::Before(50).prune_target(100), Some(49));
+        assert_eq!(PruneMode::Before(500).prune_target(100), Some(100));
+        assert_eq!(PruneMode::Before(0).prune_target(100), None);
+    }
+
+    #[test]
+    fn deserialize_prune_modes() {
+        let modes: PruneModes = serde_json::from_str(
+            r#"{"receipts":"full","account_history":{"distance":128},"storage_history":{"before":10}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            modes,
+            PruneModes {
+                receipts: Some(PruneMode::Full),
+                account_history: Some(PruneMode::Distance(128)),
+                storage_history: Some(PruneMode::Before(10)),
+            }
+        );
+        assert!(PruneModes::default().is_empty());
+    }
+}
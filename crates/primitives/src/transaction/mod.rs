@@ -269,6 +269,41 @@ impl Transaction {
         }
     }
 
+    /// Max priority fee per gas for eip1559 transaction, for legacy and eip2930 transactions this
+    /// is `None`
+    pub fn max_priority_fee_per_gas(&self) -> Option<u128> {
+        match self {
+            Transaction::Legacy(_) | Transaction::Eip2930(_) => None,
+            Transaction::Eip1559(TxEip1559 { max_priority_fee_per_gas, .. }) => {
+                Some(*max_priority_fee_per_gas)
+            }
+        }
+    }
+
+    /// Returns the price per gas the transaction pays in a block with the given base fee.
+    ///
+    /// For legacy and eip2930 transactions this is the gas price, for eip1559 transactions it is
+    /// the base fee plus the priority fee, capped by the max fee. Without a base fee this is the
+    /// max fee.
+    pub fn effective_gas_price(&self, base_fee: Option<u64>) -> u128 {
+        match (self, base_fee) {
+            (
+                Transaction::Eip1559(TxEip1559 { max_fee_per_gas, max_priority_fee_per_gas, .. }),
+                Some(base_fee),
+            ) => (*max_fee_per_gas).min(base_fee as u128 + *max_priority_fee_per_gas),
+            _ => self.max_fee_per_gas(),
+        }
+    }
+
+    /// Get the transaction's access list, legacy transactions have none.
+    pub fn access_list(&self) -> Option<&AccessList> {
+        match self {
+            Transaction::Legacy(_) => None,
+            Transaction::Eip2930(TxEip2930 { access_list, .. }) |
+            Transaction::Eip1559(TxEip1559 { access_list, .. }) => Some(access_list),
+        }
+    }
+
     /// Encodes EIP-155 arguments into the desired buffer. Only encodes values for legacy
     /// transactions.
     pub(crate) fn encode_eip155_fields(&self, out: &mut dyn bytes::BufMut) {
@@ -749,6 +784,14 @@ impl TransactionSigned {
         keccak256(&buf)
     }
 
+    /// Returns the enveloped [EIP-2718](https://eips.ethereum.org/EIPS/eip-2718) encoding of the
+    /// transaction, as it is accepted by `eth_sendRawTransaction`.
+    pub fn envelope_encoded(&self) -> Bytes {
+        let mut buf = Vec::new();
+        self.encode_inner(&mut buf, false);
+        buf.into()
+    }
+
     /// Create a new signed transaction from a transaction and its signature.
     /// This will also calculate the transaction hash using its encoding.
     pub fn from_transaction_and_signature(transaction: Transaction, signature: Signature) -> Self {
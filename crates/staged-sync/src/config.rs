@@ -7,7 +7,7 @@ use reth_network::{
     config::{mainnet_nodes, rng_secret_key},
     NetworkConfig, NetworkConfigBuilder, PeersConfig,
 };
-use reth_primitives::{ChainSpec, NodeRecord};
+use reth_primitives::{ChainSpec, NodeRecord, PruneModes};
 use reth_provider::ShareableDatabase;
 use serde::{Deserialize, Serialize};
 
@@ -20,13 +20,17 @@ pub struct Config {
     pub stages: StageConfig,
     /// Configuration for the discovery service.
     pub peers: PeersConfig,
+    /// The pruning mode of each prunable part of the database.
+    pub prune: PruneModes,
+    /// Configuration for moving finalized data to the static files.
+    pub static_files: StaticFilesConfig,
 }
 
 impl Config {
     /// Initializes network config from read data
     pub fn network_config<DB: Database>(
         &self,
-        db: Arc<DB>,
+        client: ShareableDatabase<DB>,
         chain_spec: ChainSpec,
         disable_discovery: bool,
         bootnodes: Option<Vec<NodeRecord>>,
@@ -43,7 +47,7 @@ impl Config {
             .discovery(discv4)
             .chain_spec(chain_spec)
             .set_discovery(disable_discovery)
-            .build(Arc::new(ShareableDatabase::new(db)))
+            .build(Arc::new(client))
     }
 }
 
@@ -148,6 +152,15 @@ impl Default for ExecutionConfig {
     }
 }
 
+/// Configuration for moving finalized data out of the database.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct StaticFilesConfig {
+    /// Whether the headers, transactions and receipts of finalized blocks are moved to the static
+    /// files.
+    pub enabled: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use super::Config;
@@ -2,11 +2,12 @@ use reth_db::{
     cursor::DbCursorRO,
     database::Database,
     mdbx::{Env, WriteMap},
+    static_file::StaticFiles,
     tables,
     transaction::{DbTx, DbTxMut},
 };
 use reth_primitives::{
-    constants::EIP1559_INITIAL_BASE_FEE, Account, ChainSpec, Hardfork, Header, H256,
+    constants::EIP1559_INITIAL_BASE_FEE, keccak256, Account, ChainSpec, Hardfork, Header, H256,
 };
 use std::{path::Path, sync::Arc};
 use tracing::debug;
@@ -23,6 +24,15 @@ pub fn init_db<P: AsRef<Path>>(path: P) -> eyre::Result<Env<WriteMap>> {
     Ok(db)
 }
 
+/// The directory of the static files, inside the database directory.
+pub const STATIC_FILES_DIR: &str = "static_files";
+
+/// Opens up the static files of the database at the specified path, creating their directory if
+/// it does not exist.
+pub fn init_static_files<P: AsRef<Path>>(db_path: P) -> eyre::Result<StaticFiles> {
+    Ok(StaticFiles::open(db_path.as_ref().join(STATIC_FILES_DIR))?)
+}
+
 /// Write the genesis block if it has not already been written
 #[allow(clippy::field_reassign_with_default)]
 pub fn init_genesis<DB: Database>(db: Arc<DB>, chain: ChainSpec) -> Result<H256, reth_db::Error> {
@@ -37,16 +47,15 @@ pub fn init_genesis<DB: Database>(db: Arc<DB>, chain: ChainSpec) -> Result<H256,
     debug!("Writing genesis block.");
     let tx = db.tx_mut()?;
 
-    // Insert account state
+    // Insert account state, both plain and hashed so the state trie can be built from genesis.
     for (address, account) in &genesis.alloc {
-        tx.put::<tables::PlainAccountState>(
-            *address,
-            Account {
-                nonce: account.nonce.unwrap_or_default(),
-                balance: account.balance,
-                bytecode_hash: None,
-            },
-        )?;
+        let account = Account {
+            nonce: account.nonce.unwrap_or_default(),
+            balance: account.balance,
+            bytecode_hash: None,
+        };
+        tx.put::<tables::PlainAccountState>(*address, account)?;
+        tx.put::<tables::HashedAccount>(keccak256(address), account)?;
     }
 
     // Insert header
@@ -0,0 +1,691 @@
+use crate::{
+    stages::{
+        BODIES, EXECUTION, FINISH, HEADERS, SENDER_RECOVERY, TOTAL_DIFFICULTY, TRANSACTION_LOOKUP,
+    },
+    StageId,
+};
+use reth_db::{
+    cursor::DbCursorRO,
+    database::Database,
+    models::StoredBlockBody,
+    static_file::{StaticFileSegment, StaticFiles},
+    table::Table,
+    tables,
+    transaction::{DbTx, DbTxMut},
+};
+use reth_interfaces::db::Error as DbError;
+use reth_primitives::{
+    Address, BlockHash, BlockNumber, Header, TransactionSigned, TransitionId, TxHash, TxNumber,
+    H256, U256,
+};
+use std::{collections::HashMap, fmt::Debug, sync::Arc};
+
+/// The checked stages, in the order they are run by the pipeline.
+const CHECKED_STAGES: [StageId; 7] =
+    [HEADERS, TOTAL_DIFFICULTY, BODIES, SENDER_RECOVERY, EXECUTION, TRANSACTION_LOOKUP, FINISH];
+
+/// Checks that the tables written by the stages agree with each other and with the stage
+/// checkpoints in [tables::SyncStage].
+///
+/// The canonical chain is walked up to the highest checkpoint. For every block it checks that:
+///
+/// - the header exists, matches its hash in [tables::CanonicalHeaders] and [tables::HeaderNumbers]
+///   and is a child of the previous block
+/// - the total difficulty in [tables::HeaderTD] adds up
+/// - the body exists and its transactions follow the transactions of the previous block
+/// - [tables::TxSenders] and [tables::TxHashNumber] have an entry for every transaction
+/// - the transitions in [tables::TxTransitionIndex] and [tables::BlockTransitionIndex] increase
+///   monotonically
+///
+/// Data is only expected for the blocks that the stage writing it has processed. Headers and
+/// transactions that were moved to the [StaticFiles] are read from there.
+///
+/// Missing entries that can be derived from other tables are [Repair]ed by
+/// [ConsistencyChecker::repair]. For the other findings, the stage has to be unwound and rerun
+/// from [StageStatus::rerun_from].
+pub struct ConsistencyChecker<DB> {
+    /// The checked database.
+    db: Arc<DB>,
+    /// The static files holding the moved headers and transactions.
+    static_files: Option<Arc<StaticFiles>>,
+}
+
+impl<DB> Debug for ConsistencyChecker<DB> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConsistencyChecker").finish_non_exhaustive()
+    }
+}
+
+impl<DB: Database> ConsistencyChecker<DB> {
+    /// Create a new checker of the database.
+    pub fn new(db: Arc<DB>) -> Self {
+        Self { db, static_files: None }
+    }
+
+    /// Read the headers and transactions that were moved out of the database from the static
+    /// files.
+    pub fn with_static_files(mut self, static_files: Arc<StaticFiles>) -> Self {
+        self.static_files = Some(static_files);
+        self
+    }
+
+    /// Checks the database and returns the findings.
+    pub fn check(&self) -> Result<CheckReport, DbError> {
+        self.db.view(|tx| check_tables(tx, self.static_files.as_deref()))?
+    }
+
+    /// Applies the repairs of the findings of the report and returns the number of repaired
+    /// findings.
+    pub fn repair(&self, report: &CheckReport) -> Result<usize, DbError> {
+        let tx = self.db.tx_mut()?;
+        let mut repaired = 0;
+        for repair in report.findings.iter().filter_map(|finding| finding.repair.as_ref()) {
+            match *repair {
+                Repair::HeaderNumber { hash, number } => {
+                    tx.put::<tables::HeaderNumbers>(hash, number)?
+                }
+                Repair::TxHashNumber { hash, id } => tx.put::<tables::TxHashNumber>(hash, id)?,
+                Repair::TxSender { id, sender } => tx.put::<tables::TxSenders>(id, sender)?,
+            }
+            repaired += 1;
+        }
+        tx.commit()?;
+        Ok(repaired)
+    }
+}
+
+/// The result of [ConsistencyChecker::check].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CheckReport {
+    /// The checkpoint of each checked stage.
+    pub stages: Vec<StageStatus>,
+    /// The inconsistencies, in the order they were found.
+    pub findings: Vec<Finding>,
+}
+
+impl CheckReport {
+    /// Returns `true` if no inconsistencies were found.
+    pub fn is_consistent(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Returns the lowest block that a stage has to be rerun from, or `None` if all findings can be
+    /// repaired.
+    pub fn rerun_from(&self) -> Option<BlockNumber> {
+        self.stages.iter().filter_map(|status| status.rerun_from).min()
+    }
+}
+
+/// The state of a stage found by [ConsistencyChecker::check].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageStatus {
+    /// The stage.
+    pub stage: StageId,
+    /// The checkpoint of the stage, or `None` if the stage never ran.
+    pub checkpoint: Option<BlockNumber>,
+    /// The lowest block with data of the stage that can't be repaired. The stage has to be unwound
+    /// below this block and rerun.
+    pub rerun_from: Option<BlockNumber>,
+}
+
+/// An inconsistency found by [ConsistencyChecker::check].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// The stage that writes the inconsistent data.
+    pub stage: StageId,
+    /// The block the inconsistent data belongs to.
+    pub block: BlockNumber,
+    /// The name of the table holding the inconsistent data.
+    pub table: &'static str,
+    /// The description of the inconsistency.
+    pub message: String,
+    /// The change that repairs the inconsistency, if it can be derived from other data.
+    pub repair: Option<Repair>,
+}
+
+/// A missing entry that is derived from other data and inserted by
+/// [ConsistencyChecker::repair].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repair {
+    /// The number of a canonical header in [tables::HeaderNumbers].
+    HeaderNumber {
+        /// The hash of the header.
+        hash: BlockHash,
+        /// The number of the header.
+        number: BlockNumber,
+    },
+    /// The number of a transaction in [tables::TxHashNumber].
+    TxHashNumber {
+        /// The hash of the transaction.
+        hash: TxHash,
+        /// The number of the transaction.
+        id: TxNumber,
+    },
+    /// The sender of a transaction in [tables::TxSenders].
+    TxSender {
+        /// The number of the transaction.
+        id: TxNumber,
+        /// The recovered sender of the transaction.
+        sender: Address,
+    },
+}
+
+/// Collects the findings and the lowest unrepairable block of each stage.
+#[derive(Default)]
+struct Findings {
+    findings: Vec<Finding>,
+    rerun_from: HashMap<StageId, BlockNumber>,
+}
+
+impl Findings {
+    fn add(
+        &mut self,
+        stage: StageId,
+        block: BlockNumber,
+        table: &'static str,
+        message: String,
+        repair: Option<Repair>,
+    ) {
+        if repair.is_none() {
+            self.rerun_from
+                .entry(stage)
+                .and_modify(|rerun_from| *rerun_from = block.min(*rerun_from))
+                .or_insert(block);
+        }
+        self.findings.push(Finding { stage, block, table, message, repair });
+    }
+}
+
+fn check_tables<'tx, TX: DbTx<'tx>>(
+    tx: &TX,
+    static_files: Option<&StaticFiles>,
+) -> Result<CheckReport, DbError> {
+    let mut checkpoints = Vec::with_capacity(CHECKED_STAGES.len());
+    for stage in CHECKED_STAGES {
+        checkpoints.push((stage, stage.get_progress(tx)?));
+    }
+    // The genesis block is written before any stage runs.
+    let checkpoint = |stage: StageId| {
+        checkpoints.iter().find(|(id, _)| *id == stage).and_then(|(_, cp)| *cp).unwrap_or_default()
+    };
+    let mut findings = Findings::default();
+
+    // A stage only processes the blocks that the previous stage processed.
+    for (previous, stage) in CHECKED_STAGES.iter().zip(CHECKED_STAGES.iter().skip(1)) {
+        let (previous_checkpoint, stage_checkpoint) = (checkpoint(*previous), checkpoint(*stage));
+        if stage_checkpoint > previous_checkpoint {
+            findings.add(
+                *stage,
+                previous_checkpoint + 1,
+                tables::SyncStage::NAME,
+                format!("The checkpoint #{stage_checkpoint} is ahead of the checkpoint #{previous_checkpoint} of the {previous} stage"),
+                None,
+            );
+        }
+    }
+
+    let headers = checkpoint(HEADERS);
+    let total_difficulty = checkpoint(TOTAL_DIFFICULTY);
+    let bodies = checkpoint(BODIES);
+    let senders = checkpoint(SENDER_RECOVERY);
+    let lookup = checkpoint(TRANSACTION_LOOKUP);
+    let last = headers.max(total_difficulty).max(bodies).max(senders).max(lookup);
+
+    let mut parent = Parent::default();
+    let mut next_block = 0;
+    let mut senders_next_tx_id = None;
+    let mut canonical_cursor = tx.cursor_read::<tables::CanonicalHeaders>()?;
+    for entry in canonical_cursor.walk(0)? {
+        let (number, hash) = entry?;
+        if number > last {
+            break
+        }
+        if number != next_block {
+            findings.add(
+                HEADERS,
+                next_block,
+                tables::CanonicalHeaders::NAME,
+                format!("Blocks #{next_block} to #{} are missing", number - 1),
+                None,
+            );
+            parent = Parent::default();
+        }
+
+        let mut block = Parent { hash: Some(hash), ..Default::default() };
+        if tx.get::<tables::HeaderNumbers>(hash)? != Some(number) {
+            findings.add(
+                HEADERS,
+                number,
+                tables::HeaderNumbers::NAME,
+                format!("The canonical header {hash} is not mapped to block #{number}"),
+                Some(Repair::HeaderNumber { hash, number }),
+            );
+        }
+
+        let header = match tx.get::<tables::Headers>((number, hash).into())? {
+            Some(header) => Some(header),
+            None => match static_files {
+                Some(static_files) => {
+                    static_files.get::<Header>(StaticFileSegment::Headers, number)?
+                }
+                None => None,
+            },
+        };
+        match header {
+            None => findings.add(
+                HEADERS,
+                number,
+                tables::Headers::NAME,
+                format!("The header of block #{number} is missing"),
+                None,
+            ),
+            Some(header) if header.hash_slow() != hash => findings.add(
+                HEADERS,
+                number,
+                tables::Headers::NAME,
+                format!("The header of block #{number} does not match the canonical hash {hash}"),
+                None,
+            ),
+            Some(header) => {
+                if parent.hash.map_or(false, |parent_hash| parent_hash != header.parent_hash) {
+                    findings.add(
+                        HEADERS,
+                        number,
+                        tables::Headers::NAME,
+                        format!("The header of block #{number} is not a child of the canonical block #{}", number - 1),
+                        None,
+                    );
+                }
+                if number <= total_difficulty {
+                    block.td = check_total_difficulty(tx, &mut findings, &header, hash, parent.td)?;
+                }
+            }
+        }
+
+        if number <= bodies {
+            match tx.get::<tables::BlockBodies>((number, hash).into())? {
+                Some(body) => {
+                    if parent.next_tx_id.map_or(false, |next_tx_id| next_tx_id != body.start_tx_id)
+                    {
+                        findings.add(
+                            BODIES,
+                            number,
+                            tables::BlockBodies::NAME,
+                            format!(
+                                "The transactions of block #{number} start at #{} instead of #{}",
+                                body.start_tx_id,
+                                parent.next_tx_id.unwrap_or_default()
+                            ),
+                            None,
+                        );
+                    }
+                    block.next_tx_id = Some(body.start_tx_id + body.tx_count);
+                    check_transactions(
+                        tx,
+                        static_files,
+                        &mut findings,
+                        number,
+                        &body,
+                        number <= senders,
+                        number <= lookup,
+                    )?;
+                    block.transition =
+                        check_transitions(tx, &mut findings, number, &body, parent.transition)?;
+                }
+                None => findings.add(
+                    BODIES,
+                    number,
+                    tables::BlockBodies::NAME,
+                    format!("The body of block #{number} is missing"),
+                    None,
+                ),
+            }
+        }
+        if number == senders {
+            senders_next_tx_id = block.next_tx_id;
+        }
+
+        parent = block;
+        next_block = number + 1;
+    }
+    if next_block <= last {
+        findings.add(
+            HEADERS,
+            next_block,
+            tables::CanonicalHeaders::NAME,
+            format!("Blocks #{next_block} to #{last} are missing"),
+            None,
+        );
+    }
+
+    // Entries above the checkpoint are left behind by an interrupted stage, they prevent the stage
+    // from appending the next blocks.
+    if let Some((key, _)) = tx.cursor_read::<tables::BlockBodies>()?.last()? {
+        check_no_entry_above(
+            &mut findings,
+            BODIES,
+            tables::BlockBodies::NAME,
+            key.number(),
+            bodies,
+        );
+    }
+    if let Some((number, _)) = tx.cursor_read::<tables::BlockTransitionIndex>()?.last()? {
+        check_no_entry_above(
+            &mut findings,
+            BODIES,
+            tables::BlockTransitionIndex::NAME,
+            number,
+            bodies,
+        );
+    }
+    if let (Some((id, _)), Some(next_tx_id)) =
+        (tx.cursor_read::<tables::TxSenders>()?.last()?, senders_next_tx_id)
+    {
+        if id >= next_tx_id {
+            findings.add(
+                SENDER_RECOVERY,
+                senders + 1,
+                tables::TxSenders::NAME,
+                format!(
+                    "Transaction #{id} is above the last transaction of the checkpoint #{senders}"
+                ),
+                None,
+            );
+        }
+    }
+
+    let stages = checkpoints
+        .into_iter()
+        .map(|(stage, checkpoint)| StageStatus {
+            stage,
+            checkpoint,
+            rerun_from: findings.rerun_from.get(&stage).copied(),
+        })
+        .collect();
+    Ok(CheckReport { stages, findings: findings.findings })
+}
+
+/// The data of the previous canonical block that the next block is checked against.
+///
+/// A field is `None` if the data of the previous block is missing or was not checked.
+#[derive(Default)]
+struct Parent {
+    hash: Option<H256>,
+    td: Option<U256>,
+    next_tx_id: Option<TxNumber>,
+    transition: Option<TransitionId>,
+}
+
+/// Checks the total difficulty of the block and returns it.
+fn check_total_difficulty<'tx, TX: DbTx<'tx>>(
+    tx: &TX,
+    findings: &mut Findings,
+    header: &Header,
+    hash: H256,
+    parent_td: Option<U256>,
+) -> Result<Option<U256>, DbError> {
+    let number = header.number;
+    let Some(td) = tx.get::<tables::HeaderTD>((number, hash).into())? else {
+        findings.add(
+            TOTAL_DIFFICULTY,
+            number,
+            tables::HeaderTD::NAME,
+            format!("The total difficulty of block #{number} is missing"),
+            None,
+        );
+        return Ok(None)
+    };
+    let td: U256 = td.into();
+    if parent_td.map_or(false, |parent_td| parent_td + header.difficulty != td) {
+        findings.add(
+            TOTAL_DIFFICULTY,
+            number,
+            tables::HeaderTD::NAME,
+            format!("The total difficulty of block #{number} is not the total difficulty of its parent plus its difficulty"),
+            None,
+        );
+    }
+    Ok(Some(td))
+}
+
+/// Checks that the transactions of the block exist and have a sender and hash entry, if the
+/// respective stage processed the block.
+fn check_transactions<'tx, TX: DbTx<'tx>>(
+    tx: &TX,
+    static_files: Option<&StaticFiles>,
+    findings: &mut Findings,
+    number: BlockNumber,
+    body: &StoredBlockBody,
+    check_senders: bool,
+    check_lookup: bool,
+) -> Result<(), DbError> {
+    for id in body.tx_id_range() {
+        let transaction = match tx.get::<tables::Transactions>(id)? {
+            Some(transaction) => Some(transaction),
+            None => match static_files {
+                Some(static_files) => {
+                    static_files.get::<TransactionSigned>(StaticFileSegment::Transactions, id)?
+                }
+                None => None,
+            },
+        };
+        if transaction.is_none() {
+            findings.add(
+                BODIES,
+                number,
+                tables::Transactions::NAME,
+                format!("Transaction #{id} of block #{number} is missing"),
+                None,
+            );
+        }
+
+        if check_senders && tx.get::<tables::TxSenders>(id)?.is_none() {
+            let repair = transaction
+                .as_ref()
+                .and_then(|transaction| transaction.recover_signer())
+                .map(|sender| Repair::TxSender { id, sender });
+            findings.add(
+                SENDER_RECOVERY,
+                number,
+                tables::TxSenders::NAME,
+                format!("The sender of transaction #{id} is missing"),
+                repair,
+            );
+        }
+
+        // The lookup entry of a missing transaction can't be checked, it is reported above.
+        if let Some(transaction) = transaction.filter(|_| check_lookup) {
+            let hash = transaction.hash();
+            if tx.get::<tables::TxHashNumber>(hash)? != Some(id) {
+                findings.add(
+                    TRANSACTION_LOOKUP,
+                    number,
+                    tables::TxHashNumber::NAME,
+                    format!("Transaction {hash} is not mapped to transaction #{id}"),
+                    Some(Repair::TxHashNumber { hash, id }),
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the transitions of the transactions of the block follow the transition of the
+/// parent block and returns the transition of the block.
+///
+/// Every transaction is one transition, and the block reward may add one more transition at the
+/// end of the block.
+fn check_transitions<'tx, TX: DbTx<'tx>>(
+    tx: &TX,
+    findings: &mut Findings,
+    number: BlockNumber,
+    body: &StoredBlockBody,
+    parent_transition: Option<TransitionId>,
+) -> Result<Option<TransitionId>, DbError> {
+    let transition = tx.get::<tables::BlockTransitionIndex>(number)?;
+    let Some(parent_transition) = parent_transition else { return Ok(transition) };
+
+    for (offset, id) in body.tx_id_range().enumerate() {
+        let expected = parent_transition + offset as u64;
+        match tx.get::<tables::TxTransitionIndex>(id)? {
+            Some(tx_transition) if tx_transition == expected => {}
+            Some(tx_transition) => findings.add(
+                BODIES,
+                number,
+                tables::TxTransitionIndex::NAME,
+                format!("The transition #{tx_transition} of transaction #{id} is not the expected transition #{expected}"),
+                None,
+            ),
+            None => findings.add(
+                BODIES,
+                number,
+                tables::TxTransitionIndex::NAME,
+                format!("The transition of transaction #{id} is missing"),
+                None,
+            ),
+        }
+    }
+
+    let min_transition = parent_transition + body.tx_count;
+    match transition {
+        Some(transition) if (min_transition..=min_transition + 1).contains(&transition) => {}
+        Some(transition) => findings.add(
+            BODIES,
+            number,
+            tables::BlockTransitionIndex::NAME,
+            format!("The transition #{transition} of block #{number} does not follow the transition #{parent_transition} of its parent"),
+            None,
+        ),
+        None => findings.add(
+            BODIES,
+            number,
+            tables::BlockTransitionIndex::NAME,
+            format!("The transition of block #{number} is missing"),
+            None,
+        ),
+    }
+    Ok(transition)
+}
+
+fn check_no_entry_above(
+    findings: &mut Findings,
+    stage: StageId,
+    table: &'static str,
+    last: BlockNumber,
+    checkpoint: BlockNumber,
+) {
+    if last > checkpoint {
+        findings.add(
+            stage,
+            checkpoint + 1,
+            table,
+            format!("Block #{last} is above the checkpoint #{checkpoint}"),
+            None,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::mdbx::{test_utils::create_test_db, EnvKind, WriteMap};
+    use reth_interfaces::test_utils::generators::random_block_range;
+    use reth_primitives::SealedBlock;
+
+    /// Inserts the blocks with all the data the checked stages write and saves `tip` as the
+    /// checkpoint of all stages.
+    fn insert_blocks<DB: Database>(db: &DB, blocks: &[SealedBlock], tip: BlockNumber) {
+        db.update(|tx| {
+            let (mut tx_id, mut transition, mut td) = (0, 0, U256::ZERO);
+            for block in blocks {
+                let (number, hash) = (block.number, block.hash());
+                td += block.difficulty;
+                tx.put::<tables::CanonicalHeaders>(number, hash).unwrap();
+                tx.put::<tables::HeaderNumbers>(hash, number).unwrap();
+                tx.put::<tables::Headers>((number, hash).into(), block.header.clone().unseal())
+                    .unwrap();
+                tx.put::<tables::HeaderTD>((number, hash).into(), td.into()).unwrap();
+                tx.put::<tables::BlockBodies>(
+                    (number, hash).into(),
+                    StoredBlockBody { start_tx_id: tx_id, tx_count: block.body.len() as u64 },
+                )
+                .unwrap();
+                for transaction in block.body.iter() {
+                    tx.put::<tables::Transactions>(tx_id, transaction.clone()).unwrap();
+                    tx.put::<tables::TxSenders>(tx_id, transaction.recover_signer().unwrap())
+                        .unwrap();
+                    tx.put::<tables::TxHashNumber>(transaction.hash(), tx_id).unwrap();
+                    tx.put::<tables::TxTransitionIndex>(tx_id, transition).unwrap();
+                    tx_id += 1;
+                    transition += 1;
+                }
+                // block reward
+                transition += 1;
+                tx.put::<tables::BlockTransitionIndex>(number, transition).unwrap();
+            }
+            for stage in CHECKED_STAGES {
+                stage.save_progress(tx, tip).unwrap();
+            }
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn consistent_database() {
+        let db = Arc::new(create_test_db::<WriteMap>(EnvKind::RW));
+        let blocks = random_block_range(0..5, H256::zero(), 1..3);
+        insert_blocks(db.as_ref(), &blocks, 4);
+
+        let report = ConsistencyChecker::new(db).check().unwrap();
+        assert!(report.is_consistent(), "{:?}", report.findings);
+        assert_eq!(report.stages.len(), CHECKED_STAGES.len());
+        assert!(report.stages.iter().all(|status| status.checkpoint == Some(4)));
+        assert_eq!(report.rerun_from(), None);
+    }
+
+    #[test]
+    fn repair_missing_entries() {
+        let db = Arc::new(create_test_db::<WriteMap>(EnvKind::RW));
+        let blocks = random_block_range(0..5, H256::zero(), 1..3);
+        insert_blocks(db.as_ref(), &blocks, 4);
+        db.update(|tx| {
+            tx.delete::<tables::HeaderNumbers>(blocks[1].hash(), None).unwrap();
+            tx.delete::<tables::TxSenders>(0, None).unwrap();
+            tx.delete::<tables::TxHashNumber>(blocks[3].body[0].hash(), None).unwrap();
+        })
+        .unwrap();
+
+        let checker = ConsistencyChecker::new(db);
+        let report = checker.check().unwrap();
+        let found: Vec<_> =
+            report.findings.iter().map(|finding| (finding.stage, finding.block)).collect();
+        assert_eq!(found, vec![(SENDER_RECOVERY, 0), (HEADERS, 1), (TRANSACTION_LOOKUP, 3)]);
+        assert_eq!(report.rerun_from(), None);
+
+        assert_eq!(checker.repair(&report).unwrap(), 3);
+        assert!(checker.check().unwrap().is_consistent());
+    }
+
+    #[test]
+    fn unrepairable_findings() {
+        let db = Arc::new(create_test_db::<WriteMap>(EnvKind::RW));
+        let blocks = random_block_range(0..5, H256::zero(), 1..3);
+        insert_blocks(db.as_ref(), &blocks, 4);
+        db.update(|tx| {
+            tx.delete::<tables::BlockBodies>((3, blocks[3].hash()).into(), None).unwrap();
+            HEADERS.save_progress(tx, 6).unwrap();
+        })
+        .unwrap();
+
+        let report = ConsistencyChecker::new(db).check().unwrap();
+        let found: Vec<_> =
+            report.findings.iter().map(|finding| (finding.stage, finding.block)).collect();
+        assert_eq!(found, vec![(BODIES, 3), (HEADERS, 5)]);
+        assert!(report.findings.iter().all(|finding| finding.repair.is_none()));
+        assert_eq!(report.rerun_from(), Some(3));
+        let status = |stage| report.stages.iter().find(|status| status.stage == stage).unwrap();
+        assert_eq!(status(HEADERS).rerun_from, Some(5));
+        assert_eq!(status(BODIES).rerun_from, Some(3));
+        assert_eq!(status(SENDER_RECOVERY).rerun_from, None);
+    }
+}
@@ -13,7 +13,7 @@ use reth_db::{
     transaction::{DbTx, DbTxMut},
     Error,
 };
-use reth_primitives::{BlockHash, BlockNumber, TransitionId, TxNumber};
+use reth_primitives::{BlockHash, BlockNumber, Header, TransitionId, TxNumber};
 
 use crate::{DatabaseIntegrityError, StageError};
 
@@ -118,6 +118,15 @@ where
         Ok((number, self.get_block_hash(number)?).into())
     }
 
+    /// Query the block header by number
+    pub(crate) fn get_header_by_num(&self, number: BlockNumber) -> Result<Header, StageError> {
+        let key = self.get_block_numhash(number)?;
+        let header = self
+            .get::<tables::Headers>(key)?
+            .ok_or(DatabaseIntegrityError::Header { number, hash: key.hash() })?;
+        Ok(header)
+    }
+
     /// Query the block body by [BlockNumHash] key
     pub(crate) fn get_block_body(&self, key: BlockNumHash) -> Result<StoredBlockBody, StageError> {
         let body = self
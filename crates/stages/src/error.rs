@@ -1,6 +1,6 @@
 use crate::pipeline::PipelineEvent;
 use reth_interfaces::{consensus, db::Error as DbError, executor, p2p::error::DownloadError};
-use reth_primitives::{BlockNumber, TxNumber, H256};
+use reth_primitives::{BlockNumber, PruneSegment, SealedHeader, TxNumber, H256};
 use thiserror::Error;
 use tokio::sync::mpsc::error::SendError;
 
@@ -16,6 +16,20 @@ pub enum StageError {
         #[source]
         error: consensus::Error,
     },
+    /// The downloaded headers do not connect to the local head.
+    ///
+    /// This happens if the chain tip moved to a different branch, in which case the local chain
+    /// has to be unwound.
+    #[error(
+        "Stage encountered a detached head. Local head #{}: {:?}. Downloaded parent: {:?}",
+        .local_head.number, .local_head.hash(), .header.parent_hash
+    )]
+    DetachedHead {
+        /// The local head of the chain.
+        local_head: Box<SealedHeader>,
+        /// The downloaded header with the number following the local head.
+        header: Box<SealedHeader>,
+    },
     /// The stage encountered a database error.
     #[error("An internal database error occurred: {0}")]
     Database(#[from] DbError),
@@ -103,6 +117,12 @@ pub enum DatabaseIntegrityError {
         /// The transaction id
         id: TxNumber,
     },
+    /// The receipt of a transaction is missing
+    #[error("Receipt of transaction #{id} not found")]
+    Receipt {
+        /// The transaction id
+        id: TxNumber,
+    },
     #[error("Block transition not found for block #{number}")]
     BlockTransition { number: BlockNumber },
     #[error("Gap in transaction table. Missing tx number #{missing}.")]
@@ -119,6 +139,16 @@ pub enum DatabaseIntegrityError {
         /// The block number key
         number: BlockNumber,
     },
+    /// The state root computed from the database does not match the block header.
+    #[error("State root mismatch for block #{number}: got {got:?}, expected {expected:?}")]
+    StateRoot {
+        /// The block number key
+        number: BlockNumber,
+        /// The state root computed from the database
+        got: H256,
+        /// The state root from the block header
+        expected: H256,
+    },
 }
 
 /// A pipeline execution error.
@@ -133,7 +163,49 @@ pub enum PipelineError {
     /// The pipeline encountered an error while trying to send an event.
     #[error("The pipeline encountered an error while trying to send an event.")]
     Channel(#[from] SendError<PipelineEvent>),
+    /// The pipeline was asked to unwind blocks that are finalized.
+    #[error("Unwind to block {to} would remove the finalized block {finalized}.")]
+    UnwindBelowFinalized {
+        /// The requested unwind target.
+        to: BlockNumber,
+        /// The number of the finalized block.
+        finalized: BlockNumber,
+    },
+    /// The pipeline was asked to unwind blocks whose data was pruned.
+    #[error(
+        "Unwind to block {to} requires the {segment} data that was pruned up to block {pruned_to}."
+    )]
+    UnwindBelowPruned {
+        /// The requested unwind target.
+        to: BlockNumber,
+        /// The pruned segment.
+        segment: PruneSegment,
+        /// The highest block of the segment that was pruned.
+        pruned_to: BlockNumber,
+    },
     /// The stage encountered an internal error.
     #[error(transparent)]
     Internal(Box<dyn std::error::Error + Send + Sync>),
 }
+
+/// A pruner error.
+#[derive(Error, Debug)]
+pub enum PrunerError {
+    /// The pruner encountered a database error.
+    #[error("A database error occurred.")]
+    Database(#[from] DbError),
+    /// The pruner encountered a database integrity error.
+    #[error("A database integrity error occurred: {0}")]
+    DatabaseIntegrity(#[from] DatabaseIntegrityError),
+}
+
+/// A static file producer error.
+#[derive(Error, Debug)]
+pub enum StaticFileProducerError {
+    /// The producer encountered a database or static file error.
+    #[error("A database error occurred: {0}")]
+    Database(#[from] DbError),
+    /// The producer encountered a database integrity error.
+    #[error("A database integrity error occurred: {0}")]
+    DatabaseIntegrity(#[from] DatabaseIntegrityError),
+}
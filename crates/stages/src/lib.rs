@@ -49,11 +49,14 @@
 //!     .build();
 //! #
 //! ```
+mod check;
 mod db;
 mod error;
 mod id;
 mod pipeline;
+mod prune;
 mod stage;
+mod static_file;
 mod util;
 
 #[cfg(test)]
@@ -67,11 +70,14 @@ pub mod stages;
 
 pub mod sets;
 
+pub use check::{CheckReport, ConsistencyChecker, Finding, Repair, StageStatus};
 pub use db::Transaction;
 pub use error::*;
 pub use id::*;
 pub use pipeline::*;
+pub use prune::{Pruner, MINIMUM_PRUNING_DISTANCE};
 pub use stage::*;
+pub use static_file::StaticFileProducer;
 
 // NOTE: Needed so the link in the module-level rustdoc works.
 #[allow(unused_extern_crates)]
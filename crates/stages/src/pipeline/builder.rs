@@ -1,6 +1,9 @@
 use crate::{pipeline::QueuedStage, Pipeline, Stage, StageSet};
 use reth_db::database::Database;
-use reth_interfaces::sync::{NoopSyncStateUpdate, SyncStateUpdater};
+use reth_interfaces::{
+    events::ChainEventSender,
+    sync::{NoopSyncStateUpdate, SyncStateUpdater},
+};
 use reth_primitives::BlockNumber;
 
 /// Builds a [`Pipeline`].
@@ -62,6 +65,14 @@ where
         self
     }
 
+    /// Set the [ChainEventSender] the pipeline announces canonical chain changes with.
+    ///
+    /// This allows to subscribe to the events before the pipeline is built.
+    pub fn with_chain_events(mut self, chain_events: ChainEventSender) -> Self {
+        self.pipeline.chain_events = chain_events;
+        self
+    }
+
     /// Builds the final [`Pipeline`].
     pub fn build(self) -> Pipeline<DB, U> {
         self.pipeline
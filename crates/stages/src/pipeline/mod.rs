@@ -1,9 +1,14 @@
 use crate::{
-    db::Transaction, error::*, ExecInput, ExecOutput, Stage, StageError, StageId, UnwindInput,
+    db::Transaction, error::*, id::is_downloading_stage, ExecInput, ExecOutput, Stage, StageError,
+    StageId, UnwindInput,
 };
 use reth_db::database::Database;
-use reth_interfaces::sync::{SyncState, SyncStateUpdater};
-use reth_primitives::BlockNumber;
+use reth_interfaces::{
+    events::{ChainEvent, ChainEventSender},
+    sync::{SyncState, SyncStateUpdater},
+};
+use reth_primitives::{BlockNumber, PruneSegment};
+use reth_provider::{prune_checkpoint, ForkchoiceMarker};
 use std::{
     fmt::{Debug, Formatter},
     ops::Deref,
@@ -24,6 +29,11 @@ pub use event::*;
 pub use set::*;
 use state::*;
 
+/// The number of blocks that are unwound if the downloaded headers don't connect to the local head.
+///
+/// The unwind is repeated until the common ancestor of the local chain and the new chain is found.
+pub const BEACON_CONSENSUS_REORG_UNWIND_DEPTH: u64 = 3;
+
 #[cfg_attr(doc, aquamarine::aquamarine)]
 /// A staged sync pipeline.
 ///
@@ -74,11 +84,19 @@ use state::*;
 /// In case of a validation error (as determined by the consensus engine) in one of the stages, the
 /// pipeline will unwind the stages in reverse order of execution. It is also possible to
 /// request an unwind manually (see [Pipeline::unwind]).
+///
+/// # Chain events
+///
+/// The progress of the last stage is the tip of the canonical chain. Whenever it changes after a
+/// loop or an unwind, a [ChainEvent] is sent to the subscribers of [Pipeline::chain_events].
 pub struct Pipeline<DB: Database, U: SyncStateUpdater> {
     stages: Vec<QueuedStage<DB>>,
     max_block: Option<BlockNumber>,
     listeners: PipelineEventListeners,
     sync_state_updater: Option<U>,
+    chain_events: ChainEventSender,
+    /// The canonical tip that was last announced.
+    canonical_tip: Option<BlockNumber>,
 }
 
 impl<DB: Database, U: SyncStateUpdater> Default for Pipeline<DB, U> {
@@ -88,6 +106,8 @@ impl<DB: Database, U: SyncStateUpdater> Default for Pipeline<DB, U> {
             max_block: None,
             listeners: PipelineEventListeners::default(),
             sync_state_updater: None,
+            chain_events: ChainEventSender::default(),
+            canonical_tip: None,
         }
     }
 }
@@ -115,9 +135,17 @@ impl<DB: Database, U: SyncStateUpdater> Pipeline<DB, U> {
         self.listeners.new_listener()
     }
 
+    /// Returns the sender of the canonical [ChainEvent]s, which can be used to subscribe to them.
+    pub fn chain_events(&self) -> ChainEventSender {
+        self.chain_events.clone()
+    }
+
     /// Run the pipeline in an infinite loop. Will terminate early if the user has specified
     /// a `max_block` in the pipeline.
     pub async fn run(&mut self, db: Arc<DB>) -> Result<(), PipelineError> {
+        // record the current tip, so only changes made by this run are announced
+        self.notify_chain_events(db.as_ref())?;
+
         loop {
             let mut state = PipelineState {
                 listeners: self.listeners.clone(),
@@ -125,6 +153,7 @@ impl<DB: Database, U: SyncStateUpdater> Pipeline<DB, U> {
                 ..Default::default()
             };
             let next_action = self.run_loop(&mut state, db.as_ref()).await?;
+            self.notify_chain_events(db.as_ref())?;
 
             // Terminate the loop early if it's reached the maximum user
             // configured block.
@@ -157,7 +186,7 @@ impl<DB: Database, U: SyncStateUpdater> Pipeline<DB, U> {
 
             // Update sync state
             if let Some(ref updater) = self.sync_state_updater {
-                let state = pipeline_progress.current_sync_state(stage_id.is_downloading_stage());
+                let state = pipeline_progress.current_sync_state(is_downloading_stage(stage_id));
                 updater.update_sync_state(state);
             }
 
@@ -194,6 +223,9 @@ impl<DB: Database, U: SyncStateUpdater> Pipeline<DB, U> {
     /// Unwind the stages to the target block.
     ///
     /// If the unwind is due to a bad block the number of that block should be specified.
+    ///
+    /// Finalized blocks are immutable: unwinding below the finalized block is an error. So is
+    /// unwinding blocks whose data was pruned.
     pub async fn unwind(
         &mut self,
         db: &DB,
@@ -205,6 +237,21 @@ impl<DB: Database, U: SyncStateUpdater> Pipeline<DB, U> {
 
         let mut tx = Transaction::new(db)?;
 
+        if let Some(finalized) = ForkchoiceMarker::Finalized.block_number(tx.deref())? {
+            if to < finalized {
+                error!(target: "sync::pipeline", %to, %finalized, "Refusing to unwind finalized blocks");
+                return Err(PipelineError::UnwindBelowFinalized { to, finalized })
+            }
+        }
+        for segment in PruneSegment::ALL {
+            if let Some(pruned_to) = prune_checkpoint(tx.deref(), segment)? {
+                if to < pruned_to {
+                    error!(target: "sync::pipeline", %to, %segment, %pruned_to, "Refusing to unwind pruned blocks");
+                    return Err(PipelineError::UnwindBelowPruned { to, segment, pruned_to })
+                }
+            }
+        }
+
         for QueuedStage { stage, .. } in unwind_pipeline {
             let stage_id = stage.id();
             let span = info_span!("Unwinding", stage = %stage_id);
@@ -214,7 +261,7 @@ impl<DB: Database, U: SyncStateUpdater> Pipeline<DB, U> {
             if stage_progress < to {
                 debug!(from = %stage_progress, %to, "Unwind point too far for stage");
                 self.listeners.notify(PipelineEvent::Skipped { stage_id });
-                return Ok(())
+                continue
             }
 
             debug!(from = %stage_progress, %to, ?bad_block, "Starting unwind");
@@ -240,6 +287,27 @@ impl<DB: Database, U: SyncStateUpdater> Pipeline<DB, U> {
         }
 
         tx.commit()?;
+        self.notify_chain_events(db)?;
+        Ok(())
+    }
+
+    /// Sends a [ChainEvent] if the progress of the last stage changed since the last call.
+    ///
+    /// The first call only records the current progress.
+    fn notify_chain_events(&mut self, db: &DB) -> Result<(), PipelineError> {
+        let Some(last_stage) = self.stages.last() else { return Ok(()) };
+        let stage_id = last_stage.stage.id();
+        let tip = db.view(|tx| stage_id.get_progress(tx))??.unwrap_or_default();
+
+        match self.canonical_tip.replace(tip) {
+            Some(previous) if tip > previous => {
+                self.chain_events.notify(ChainEvent::Extended { first: previous + 1, tip })
+            }
+            Some(previous) if tip < previous => {
+                self.chain_events.notify(ChainEvent::Unwound { tip })
+            }
+            _ => {}
+        }
         Ok(())
     }
 }
@@ -346,7 +414,23 @@ impl<DB: Database> QueuedStage<DB> {
                 Err(err) => {
                     state.listeners.notify(PipelineEvent::Error { stage_id });
 
-                    return if let StageError::Validation { block, error } = err {
+                    return if let StageError::DetachedHead { local_head, header } = err {
+                        warn!(
+                            target: "sync::pipeline",
+                            stage = %stage_id,
+                            local_head = ?local_head.hash(),
+                            parent = ?header.parent_hash,
+                            "Stage encountered a detached head"
+                        );
+
+                        // The chain tip moved to a different branch. We unwind a few blocks below
+                        // the fork and try to connect the downloaded headers again.
+                        let fork = header.number.saturating_sub(1).min(local_head.number);
+                        Ok(ControlFlow::Unwind {
+                            target: fork.saturating_sub(BEACON_CONSENSUS_REORG_UNWIND_DEPTH),
+                            bad_block: None,
+                        })
+                    } else if let StageError::Validation { block, error } = err {
                         warn!(
                             target: "sync::pipeline",
                             stage = %stage_id,
@@ -389,8 +473,14 @@ mod tests {
     use super::*;
     use crate::{StageId, UnwindOutput};
     use assert_matches::assert_matches;
-    use reth_db::mdbx::{self, test_utils, EnvKind};
-    use reth_interfaces::{consensus, sync::NoopSyncStateUpdate};
+    use reth_db::{
+        mdbx::{self, test_utils, EnvKind},
+        tables,
+        transaction::DbTxMut,
+    };
+    use reth_interfaces::{consensus, events::ChainEventSubscriptions, sync::NoopSyncStateUpdate};
+    use reth_primitives::{Header, H256};
+    use reth_provider::save_prune_checkpoint;
     use tokio_stream::StreamExt;
     use utils::TestStage;
 
@@ -516,6 +606,93 @@ mod tests {
         );
     }
 
+    /// Announces the changes of the canonical tip.
+    #[tokio::test]
+    async fn chain_events() {
+        let db = test_utils::create_test_db::<mdbx::WriteMap>(EnvKind::RW);
+
+        let mut pipeline: Pipeline<_, NoopSyncStateUpdate> = Pipeline::builder()
+            .add_stage(
+                TestStage::new(StageId("A"))
+                    .add_exec(Ok(ExecOutput { stage_progress: 20, done: true }))
+                    .add_unwind(Ok(UnwindOutput { stage_progress: 1 })),
+            )
+            .add_stage(
+                TestStage::new(StageId("B"))
+                    .add_exec(Ok(ExecOutput { stage_progress: 10, done: true }))
+                    .add_unwind(Ok(UnwindOutput { stage_progress: 1 })),
+            )
+            .with_max_block(10)
+            .build();
+        let mut chain_events = pipeline.chain_events().subscribe_chain_events();
+
+        pipeline.run(db.clone()).await.expect("Could not run pipeline");
+        pipeline.unwind(&db, 1, None).await.expect("Could not unwind pipeline");
+
+        assert_eq!(chain_events.try_recv(), Ok(ChainEvent::Extended { first: 1, tip: 10 }));
+        assert_eq!(chain_events.try_recv(), Ok(ChainEvent::Unwound { tip: 1 }));
+        assert!(chain_events.try_recv().is_err());
+    }
+
+    /// Refuses to unwind below the finalized block.
+    #[tokio::test]
+    async fn unwind_below_finalized() {
+        let db = test_utils::create_test_db::<mdbx::WriteMap>(EnvKind::RW);
+        let finalized = H256::random();
+        db.update(|tx| {
+            tx.put::<tables::CanonicalHeaders>(5, finalized).unwrap();
+            tx.put::<tables::HeaderNumbers>(finalized, 5).unwrap();
+            ForkchoiceMarker::Finalized.save(tx, finalized).unwrap();
+        })
+        .unwrap();
+
+        let mut pipeline: Pipeline<_, NoopSyncStateUpdate> = Pipeline::builder()
+            .add_stage(
+                TestStage::new(StageId("A"))
+                    .add_exec(Ok(ExecOutput { stage_progress: 10, done: true }))
+                    .add_unwind(Ok(UnwindOutput { stage_progress: 5 })),
+            )
+            .with_max_block(10)
+            .build();
+
+        pipeline.run(db.clone()).await.expect("Could not run pipeline");
+        assert_matches!(
+            pipeline.unwind(&db, 4, None).await,
+            Err(PipelineError::UnwindBelowFinalized { to: 4, finalized: 5 })
+        );
+        assert_matches!(pipeline.unwind(&db, 5, None).await, Ok(()));
+        assert_eq!(db.view(|tx| StageId("A").get_progress(tx)).unwrap().unwrap(), Some(5));
+    }
+
+    /// Refuses to unwind blocks whose data was pruned.
+    #[tokio::test]
+    async fn unwind_below_pruned() {
+        let db = test_utils::create_test_db::<mdbx::WriteMap>(EnvKind::RW);
+        db.update(|tx| save_prune_checkpoint(tx, PruneSegment::AccountHistory, 5).unwrap())
+            .unwrap();
+
+        let mut pipeline: Pipeline<_, NoopSyncStateUpdate> = Pipeline::builder()
+            .add_stage(
+                TestStage::new(StageId("A"))
+                    .add_exec(Ok(ExecOutput { stage_progress: 10, done: true }))
+                    .add_unwind(Ok(UnwindOutput { stage_progress: 5 })),
+            )
+            .with_max_block(10)
+            .build();
+
+        pipeline.run(db.clone()).await.expect("Could not run pipeline");
+        assert_matches!(
+            pipeline.unwind(&db, 4, None).await,
+            Err(PipelineError::UnwindBelowPruned {
+                to: 4,
+                segment: PruneSegment::AccountHistory,
+                pruned_to: 5
+            })
+        );
+        assert_matches!(pipeline.unwind(&db, 5, None).await, Ok(()));
+        assert_eq!(db.view(|tx| StageId("A").get_progress(tx)).unwrap().unwrap(), Some(5));
+    }
+
     /// Runs a pipeline that unwinds during sync.
     ///
     /// The flow is:
@@ -590,6 +767,78 @@ mod tests {
         );
     }
 
+    /// Runs a pipeline that unwinds because of a detached head.
+    ///
+    /// The flow is:
+    ///
+    /// - Stage A syncs to block 5, but is not done yet
+    /// - Stage A downloads headers that don't connect to block 5
+    /// - Stage B is skipped since it did not sync past the unwind target
+    /// - Stage A unwinds by [BEACON_CONSENSUS_REORG_UNWIND_DEPTH] blocks
+    /// - Stage A and B sync to block 10
+    #[tokio::test]
+    async fn run_pipeline_with_detached_head() {
+        let db = test_utils::create_test_db::<mdbx::WriteMap>(EnvKind::RW);
+        let local_head = Header { number: 5, ..Default::default() }.seal();
+        let header = Header { number: 6, parent_hash: H256::random(), ..Default::default() };
+
+        let mut pipeline: Pipeline<_, NoopSyncStateUpdate> = Pipeline::builder()
+            .add_stage(
+                TestStage::new(StageId("A"))
+                    .add_exec(Ok(ExecOutput { stage_progress: 5, done: false }))
+                    .add_exec(Err(StageError::DetachedHead {
+                        local_head: Box::new(local_head),
+                        header: Box::new(header.seal()),
+                    }))
+                    .add_unwind(Ok(UnwindOutput { stage_progress: 2 }))
+                    .add_exec(Ok(ExecOutput { stage_progress: 10, done: true })),
+            )
+            .add_stage(
+                TestStage::new(StageId("B"))
+                    .add_exec(Ok(ExecOutput { stage_progress: 10, done: true })),
+            )
+            .with_max_block(10)
+            .build();
+        let events = pipeline.events();
+
+        // Run pipeline
+        tokio::spawn(async move {
+            pipeline.run(db).await.expect("Could not run pipeline");
+        });
+
+        assert_eq!(
+            events.collect::<Vec<PipelineEvent>>().await,
+            vec![
+                PipelineEvent::Running { stage_id: StageId("A"), stage_progress: None },
+                PipelineEvent::Ran {
+                    stage_id: StageId("A"),
+                    result: ExecOutput { stage_progress: 5, done: false },
+                },
+                PipelineEvent::Running { stage_id: StageId("A"), stage_progress: Some(5) },
+                PipelineEvent::Error { stage_id: StageId("A") },
+                PipelineEvent::Skipped { stage_id: StageId("B") },
+                PipelineEvent::Unwinding {
+                    stage_id: StageId("A"),
+                    input: UnwindInput { stage_progress: 5, unwind_to: 2, bad_block: None }
+                },
+                PipelineEvent::Unwound {
+                    stage_id: StageId("A"),
+                    result: UnwindOutput { stage_progress: 2 },
+                },
+                PipelineEvent::Running { stage_id: StageId("A"), stage_progress: Some(2) },
+                PipelineEvent::Ran {
+                    stage_id: StageId("A"),
+                    result: ExecOutput { stage_progress: 10, done: true },
+                },
+                PipelineEvent::Running { stage_id: StageId("B"), stage_progress: None },
+                PipelineEvent::Ran {
+                    stage_id: StageId("B"),
+                    result: ExecOutput { stage_progress: 10, done: true },
+                },
+            ]
+        );
+    }
+
     /// Checks that the pipeline re-runs stages on non-fatal errors and stops on fatal ones.
     #[tokio::test]
     async fn pipeline_error_handling() {
@@ -0,0 +1,561 @@
+use crate::{db::Transaction, DatabaseIntegrityError, PrunerError};
+use reth_db::{
+    cursor::{DbCursorRO, DbCursorRW},
+    database::Database,
+    models::{storage_sharded_key::StorageShardedKey, ShardedKey, TransitionIdAddress},
+    static_file::{StaticFileSegment, StaticFiles},
+    table::Table,
+    tables,
+    transaction::{DbTx, DbTxMut},
+    TransitionList,
+};
+use reth_interfaces::events::ChainEvent;
+use reth_primitives::{
+    Address, BlockNumber, PruneModes, PruneSegment, Receipt, TransitionId, TxNumber, H256,
+};
+use reth_provider::{prune_checkpoint, save_prune_checkpoint, ForkchoiceMarker};
+use std::{collections::BTreeSet, fmt::Debug, ops::Deref, sync::Arc};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::*;
+
+/// The number of blocks below the chain tip that are never pruned if there is no finalized block.
+pub const MINIMUM_PRUNING_DISTANCE: u64 = 128;
+
+/// The maximum number of blocks of a segment that are pruned before the progress is committed.
+const PRUNE_COMMIT_THRESHOLD: u64 = 1_000;
+
+/// Deletes the data of old blocks, according to the [PruneMode][reth_primitives::PruneMode] of
+/// each [PruneSegment].
+///
+/// The highest pruned block of each segment is saved in [tables::PruneCheckpoints], so readers
+/// can tell pruned data apart from data that does not exist.
+///
+/// Only blocks that can't be unwound anymore are pruned: blocks up to the finalized block, or, if
+/// there is no finalized block, blocks at least [MINIMUM_PRUNING_DISTANCE] below the tip.
+///
+/// Receipts that were already moved to the [StaticFiles] can't be deleted from them, but they are
+/// read to remove their blocks from the log indices.
+pub struct Pruner<DB> {
+    /// The database that is pruned.
+    db: Arc<DB>,
+    /// The static files that may hold moved receipts.
+    static_files: Option<Arc<StaticFiles>>,
+    /// The pruning mode of each segment.
+    modes: PruneModes,
+}
+
+impl<DB> Debug for Pruner<DB> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pruner").field("modes", &self.modes).finish()
+    }
+}
+
+impl<DB: Database> Pruner<DB> {
+    /// Create a new pruner.
+    pub fn new(db: Arc<DB>, modes: PruneModes) -> Self {
+        Self { db, static_files: None, modes }
+    }
+
+    /// Sets the static files that hold the receipts moved out of the database.
+    pub fn with_static_files(mut self, static_files: Arc<StaticFiles>) -> Self {
+        self.static_files = Some(static_files);
+        self
+    }
+
+    /// Prunes all segments, given the current tip of the canonical chain.
+    pub fn run(&self, tip: BlockNumber) -> Result<(), PrunerError> {
+        let mut tx = Transaction::new(self.db.as_ref())?;
+
+        // Finalized blocks are never unwound, so their changesets are not needed anymore.
+        let finalized = ForkchoiceMarker::Finalized.block_number(tx.deref())?;
+        let Some(limit) = finalized.or_else(|| tip.checked_sub(MINIMUM_PRUNING_DISTANCE)) else {
+            return Ok(())
+        };
+
+        for segment in PruneSegment::ALL {
+            let Some(mode) = self.modes.mode(segment) else { continue };
+            let Some(target) = mode.prune_target(tip) else { continue };
+            let target = target.min(limit);
+
+            let mut checkpoint = prune_checkpoint(tx.deref(), segment)?;
+            while checkpoint.map_or(true, |checkpoint| checkpoint < target) {
+                let from = checkpoint.map_or(0, |checkpoint| checkpoint + 1);
+                let to = target.min(from + PRUNE_COMMIT_THRESHOLD - 1);
+                debug!(target: "sync::pruner", %segment, %from, %to, "Pruning blocks");
+
+                match segment {
+                    PruneSegment::Receipts => {
+                        prune_receipts(&tx, self.static_files.as_deref(), checkpoint, to)?
+                    }
+                    PruneSegment::AccountHistory => prune_account_history(&tx, checkpoint, to)?,
+                    PruneSegment::StorageHistory => prune_storage_history(&tx, checkpoint, to)?,
+                }
+                save_prune_checkpoint(tx.deref(), segment, to)?;
+                tx.commit()?;
+                checkpoint = Some(to);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<DB: Database + 'static> Pruner<DB> {
+    /// Prunes the database whenever the canonical chain is extended.
+    ///
+    /// The pruning runs on a blocking thread, since it waits for the write transactions of the
+    /// pipeline.
+    pub async fn run_on_chain_events(self, mut events: broadcast::Receiver<ChainEvent>) {
+        if self.modes.is_empty() {
+            return
+        }
+
+        let pruner = Arc::new(self);
+        loop {
+            let tip = match events.recv().await {
+                Ok(ChainEvent::Extended { tip, .. }) => tip,
+                Ok(ChainEvent::Unwound { .. }) | Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return,
+            };
+
+            let task = pruner.clone();
+            match tokio::task::spawn_blocking(move || task.run(tip)).await {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => error!(target: "sync::pruner", %tip, "Failed to prune: {err}"),
+                Err(err) => error!(target: "sync::pruner", %tip, "Pruning panicked: {err}"),
+            }
+        }
+    }
+}
+
+/// Returns the first transaction after the block.
+fn next_tx_id<DB: Database>(
+    tx: &Transaction<'_, DB>,
+    number: BlockNumber,
+) -> Result<TxNumber, PrunerError> {
+    let hash = tx
+        .get::<tables::CanonicalHeaders>(number)?
+        .ok_or(DatabaseIntegrityError::CanonicalHeader { number })?;
+    let body = tx
+        .get::<tables::BlockBodies>((number, hash).into())?
+        .ok_or(DatabaseIntegrityError::BlockBody { number })?;
+    Ok(body.start_tx_id + body.tx_count)
+}
+
+/// Returns the transition at the end of the block.
+fn block_transition<DB: Database>(
+    tx: &Transaction<'_, DB>,
+    number: BlockNumber,
+) -> Result<TransitionId, PrunerError> {
+    Ok(tx
+        .get::<tables::BlockTransitionIndex>(number)?
+        .ok_or(DatabaseIntegrityError::BlockTransition { number })?)
+}
+
+/// Deletes the entries of the table in the key range and calls `on_delete` with each of them.
+///
+/// The range is walked backwards and every entry is deleted as soon as it is visited, so the
+/// entries are never collected.
+fn delete_range<DB: Database, T: Table>(
+    tx: &Transaction<'_, DB>,
+    range: std::ops::Range<T::Key>,
+    mut on_delete: impl FnMut(T::Key, T::Value),
+) -> Result<(), PrunerError> {
+    let mut cursor = tx.cursor_write::<T>()?;
+    let mut entry = match cursor.seek(range.end)? {
+        Some(_) => cursor.prev()?,
+        None => cursor.last()?,
+    };
+    while let Some((key, value)) = entry.filter(|(key, _)| *key >= range.start) {
+        cursor.delete_current()?;
+        on_delete(key, value);
+        entry = cursor.prev()?;
+    }
+    Ok(())
+}
+
+/// Deletes the receipts and logs of the blocks after `checkpoint` up to `to`, and removes the
+/// blocks from the log indices.
+///
+/// The log indices of receipts that were moved to the static files are pruned as well, the
+/// receipts themselves stay in the static files.
+fn prune_receipts<DB: Database>(
+    tx: &Transaction<'_, DB>,
+    static_files: Option<&StaticFiles>,
+    checkpoint: Option<BlockNumber>,
+    to: BlockNumber,
+) -> Result<(), PrunerError> {
+    let start = checkpoint.map(|checkpoint| next_tx_id(tx, checkpoint)).transpose()?.unwrap_or(0);
+    let end = next_tx_id(tx, to)?;
+    let mut addresses = BTreeSet::new();
+    let mut topics = BTreeSet::new();
+    let mut on_receipt = |receipt: Receipt| {
+        for log in receipt.logs {
+            addresses.insert(log.address);
+            topics.extend(log.topics);
+        }
+    };
+
+    if let Some(static_files) = static_files {
+        let moved = static_files.next_key(StaticFileSegment::Receipts).unwrap_or_default();
+        for id in start..end.min(moved) {
+            if let Some(receipt) = static_files.get(StaticFileSegment::Receipts, id)? {
+                on_receipt(receipt);
+            }
+        }
+    }
+    delete_range::<_, tables::Receipts>(tx, start..end, |_, receipt| on_receipt(receipt))?;
+    delete_range::<_, tables::Logs>(tx, start..end, |_, _| {})?;
+
+    for address in addresses {
+        prune_shards::<_, tables::LogAddressIndex>(
+            tx,
+            ShardedKey::new(address, 0),
+            |key| key.key == address,
+            |key| key.highest_transition_id,
+            to + 1,
+        )?;
+    }
+    for topic in topics {
+        prune_shards::<_, tables::LogTopicIndex>(
+            tx,
+            ShardedKey::new(topic, 0),
+            |key| key.key == topic,
+            |key| key.highest_transition_id,
+            to + 1,
+        )?;
+    }
+    Ok(())
+}
+
+/// Deletes the account changesets of the blocks after `checkpoint` up to `to`, and removes their
+/// transitions from the account history index.
+fn prune_account_history<DB: Database>(
+    tx: &Transaction<'_, DB>,
+    checkpoint: Option<BlockNumber>,
+    to: BlockNumber,
+) -> Result<(), PrunerError> {
+    let start =
+        checkpoint.map(|checkpoint| block_transition(tx, checkpoint)).transpose()?.unwrap_or(0);
+    let end = block_transition(tx, to)?;
+    let mut addresses = BTreeSet::new();
+    delete_range::<_, tables::AccountChangeSet>(tx, start..end, |_, account| {
+        addresses.insert(account.address);
+    })?;
+
+    for address in addresses {
+        prune_shards::<_, tables::AccountHistory>(
+            tx,
+            ShardedKey::new(address, 0),
+            |key| key.key == address,
+            |key| key.highest_transition_id,
+            end,
+        )?;
+    }
+    Ok(())
+}
+
+/// Deletes the storage changesets of the blocks after `checkpoint` up to `to`, and removes their
+/// transitions from the storage history index.
+fn prune_storage_history<DB: Database>(
+    tx: &Transaction<'_, DB>,
+    checkpoint: Option<BlockNumber>,
+    to: BlockNumber,
+) -> Result<(), PrunerError> {
+    let start =
+        checkpoint.map(|checkpoint| block_transition(tx, checkpoint)).transpose()?.unwrap_or(0);
+    let end = block_transition(tx, to)?;
+    let mut slots = BTreeSet::<(Address, H256)>::new();
+    delete_range::<_, tables::StorageChangeSet>(
+        tx,
+        TransitionIdAddress((start, Address::zero()))..TransitionIdAddress((end, Address::zero())),
+        |key, entry| {
+            slots.insert((key.address(), entry.key));
+        },
+    )?;
+
+    for (address, slot) in slots {
+        prune_shards::<_, tables::StorageHistory>(
+            tx,
+            StorageShardedKey::new(address, slot, 0),
+            |key| key.address == address && key.sharded_key.key == slot,
+            |key| key.sharded_key.highest_transition_id,
+            end,
+        )?;
+    }
+    Ok(())
+}
+
+/// Removes the indices (transitions or block numbers) before `first_kept` from the shards of a
+/// key.
+///
+/// Shards that only contain pruned indices are deleted. The last shard of a key is never deleted,
+/// since a missing shard means that the key never changed. The pruned indices it keeps are never
+/// read, as the data before `first_kept` is not available anymore.
+fn prune_shards<DB, T>(
+    tx: &Transaction<'_, DB>,
+    start_key: T::Key,
+    is_same_key: impl Fn(&T::Key) -> bool,
+    highest: impl Fn(&T::Key) -> u64,
+    first_kept: u64,
+) -> Result<(), PrunerError>
+where
+    DB: Database,
+    T: Table<Value = TransitionList>,
+{
+    let mut cursor = tx.cursor_read::<T>()?;
+    let mut shard = cursor.seek(start_key)?;
+    while let Some((key, list)) = shard.filter(|(key, _)| is_same_key(key)) {
+        if highest(&key) == u64::MAX || highest(&key) >= first_kept {
+            let transitions = list.iter(0).collect::<Vec<_>>();
+            let kept = transitions
+                .iter()
+                .copied()
+                .filter(|transition| *transition as u64 >= first_kept)
+                .collect::<Vec<_>>();
+            if !kept.is_empty() && kept.len() != transitions.len() {
+                tx.put::<T>(key, TransitionList::new(kept).expect("Indices are presorted"))?;
+            }
+            break
+        }
+
+        tx.delete::<T>(key, None)?;
+        shard = cursor.next()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{
+        mdbx::{test_utils::create_test_db, EnvKind, WriteMap},
+        models::{AccountBeforeTx, StoredBlockBody},
+        BlockNumberList,
+    };
+    use reth_primitives::{Log, PruneMode, Receipt, StorageEntry};
+
+    const ADDRESS: Address = Address([1; 20]);
+    const SLOT: H256 = H256([2; 32]);
+
+    /// Inserts blocks `0..=tip` with one transaction, one log and one state transition each.
+    fn insert_blocks<DB: Database>(db: &DB, tip: BlockNumber) {
+        db.update(|tx| {
+            for number in 0..=tip {
+                let hash = H256::from_low_u64_be(number);
+                tx.put::<tables::CanonicalHeaders>(number, hash).unwrap();
+                tx.put::<tables::HeaderNumbers>(hash, number).unwrap();
+                tx.put::<tables::BlockBodies>(
+                    (number, hash).into(),
+                    StoredBlockBody { start_tx_id: number, tx_count: 1 },
+                )
+                .unwrap();
+                tx.put::<tables::BlockTransitionIndex>(number, number + 1).unwrap();
+                let receipt = Receipt {
+                    logs: vec![Log {
+                        address: ADDRESS,
+                        topics: vec![SLOT],
+                        data: Default::default(),
+                    }],
+                    ..Default::default()
+                };
+                tx.put::<tables::Receipts>(number, receipt.clone()).unwrap();
+                tx.put::<tables::Logs>(number, receipt).unwrap();
+                tx.put::<tables::AccountChangeSet>(
+                    number,
+                    AccountBeforeTx { address: ADDRESS, info: None },
+                )
+                .unwrap();
+                tx.put::<tables::StorageChangeSet>(
+                    (number, ADDRESS).into(),
+                    StorageEntry { key: SLOT, value: Default::default() },
+                )
+                .unwrap();
+            }
+
+            let transitions = (0..=tip as usize).collect::<Vec<_>>();
+            let (first, last) = transitions.split_at(transitions.len() / 2);
+            for (list, highest) in
+                [(first, first.last().copied().unwrap() as u64), (last, u64::MAX)]
+            {
+                tx.put::<tables::AccountHistory>(
+                    ShardedKey::new(ADDRESS, highest),
+                    TransitionList::new(list).unwrap(),
+                )
+                .unwrap();
+                tx.put::<tables::StorageHistory>(
+                    StorageShardedKey::new(ADDRESS, SLOT, highest),
+                    TransitionList::new(list).unwrap(),
+                )
+                .unwrap();
+                // transition `n` belongs to block `n`, so the block numbers match the transitions
+                tx.put::<tables::LogAddressIndex>(
+                    ShardedKey::new(ADDRESS, highest),
+                    BlockNumberList::new(list).unwrap(),
+                )
+                .unwrap();
+                tx.put::<tables::LogTopicIndex>(
+                    ShardedKey::new(SLOT, highest),
+                    BlockNumberList::new(list).unwrap(),
+                )
+                .unwrap();
+            }
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn prune_segments() {
+        let db = Arc::new(create_test_db::<WriteMap>(EnvKind::RW));
+        insert_blocks(db.as_ref(), 300);
+
+        let modes = PruneModes {
+            receipts: Some(PruneMode::Full),
+            account_history: Some(PruneMode::Distance(200)),
+            storage_history: Some(PruneMode::Before(50)),
+        };
+        Pruner::new(db.clone(), modes).run(300).unwrap();
+
+        let tx = db.tx().unwrap();
+        // without a finalized block, the last blocks are kept
+        let limit = 300 - MINIMUM_PRUNING_DISTANCE;
+        assert_eq!(prune_checkpoint(&tx, PruneSegment::Receipts).unwrap(), Some(limit));
+        assert_eq!(prune_checkpoint(&tx, PruneSegment::AccountHistory).unwrap(), Some(100));
+        assert_eq!(prune_checkpoint(&tx, PruneSegment::StorageHistory).unwrap(), Some(49));
+
+        // the receipt of block `limit` is transaction `limit`
+        let receipts = tx.cursor_read::<tables::Receipts>().unwrap().first().unwrap();
+        assert_eq!(receipts.map(|(id, _)| id), Some(limit + 1));
+        let logs = tx.cursor_read::<tables::Logs>().unwrap().first().unwrap();
+        assert_eq!(logs.map(|(id, _)| id), Some(limit + 1));
+        // the log indices only keep the blocks after `limit`
+        let shards = tx
+            .cursor_read::<tables::LogAddressIndex>()
+            .unwrap()
+            .walk(ShardedKey::new(ADDRESS, 0))
+            .unwrap()
+            .map(|entry| entry.map(|(key, list)| (key.highest_transition_id, list.iter(0).count())))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(shards, vec![(u64::MAX, (300 - limit) as usize)]);
+        let shards = tx
+            .cursor_read::<tables::LogTopicIndex>()
+            .unwrap()
+            .walk(ShardedKey::new(SLOT, 0))
+            .unwrap()
+            .map(|entry| entry.map(|(key, list)| (key.highest_transition_id, list.iter(0).count())))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(shards, vec![(u64::MAX, (300 - limit) as usize)]);
+
+        // the changesets of block 100 end at transition 101
+        let changesets = tx.cursor_read::<tables::AccountChangeSet>().unwrap().first().unwrap();
+        assert_eq!(changesets.map(|(transition, _)| transition), Some(101));
+        let shards = tx
+            .cursor_read::<tables::AccountHistory>()
+            .unwrap()
+            .walk(ShardedKey::new(ADDRESS, 0))
+            .unwrap()
+            .map(|entry| entry.map(|(key, list)| (key.highest_transition_id, list.iter(0).count())))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(shards, vec![(149, 49), (u64::MAX, 151)]);
+
+        let changesets = tx.cursor_read::<tables::StorageChangeSet>().unwrap().first().unwrap();
+        assert_eq!(changesets.map(|(key, _)| key.transition_id()), Some(50));
+        let shards = tx
+            .cursor_read::<tables::StorageHistory>()
+            .unwrap()
+            .walk(StorageShardedKey::new(ADDRESS, SLOT, 0))
+            .unwrap()
+            .map(|entry| {
+                entry.map(|(key, list)| {
+                    (key.sharded_key.highest_transition_id, list.iter(0).count())
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(shards, vec![(149, 100), (u64::MAX, 151)]);
+    }
+
+    #[test]
+    fn prune_log_indices_of_moved_receipts() {
+        let db = Arc::new(create_test_db::<WriteMap>(EnvKind::RW));
+        let dir = tempfile::tempdir().unwrap();
+        let static_files = Arc::new(StaticFiles::open(dir.path()).unwrap());
+        insert_blocks(db.as_ref(), 300);
+
+        // the receipts of the first blocks were moved to the static files, their logs are only
+        // emitted by another address
+        let other = Address([3; 20]);
+        db.update(|tx| {
+            for id in 0..100 {
+                let log = Log { address: other, topics: vec![], data: Default::default() };
+                let receipt = Receipt { logs: vec![log], ..Default::default() };
+                static_files.append(StaticFileSegment::Receipts, id, id, receipt).unwrap();
+                tx.delete::<tables::Receipts>(id, None).unwrap();
+            }
+            let blocks = (0..100).collect::<Vec<usize>>();
+            tx.put::<tables::LogAddressIndex>(
+                ShardedKey::new(other, 99),
+                BlockNumberList::new(blocks).unwrap(),
+            )
+            .unwrap();
+            tx.put::<tables::LogAddressIndex>(
+                ShardedKey::new(other, u64::MAX),
+                BlockNumberList::new([250]).unwrap(),
+            )
+            .unwrap();
+        })
+        .unwrap();
+        static_files.commit().unwrap();
+
+        let modes = PruneModes { receipts: Some(PruneMode::Full), ..Default::default() };
+        Pruner::new(db.clone(), modes).with_static_files(static_files).run(300).unwrap();
+
+        let tx = db.tx().unwrap();
+        let shards = tx
+            .cursor_read::<tables::LogAddressIndex>()
+            .unwrap()
+            .walk(ShardedKey::new(other, 0))
+            .unwrap()
+            .map(|entry| entry.map(|(key, list)| (key.highest_transition_id, list.iter(0).count())))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(shards, vec![(u64::MAX, 1)]);
+    }
+
+    #[test]
+    fn prune_up_to_finalized_block() {
+        let db = Arc::new(create_test_db::<WriteMap>(EnvKind::RW));
+        insert_blocks(db.as_ref(), 300);
+        db.update(|tx| {
+            ForkchoiceMarker::Finalized.save(tx, H256::from_low_u64_be(290)).unwrap();
+        })
+        .unwrap();
+
+        let modes = PruneModes { account_history: Some(PruneMode::Full), ..Default::default() };
+        let pruner = Pruner::new(db.clone(), modes);
+        pruner.run(300).unwrap();
+
+        let tx = db.tx().unwrap();
+        assert_eq!(prune_checkpoint(&tx, PruneSegment::AccountHistory).unwrap(), Some(290));
+        assert_eq!(prune_checkpoint(&tx, PruneSegment::Receipts).unwrap(), None);
+        // the last shard keeps its pruned transitions
+        let shards = tx
+            .cursor_read::<tables::AccountHistory>()
+            .unwrap()
+            .walk(ShardedKey::new(ADDRESS, 0))
+            .unwrap()
+            .map(|entry| entry.map(|(key, list)| (key.highest_transition_id, list.iter(0).count())))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(shards, vec![(u64::MAX, 10)]);
+        drop(tx);
+
+        // pruning again continues at the checkpoint
+        pruner.run(300).unwrap();
+        let tx = db.tx().unwrap();
+        assert_eq!(prune_checkpoint(&tx, PruneSegment::AccountHistory).unwrap(), Some(290));
+    }
+}
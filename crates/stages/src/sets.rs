@@ -31,9 +31,9 @@
 //! ```
 use crate::{
     stages::{
-        AccountHashingStage, BodyStage, ExecutionStage, HeaderStage, IndexAccountHistoryStage,
-        IndexStorageHistoryStage, MerkleStage, SenderRecoveryStage, StorageHashingStage,
-        TotalDifficultyStage, TransactionLookupStage,
+        AccountHashingStage, BodyStage, ExecutionStage, FinishStage, HeaderStage,
+        IndexAccountHistoryStage, IndexLogsStage, IndexStorageHistoryStage, MerkleStage,
+        SenderRecoveryStage, StorageHashingStage, TotalDifficultyStage, TransactionLookupStage,
     },
     StageSet, StageSetBuilder,
 };
@@ -117,13 +117,18 @@ where
 /// - [`ExecutionStages`]
 /// - [`HashingStages`]
 /// - [`HistoryIndexingStages`]
+/// - [`FinishStage`]
 #[derive(Debug, Default)]
 #[non_exhaustive]
 pub struct OfflineStages;
 
 impl<DB: Database> StageSet<DB> for OfflineStages {
     fn builder(self) -> StageSetBuilder<DB> {
-        ExecutionStages::default().builder().add_set(HashingStages).add_set(HistoryIndexingStages)
+        ExecutionStages::default()
+            .builder()
+            .add_set(HashingStages)
+            .add_set(HistoryIndexingStages)
+            .add_stage(FinishStage)
     }
 }
 
@@ -167,7 +172,7 @@ impl<DB: Database> StageSet<DB> for HashingStages {
             .add_stage(MerkleStage::Unwind)
             .add_stage(AccountHashingStage::default())
             .add_stage(StorageHashingStage::default())
-            .add_stage(MerkleStage::Execution)
+            .add_stage(MerkleStage::default_execution())
     }
 }
 
@@ -182,5 +187,6 @@ impl<DB: Database> StageSet<DB> for HistoryIndexingStages {
             .add_stage(TransactionLookupStage::default())
             .add_stage(IndexStorageHistoryStage::default())
             .add_stage(IndexAccountHistoryStage::default())
+            .add_stage(IndexLogsStage::default())
     }
 }
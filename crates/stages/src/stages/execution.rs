@@ -10,12 +10,13 @@ use reth_db::{
     transaction::{DbTx, DbTxMut},
 };
 use reth_executor::{
+    block_tree::SharedBlockTree,
     executor::AccountChangeSet,
     revm_wrap::{State, SubState},
 };
 use reth_primitives::{
-    Address, ChainSpec, Hardfork, Header, StorageEntry, TransactionSignedEcRecovered, H256,
-    MAINNET, U256,
+    Address, BlockNumber, ChainSpec, Hardfork, Header, StorageEntry, TransactionSignedEcRecovered,
+    H256, MAINNET, U256,
 };
 use reth_provider::LatestStateProviderRef;
 use std::fmt::Debug;
@@ -45,30 +46,60 @@ pub const EXECUTION: StageId = StageId("Execution");
 /// - [tables::Bytecodes]
 /// - [tables::AccountChangeSet]
 /// - [tables::StorageChangeSet]
+/// - [tables::Receipts]
 ///
 /// For unwinds we are accessing:
 /// - [tables::BlockBodies] get tx index to know what needs to be unwinded
 /// - [tables::AccountHistory] to remove change set and apply old values to
 /// - [tables::PlainAccountState] [tables::StorageHistory] to remove change set and apply old values
 /// to [tables::PlainStorageState]
+///
+/// The results of blocks that were already executed by the engine API are taken from the
+/// [SharedBlockTree] instead of executing the blocks again. The blocks are removed from the tree
+/// once their results were committed.
 #[derive(Debug)]
 pub struct ExecutionStage {
     /// Executor configuration.
     pub chain_spec: ChainSpec,
     /// Commit threshold
     pub commit_threshold: u64,
+    /// Blocks that were executed, but not written to the database.
+    pub block_tree: Option<SharedBlockTree>,
 }
 
 impl Default for ExecutionStage {
     fn default() -> Self {
-        Self { chain_spec: MAINNET.clone(), commit_threshold: 1000 }
+        Self { chain_spec: MAINNET.clone(), commit_threshold: 1000, block_tree: None }
     }
 }
 
 impl ExecutionStage {
     /// Create new execution stage with specified config.
     pub fn new(chain_spec: ChainSpec, commit_threshold: u64) -> Self {
-        Self { chain_spec, commit_threshold }
+        Self { chain_spec, commit_threshold, block_tree: None }
+    }
+
+    /// Reuse the results of the blocks that were executed in the given tree.
+    pub fn with_block_tree(mut self, block_tree: SharedBlockTree) -> Self {
+        self.block_tree = Some(block_tree);
+        self
+    }
+
+    /// Removes the canonical blocks up to the committed progress of the stage from the block tree,
+    /// their results are written to the database.
+    fn remove_committed_blocks<DB: Database>(
+        &self,
+        tx: &Transaction<'_, DB>,
+        stage_progress: BlockNumber,
+    ) -> Result<(), StageError> {
+        let Some(block_tree) = &self.block_tree else { return Ok(()) };
+        let mut block_tree = block_tree.lock();
+        for (number, hash) in block_tree.executed_up_to(stage_progress) {
+            if tx.get::<tables::CanonicalHeaders>(number)? == Some(hash) {
+                block_tree.take_executed(&hash);
+            }
+        }
+        Ok(())
     }
 }
 
@@ -85,9 +116,12 @@ impl<DB: Database> Stage<DB> for ExecutionStage {
         tx: &mut Transaction<'_, DB>,
         input: ExecInput,
     ) -> Result<ExecOutput, StageError> {
+        let last_block = input.stage_progress.unwrap_or_default();
+        // the results of the blocks reused by the previous run were committed since
+        self.remove_committed_blocks(tx, last_block)?;
+
         let ((start_block, end_block), capped) =
             exec_or_return!(input, self.commit_threshold, "sync::stages::execution");
-        let last_block = input.stage_progress.unwrap_or_default();
 
         // Get next canonical block hashes to execute.
         let mut canonicals = tx.cursor_read::<tables::CanonicalHeaders>()?;
@@ -134,8 +168,23 @@ impl<DB: Database> Stage<DB> for ExecutionStage {
 
         // Fetch transactions, execute them and generate results
         let mut block_change_patches = Vec::with_capacity(canonical_batch.len());
-        for (header, body, ommers) in block_batch.iter() {
+        for (key, (header, body, ommers)) in canonical_batch.iter().zip(block_batch.iter()) {
             let num = header.number;
+
+            // The block was executed before it became canonical. It stays in the tree until the
+            // results are committed.
+            let reused = self.block_tree.as_ref().and_then(|tree| {
+                let tree = tree.lock();
+                let executed = tree.executed_block(&key.hash())?;
+                executed.post_state.apply_to(&mut state_provider);
+                Some(executed.result.clone())
+            });
+            if let Some(result) = reused {
+                trace!(target: "sync::stages::execution", ?num, "Reusing execution result.");
+                block_change_patches.push((result, num, body.start_tx_id));
+                continue
+            }
+
             tracing::trace!(target: "sync::stages::execution", ?num, "Execute block.");
             // iterate over all transactions
             let mut tx_walker = tx_cursor.walk(body.start_tx_id)?;
@@ -199,7 +248,7 @@ impl<DB: Database> Stage<DB> for ExecutionStage {
                 handle.join().expect("Expects for thread to not panic")
             })
             .map_err(|error| StageError::ExecutionError { block: header.number, error })?;
-            block_change_patches.push((changeset, num));
+            block_change_patches.push((changeset, num, body.start_tx_id));
         }
 
         // Get last tx count so that we can know amount of transaction in the block.
@@ -210,10 +259,13 @@ impl<DB: Database> Stage<DB> for ExecutionStage {
             self.chain_spec.fork_block(Hardfork::SpuriousDragon).unwrap_or_default();
 
         // apply changes to plain database.
-        for (results, block_number) in block_change_patches.into_iter() {
+        for (results, block_number, start_tx_id) in block_change_patches.into_iter() {
             let spurious_dragon_active = block_number >= spurious_dragon_activation;
             // insert state change set
-            for result in results.changesets.into_iter() {
+            for (tx_id, result) in (start_tx_id..).zip(results.changesets.into_iter()) {
+                // insert the receipt of the transaction
+                tx.put::<tables::Receipts>(tx_id, result.receipt)?;
+
                 for (address, account_change_set) in result.changeset.into_iter() {
                     let AccountChangeSet { account, wipe_storage, storage } = account_change_set;
                     // apply account change to db. Updates AccountChangeSet and PlainAccountState
@@ -333,6 +385,18 @@ impl<DB: Database> Stage<DB> for ExecutionStage {
         let from_transition_rev = tx.get_block_transition(input.unwind_to)?;
         let to_transition_rev = tx.get_block_transition(input.stage_progress)?;
 
+        // Discard receipts of the unwinded transactions
+        let (first_unwinded_tx_id, _) = tx.get_next_block_ids(input.unwind_to + 1)?;
+        let mut receipts = tx.cursor_write::<tables::Receipts>()?;
+        let mut receipt = receipts.last()?;
+        while let Some((tx_id, _)) = receipt {
+            if tx_id < first_unwinded_tx_id {
+                break
+            }
+            receipts.delete_current()?;
+            receipt = receipts.prev()?;
+        }
+
         if from_transition_rev > to_transition_rev {
             panic!("Unwind transition {} (stage progress block #{}) is higher than the transition {} of (unwind block #{})", from_transition_rev, input.stage_progress, to_transition_rev, input.unwind_to);
         }
@@ -414,8 +478,12 @@ mod tests {
         mdbx::{test_utils::create_test_db, EnvKind, WriteMap},
         models::AccountBeforeTx,
     };
+    use reth_executor::{
+        block_tree::ExecutedBlock,
+        executor::{ExecutionResult, TransactionChangeSet},
+    };
     use reth_primitives::{
-        hex_literal::hex, keccak256, Account, ChainSpecBuilder, SealedBlock, H160, U256,
+        hex_literal::hex, keccak256, Account, ChainSpecBuilder, Receipt, SealedBlock, H160, U256,
     };
     use reth_provider::insert_canonical_block;
     use reth_rlp::Decodable;
@@ -510,6 +578,55 @@ mod tests {
             Ok(Some(StorageEntry { key: H256::from_low_u64_be(1), value: U256::from(2) })),
             "Post changed of a account"
         );
+        // assert receipt
+        let receipt = tx.get::<tables::Receipts>(0).unwrap().expect("Receipt is stored");
+        assert!(receipt.success);
+        assert_eq!(receipt.cumulative_gas_used, 0xa879);
+    }
+
+    #[tokio::test]
+    async fn reuses_executed_blocks() {
+        let state_db = create_test_db::<WriteMap>(EnvKind::RW);
+        let mut tx = Transaction::new(state_db.as_ref()).unwrap();
+        let input = ExecInput { previous_stage: Some((PREV_STAGE_ID, 1)), stage_progress: None };
+        let mut genesis_rlp = hex!("f901faf901f5a00000000000000000000000000000000000000000000000000000000000000000a01dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347942adc25665018aa1fe0e6bc666dac8fc2697ff9baa045571b40ae66ca7480791bbb2887286e4e4c4b1b298b191c889d6959023a32eda056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421b901000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000083020000808502540be400808000a00000000000000000000000000000000000000000000000000000000000000000880000000000000000c0c0").as_slice();
+        let genesis = SealedBlock::decode(&mut genesis_rlp).unwrap();
+        let mut block_rlp = hex!("f90262f901f9a075c371ba45999d87f4542326910a11af515897aebce5265d3f6acd1f1161f82fa01dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347942adc25665018aa1fe0e6bc666dac8fc2697ff9baa098f2dcd87c8ae4083e7017a05456c14eea4b1db2032126e27b3b1563d57d7cc0a08151d548273f6683169524b66ca9fe338b9ce42bc3540046c828fd939ae23bcba03f4e5c2ec5b2170b711d97ee755c160457bb58d8daa338e835ec02ae6860bbabb901000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000083020000018502540be40082a8798203e800a00000000000000000000000000000000000000000000000000000000000000000880000000000000000f863f861800a8405f5e10094100000000000000000000000000000000000000080801ba07e09e26678ed4fac08a249ebe8ed680bf9051a5e14ad223e4b2b9d26e0208f37a05f6e3f188e3e6eab7d7d3b6568f5eac7d687b08d307d3154ccd8c87b4630509bc0").as_slice();
+        let block = SealedBlock::decode(&mut block_rlp).unwrap();
+        insert_canonical_block(tx.deref_mut(), &genesis, true).unwrap();
+        insert_canonical_block(tx.deref_mut(), &block, true).unwrap();
+        tx.commit().unwrap();
+
+        // the block was executed by the engine API, the sender has no balance in the database
+        let receipt = Receipt { success: true, cumulative_gas_used: 0xa879, ..Default::default() };
+        let block_tree = SharedBlockTree::default();
+        block_tree.lock().insert_executed(ExecutedBlock {
+            block: block.clone(),
+            result: ExecutionResult {
+                changesets: vec![TransactionChangeSet {
+                    receipt: receipt.clone(),
+                    changeset: Default::default(),
+                    new_bytecodes: Default::default(),
+                }],
+                block_reward: None,
+            },
+            post_state: Default::default(),
+        });
+
+        let mut execution_stage =
+            ExecutionStage::new(ChainSpecBuilder::mainnet().berlin_activated().build(), 1000)
+                .with_block_tree(block_tree.clone());
+        let output = execution_stage.execute(&mut tx, input).await.unwrap();
+        assert_eq!(output, ExecOutput { stage_progress: 1, done: true });
+        assert_eq!(tx.get::<tables::Receipts>(0), Ok(Some(receipt)));
+        // the block is kept until the results are committed
+        assert!(block_tree.lock().is_executed(&block.hash()));
+        tx.commit().unwrap();
+
+        let input = ExecInput { stage_progress: Some(1), ..input };
+        let output = execution_stage.execute(&mut tx, input).await.unwrap();
+        assert_eq!(output, ExecOutput { stage_progress: 1, done: true });
+        assert!(!block_tree.lock().is_executed(&block.hash()));
     }
 
     #[tokio::test]
@@ -582,6 +699,7 @@ mod tests {
             Ok(None),
             "Third account should be unwinded"
         );
+        assert_eq!(db_tx.get::<tables::Receipts>(0), Ok(None), "Receipt should be unwinded");
     }
 
     #[tokio::test]
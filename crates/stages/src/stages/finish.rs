@@ -0,0 +1,61 @@
+use crate::{
+    db::Transaction, ExecInput, ExecOutput, Stage, StageError, StageId, UnwindInput, UnwindOutput,
+};
+use reth_db::database::Database;
+
+pub use reth_provider::FINISH;
+
+/// The finish stage.
+///
+/// This stage does not write any data. It is the last stage of the pipeline and its progress is
+/// the highest block that was processed by all stages, which is the tip of the canonical chain
+/// that is served to users.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FinishStage;
+
+#[async_trait::async_trait]
+impl<DB: Database> Stage<DB> for FinishStage {
+    /// Return the id of the stage
+    fn id(&self) -> StageId {
+        FINISH
+    }
+
+    /// Advance to the progress of the previous stage
+    async fn execute(
+        &mut self,
+        _tx: &mut Transaction<'_, DB>,
+        input: ExecInput,
+    ) -> Result<ExecOutput, StageError> {
+        Ok(ExecOutput { stage_progress: input.previous_stage_progress(), done: true })
+    }
+
+    /// Unwind the stage.
+    async fn unwind(
+        &mut self,
+        _tx: &mut Transaction<'_, DB>,
+        input: UnwindInput,
+    ) -> Result<UnwindOutput, StageError> {
+        Ok(UnwindOutput { stage_progress: input.unwind_to })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{TestTransaction, PREV_STAGE_ID};
+
+    #[tokio::test]
+    async fn follows_previous_stage() {
+        let tx = TestTransaction::default();
+        let mut stage = FinishStage;
+
+        let input =
+            ExecInput { previous_stage: Some((PREV_STAGE_ID, 10)), stage_progress: Some(5) };
+        let output = stage.execute(&mut tx.inner(), input).await.unwrap();
+        assert_eq!(output, ExecOutput { stage_progress: 10, done: true });
+
+        let input = UnwindInput { stage_progress: 10, unwind_to: 7, bad_block: None };
+        let output = stage.unwind(&mut tx.inner(), input).await.unwrap();
+        assert_eq!(output, UnwindOutput { stage_progress: 7 });
+    }
+}
@@ -14,8 +14,10 @@ use reth_interfaces::{
     consensus::{Consensus, ForkchoiceState},
     p2p::headers::downloader::{HeaderDownloader, SyncTarget},
 };
-use reth_primitives::{BlockNumber, Header, SealedHeader};
-use std::sync::Arc;
+use reth_primitives::{BlockHash, BlockNumber, Header, SealedHeader};
+use reth_provider::ForkchoiceMarker;
+use std::{ops::Deref, sync::Arc};
+use tokio::sync::watch;
 use tracing::*;
 
 /// The [`StageId`] of the headers downloader stage.
@@ -32,14 +34,18 @@ pub const HEADERS: StageId = StageId("Headers");
 /// - [`Headers`][reth_db::tables::Headers]
 /// - [`CanonicalHeaders`][reth_db::tables::CanonicalHeaders]
 ///
+/// The chain tip is the head of the latest [ForkchoiceState] received from the consensus layer. The
+/// safe and finalized blocks of the forkchoice state are stored in
+/// [`ForkchoiceMarkers`][reth_db::tables::ForkchoiceMarkers].
+///
 /// NOTE: This stage downloads headers in reverse. Upon returning the control flow to the pipeline,
 /// the stage progress is not updated unless this stage is done.
 #[derive(Debug)]
 pub struct HeaderStage<D: HeaderDownloader> {
     /// Strategy for downloading the headers
     downloader: D,
-    /// Consensus client implementation
-    consensus: Arc<dyn Consensus>,
+    /// Receiver of the forkchoice states from the consensus layer
+    forkchoice_state_rx: watch::Receiver<ForkchoiceState>,
 }
 
 // === impl HeaderStage ===
@@ -50,7 +56,7 @@ where
 {
     /// Create a new header stage
     pub fn new(downloader: D, consensus: Arc<dyn Consensus>) -> Self {
-        Self { downloader, consensus }
+        Self { downloader, forkchoice_state_rx: consensus.fork_choice_state() }
     }
 
     fn is_stage_done<DB: Database>(
@@ -70,7 +76,7 @@ where
     ///
     /// See also [SyncTarget]
     async fn get_sync_gap<DB: Database>(
-        &self,
+        &mut self,
         tx: &Transaction<'_, DB>,
         stage_progress: u64,
     ) -> Result<SyncGap, StageError> {
@@ -106,23 +112,89 @@ where
         // reverse from there. Else, it should use whatever the forkchoice state reports.
         let target = match next_header {
             Some(header) if stage_progress + 1 != header.number => SyncTarget::Gap(header.seal()),
-            None => SyncTarget::Tip(self.next_fork_choice_state().await.head_block_hash),
+            None => {
+                let forkchoice = self.next_fork_choice_state(tx).await?;
+                self.save_forkchoice_markers(tx, &forkchoice)?;
+                SyncTarget::Tip(forkchoice.head_block_hash)
+            }
             _ => return Err(StageError::StageProgress(stage_progress)),
         };
 
         Ok(SyncGap { local_head, target })
     }
 
-    /// Awaits the next [ForkchoiceState] message from [Consensus] with a non-zero block hash
-    async fn next_fork_choice_state(&self) -> ForkchoiceState {
-        let mut state_rcv = self.consensus.fork_choice_state();
+    /// Returns the latest [ForkchoiceState] from [Consensus] with a non-zero head block hash.
+    ///
+    /// A state that was already returned is only returned again if its head is not part of the
+    /// local canonical chain yet, otherwise this waits for the next state.
+    async fn next_fork_choice_state<DB: Database>(
+        &mut self,
+        tx: &Transaction<'_, DB>,
+    ) -> Result<ForkchoiceState, StageError> {
+        let mut changed = self.forkchoice_state_rx.has_changed().unwrap_or_default();
         loop {
-            let _ = state_rcv.changed().await;
-            let forkchoice = state_rcv.borrow();
-            if !forkchoice.head_block_hash.is_zero() {
-                return forkchoice.clone()
+            let forkchoice = self.forkchoice_state_rx.borrow_and_update().clone();
+            if !forkchoice.head_block_hash.is_zero() &&
+                (changed || !self.is_canonical(tx, forkchoice.head_block_hash)?)
+            {
+                return Ok(forkchoice)
+            }
+            self.forkchoice_state_rx
+                .changed()
+                .await
+                .map_err(|err| StageError::Fatal(Box::new(err)))?;
+            changed = true;
+        }
+    }
+
+    /// Returns `true` if the block with the given hash is part of the local canonical chain.
+    fn is_canonical<DB: Database>(
+        &self,
+        tx: &Transaction<'_, DB>,
+        hash: BlockHash,
+    ) -> Result<bool, StageError> {
+        Ok(self.canonical_number(tx, hash)?.is_some())
+    }
+
+    /// Returns the number of the block with the given hash if it is part of the local canonical
+    /// chain.
+    fn canonical_number<DB: Database>(
+        &self,
+        tx: &Transaction<'_, DB>,
+        hash: BlockHash,
+    ) -> Result<Option<BlockNumber>, StageError> {
+        let Some(number) = tx.get::<tables::HeaderNumbers>(hash)? else { return Ok(None) };
+        Ok((tx.get::<tables::CanonicalHeaders>(number)? == Some(hash)).then_some(number))
+    }
+
+    /// Stores the safe and finalized blocks of the forkchoice state, if they are set.
+    ///
+    /// Only blocks of the local canonical chain are stored, which are not above the head if the
+    /// head is canonical as well. Blocks that are not known yet are stored with a later
+    /// forkchoice state, once they were synced.
+    fn save_forkchoice_markers<DB: Database>(
+        &self,
+        tx: &Transaction<'_, DB>,
+        forkchoice: &ForkchoiceState,
+    ) -> Result<(), StageError> {
+        let head = self.canonical_number(tx, forkchoice.head_block_hash)?;
+        for (marker, hash) in [
+            (ForkchoiceMarker::Safe, forkchoice.safe_block_hash),
+            (ForkchoiceMarker::Finalized, forkchoice.finalized_block_hash),
+        ] {
+            if hash.is_zero() {
+                continue
+            }
+            match self.canonical_number(tx, hash)? {
+                Some(number) if head.map_or(true, |head| number <= head) => {
+                    marker.save(tx.deref(), hash)?;
+                }
+                _ => {
+                    trace!(target: "sync::stages::headers", ?marker, ?hash, "Skipping forkchoice marker that is not a canonical ancestor of the head");
+                }
             }
         }
+        Ok(())
     }
 
     /// Write downloaded headers to the given transaction
@@ -184,8 +256,8 @@ where
         let gap = self.get_sync_gap(tx, current_progress).await?;
         let tip = gap.target.tip();
 
-        // Nothing to sync
-        if gap.is_closed() {
+        // Nothing to sync. If the tip is an ancestor of the local head, the chain is not unwound.
+        if gap.is_closed() || self.is_canonical(tx, tip)? {
             info!(target: "sync::stages::headers", stage_progress = current_progress, target = ?tip, "Target block already reached");
             return Ok(ExecOutput { stage_progress: current_progress, done: true })
         }
@@ -193,7 +265,7 @@ where
         debug!(target: "sync::stages::headers", ?tip, head = ?gap.local_head.hash(), "Commencing sync");
 
         // let the downloader know what to sync
-        self.downloader.update_sync_gap(gap.local_head, gap.target);
+        self.downloader.update_sync_gap(gap.local_head.clone(), gap.target);
 
         // The downloader returns the headers in descending order starting from the tip
         // down to the local head (latest block in db)
@@ -207,6 +279,19 @@ where
 
         info!(target: "sync::stages::headers", len = downloaded_headers.len(), "Received headers");
 
+        // The headers have to connect to the local head, otherwise the tip is on a different branch
+        // and the local chain has to be unwound.
+        if let Some(lowest) = downloaded_headers.last() {
+            if lowest.number <= gap.local_head.number + 1 &&
+                lowest.parent_hash != gap.local_head.hash()
+            {
+                return Err(StageError::DetachedHead {
+                    local_head: Box::new(gap.local_head),
+                    header: Box::new(lowest.clone()),
+                })
+            }
+        }
+
         // Write the headers to db
         self.write_headers::<DB>(tx, downloaded_headers)?.unwrap_or_default();
 
@@ -265,7 +350,7 @@ mod tests {
         PREV_STAGE_ID,
     };
     use assert_matches::assert_matches;
-    use reth_interfaces::test_utils::generators::random_header;
+    use reth_interfaces::test_utils::generators::{random_header, random_header_range};
     use reth_primitives::H256;
     use test_runner::HeadersTestRunner;
 
@@ -327,10 +412,7 @@ mod tests {
             }
 
             fn stage(&self) -> Self::S {
-                HeaderStage {
-                    consensus: self.consensus.clone(),
-                    downloader: (*self.downloader_factory)(),
-                }
+                HeaderStage::new((*self.downloader_factory)(), self.consensus.clone())
             }
         }
 
@@ -480,12 +562,56 @@ mod tests {
         assert!(runner.validate_execution(input, result.ok()).is_ok(), "validation failed");
     }
 
+    /// Execute the stage with a tip on a branch that does not contain the local head
+    #[tokio::test]
+    async fn execute_with_detached_head() {
+        let mut runner = HeadersTestRunner::with_linear_downloader();
+        let (stage_progress, previous_stage) = (1000, 1010);
+        let input = ExecInput {
+            previous_stage: Some((PREV_STAGE_ID, previous_stage)),
+            stage_progress: Some(stage_progress),
+        };
+        let headers = runner.seed_execution(input).expect("failed to seed execution");
+        let local_head = headers.first().unwrap().clone();
+        let rx = runner.execute(input);
+
+        // the branch forks off below the local head
+        let branch = random_header_range(stage_progress..previous_stage + 1, H256::random());
+        runner.client.extend(branch.iter().rev().map(|h| h.clone().unseal())).await;
+
+        let tip = branch.last().unwrap();
+        let state = ForkchoiceState {
+            head_block_hash: tip.hash(),
+            safe_block_hash: H256::random(),
+            finalized_block_hash: local_head.hash(),
+        };
+        runner.consensus.notify_fork_choice_state(state.clone()).expect("Setting tip failed");
+
+        let result = rx.await.unwrap();
+        assert_matches!(
+            result,
+            Err(StageError::DetachedHead { local_head: head, header })
+                if *head == local_head && header.number == stage_progress + 1
+        );
+        runner.check_no_header_entry_above(stage_progress).expect("headers were written");
+
+        // the known finalized block is stored nonetheless, the unknown safe block is not
+        runner
+            .tx()
+            .query(|tx| {
+                assert_eq!(ForkchoiceMarker::Safe.get(tx)?, None);
+                assert_eq!(ForkchoiceMarker::Finalized.get(tx)?, Some(state.finalized_block_hash));
+                Ok(())
+            })
+            .unwrap();
+    }
+
     /// Test the head and tip range lookup
     #[tokio::test]
     async fn head_and_tip_lookup() {
         let runner = HeadersTestRunner::default();
         let tx = runner.tx().inner();
-        let stage = runner.stage();
+        let mut stage = runner.stage();
 
         let consensus_tip = H256::random();
         runner
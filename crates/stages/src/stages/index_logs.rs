@@ -0,0 +1,350 @@
+use crate::{
+    db::Transaction, exec_or_return, DatabaseIntegrityError, ExecAction, ExecInput, ExecOutput,
+    Stage, StageError, StageId, UnwindInput, UnwindOutput,
+};
+use reth_db::{
+    cursor::{DbCursorRO, DbCursorRW},
+    database::Database,
+    models::{sharded_key::NUM_OF_INDICES_IN_SHARD, ShardedKey},
+    table::Table,
+    tables,
+    transaction::{DbTx, DbTxMut},
+    BlockNumberList,
+};
+use reth_primitives::{Address, BlockNumber, H256};
+use std::{collections::BTreeMap, ops::RangeInclusive};
+use tracing::*;
+
+/// The [`StageId`] of the log indexing stage.
+pub const INDEX_LOGS: StageId = StageId("IndexLogs");
+
+/// The log indexing stage.
+///
+/// This stage walks over the receipts generated in
+/// [`ExecutionStage`][crate::stages::ExecutionStage] and records for every log address and topic
+/// the blocks that contain a matching log in [`tables::LogAddressIndex`] and
+/// [`tables::LogTopicIndex`]. The indices are sharded like the history indices, see
+/// [`tables::AccountHistory`].
+#[derive(Debug)]
+pub struct IndexLogsStage {
+    /// Number of blocks after which the control
+    /// flow will be returned to the pipeline for commit.
+    pub commit_threshold: u64,
+}
+
+impl Default for IndexLogsStage {
+    fn default() -> Self {
+        Self { commit_threshold: 100_000 }
+    }
+}
+
+#[async_trait::async_trait]
+impl<DB: Database> Stage<DB> for IndexLogsStage {
+    /// Return the id of the stage
+    fn id(&self) -> StageId {
+        INDEX_LOGS
+    }
+
+    /// Execute the stage.
+    async fn execute(
+        &mut self,
+        tx: &mut Transaction<'_, DB>,
+        input: ExecInput,
+    ) -> Result<ExecOutput, StageError> {
+        let ((start_block, end_block), capped) =
+            exec_or_return!(input, self.commit_threshold, "sync::stages::index_logs");
+
+        let (addresses, topics) = collect_log_keys(tx, start_block..=end_block)?;
+
+        for (address, blocks) in addresses {
+            append_to_last_shard::<DB, tables::LogAddressIndex, _>(tx, address, blocks)?;
+        }
+        for (topic, blocks) in topics {
+            append_to_last_shard::<DB, tables::LogTopicIndex, _>(tx, topic, blocks)?;
+        }
+
+        let done = !capped;
+        info!(target: "sync::stages::index_logs", stage_progress = end_block, done, "Stage finished");
+        Ok(ExecOutput { stage_progress: end_block, done })
+    }
+
+    /// Unwind the stage.
+    async fn unwind(
+        &mut self,
+        tx: &mut Transaction<'_, DB>,
+        input: UnwindInput,
+    ) -> Result<UnwindOutput, StageError> {
+        info!(target: "sync::stages::index_logs", to_block = input.unwind_to, "Unwinding");
+
+        let unwind_range = input.unwind_to + 1..=input.stage_progress;
+        let (addresses, topics) = collect_log_keys(tx, unwind_range)?;
+
+        for address in addresses.into_keys() {
+            unwind_shards::<DB, tables::LogAddressIndex, _>(tx, address, input.unwind_to)?;
+        }
+        for topic in topics.into_keys() {
+            unwind_shards::<DB, tables::LogTopicIndex, _>(tx, topic, input.unwind_to)?;
+        }
+
+        Ok(UnwindOutput { stage_progress: input.unwind_to })
+    }
+}
+
+/// Log addresses and topics, each with the sorted numbers of the blocks they appear in.
+type LogKeys = (BTreeMap<Address, Vec<usize>>, BTreeMap<H256, Vec<usize>>);
+
+/// Reads the receipts of the blocks in the range and collects the blocks that contain logs of
+/// each address and topic.
+fn collect_log_keys<DB: Database>(
+    tx: &Transaction<'_, DB>,
+    range: RangeInclusive<BlockNumber>,
+) -> Result<LogKeys, StageError> {
+    let mut addresses: BTreeMap<Address, Vec<usize>> = BTreeMap::new();
+    let mut topics: BTreeMap<H256, Vec<usize>> = BTreeMap::new();
+    let mut receipts = tx.cursor_read::<tables::Receipts>()?;
+
+    for block_number in range {
+        let body = tx.get_block_body_by_num(block_number)?;
+        if body.tx_count == 0 {
+            continue
+        }
+
+        let mut walker = receipts.walk(body.start_tx_id)?;
+        for id in body.tx_id_range() {
+            let (receipt_id, receipt) =
+                walker.next().transpose()?.ok_or(DatabaseIntegrityError::Receipt { id })?;
+            if receipt_id != id {
+                return Err(DatabaseIntegrityError::Receipt { id }.into())
+            }
+
+            for log in receipt.logs {
+                record_block(addresses.entry(log.address).or_default(), block_number);
+                for topic in log.topics {
+                    record_block(topics.entry(topic).or_default(), block_number);
+                }
+            }
+        }
+    }
+
+    Ok((addresses, topics))
+}
+
+/// Pushes the block number to the ascending list of blocks, unless it is already the last one.
+fn record_block(blocks: &mut Vec<usize>, block_number: BlockNumber) {
+    let block_number = block_number as usize;
+    if blocks.last() != Some(&block_number) {
+        blocks.push(block_number);
+    }
+}
+
+/// Appends the block numbers to the shards of the key. The last shard, keyed by `u64::MAX`, is
+/// taken out and re-inserted in chunks of [`NUM_OF_INDICES_IN_SHARD`].
+fn append_to_last_shard<DB, T, K>(
+    tx: &Transaction<'_, DB>,
+    key: K,
+    mut blocks: Vec<usize>,
+) -> Result<(), StageError>
+where
+    DB: Database,
+    T: Table<Key = ShardedKey<K>, Value = BlockNumberList>,
+    K: Clone,
+{
+    let mut shard =
+        match tx.cursor_read::<T>()?.seek_exact(ShardedKey::new(key.clone(), u64::MAX))? {
+            Some((shard_key, list)) => {
+                // delete old shard so new one can be inserted.
+                tx.delete::<T>(shard_key, None)?;
+                list.iter(0).collect::<Vec<_>>()
+            }
+            None => Vec::new(),
+        };
+    shard.append(&mut blocks);
+
+    let mut chunks = shard.chunks(NUM_OF_INDICES_IN_SHARD).peekable();
+    while let Some(chunk) = chunks.next() {
+        let highest_block_number = if chunks.peek().is_some() {
+            *chunk.last().expect("Chunks are not empty") as u64
+        } else {
+            u64::MAX
+        };
+        tx.put::<T>(
+            ShardedKey::new(key.clone(), highest_block_number),
+            BlockNumberList::new(chunk).expect("Block numbers are sorted and not empty"),
+        )?;
+    }
+    Ok(())
+}
+
+/// Removes all block numbers higher than `unwind_to` from the shards of the key. The remaining
+/// block numbers of the boundary shard are re-inserted as the last shard.
+fn unwind_shards<DB, T, K>(
+    tx: &Transaction<'_, DB>,
+    key: K,
+    unwind_to: BlockNumber,
+) -> Result<(), StageError>
+where
+    DB: Database,
+    T: Table<Key = ShardedKey<K>, Value = BlockNumberList>,
+    K: Clone + PartialEq,
+{
+    let mut cursor = tx.cursor_write::<T>()?;
+    let mut item = cursor.seek_exact(ShardedKey::new(key.clone(), u64::MAX))?;
+
+    while let Some((sharded_key, list)) = item {
+        // there are no more shards for the key
+        if sharded_key.key != key {
+            break
+        }
+        cursor.delete_current()?;
+
+        // the whole shard is unwound, continue with the previous one
+        let first = list.iter(0).next().expect("Shards are not empty");
+        if first as u64 > unwind_to {
+            item = cursor.prev()?;
+            continue
+        }
+
+        let remaining =
+            list.iter(0).take_while(|block| *block as u64 <= unwind_to).collect::<Vec<_>>();
+        tx.put::<T>(
+            ShardedKey::new(key, u64::MAX),
+            BlockNumberList::new(remaining)
+                .expect("There is at least one element and it is sorted"),
+        )?;
+        break
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{TestTransaction, PREV_STAGE_ID};
+    use reth_db::models::StoredBlockBody;
+    use reth_primitives::{Log, Receipt};
+
+    fn address(n: u64) -> Address {
+        Address::from_low_u64_be(n)
+    }
+
+    fn topic(n: u64) -> H256 {
+        H256::from_low_u64_be(n)
+    }
+
+    fn receipt(logs: Vec<Log>) -> Receipt {
+        Receipt { logs, ..Default::default() }
+    }
+
+    fn log(address: Address, topics: Vec<H256>) -> Log {
+        Log { address, topics, data: Default::default() }
+    }
+
+    fn cast<K: Ord>(
+        table: Vec<(ShardedKey<K>, BlockNumberList)>,
+    ) -> BTreeMap<(K, u64), Vec<usize>> {
+        table
+            .into_iter()
+            .map(|(k, v)| ((k.key, k.highest_transition_id), v.iter(0).collect()))
+            .collect()
+    }
+
+    /// Inserts three blocks: block 1 with two transactions, an empty block 2 and block 3 with one
+    /// transaction.
+    fn setup(tx: &TestTransaction) {
+        tx.commit(|tx| {
+            for (number, start_tx_id, tx_count) in [(0, 0, 0), (1, 0, 2), (2, 2, 0), (3, 2, 1)] {
+                let hash = H256::from_low_u64_be(number);
+                tx.put::<tables::CanonicalHeaders>(number, hash)?;
+                tx.put::<tables::BlockBodies>(
+                    (number, hash).into(),
+                    StoredBlockBody { start_tx_id, tx_count },
+                )?;
+            }
+            tx.put::<tables::Receipts>(0, receipt(vec![log(address(1), vec![topic(1)])]))?;
+            tx.put::<tables::Receipts>(
+                1,
+                receipt(vec![
+                    log(address(1), vec![topic(1), topic(2)]),
+                    log(address(2), vec![topic(2)]),
+                ]),
+            )?;
+            tx.put::<tables::Receipts>(2, receipt(vec![log(address(1), vec![topic(3)])]))?;
+            Ok(())
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn index_and_unwind_logs() {
+        let tx = TestTransaction::default();
+        setup(&tx);
+
+        let mut stage = IndexLogsStage::default();
+        let input = ExecInput { previous_stage: Some((PREV_STAGE_ID, 3)), stage_progress: None };
+        let mut db_tx = tx.inner();
+        let out = stage.execute(&mut db_tx, input).await.unwrap();
+        assert_eq!(out, ExecOutput { stage_progress: 3, done: true });
+        db_tx.commit().unwrap();
+
+        assert_eq!(
+            cast(tx.table::<tables::LogAddressIndex>().unwrap()),
+            BTreeMap::from([
+                ((address(1), u64::MAX), vec![1, 3]),
+                ((address(2), u64::MAX), vec![1]),
+            ])
+        );
+        assert_eq!(
+            cast(tx.table::<tables::LogTopicIndex>().unwrap()),
+            BTreeMap::from([
+                ((topic(1), u64::MAX), vec![1]),
+                ((topic(2), u64::MAX), vec![1]),
+                ((topic(3), u64::MAX), vec![3]),
+            ])
+        );
+
+        let input = UnwindInput { stage_progress: 3, unwind_to: 1, bad_block: None };
+        let out = stage.unwind(&mut db_tx, input).await.unwrap();
+        assert_eq!(out, UnwindOutput { stage_progress: 1 });
+        db_tx.commit().unwrap();
+
+        assert_eq!(
+            cast(tx.table::<tables::LogAddressIndex>().unwrap()),
+            BTreeMap::from([((address(1), u64::MAX), vec![1]), ((address(2), u64::MAX), vec![1])])
+        );
+        assert_eq!(
+            cast(tx.table::<tables::LogTopicIndex>().unwrap()),
+            BTreeMap::from([((topic(1), u64::MAX), vec![1]), ((topic(2), u64::MAX), vec![1])])
+        );
+    }
+
+    #[tokio::test]
+    async fn append_and_unwind_full_shards() {
+        let tx = TestTransaction::default();
+        let full_shard = (0..NUM_OF_INDICES_IN_SHARD).collect::<Vec<_>>();
+        let highest = (NUM_OF_INDICES_IN_SHARD - 1) as u64;
+
+        let mut db_tx = tx.inner();
+        append_to_last_shard::<_, tables::LogAddressIndex, _>(
+            &db_tx,
+            address(1),
+            full_shard.iter().copied().chain([200, 201]).collect(),
+        )
+        .unwrap();
+        db_tx.commit().unwrap();
+        assert_eq!(
+            cast(tx.table::<tables::LogAddressIndex>().unwrap()),
+            BTreeMap::from([
+                ((address(1), highest), full_shard),
+                ((address(1), u64::MAX), vec![200, 201]),
+            ])
+        );
+
+        // unwinding into the full shard makes its remainder the last shard
+        unwind_shards::<_, tables::LogAddressIndex, _>(&db_tx, address(1), 50).unwrap();
+        db_tx.commit().unwrap();
+        assert_eq!(
+            cast(tx.table::<tables::LogAddressIndex>().unwrap()),
+            BTreeMap::from([((address(1), u64::MAX), (0..=50).collect())])
+        );
+    }
+}
@@ -1,12 +1,14 @@
 use crate::{
-    db::Transaction, ExecInput, ExecOutput, Stage, StageError, StageId, UnwindInput, UnwindOutput,
+    db::Transaction, DatabaseIntegrityError, ExecInput, ExecOutput, Stage, StageError, StageId,
+    UnwindInput, UnwindOutput,
 };
-use reth_db::database::Database;
+use reth_db::{database::Database, tables};
+use reth_interfaces::consensus;
+use reth_provider::trie::{DBTrieLoader, PatriciaTrie};
 use std::fmt::Debug;
 use tracing::*;
 
-/// The [`StageId`] of the merkle hashing execution stage.
-pub const MERKLE_EXECUTION: StageId = StageId("MerkleExecute");
+pub use reth_provider::MERKLE_EXECUTION;
 
 /// The [`StageId`] of the merkle hashing unwind stage.
 pub const MERKLE_UNWIND: StageId = StageId("MerkleUnwind");
@@ -26,6 +28,12 @@ pub const MERKLE_UNWIND: StageId = StageId("MerkleUnwind");
 /// stages. The order of these two variants is important. The unwind variant should be added to the
 /// pipeline before the execution variant.
 ///
+/// The trie nodes are stored in [tables::AccountsTrie] and [tables::StoragesTrie], which only hold
+/// the trie of the block the stage was last executed for. The computed state root is checked
+/// against the state root of the header the stage is executed up to.
+/// On unwind, the accounts and storage slots touched by the unwound blocks are read from the
+/// changesets and re-inserted into the trie from the already unwound hashed state.
+///
 /// An example pipeline to only hash state would be:
 ///
 /// - [`MerkleStage::Unwind`]
@@ -35,17 +43,28 @@ pub const MERKLE_UNWIND: StageId = StageId("MerkleUnwind");
 #[derive(Debug)]
 pub enum MerkleStage {
     /// The execution portion of the hashing stage.
-    Execution,
+    Execution {
+        /// The threshold (in number of state transitions) for switching from incremental trie
+        /// updates to rebuilding the trie from the hashed state.
+        clean_threshold: u64,
+    },
     /// The unwind portion of the hasing stage.
     Unwind,
 }
 
+impl MerkleStage {
+    /// Stage default for the Execution variant.
+    pub fn default_execution() -> Self {
+        Self::Execution { clean_threshold: 50_000 }
+    }
+}
+
 #[async_trait::async_trait]
 impl<DB: Database> Stage<DB> for MerkleStage {
     /// Return the id of the stage
     fn id(&self) -> StageId {
         match self {
-            MerkleStage::Execution => MERKLE_EXECUTION,
+            MerkleStage::Execution { .. } => MERKLE_EXECUTION,
             MerkleStage::Unwind => MERKLE_UNWIND,
         }
     }
@@ -53,32 +72,260 @@ impl<DB: Database> Stage<DB> for MerkleStage {
     /// Execute the stage.
     async fn execute(
         &mut self,
-        _tx: &mut Transaction<'_, DB>,
+        tx: &mut Transaction<'_, DB>,
         input: ExecInput,
     ) -> Result<ExecOutput, StageError> {
-        if matches!(self, MerkleStage::Unwind) {
-            info!(target: "sync::stages::merkle::unwind", "Stage is always skipped");
-            return Ok(ExecOutput { stage_progress: input.previous_stage_progress(), done: true })
+        let clean_threshold = match self {
+            MerkleStage::Unwind => {
+                info!(target: "sync::stages::merkle::unwind", "Stage is always skipped");
+                return Ok(ExecOutput {
+                    stage_progress: input.previous_stage_progress(),
+                    done: true,
+                })
+            }
+            MerkleStage::Execution { clean_threshold } => *clean_threshold,
+        };
+
+        let stage_progress = input.stage_progress.unwrap_or_default();
+        let previous_stage_progress = input.previous_stage_progress();
+        let current_root = tx.get_header_by_num(stage_progress)?.state_root;
+        let stored_root =
+            PatriciaTrie::<_, tables::AccountsTrie>::stored_root(&**tx).map_err(trie_error)?;
+        if stage_progress == previous_stage_progress && stored_root == current_root {
+            info!(target: "sync::stages::merkle::exec", "Nothing to hash");
+            return Ok(ExecOutput { stage_progress, done: true })
         }
 
-        // Iterate over changeset (similar to Hashing stages) and take new values
+        let from_transition = tx.get_block_transition(stage_progress)?;
+        let to_transition = tx.get_block_transition(previous_stage_progress)?;
+        let block_root = tx.get_header_by_num(previous_stage_progress)?.state_root;
+
+        let loader = DBTrieLoader::default();
+        let trie_root =
+            if to_transition - from_transition > clean_threshold || stored_root != current_root {
+                debug!(
+                    target: "sync::stages::merkle::exec",
+                    previous_stage_progress, "Rebuilding trie"
+                );
+                loader.calculate_root(&**tx).map_err(trie_error)?
+            } else {
+                debug!(
+                    target: "sync::stages::merkle::exec",
+                    stage_progress, previous_stage_progress, "Updating trie"
+                );
+                loader.update_root(&**tx, from_transition..to_transition).map_err(trie_error)?
+            };
+
+        if trie_root != block_root {
+            warn!(
+                target: "sync::stages::merkle::exec",
+                block = previous_stage_progress, ?trie_root, ?block_root, "State root mismatch"
+            );
+            return Err(StageError::Validation {
+                block: previous_stage_progress,
+                error: consensus::Error::BodyStateRootDiff { got: trie_root, expected: block_root },
+            })
+        }
 
         info!(target: "sync::stages::merkle::exec", "Stage finished");
-        Ok(ExecOutput { stage_progress: input.previous_stage_progress(), done: true })
+        Ok(ExecOutput { stage_progress: previous_stage_progress, done: true })
     }
 
     /// Unwind the stage.
     async fn unwind(
         &mut self,
-        _tx: &mut Transaction<'_, DB>,
+        tx: &mut Transaction<'_, DB>,
         input: UnwindInput,
     ) -> Result<UnwindOutput, StageError> {
-        if matches!(self, MerkleStage::Execution) {
+        if matches!(self, MerkleStage::Execution { .. }) {
             info!(target: "sync::stages::merkle::exec", "Stage is always skipped");
             return Ok(UnwindOutput { stage_progress: input.unwind_to })
         }
 
+        // The hashing stages have already been unwound at this point, so the hashed state reflects
+        // the state at `unwind_to`. The changesets of the unwound blocks are still present and
+        // tell which accounts and storage slots have to be reverted in the trie.
+        let from_transition = tx.get_block_transition(input.unwind_to)?;
+        let to_transition = tx.get_block_transition(input.stage_progress)?;
+        let current_root = tx.get_header_by_num(input.stage_progress)?.state_root;
+        let target_root = tx.get_header_by_num(input.unwind_to)?.state_root;
+        let stored_root =
+            PatriciaTrie::<_, tables::AccountsTrie>::stored_root(&**tx).map_err(trie_error)?;
+
+        let loader = DBTrieLoader::default();
+        let trie_root = if stored_root == current_root {
+            debug!(
+                target: "sync::stages::merkle::unwind",
+                stage_progress = input.stage_progress, unwind_to = input.unwind_to, "Reverting trie"
+            );
+            loader.update_root(&**tx, from_transition..to_transition).map_err(trie_error)?
+        } else {
+            debug!(
+                target: "sync::stages::merkle::unwind",
+                unwind_to = input.unwind_to, "Rebuilding trie"
+            );
+            loader.calculate_root(&**tx).map_err(trie_error)?
+        };
+
+        if trie_root != target_root {
+            warn!(
+                target: "sync::stages::merkle::unwind",
+                block = input.unwind_to, ?trie_root, ?target_root, "State root mismatch"
+            );
+            return Err(DatabaseIntegrityError::StateRoot {
+                number: input.unwind_to,
+                got: trie_root,
+                expected: target_root,
+            }
+            .into())
+        }
+
         info!(target: "sync::stages::merkle::unwind", "Stage finished");
         Ok(UnwindOutput { stage_progress: input.unwind_to })
     }
 }
+
+/// Trie errors are either database errors or missing/corrupt trie nodes, which can not be
+/// recovered from by retrying.
+fn trie_error(error: reth_interfaces::Error) -> StageError {
+    match error {
+        reth_interfaces::Error::Database(error) => StageError::Database(error),
+        error => StageError::Fatal(Box::new(error)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{TestTransaction, PREV_STAGE_ID};
+    use assert_matches::assert_matches;
+    use reth_db::{models::AccountBeforeTx, transaction::DbTxMut};
+    use reth_interfaces::test_utils::generators::{random_block_range, random_eoa_account_range};
+    use reth_primitives::{
+        keccak256, proofs::genesis_state_root, Account, Address, GenesisAccount, SealedBlock, H256,
+    };
+    use reth_provider::insert_canonical_block;
+
+    /// Compute the state root of the given accounts.
+    fn state_root(accounts: &[(Address, Account)]) -> H256 {
+        genesis_state_root(
+            accounts
+                .iter()
+                .map(|(address, account)| {
+                    let account = GenesisAccount {
+                        nonce: Some(account.nonce),
+                        balance: account.balance,
+                        ..Default::default()
+                    };
+                    (*address, account)
+                })
+                .collect(),
+        )
+    }
+
+    /// Overwrite the state root of the block header.
+    fn set_state_root(block: &mut SealedBlock, state_root: H256) {
+        let mut header = block.header.clone().unseal();
+        header.state_root = state_root;
+        block.header = header.seal();
+    }
+
+    /// Seed blocks `0..=previous_stage_progress` and the hashed state of `accounts`, with the
+    /// state root of the last header set to `root` (or the actual root of the seeded state if
+    /// `None`).
+    fn seed(
+        tx: &TestTransaction,
+        previous_stage_progress: u64,
+        accounts: &[(Address, Account)],
+        root: Option<H256>,
+    ) -> Vec<SealedBlock> {
+        let mut blocks = random_block_range(0..previous_stage_progress + 1, H256::zero(), 0..3);
+        set_state_root(blocks.last_mut().unwrap(), root.unwrap_or_else(|| state_root(accounts)));
+
+        tx.commit(|tx| {
+            for block in blocks.iter() {
+                insert_canonical_block(tx, block, true).unwrap();
+            }
+            for (address, account) in accounts.iter() {
+                tx.put::<tables::HashedAccount>(keccak256(address), *account)?;
+            }
+            Ok(())
+        })
+        .unwrap();
+        blocks
+    }
+
+    #[tokio::test]
+    async fn execute_computes_state_root() {
+        let tx = TestTransaction::default();
+        seed(&tx, 10, &random_eoa_account_range(&mut (0..10)), None);
+
+        let input = ExecInput { previous_stage: Some((PREV_STAGE_ID, 10)), stage_progress: None };
+        let mut db = tx.inner();
+        let result = MerkleStage::default_execution().execute(&mut db, input).await;
+        assert_matches!(result, Ok(ExecOutput { stage_progress: 10, done: true }));
+    }
+
+    #[tokio::test]
+    async fn execute_fails_on_state_root_mismatch() {
+        let tx = TestTransaction::default();
+        seed(&tx, 10, &random_eoa_account_range(&mut (0..10)), Some(H256::repeat_byte(0xab)));
+
+        let input = ExecInput { previous_stage: Some((PREV_STAGE_ID, 10)), stage_progress: None };
+        let mut db = tx.inner();
+        let result = MerkleStage::default_execution().execute(&mut db, input).await;
+        assert_matches!(
+            result,
+            Err(StageError::Validation {
+                block: 10,
+                error: consensus::Error::BodyStateRootDiff { .. }
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn unwind_reverts_state_root() {
+        let tx = TestTransaction::default();
+        let accounts = random_eoa_account_range(&mut (0..10));
+        let (changed, old_account) = accounts[0];
+        let mut new_accounts = accounts.clone();
+        new_accounts[0].1.nonce += 1;
+
+        // Block 5 commits to the old state, block 10 to the state with the changed account.
+        let blocks = seed(&tx, 10, &new_accounts, None);
+        tx.commit(|tx| {
+            let mut header = blocks[5].header.clone().unseal();
+            header.state_root = state_root(&accounts);
+            tx.put::<tables::Headers>(blocks[5].header.num_hash().into(), header)
+        })
+        .unwrap();
+
+        let input = ExecInput { previous_stage: Some((PREV_STAGE_ID, 10)), stage_progress: None };
+        let mut db = tx.inner();
+        let result = MerkleStage::default_execution().execute(&mut db, input).await;
+        assert_matches!(result, Ok(ExecOutput { stage_progress: 10, done: true }));
+        db.commit().unwrap();
+
+        // Unwind the hashed state the way the hashing stages would and record the change. The
+        // hashed state also gets an account that is not in any changeset: the incremental revert
+        // never looks at it, while rebuilding the trie would include it and miss the state root.
+        tx.commit(|tx| {
+            let transition = tx.get::<tables::BlockTransitionIndex>(5)?.unwrap();
+            tx.put::<tables::HashedAccount>(keccak256(changed), old_account)?;
+            tx.put::<tables::HashedAccount>(
+                H256::repeat_byte(0xab),
+                Account { nonce: 1, ..Default::default() },
+            )?;
+            tx.put::<tables::AccountChangeSet>(
+                transition,
+                AccountBeforeTx { address: changed, info: Some(old_account) },
+            )
+        })
+        .unwrap();
+
+        let input = UnwindInput { stage_progress: 10, unwind_to: 5, bad_block: None };
+        let mut db = tx.inner();
+        let result = MerkleStage::Unwind.unwind(&mut db, input).await;
+        assert_matches!(result, Ok(UnwindOutput { stage_progress: 5 }));
+    }
+}
@@ -2,6 +2,8 @@
 mod bodies;
 /// The execution stage that generates state diff.
 mod execution;
+/// The finish stage
+mod finish;
 /// Account hashing stage.
 mod hashing_account;
 /// Storage hashing stage.
@@ -10,6 +12,8 @@ mod hashing_storage;
 mod headers;
 /// Index history of account changes
 mod index_account_history;
+/// Index logs by address and topic
+mod index_logs;
 /// Index history of storage changes
 mod index_storage_history;
 /// Intermediate hashes and creating merkle root
@@ -23,10 +27,12 @@ mod tx_lookup;
 
 pub use bodies::*;
 pub use execution::*;
+pub use finish::*;
 pub use hashing_account::*;
 pub use hashing_storage::*;
 pub use headers::*;
 pub use index_account_history::*;
+pub use index_logs::*;
 pub use index_storage_history::*;
 pub use merkle::*;
 pub use sender_recovery::*;
@@ -16,7 +16,8 @@ use std::fmt::Debug;
 use thiserror::Error;
 use tracing::*;
 
-const SENDER_RECOVERY: StageId = StageId("SenderRecovery");
+/// The [`StageId`] of the sender recovery stage.
+pub const SENDER_RECOVERY: StageId = StageId("SenderRecovery");
 
 /// The sender recovery stage iterates over existing transactions,
 /// recovers the transaction signer and stores them
@@ -11,7 +11,8 @@ use reth_db::{
 use reth_primitives::U256;
 use tracing::*;
 
-const TOTAL_DIFFICULTY: StageId = StageId("TotalDifficulty");
+/// The [`StageId`] of the total difficulty stage.
+pub const TOTAL_DIFFICULTY: StageId = StageId("TotalDifficulty");
 
 /// The total difficulty stage.
 ///
@@ -0,0 +1,327 @@
+use crate::{db::Transaction, DatabaseIntegrityError, StaticFileProducerError};
+use reth_db::{
+    cursor::DbCursorRO,
+    database::Database,
+    static_file::{StaticFileSegment, StaticFiles},
+    table::Table,
+    tables,
+    transaction::{DbTx, DbTxMut},
+};
+use reth_interfaces::events::ChainEvent;
+use reth_primitives::{BlockNumber, PruneSegment, TxNumber};
+use reth_provider::{
+    prune_checkpoint, save_static_file_checkpoint, static_file_checkpoint, ForkchoiceMarker,
+};
+use std::{fmt::Debug, ops::Deref, sync::Arc};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::*;
+
+/// The maximum number of blocks of a segment that are moved before the progress is committed.
+const MOVE_COMMIT_THRESHOLD: u64 = 10_000;
+
+/// Moves the headers, transactions and receipts of finalized blocks out of the database into the
+/// [StaticFiles].
+///
+/// The values of a block are appended to the static files and made durable before they are
+/// deleted from the database, in the same transaction that saves the highest moved block of the
+/// segment in [tables::StaticFileCheckpoints]. Values that were appended after the checkpoint are
+/// removed from the static files before moving continues.
+///
+/// Only blocks below the finalized block are moved, so the database always holds the chain tip
+/// and the blocks that can still be unwound. Block bodies stay in the database, as they index the
+/// transactions of each block.
+pub struct StaticFileProducer<DB> {
+    /// The database the data is moved out of.
+    db: Arc<DB>,
+    /// The static files the data is moved to.
+    static_files: Arc<StaticFiles>,
+    /// The segments that are moved.
+    segments: Vec<StaticFileSegment>,
+}
+
+impl<DB> Debug for StaticFileProducer<DB> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StaticFileProducer").field("segments", &self.segments).finish()
+    }
+}
+
+impl<DB: Database> StaticFileProducer<DB> {
+    /// Create a new producer that moves the given segments.
+    pub fn new(
+        db: Arc<DB>,
+        static_files: Arc<StaticFiles>,
+        segments: impl IntoIterator<Item = StaticFileSegment>,
+    ) -> Self {
+        Self { db, static_files, segments: segments.into_iter().collect() }
+    }
+
+    /// Moves the finalized blocks of all segments, given the current tip of the canonical chain.
+    pub fn run(&self, tip: BlockNumber) -> Result<(), StaticFileProducerError> {
+        let mut tx = Transaction::new(self.db.as_ref())?;
+
+        let Some(finalized) = ForkchoiceMarker::Finalized.block_number(tx.deref())? else {
+            return Ok(())
+        };
+        let Some(target) = finalized.min(tip).checked_sub(1) else { return Ok(()) };
+
+        for segment in self.segments.iter().copied() {
+            let mut checkpoint = static_file_checkpoint(tx.deref(), segment)?;
+            self.static_files.truncate(segment, next_key(&tx, segment, checkpoint)?)?;
+
+            // Pruned receipts can't be moved, the segment starts after them.
+            if segment == StaticFileSegment::Receipts {
+                let pruned = prune_checkpoint(tx.deref(), PruneSegment::Receipts)?;
+                if pruned > checkpoint {
+                    self.static_files.truncate(segment, 0)?;
+                    checkpoint = pruned;
+                }
+            }
+
+            while checkpoint.map_or(true, |checkpoint| checkpoint < target) {
+                let from = checkpoint.map_or(0, |checkpoint| checkpoint + 1);
+                let to = target.min(from + MOVE_COMMIT_THRESHOLD - 1);
+                debug!(target: "sync::static_files", %segment, %from, %to, "Moving blocks");
+
+                for number in from..=to {
+                    match segment {
+                        StaticFileSegment::Headers => self.move_header(&tx, number)?,
+                        StaticFileSegment::Transactions => {
+                            self.move_tx_values::<tables::Transactions>(&tx, segment, number)?
+                        }
+                        StaticFileSegment::Receipts => {
+                            self.move_tx_values::<tables::Receipts>(&tx, segment, number)?
+                        }
+                    }
+                }
+                self.static_files.commit()?;
+                save_static_file_checkpoint(tx.deref(), segment, to)?;
+                tx.commit()?;
+                checkpoint = Some(to);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Moves the canonical header of the block.
+    fn move_header(
+        &self,
+        tx: &Transaction<'_, DB>,
+        number: BlockNumber,
+    ) -> Result<(), StaticFileProducerError> {
+        let hash = tx
+            .get::<tables::CanonicalHeaders>(number)?
+            .ok_or(DatabaseIntegrityError::CanonicalHeader { number })?;
+        let key = (number, hash).into();
+        let header = tx
+            .get::<tables::Headers>(key)?
+            .ok_or(DatabaseIntegrityError::Header { number, hash })?;
+        self.static_files.append(StaticFileSegment::Headers, number, number, header)?;
+        tx.delete::<tables::Headers>(key, None)?;
+        Ok(())
+    }
+
+    /// Moves the values of the transactions of the block.
+    fn move_tx_values<T: Table<Key = TxNumber>>(
+        &self,
+        tx: &Transaction<'_, DB>,
+        segment: StaticFileSegment,
+        number: BlockNumber,
+    ) -> Result<(), StaticFileProducerError> {
+        let start = first_tx_id(tx, number)?;
+        let end = first_tx_id(tx, number + 1)?;
+        let mut values = tx
+            .cursor_read::<T>()?
+            .walk_range(start..end)?
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter();
+
+        for id in start..end {
+            let value = match values.next() {
+                Some((key, value)) if key == id => value,
+                _ => return Err(missing_tx_value(segment, id)),
+            };
+            self.static_files.append(segment, number, id, value)?;
+            tx.delete::<T>(id, None)?;
+        }
+        Ok(())
+    }
+}
+
+impl<DB: Database + 'static> StaticFileProducer<DB> {
+    /// Moves the finalized blocks whenever the canonical chain is extended.
+    ///
+    /// Moving runs on a blocking thread, since it writes files and waits for the write
+    /// transactions of the pipeline.
+    pub async fn run_on_chain_events(self, mut events: broadcast::Receiver<ChainEvent>) {
+        if self.segments.is_empty() {
+            return
+        }
+
+        let producer = Arc::new(self);
+        loop {
+            let tip = match events.recv().await {
+                Ok(ChainEvent::Extended { tip, .. }) => tip,
+                Ok(ChainEvent::Unwound { .. }) | Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return,
+            };
+
+            let task = producer.clone();
+            match tokio::task::spawn_blocking(move || task.run(tip)).await {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => {
+                    error!(target: "sync::static_files", %tip, "Failed to move blocks: {err}")
+                }
+                Err(err) => error!(target: "sync::static_files", %tip, "Moving panicked: {err}"),
+            }
+        }
+    }
+}
+
+/// Returns the first transaction of the block.
+fn first_tx_id<DB: Database>(
+    tx: &Transaction<'_, DB>,
+    number: BlockNumber,
+) -> Result<TxNumber, StaticFileProducerError> {
+    let hash = tx
+        .get::<tables::CanonicalHeaders>(number)?
+        .ok_or(DatabaseIntegrityError::CanonicalHeader { number })?;
+    let body = tx
+        .get::<tables::BlockBodies>((number, hash).into())?
+        .ok_or(DatabaseIntegrityError::BlockBody { number })?;
+    Ok(body.start_tx_id)
+}
+
+/// Returns the key of the first value of the segment after the block `checkpoint`.
+fn next_key<DB: Database>(
+    tx: &Transaction<'_, DB>,
+    segment: StaticFileSegment,
+    checkpoint: Option<BlockNumber>,
+) -> Result<u64, StaticFileProducerError> {
+    let Some(checkpoint) = checkpoint else { return Ok(0) };
+    match segment {
+        StaticFileSegment::Headers => Ok(checkpoint + 1),
+        StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+            first_tx_id(tx, checkpoint + 1)
+        }
+    }
+}
+
+fn missing_tx_value(segment: StaticFileSegment, id: TxNumber) -> StaticFileProducerError {
+    match segment {
+        StaticFileSegment::Receipts => DatabaseIntegrityError::Receipt { id },
+        _ => DatabaseIntegrityError::Transaction { id },
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{
+        mdbx::{test_utils::create_test_db, EnvKind, WriteMap},
+        models::StoredBlockBody,
+    };
+    use reth_primitives::{Header, Receipt, TransactionSigned, H256};
+    use reth_provider::save_prune_checkpoint;
+
+    /// Inserts blocks `0..=tip` with two transactions each and finalizes block `finalized`.
+    fn insert_blocks<DB: Database>(db: &DB, tip: BlockNumber, finalized: BlockNumber) {
+        db.update(|tx| {
+            for number in 0..=tip {
+                let header = Header { number, ..Default::default() };
+                let hash = H256::from_low_u64_be(number);
+                tx.put::<tables::CanonicalHeaders>(number, hash).unwrap();
+                tx.put::<tables::HeaderNumbers>(hash, number).unwrap();
+                tx.put::<tables::Headers>((number, hash).into(), header).unwrap();
+                tx.put::<tables::BlockBodies>(
+                    (number, hash).into(),
+                    StoredBlockBody { start_tx_id: number * 2, tx_count: 2 },
+                )
+                .unwrap();
+                for id in number * 2..number * 2 + 2 {
+                    let transaction =
+                        TransactionSigned { hash: H256::from_low_u64_be(id), ..Default::default() };
+                    tx.put::<tables::Transactions>(id, transaction).unwrap();
+                    let receipt = Receipt { cumulative_gas_used: id, ..Default::default() };
+                    tx.put::<tables::Receipts>(id, receipt).unwrap();
+                }
+            }
+            ForkchoiceMarker::Finalized.save(tx, H256::from_low_u64_be(finalized)).unwrap();
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn move_finalized_blocks() {
+        let db = Arc::new(create_test_db::<WriteMap>(EnvKind::RW));
+        let dir = tempfile::tempdir().unwrap();
+        let static_files = Arc::new(StaticFiles::open(dir.path()).unwrap());
+        insert_blocks(db.as_ref(), 10, 5);
+
+        let producer = StaticFileProducer::new(
+            db.clone(),
+            static_files.clone(),
+            [StaticFileSegment::Headers, StaticFileSegment::Transactions],
+        );
+        producer.run(10).unwrap();
+
+        let tx = db.tx().unwrap();
+        for segment in [StaticFileSegment::Headers, StaticFileSegment::Transactions] {
+            assert_eq!(static_file_checkpoint(&tx, segment).unwrap(), Some(4));
+        }
+        assert_eq!(static_file_checkpoint(&tx, StaticFileSegment::Receipts).unwrap(), None);
+        assert_eq!(static_files.next_key(StaticFileSegment::Headers), Some(5));
+        assert_eq!(static_files.next_key(StaticFileSegment::Transactions), Some(10));
+        assert_eq!(static_files.next_key(StaticFileSegment::Receipts), None);
+
+        // the finalized block stays in the database
+        let headers = tx.cursor_read::<tables::Headers>().unwrap().first().unwrap();
+        assert_eq!(headers.map(|(key, _)| key.number()), Some(5));
+        let transactions = tx.cursor_read::<tables::Transactions>().unwrap().first().unwrap();
+        assert_eq!(transactions.map(|(id, _)| id), Some(10));
+        assert_eq!(
+            static_files.get::<TransactionSigned>(StaticFileSegment::Transactions, 9).unwrap(),
+            Some(TransactionSigned { hash: H256::from_low_u64_be(9), ..Default::default() })
+        );
+    }
+
+    #[test]
+    fn recover_and_skip_pruned_receipts() {
+        let db = Arc::new(create_test_db::<WriteMap>(EnvKind::RW));
+        let dir = tempfile::tempdir().unwrap();
+        let static_files = Arc::new(StaticFiles::open(dir.path()).unwrap());
+        insert_blocks(db.as_ref(), 10, 5);
+        db.update(|tx| {
+            for id in 0..6 {
+                tx.delete::<tables::Receipts>(id, None).unwrap();
+            }
+            save_prune_checkpoint(tx, PruneSegment::Receipts, 2).unwrap();
+        })
+        .unwrap();
+
+        // a value that was appended, but not removed from the database before the node stopped
+        let receipt = Receipt { cumulative_gas_used: 6, ..Default::default() };
+        static_files.append(StaticFileSegment::Receipts, 3, 6, receipt).unwrap();
+
+        let producer = StaticFileProducer::new(
+            db.clone(),
+            static_files.clone(),
+            [StaticFileSegment::Receipts],
+        );
+        producer.run(10).unwrap();
+
+        let tx = db.tx().unwrap();
+        assert_eq!(static_file_checkpoint(&tx, StaticFileSegment::Receipts).unwrap(), Some(4));
+        assert!(!static_files.contains(StaticFileSegment::Receipts, 5));
+        assert_eq!(static_files.next_key(StaticFileSegment::Receipts), Some(10));
+        for id in 6..10 {
+            assert_eq!(
+                static_files.get::<Receipt>(StaticFileSegment::Receipts, id).unwrap(),
+                Some(Receipt { cumulative_gas_used: id, ..Default::default() })
+            );
+        }
+        let receipts = tx.cursor_read::<tables::Receipts>().unwrap().first().unwrap();
+        assert_eq!(receipts.map(|(id, _)| id), Some(10));
+    }
+}
@@ -11,6 +11,7 @@
 pub mod abstraction;
 
 mod implementation;
+pub mod static_file;
 pub mod tables;
 mod utils;
 
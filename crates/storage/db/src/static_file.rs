@@ -0,0 +1,432 @@
+//! Append-only storage for finalized data that no longer changes.
+//!
+//! Each [StaticFileSegment] is a sequence of values keyed by consecutive numbers. The values are
+//! compressed with snappy and appended to files that cover [BLOCKS_PER_STATIC_FILE] blocks each.
+//! Every data file (`{segment}_{block_start}.dat`) has an index file
+//! (`{segment}_{block_start}.idx`) that holds the key of its first value, followed by the end
+//! offset of each value in the data file.
+//!
+//! Appended values are only durable after [StaticFiles::commit]. Values that were not completely
+//! written when the node stopped are dropped when the files are opened again.
+
+use crate::{
+    table::{Compress, Decompress},
+    Error,
+};
+use parking_lot::RwLock;
+use reth_primitives::BlockNumber;
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs::{self, File, OpenOptions},
+    io::{self, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+/// The number of blocks whose values are stored in the same file.
+pub const BLOCKS_PER_STATIC_FILE: u64 = 500_000;
+
+/// The size of a key or an offset in the index file.
+const INDEX_ENTRY_SIZE: u64 = 8;
+
+/// A kind of data that is stored in the static files.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum StaticFileSegment {
+    /// The [Header](reth_primitives::Header)s, keyed by block number.
+    Headers,
+    /// The [TransactionSigned](reth_primitives::TransactionSigned)s, keyed by transaction id.
+    Transactions,
+    /// The [Receipt](reth_primitives::Receipt)s, keyed by transaction id.
+    Receipts,
+}
+
+impl StaticFileSegment {
+    /// All segments.
+    pub const ALL: [StaticFileSegment; 3] =
+        [StaticFileSegment::Headers, StaticFileSegment::Transactions, StaticFileSegment::Receipts];
+
+    /// The name of the segment, which is also the prefix of its file names and the key of its
+    /// progress.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StaticFileSegment::Headers => "headers",
+            StaticFileSegment::Transactions => "transactions",
+            StaticFileSegment::Receipts => "receipts",
+        }
+    }
+}
+
+impl Display for StaticFileSegment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The static files of all segments in a directory.
+///
+/// Reads can happen concurrently with appends, but there should only be a single writer.
+#[derive(Debug)]
+pub struct StaticFiles {
+    /// The directory of the files.
+    dir: PathBuf,
+    /// The files of each segment, ordered by their first block.
+    segments: RwLock<HashMap<StaticFileSegment, Vec<StaticFile>>>,
+}
+
+impl StaticFiles {
+    /// Opens the static files in the directory, creating it if it does not exist.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, Error> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir).map_err(io_error)?;
+
+        let mut segments: HashMap<StaticFileSegment, Vec<StaticFile>> = HashMap::new();
+        for entry in fs::read_dir(&dir).map_err(io_error)? {
+            let name = entry.map_err(io_error)?.file_name();
+            let Some((segment, block_start)) = name.to_str().and_then(parse_index_name) else {
+                continue
+            };
+            if let Some(file) = StaticFile::open(&dir, segment, block_start).map_err(io_error)? {
+                segments.entry(segment).or_default().push(file);
+            }
+        }
+        for files in segments.values_mut() {
+            files.sort_by_key(|file| file.block_start);
+        }
+
+        Ok(Self { dir, segments: RwLock::new(segments) })
+    }
+
+    /// Returns the key that the next value appended to the segment must have, or `None` if the
+    /// segment is empty.
+    pub fn next_key(&self, segment: StaticFileSegment) -> Option<u64> {
+        self.segments.read().get(&segment)?.last().map(StaticFile::next_key)
+    }
+
+    /// Returns `true` if the segment holds the value with the given key.
+    pub fn contains(&self, segment: StaticFileSegment, key: u64) -> bool {
+        self.segments.read().get(&segment).map_or(false, |files| find_file(files, key).is_some())
+    }
+
+    /// Returns the value with the given key, if the segment holds it.
+    ///
+    /// The value type must be the type that is stored in the segment.
+    pub fn get<V: Decompress>(
+        &self,
+        segment: StaticFileSegment,
+        key: u64,
+    ) -> Result<Option<V>, Error> {
+        let compressed = {
+            let segments = self.segments.read();
+            let Some(file) = segments.get(&segment).and_then(|files| find_file(files, key)) else {
+                return Ok(None)
+            };
+            file.read(key).map_err(io_error)?
+        };
+        let value = snap::raw::Decoder::new()
+            .decompress_vec(&compressed)
+            .map_err(|err| Error::StaticFile(err.to_string()))?;
+        V::decompress(value).map(Some)
+    }
+
+    /// Appends the value of a block to the segment.
+    ///
+    /// The key must follow the last key of the segment, and the block must not be lower than the
+    /// block of the last value. The first value of a segment can have any key.
+    pub fn append<V: Compress>(
+        &self,
+        segment: StaticFileSegment,
+        block: BlockNumber,
+        key: u64,
+        value: V,
+    ) -> Result<(), Error> {
+        let compressed = snap::raw::Encoder::new()
+            .compress_vec(value.compress().as_ref())
+            .map_err(|err| Error::StaticFile(err.to_string()))?;
+        let block_start = block - block % BLOCKS_PER_STATIC_FILE;
+
+        let mut segments = self.segments.write();
+        let files = segments.entry(segment).or_default();
+        if let Some(last) = files.last() {
+            if key != last.next_key() || block_start < last.block_start {
+                return Err(Error::StaticFile(format!(
+                    "can't append key {key} of block {block} to {segment}, expected key {}",
+                    last.next_key()
+                )))
+            }
+        }
+        if files.last().map_or(true, |last| last.block_start != block_start) {
+            if let Some(last) = files.last() {
+                last.sync().map_err(io_error)?;
+            }
+            files.push(StaticFile::create(&self.dir, segment, block_start, key).map_err(io_error)?);
+        }
+        files.last_mut().expect("file exists").append(&compressed).map_err(io_error)
+    }
+
+    /// Removes the values of the segment starting at the given key.
+    pub fn truncate(&self, segment: StaticFileSegment, next_key: u64) -> Result<(), Error> {
+        let mut segments = self.segments.write();
+        let Some(files) = segments.get_mut(&segment) else { return Ok(()) };
+        while let Some(last) = files.last_mut() {
+            if last.first_key < next_key {
+                if last.next_key() > next_key {
+                    last.truncate(next_key - last.first_key).map_err(io_error)?;
+                }
+                break
+            }
+            let (data, index) = file_paths(&self.dir, segment, last.block_start);
+            files.pop();
+            fs::remove_file(data).map_err(io_error)?;
+            fs::remove_file(index).map_err(io_error)?;
+        }
+        Ok(())
+    }
+
+    /// Makes all appended values durable.
+    pub fn commit(&self) -> Result<(), Error> {
+        for file in self.segments.read().values().filter_map(|files| files.last()) {
+            file.sync().map_err(io_error)?;
+        }
+        Ok(())
+    }
+}
+
+/// The data and index file of a segment, starting at a block.
+#[derive(Debug)]
+struct StaticFile {
+    /// The first block whose values are stored in the file.
+    block_start: BlockNumber,
+    /// The key of the first value.
+    first_key: u64,
+    /// The number of values.
+    len: u64,
+    /// The size of the values in the data file.
+    data_len: u64,
+    /// The compressed values.
+    data: File,
+    /// The first key and the end offset of each value.
+    index: File,
+}
+
+impl StaticFile {
+    /// Creates an empty file whose first value has the given key.
+    fn create(
+        dir: &Path,
+        segment: StaticFileSegment,
+        block_start: BlockNumber,
+        first_key: u64,
+    ) -> io::Result<Self> {
+        let (data_path, index_path) = file_paths(dir, segment, block_start);
+        let data = OpenOptions::new().read(true).write(true).create_new(true).open(data_path)?;
+        let mut index =
+            OpenOptions::new().read(true).write(true).create_new(true).open(index_path)?;
+        index.write_all(&first_key.to_le_bytes())?;
+        Ok(Self { block_start, first_key, len: 0, data_len: 0, data, index })
+    }
+
+    /// Opens the file and drops the values that were not completely written.
+    ///
+    /// Returns `None` and removes the file if the key of its first value was not written.
+    fn open(
+        dir: &Path,
+        segment: StaticFileSegment,
+        block_start: BlockNumber,
+    ) -> io::Result<Option<Self>> {
+        let (data_path, index_path) = file_paths(dir, segment, block_start);
+        let open = |path: &Path| OpenOptions::new().read(true).write(true).create(true).open(path);
+        let (data, index) = (open(&data_path)?, open(&index_path)?);
+
+        let index_size = index.metadata()?.len();
+        if index_size < INDEX_ENTRY_SIZE {
+            fs::remove_file(data_path)?;
+            fs::remove_file(index_path)?;
+            return Ok(None)
+        }
+        let first_key = read_u64(&index, 0)?;
+
+        let mut file = Self {
+            block_start,
+            first_key,
+            len: index_size / INDEX_ENTRY_SIZE - 1,
+            data_len: 0,
+            data,
+            index,
+        };
+        let data_size = file.data.metadata()?.len();
+        while file.len > 0 && file.end_offset(file.len - 1)? > data_size {
+            file.len -= 1;
+        }
+        file.truncate(file.len)?;
+        Ok(Some(file))
+    }
+
+    /// The key that follows the last value.
+    fn next_key(&self) -> u64 {
+        self.first_key + self.len
+    }
+
+    /// Returns the end offset of the n-th value in the data file.
+    fn end_offset(&self, n: u64) -> io::Result<u64> {
+        read_u64(&self.index, (n + 1) * INDEX_ENTRY_SIZE)
+    }
+
+    /// Returns the compressed value with the given key.
+    fn read(&self, key: u64) -> io::Result<Vec<u8>> {
+        let n = key - self.first_key;
+        let start = if n == 0 { 0 } else { self.end_offset(n - 1)? };
+        let mut buf = vec![0; (self.end_offset(n)? - start) as usize];
+        read_exact_at(&self.data, &mut buf, start)?;
+        Ok(buf)
+    }
+
+    /// Appends a compressed value.
+    fn append(&mut self, value: &[u8]) -> io::Result<()> {
+        self.data.seek(SeekFrom::Start(self.data_len))?;
+        self.data.write_all(value)?;
+        self.data_len += value.len() as u64;
+
+        self.index.seek(SeekFrom::Start((self.len + 1) * INDEX_ENTRY_SIZE))?;
+        self.index.write_all(&self.data_len.to_le_bytes())?;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Keeps the first `len` values.
+    fn truncate(&mut self, len: u64) -> io::Result<()> {
+        self.data_len = if len == 0 { 0 } else { self.end_offset(len - 1)? };
+        self.len = len;
+        self.data.set_len(self.data_len)?;
+        self.index.set_len((len + 1) * INDEX_ENTRY_SIZE)
+    }
+
+    /// Flushes the data before the index, so an index entry never points to unwritten data.
+    fn sync(&self) -> io::Result<()> {
+        self.data.sync_all()?;
+        self.index.sync_all()
+    }
+}
+
+/// Returns the file of the segment that holds the key.
+fn find_file(files: &[StaticFile], key: u64) -> Option<&StaticFile> {
+    let file = files[..files.partition_point(|file| file.first_key <= key)].last()?;
+    (key < file.next_key()).then_some(file)
+}
+
+/// Returns the paths of the data and the index file.
+fn file_paths(
+    dir: &Path,
+    segment: StaticFileSegment,
+    block_start: BlockNumber,
+) -> (PathBuf, PathBuf) {
+    (
+        dir.join(format!("{segment}_{block_start}.dat")),
+        dir.join(format!("{segment}_{block_start}.idx")),
+    )
+}
+
+/// Parses the segment and the first block from the name of an index file.
+fn parse_index_name(name: &str) -> Option<(StaticFileSegment, BlockNumber)> {
+    let (segment, block_start) = name.strip_suffix(".idx")?.split_once('_')?;
+    let segment =
+        StaticFileSegment::ALL.into_iter().find(|candidate| candidate.as_str() == segment)?;
+    Some((segment, block_start.parse().ok()?))
+}
+
+fn read_u64(file: &File, offset: u64) -> io::Result<u64> {
+    let mut buf = [0; 8];
+    read_exact_at(file, &mut buf, offset)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+#[cfg(unix)]
+fn read_exact_at(file: &File, buf: &mut [u8], offset: u64) -> io::Result<()> {
+    std::os::unix::fs::FileExt::read_exact_at(file, buf, offset)
+}
+
+#[cfg(windows)]
+fn read_exact_at(file: &File, mut buf: &mut [u8], mut offset: u64) -> io::Result<()> {
+    use std::os::windows::fs::FileExt;
+    while !buf.is_empty() {
+        match file.seek_read(buf, offset) {
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => {
+                let rest = buf;
+                buf = &mut rest[n..];
+                offset += n as u64;
+            }
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+fn io_error(err: io::Error) -> Error {
+    Error::StaticFile(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::Header;
+
+    fn header(number: BlockNumber) -> Header {
+        Header { number, gas_used: number * 3, ..Default::default() }
+    }
+
+    #[test]
+    fn append_and_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = StaticFiles::open(dir.path()).unwrap();
+        let segment = StaticFileSegment::Headers;
+        assert_eq!(files.next_key(segment), None);
+
+        let blocks = [0, 1, BLOCKS_PER_STATIC_FILE - 1, BLOCKS_PER_STATIC_FILE];
+        for (key, block) in blocks.into_iter().enumerate() {
+            files.append(segment, block, key as u64, header(block)).unwrap();
+        }
+        assert!(files.append(segment, BLOCKS_PER_STATIC_FILE, 10, header(10)).is_err());
+        files.commit().unwrap();
+        drop(files);
+
+        let files = StaticFiles::open(dir.path()).unwrap();
+        assert_eq!(files.next_key(segment), Some(4));
+        for (key, block) in blocks.into_iter().enumerate() {
+            assert_eq!(files.get::<Header>(segment, key as u64).unwrap(), Some(header(block)));
+        }
+        assert_eq!(files.get::<Header>(segment, 4).unwrap(), None);
+        assert_eq!(files.next_key(StaticFileSegment::Receipts), None);
+
+        files.truncate(segment, 2).unwrap();
+        assert_eq!(files.next_key(segment), Some(2));
+        assert!(!files.contains(segment, 2));
+        assert!(!file_paths(dir.path(), segment, BLOCKS_PER_STATIC_FILE).0.exists());
+        files.append(segment, 5, 2, header(5)).unwrap();
+        assert_eq!(files.get::<Header>(segment, 2).unwrap(), Some(header(5)));
+    }
+
+    #[test]
+    fn drop_incomplete_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = StaticFiles::open(dir.path()).unwrap();
+        let segment = StaticFileSegment::Headers;
+        for key in 10..13 {
+            files.append(segment, key, key, header(key)).unwrap();
+        }
+        files.commit().unwrap();
+        drop(files);
+
+        // the last value was only partially written
+        let (data_path, _) = file_paths(dir.path(), segment, 0);
+        let data = OpenOptions::new().write(true).open(&data_path).unwrap();
+        data.set_len(data.metadata().unwrap().len() - 1).unwrap();
+
+        let files = StaticFiles::open(dir.path()).unwrap();
+        assert_eq!(files.next_key(segment), Some(12));
+        assert!(files.contains(segment, 10));
+        assert!(!files.contains(segment, 9));
+        assert_eq!(files.get::<Header>(segment, 11).unwrap(), Some(header(11)));
+        files.append(segment, 12, 12, header(12)).unwrap();
+        assert_eq!(files.get::<Header>(segment, 12).unwrap(), Some(header(12)));
+    }
+}
@@ -33,7 +33,7 @@ pub enum TableType {
 }
 
 /// Default tables that should be present inside database.
-pub const TABLES: [(TableType, &str); 25] = [
+pub const TABLES: [(TableType, &str); 32] = [
     (TableType::Table, CanonicalHeaders::const_name()),
     (TableType::Table, HeaderTD::const_name()),
     (TableType::Table, HeaderNumbers::const_name()),
@@ -52,13 +52,20 @@ pub const TABLES: [(TableType, &str); 25] = [
     (TableType::Table, TxTransitionIndex::const_name()),
     (TableType::Table, AccountHistory::const_name()),
     (TableType::Table, StorageHistory::const_name()),
+    (TableType::Table, LogAddressIndex::const_name()),
+    (TableType::Table, LogTopicIndex::const_name()),
     (TableType::DupSort, AccountChangeSet::const_name()),
     (TableType::DupSort, StorageChangeSet::const_name()),
     (TableType::Table, HashedAccount::const_name()),
     (TableType::DupSort, HashedStorage::const_name()),
+    (TableType::Table, AccountsTrie::const_name()),
+    (TableType::Table, StoragesTrie::const_name()),
     (TableType::Table, TxSenders::const_name()),
     (TableType::Table, Config::const_name()),
     (TableType::Table, SyncStage::const_name()),
+    (TableType::Table, ForkchoiceMarkers::const_name()),
+    (TableType::Table, PruneCheckpoints::const_name()),
+    (TableType::Table, StaticFileCheckpoints::const_name()),
 ];
 
 #[macro_export]
@@ -241,6 +248,21 @@ table!(
     ( StorageHistory ) StorageShardedKey | TransitionList
 );
 
+table!(
+    /// Stores pointers to the blocks that contain logs emitted by an address.
+    ///
+    /// Sharded the same way as [`AccountHistory`], with the last shard of an address keyed by
+    /// `u64::MAX`, but the shards hold block numbers instead of transition ids.
+    ( LogAddressIndex ) ShardedKey<Address> | BlockNumberList
+);
+
+table!(
+    /// Stores pointers to the blocks that contain logs with a topic, at any topic position.
+    ///
+    /// Sharded the same way as [`LogAddressIndex`].
+    ( LogTopicIndex ) ShardedKey<H256> | BlockNumberList
+);
+
 dupsort!(
     /// Stores the state of an account before a certain transaction changed it.
     /// Change on state can be: account is created, selfdestructed, touched while empty
@@ -271,6 +293,22 @@ dupsort!(
     ( HashedStorage ) H256 | [H256] StorageEntry
 );
 
+table!(
+    /// Stores the RLP encoded nodes of the state trie, indexed by their nibble path from the root.
+    /// Only nodes whose encoding is at least 32 bytes long (and the root, under the empty path)
+    /// are stored, smaller nodes are inlined into their parent.
+    /// Only the trie of the latest computed state root is kept, nodes are overwritten or deleted
+    /// when the trie is updated.
+    ( AccountsTrie ) TrieNodePath | TrieNode
+);
+
+table!(
+    /// Stores the RLP encoded nodes of all account storage tries, indexed by the hashed address of
+    /// the account followed by the nibble path of the node.
+    /// Follows the same layout as [`AccountsTrie`].
+    ( StoragesTrie ) TrieNodePath | TrieNode
+);
+
 table!(
     /// Stores the transaction sender for each transaction.
     /// It is needed to speed up execution stage and allows fetching signer without doing
@@ -288,13 +326,41 @@ table!(
     ( SyncStage ) StageId | BlockNumber
 );
 
+table!(
+    /// Stores the hashes of the latest safe and finalized blocks announced by the consensus layer.
+    ( ForkchoiceMarkers ) ForkchoiceMarkerKey | BlockHash
+);
+
+table!(
+    /// Stores the highest pruned block number of each prune segment.
+    ( PruneCheckpoints ) PruneSegmentKey | BlockNumber
+);
+
+table!(
+    /// Stores the highest block number of each static file segment whose data was moved out of
+    /// the database.
+    ( StaticFileCheckpoints ) StaticFileSegmentKey | BlockNumber
+);
+
 ///
 /// Alias Types
 
 /// List with transaction numbers.
 pub type TransitionList = IntegerList;
+/// List with block numbers.
+pub type BlockNumberList = IntegerList;
 /// Encoded stage id.
 pub type StageId = Vec<u8>;
+/// Encoded forkchoice marker name.
+pub type ForkchoiceMarkerKey = Vec<u8>;
+/// Encoded prune segment name.
+pub type PruneSegmentKey = Vec<u8>;
+/// Encoded static file segment name.
+pub type StaticFileSegmentKey = Vec<u8>;
+/// Nibble path of a Merkle Patricia Trie node, one nibble per byte.
+pub type TrieNodePath = Vec<u8>;
+/// RLP encoded Merkle Patricia Trie node.
+pub type TrieNode = Vec<u8>;
 
 //
 // TODO: Temporary types, until they're properly defined alongside with the Encode and Decode Trait
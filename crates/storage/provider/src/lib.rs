@@ -11,8 +11,9 @@
 /// Various provider traits.
 mod traits;
 pub use traits::{
-    AccountProvider, BlockHashProvider, BlockProvider, HeaderProvider, StateProvider,
-    StateProviderFactory,
+    AccountProvider, BlockHashProvider, BlockProvider, HeaderProvider, LogIndexProvider,
+    ReceiptProvider, StateProvider, StateProviderBox, StateProviderFactory, TransactionMeta,
+    TransactionsProvider,
 };
 
 /// Provider trait implementations.
@@ -22,9 +23,19 @@ pub use providers::{
     LatestStateProviderRef, ShareableDatabase,
 };
 
+/// Stage identifiers and their progress.
+mod stage;
+pub use stage::{StageId, FINISH, MERKLE_EXECUTION};
+
+/// Merkle Patricia Trie backed by the database.
+pub mod trie;
+
 /// Common database utilities.
 mod utils;
-pub use utils::{insert_block, insert_canonical_block};
+pub use utils::{
+    insert_block, insert_canonical_block, prune_checkpoint, save_prune_checkpoint,
+    save_static_file_checkpoint, static_file_checkpoint, ForkchoiceMarker,
+};
 
 #[cfg(any(test, feature = "test-utils"))]
 /// Common test helpers for mocking the Provider.
@@ -1,4 +1,8 @@
-use crate::{AccountProvider, BlockHashProvider, Error, StateProvider};
+use crate::{
+    prune_checkpoint,
+    trie::{merge_changes, AccountChanges, DBTrieLoader},
+    AccountProvider, BlockHashProvider, Error, StateProvider,
+};
 use reth_db::{
     cursor::{DbCursorRO, DbDupCursorRO},
     models::{storage_sharded_key::StorageShardedKey, ShardedKey},
@@ -7,9 +11,9 @@ use reth_db::{
 };
 use reth_interfaces::Result;
 use reth_primitives::{
-    Account, Address, Bytes, StorageKey, StorageValue, TransitionId, H256, U256,
+    Account, Address, Bytes, PruneSegment, StorageKey, StorageValue, TransitionId, H256, U256,
 };
-use std::marker::PhantomData;
+use std::{collections::BTreeMap, marker::PhantomData};
 
 /// State provider for a given transition id which takes a tx reference.
 ///
@@ -19,6 +23,8 @@ use std::marker::PhantomData;
 /// [tables::StorageHistory]
 /// [tables::AccountChangeSet]
 /// [tables::StorageChangeSet]
+///
+/// Reading history that was pruned returns [Error::Pruned].
 pub struct HistoricalStateProviderRef<'a, 'b, TX: DbTx<'a>> {
     /// Transaction
     tx: &'b TX,
@@ -33,10 +39,29 @@ impl<'a, 'b, TX: DbTx<'a>> HistoricalStateProviderRef<'a, 'b, TX> {
     pub fn new(tx: &'b TX, transition: TransitionId) -> Self {
         Self { tx, transition, _phantom: PhantomData {} }
     }
+
+    /// Returns an error if the changesets of the segment that are needed to read the state at the
+    /// transition of the provider were pruned.
+    ///
+    /// The changesets of all transitions before the end of the highest pruned block are removed.
+    fn ensure_not_pruned(&self, segment: PruneSegment) -> Result<()> {
+        let Some(pruned_to) = prune_checkpoint(self.tx, segment)? else { return Ok(()) };
+        let first_transition = self
+            .tx
+            .get::<tables::BlockTransitionIndex>(pruned_to)?
+            .ok_or(Error::BlockTransition { block_number: pruned_to })?;
+        if self.transition < first_transition {
+            return Err(Error::Pruned { segment, pruned_to }.into())
+        }
+        Ok(())
+    }
 }
+
 impl<'a, 'b, TX: DbTx<'a>> AccountProvider for HistoricalStateProviderRef<'a, 'b, TX> {
     /// Get basic account information.
     fn basic_account(&self, address: Address) -> Result<Option<Account>> {
+        self.ensure_not_pruned(PruneSegment::AccountHistory)?;
+
         // history key to search IntegerList of transition id changesets.
         let history_key = ShardedKey::new(address, self.transition);
 
@@ -76,6 +101,8 @@ impl<'a, 'b, TX: DbTx<'a>> BlockHashProvider for HistoricalStateProviderRef<'a,
 impl<'a, 'b, TX: DbTx<'a>> StateProvider for HistoricalStateProviderRef<'a, 'b, TX> {
     /// Get storage.
     fn storage(&self, address: Address, storage_key: StorageKey) -> Result<Option<StorageValue>> {
+        self.ensure_not_pruned(PruneSegment::StorageHistory)?;
+
         // history key to search IntegerList of transition id changesets.
         let history_key = StorageShardedKey::new(address, storage_key, self.transition);
 
@@ -113,6 +140,25 @@ impl<'a, 'b, TX: DbTx<'a>> StateProvider for HistoricalStateProviderRef<'a, 'b,
     fn bytecode_by_hash(&self, code_hash: H256) -> Result<Option<Bytes>> {
         self.tx.get::<tables::Bytecodes>(code_hash).map_err(Into::into).map(|r| r.map(Bytes::from))
     }
+
+    /// Get account and storage proofs against the state at the transition.
+    fn proof(
+        &self,
+        address: Address,
+        keys: &[H256],
+    ) -> Result<(Vec<Bytes>, H256, Vec<Vec<Bytes>>)> {
+        let loader = DBTrieLoader::default();
+        let state_changes = loader.state_changes(self.tx, self, Some(self.transition))?;
+        loader.generate_proof(self.tx, address, keys, &state_changes)
+    }
+
+    /// Apply the changes on top of the state at the transition.
+    fn state_root_with_changes(&self, changes: &BTreeMap<Address, AccountChanges>) -> Result<H256> {
+        let loader = DBTrieLoader::default();
+        let mut state_changes = loader.state_changes(self.tx, self, Some(self.transition))?;
+        merge_changes(&mut state_changes, changes);
+        loader.state_root_with_changes(self.tx, &state_changes)
+    }
 }
 
 /// State provider for a given transition
@@ -151,7 +197,9 @@ derive_from_ref!(BlockHashProvider, fn block_hash(&self, number: U256) -> Result
 derive_from_ref!(
     StateProvider,
     fn storage(&self, account: Address, storage_key: StorageKey) -> Result<Option<StorageValue>>,
-    fn bytecode_by_hash(&self, code_hash: H256) -> Result<Option<Bytes>>
+    fn bytecode_by_hash(&self, code_hash: H256) -> Result<Option<Bytes>>,
+    fn proof(&self, address: Address, keys: &[H256]) -> Result<(Vec<Bytes>, H256, Vec<Vec<Bytes>>)>,
+    fn state_root_with_changes(&self, changes: &BTreeMap<Address, AccountChanges>) -> Result<H256>
 );
 
 #[cfg(test)]
@@ -164,9 +212,13 @@ mod tests {
         transaction::{DbTx, DbTxMut},
         TransitionList,
     };
-    use reth_primitives::{hex_literal::hex, Account, StorageEntry, H160, H256, U256};
+    use reth_primitives::{
+        hex_literal::hex, Account, PruneSegment, StorageEntry, H160, H256, U256,
+    };
 
-    use crate::{AccountProvider, HistoricalStateProviderRef, StateProvider};
+    use crate::{
+        save_prune_checkpoint, AccountProvider, Error, HistoricalStateProviderRef, StateProvider,
+    };
 
     const ADDRESS: H160 = H160(hex!("0000000000000000000000000000000000000001"));
     const STORAGE: H256 =
@@ -331,4 +383,23 @@ mod tests {
             Ok(Some(entry_plain.value))
         );
     }
+
+    #[test]
+    fn history_provider_pruned() {
+        let db = create_test_rw_db();
+        let tx = db.tx_mut().unwrap();
+
+        tx.put::<tables::BlockTransitionIndex>(1, 5).unwrap();
+        save_prune_checkpoint(&tx, PruneSegment::AccountHistory, 1).unwrap();
+        tx.commit().unwrap();
+
+        let tx = db.tx().unwrap();
+        assert_eq!(
+            HistoricalStateProviderRef::new(&tx, 4).basic_account(ADDRESS),
+            Err(Error::Pruned { segment: PruneSegment::AccountHistory, pruned_to: 1 }.into())
+        );
+        assert_eq!(HistoricalStateProviderRef::new(&tx, 5).basic_account(ADDRESS), Ok(None));
+        // the storage history is not pruned
+        assert_eq!(HistoricalStateProviderRef::new(&tx, 4).storage(ADDRESS, STORAGE), Ok(None));
+    }
 }
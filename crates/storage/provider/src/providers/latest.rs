@@ -1,8 +1,11 @@
-use crate::{AccountProvider, BlockHashProvider, StateProvider};
+use crate::{
+    trie::{merge_changes, AccountChanges, DBTrieLoader},
+    AccountProvider, BlockHashProvider, StateProvider,
+};
 use reth_db::{cursor::DbDupCursorRO, tables, transaction::DbTx};
 use reth_interfaces::Result;
 use reth_primitives::{Account, Address, Bytes, StorageKey, StorageValue, H256, U256};
-use std::marker::PhantomData;
+use std::{collections::BTreeMap, marker::PhantomData};
 
 /// State provider over latest state that takes tx reference.
 pub struct LatestStateProviderRef<'a, 'b, TX: DbTx<'a>> {
@@ -49,6 +52,25 @@ impl<'a, 'b, TX: DbTx<'a>> StateProvider for LatestStateProviderRef<'a, 'b, TX>
     fn bytecode_by_hash(&self, code_hash: H256) -> Result<Option<Bytes>> {
         self.db.get::<tables::Bytecodes>(code_hash).map_err(Into::into).map(|r| r.map(Bytes::from))
     }
+
+    /// Get account and storage proofs against the latest state.
+    fn proof(
+        &self,
+        address: Address,
+        keys: &[H256],
+    ) -> Result<(Vec<Bytes>, H256, Vec<Vec<Bytes>>)> {
+        let loader = DBTrieLoader::default();
+        let state_changes = loader.state_changes(self.db, self, None)?;
+        loader.generate_proof(self.db, address, keys, &state_changes)
+    }
+
+    /// Apply the changes on top of the latest state.
+    fn state_root_with_changes(&self, changes: &BTreeMap<Address, AccountChanges>) -> Result<H256> {
+        let loader = DBTrieLoader::default();
+        let mut state_changes = loader.state_changes(self.db, self, None)?;
+        merge_changes(&mut state_changes, changes);
+        loader.state_root_with_changes(self.db, &state_changes)
+    }
 }
 
 /// State provider for the latest state.
@@ -85,5 +107,7 @@ derive_from_ref!(BlockHashProvider, fn block_hash(&self, number: U256) -> Result
 derive_from_ref!(
     StateProvider,
     fn storage(&self, account: Address, storage_key: StorageKey) -> Result<Option<StorageValue>>,
-    fn bytecode_by_hash(&self, code_hash: H256) -> Result<Option<Bytes>>
+    fn bytecode_by_hash(&self, code_hash: H256) -> Result<Option<Bytes>>,
+    fn proof(&self, address: Address, keys: &[H256]) -> Result<(Vec<Bytes>, H256, Vec<Vec<Bytes>>)>,
+    fn state_root_with_changes(&self, changes: &BTreeMap<Address, AccountChanges>) -> Result<H256>
 );
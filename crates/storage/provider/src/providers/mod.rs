@@ -1,12 +1,28 @@
-use crate::{BlockHashProvider, BlockProvider, Error, HeaderProvider, StateProviderFactory};
+use crate::{
+    prune_checkpoint, BlockHashProvider, BlockProvider, Error, ForkchoiceMarker, HeaderProvider,
+    LogIndexProvider, ReceiptProvider, StateProviderFactory, TransactionMeta, TransactionsProvider,
+    FINISH,
+};
 use reth_db::{
+    cursor::DbCursorRO,
     database::{Database, DatabaseGAT},
+    models::{ShardedKey, StoredBlockBody},
+    static_file::{StaticFileSegment, StaticFiles},
+    table::Table,
     tables,
     transaction::DbTx,
+    BlockNumberList,
 };
 use reth_interfaces::Result;
-use reth_primitives::{rpc::BlockId, Block, BlockHash, BlockNumber, ChainInfo, Header, H256, U256};
-use std::sync::Arc;
+use reth_primitives::{
+    rpc::{self, BlockId},
+    Address, Block, BlockHash, BlockNumber, BlockWithSenders, ChainInfo, Header, PruneSegment,
+    Receipt, TransactionSigned, TxHash, TxNumber, H256, U256,
+};
+use std::{
+    ops::{Range, RangeInclusive},
+    sync::Arc,
+};
 
 mod historical;
 pub use historical::{HistoricalStateProvider, HistoricalStateProviderRef};
@@ -17,21 +33,38 @@ pub use latest::{LatestStateProvider, LatestStateProviderRef};
 /// A common provider that fetches data from a database.
 ///
 /// This provider implements most provider or provider factory traits.
+///
+/// Finalized headers, transactions and receipts can be moved out of the database into
+/// [StaticFiles]. Values whose key is held by the static files are read from them, all other
+/// values are read from the database.
 pub struct ShareableDatabase<DB: Database> {
     /// Database
     db: Arc<DB>,
+    /// The static files that hold the data that was moved out of the database.
+    static_files: Option<Arc<StaticFiles>>,
 }
 
 impl<DB: Database> ShareableDatabase<DB> {
     /// create new database provider
     pub fn new(db: Arc<DB>) -> Self {
-        Self { db }
+        Self { db, static_files: None }
+    }
+
+    /// Read the data that was moved out of the database from the static files.
+    pub fn with_static_files(mut self, static_files: Arc<StaticFiles>) -> Self {
+        self.static_files = Some(static_files);
+        self
     }
 }
 
 impl<DB: Database> HeaderProvider for ShareableDatabase<DB> {
     fn header(&self, block_hash: &BlockHash) -> Result<Option<Header>> {
-        self.db.view(|tx| tx.get::<tables::Headers>((0, *block_hash).into()))?.map_err(Into::into)
+        self.db.view(|tx| -> Result<_> {
+            let Some(number) = tx.get::<tables::HeaderNumbers>(*block_hash)? else {
+                return Ok(None)
+            };
+            read_header(tx, self.static_files.as_deref(), number, *block_hash)
+        })?
     }
 
     fn header_by_number(&self, num: BlockNumber) -> Result<Option<Header>> {
@@ -63,17 +96,74 @@ impl<DB: Database> BlockHashProvider for ShareableDatabase<DB> {
 
 impl<DB: Database> BlockProvider for ShareableDatabase<DB> {
     fn chain_info(&self) -> Result<ChainInfo> {
-        Ok(ChainInfo {
-            best_hash: Default::default(),
-            best_number: 0,
-            last_finalized: None,
-            safe_finalized: None,
-        })
+        self.db.view(|tx| -> Result<_> {
+            let best_number = FINISH.get_progress(tx)?.unwrap_or_default();
+            let best_hash = tx.get::<tables::CanonicalHeaders>(best_number)?.unwrap_or_default();
+            // Markers ahead of the chain tip point to blocks that were not processed yet.
+            let last_finalized = ForkchoiceMarker::Finalized
+                .block_number(tx)?
+                .filter(|number| *number <= best_number);
+            let safe_finalized =
+                ForkchoiceMarker::Safe.block_number(tx)?.filter(|number| *number <= best_number);
+            Ok(ChainInfo { best_hash, best_number, last_finalized, safe_finalized })
+        })?
     }
 
-    fn block(&self, _id: BlockId) -> Result<Option<Block>> {
-        // TODO
-        Ok(None)
+    fn block(&self, id: BlockId) -> Result<Option<Block>> {
+        let Some((number, hash)) = self.block_num_hash(id)? else { return Ok(None) };
+        self.db.view(|tx| -> Result<_> {
+            let block = read_block(tx, self.static_files.as_deref(), number, hash)?;
+            Ok(block.map(|(block, _)| block))
+        })?
+    }
+
+    fn block_with_senders(&self, id: BlockId) -> Result<Option<BlockWithSenders>> {
+        let Some((number, hash)) = self.block_num_hash(id)? else { return Ok(None) };
+        self.db.view(|tx| -> Result<_> {
+            let Some((block, body)) = read_block(tx, self.static_files.as_deref(), number, hash)?
+            else {
+                return Ok(None)
+            };
+            let senders = read_senders(tx, &body, &block.body)?;
+            Ok(Some(BlockWithSenders { block, senders }))
+        })?
+    }
+
+    fn blocks_range(&self, range: RangeInclusive<BlockNumber>) -> Result<Vec<Block>> {
+        let range = self.clamp_to_tip(range)?;
+        self.db.view(|tx| -> Result<_> {
+            let mut blocks = Vec::new();
+            for number in range {
+                let Some(hash) = tx.get::<tables::CanonicalHeaders>(number)? else { break };
+                let Some((block, _)) = read_block(tx, self.static_files.as_deref(), number, hash)?
+                else {
+                    break
+                };
+                blocks.push(block);
+            }
+            Ok(blocks)
+        })?
+    }
+
+    fn blocks_range_with_senders(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockWithSenders>> {
+        let range = self.clamp_to_tip(range)?;
+        self.db.view(|tx| -> Result<_> {
+            let mut blocks = Vec::new();
+            for number in range {
+                let Some(hash) = tx.get::<tables::CanonicalHeaders>(number)? else { break };
+                let Some((block, body)) =
+                    read_block(tx, self.static_files.as_deref(), number, hash)?
+                else {
+                    break
+                };
+                let senders = read_senders(tx, &body, &block.body)?;
+                blocks.push(BlockWithSenders { block, senders });
+            }
+            Ok(blocks)
+        })?
     }
 
     fn block_number(&self, hash: H256) -> Result<Option<BlockNumber>> {
@@ -81,6 +171,381 @@ impl<DB: Database> BlockProvider for ShareableDatabase<DB> {
     }
 }
 
+impl<DB: Database> ShareableDatabase<DB> {
+    /// Limits the block range to the chain tip, since canonical blocks above it are still being
+    /// synced.
+    fn clamp_to_tip(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<RangeInclusive<BlockNumber>> {
+        let best_number = self.chain_info()?.best_number;
+        Ok(*range.start()..=(*range.end()).min(best_number))
+    }
+
+    /// Returns the number and the hash of the block with the given id.
+    ///
+    /// Blocks are looked up by hash among all known blocks, and by number among the canonical
+    /// blocks up to the chain tip. There is no pending block in the database, so the pending block
+    /// is the latest block.
+    fn block_num_hash(&self, id: BlockId) -> Result<Option<(BlockNumber, BlockHash)>> {
+        match id {
+            BlockId::Hash(hash) => {
+                let hash = H256(hash.0);
+                Ok(self.block_number(hash)?.map(|number| (number, hash)))
+            }
+            BlockId::Number(number) => {
+                let number = match number {
+                    rpc::BlockNumber::Pending => rpc::BlockNumber::Latest,
+                    number => number,
+                };
+                let Some(number) = self.convert_block_number(number)? else { return Ok(None) };
+                // Canonical headers above the tip belong to blocks that are still being synced.
+                if number > self.chain_info()?.best_number {
+                    return Ok(None)
+                }
+                Ok(self.block_hash(U256::from(number))?.map(|hash| (number, hash)))
+            }
+        }
+    }
+
+    /// Returns the body of the canonical block with the given id.
+    ///
+    /// Returns `None` if the block is unknown or not canonical.
+    fn canonical_block_body(&self, id: BlockId) -> Result<Option<StoredBlockBody>> {
+        let Some(number) = self.block_number_for_id(id)? else { return Ok(None) };
+        self.db.view(|tx| -> Result<_> {
+            let Some(hash) = tx.get::<tables::CanonicalHeaders>(number)? else { return Ok(None) };
+            if matches!(id, BlockId::Hash(block_hash) if H256(block_hash.0) != hash) {
+                return Ok(None)
+            }
+            Ok(tx.get::<tables::BlockBodies>((number, hash).into())?)
+        })?
+    }
+}
+
+/// Reads a header, from the static files if it is the canonical header of a block that was moved
+/// there.
+///
+/// Only canonical headers are moved, the headers of sidechains stay in the database.
+pub(crate) fn read_header<'a, TX: DbTx<'a>>(
+    tx: &TX,
+    static_files: Option<&StaticFiles>,
+    number: BlockNumber,
+    hash: BlockHash,
+) -> Result<Option<Header>> {
+    if let Some(static_files) = static_files
+        .filter(|static_files| static_files.contains(StaticFileSegment::Headers, number))
+    {
+        if tx.get::<tables::CanonicalHeaders>(number)? == Some(hash) {
+            return Ok(static_files.get(StaticFileSegment::Headers, number)?)
+        }
+    }
+    Ok(tx.get::<tables::Headers>((number, hash).into())?)
+}
+
+/// Reads the block with the given number and hash, together with its stored body.
+///
+/// Returns `None` if the header or the body of the block is missing.
+fn read_block<'a, TX: DbTx<'a>>(
+    tx: &TX,
+    static_files: Option<&StaticFiles>,
+    number: BlockNumber,
+    hash: BlockHash,
+) -> Result<Option<(Block, StoredBlockBody)>> {
+    let Some(header) = read_header(tx, static_files, number, hash)? else { return Ok(None) };
+    let Some(body) = tx.get::<tables::BlockBodies>((number, hash).into())? else { return Ok(None) };
+    let ommers = tx
+        .get::<tables::BlockOmmers>((number, hash).into())?
+        .map(|stored| stored.ommers)
+        .unwrap_or_default();
+
+    let transactions = read_tx_range::<_, tables::Transactions>(
+        tx,
+        static_files,
+        StaticFileSegment::Transactions,
+        body.tx_id_range(),
+    )?;
+    if transactions.len() as u64 != body.tx_count {
+        let id = body.start_tx_id + transactions.len() as u64;
+        return Err(Error::Transaction { id }.into())
+    }
+
+    Ok(Some((Block { header, body: transactions, ommers }, body)))
+}
+
+/// Reads the senders of the transactions of a block from [tables::TxSenders].
+///
+/// Senders that were not recovered by the sender recovery stage yet are recovered from the
+/// transaction signatures.
+fn read_senders<'a, TX: DbTx<'a>>(
+    tx: &TX,
+    body: &StoredBlockBody,
+    transactions: &[TransactionSigned],
+) -> Result<Vec<Address>> {
+    let mut senders = tx
+        .cursor_read::<tables::TxSenders>()?
+        .walk_range(body.tx_id_range())?
+        .collect::<std::result::Result<Vec<_>, _>>()?
+        .into_iter()
+        .peekable();
+
+    body.tx_id_range()
+        .zip(transactions)
+        .map(|(id, transaction)| match senders.next_if(|(key, _)| *key == id) {
+            Some((_, sender)) => Ok(sender),
+            None => transaction.recover_signer().ok_or(Error::TransactionSender { id }.into()),
+        })
+        .collect()
+}
+
+/// Reads the value of a transaction, from the static files if it was moved there.
+fn read_tx_value<'a, TX: DbTx<'a>, T: Table<Key = TxNumber>>(
+    tx: &TX,
+    static_files: Option<&StaticFiles>,
+    segment: StaticFileSegment,
+    id: TxNumber,
+) -> Result<Option<T::Value>> {
+    match static_files.filter(|static_files| static_files.contains(segment, id)) {
+        Some(static_files) => Ok(static_files.get(segment, id)?),
+        None => Ok(tx.get::<T>(id)?),
+    }
+}
+
+/// Reads the values of a range of transactions.
+///
+/// The static files hold the lower transactions, so the range is read from them until the first
+/// transaction that is not held by them, and the rest of the range is read from the database.
+fn read_tx_range<'a, TX: DbTx<'a>, T: Table<Key = TxNumber>>(
+    tx: &TX,
+    static_files: Option<&StaticFiles>,
+    segment: StaticFileSegment,
+    range: Range<TxNumber>,
+) -> Result<Vec<T::Value>> {
+    let mut values = Vec::with_capacity((range.end - range.start) as usize);
+    let mut next = range.start;
+    if let Some(static_files) = static_files {
+        while next < range.end {
+            let Some(value) = static_files.get(segment, next)? else { break };
+            values.push(value);
+            next += 1;
+        }
+    }
+
+    let mut cursor = tx.cursor_read::<T>()?;
+    for entry in cursor.walk_range(next..range.end)? {
+        values.push(entry?.1);
+    }
+    Ok(values)
+}
+
+/// Returns an error if the receipt of the transaction with the given id was pruned.
+fn ensure_receipt_not_pruned<'a, TX: DbTx<'a>>(tx: &TX, id: TxNumber) -> Result<()> {
+    let segment = PruneSegment::Receipts;
+    let Some(pruned_to) = prune_checkpoint(tx, segment)? else { return Ok(()) };
+    let hash = tx
+        .get::<tables::CanonicalHeaders>(pruned_to)?
+        .ok_or(Error::BlockNumber { block_number: pruned_to })?;
+    let body = tx
+        .get::<tables::BlockBodies>((pruned_to, hash).into())?
+        .ok_or(Error::BlockBody { block_number: pruned_to, block_hash: hash })?;
+    if id < body.start_tx_id + body.tx_count {
+        return Err(Error::Pruned { segment, pruned_to }.into())
+    }
+    Ok(())
+}
+
+/// Finds the canonical block that contains the transaction with the given id.
+///
+/// Transaction ids only increase with the block number, so the block is found by a binary search
+/// over the canonical block bodies.
+fn canonical_block_by_tx_id<'a, TX: DbTx<'a>>(
+    tx: &TX,
+    id: TxNumber,
+) -> Result<Option<(BlockNumber, BlockHash, StoredBlockBody)>> {
+    let body = |number: BlockNumber| -> Result<Option<(BlockHash, StoredBlockBody)>> {
+        let Some(hash) = tx.get::<tables::CanonicalHeaders>(number)? else { return Ok(None) };
+        Ok(tx.get::<tables::BlockBodies>((number, hash).into())?.map(|body| (hash, body)))
+    };
+
+    let Some((mut high, _)) = tx.cursor_read::<tables::CanonicalHeaders>()?.last()? else {
+        return Ok(None)
+    };
+    let mut low = 0;
+    while low < high {
+        let mid = low + (high - low) / 2;
+        match body(mid)? {
+            Some((_, body)) if body.start_tx_id + body.tx_count <= id => low = mid + 1,
+            // Bodies are missing above the highest downloaded block.
+            _ => high = mid,
+        }
+    }
+
+    Ok(body(low)?
+        .filter(|(_, body)| body.tx_id_range().contains(&id))
+        .map(|(hash, body)| (low, hash, body)))
+}
+
+impl<DB: Database> TransactionsProvider for ShareableDatabase<DB> {
+    fn transaction_id(&self, tx_hash: TxHash) -> Result<Option<TxNumber>> {
+        self.db.view(|tx| tx.get::<tables::TxHashNumber>(tx_hash))?.map_err(Into::into)
+    }
+
+    fn transaction_by_id(&self, id: TxNumber) -> Result<Option<TransactionSigned>> {
+        self.db.view(|tx| {
+            read_tx_value::<_, tables::Transactions>(
+                tx,
+                self.static_files.as_deref(),
+                StaticFileSegment::Transactions,
+                id,
+            )
+        })?
+    }
+
+    fn transaction_by_hash(&self, hash: TxHash) -> Result<Option<TransactionSigned>> {
+        match self.transaction_id(hash)? {
+            Some(id) => self.transaction_by_id(id),
+            None => Ok(None),
+        }
+    }
+
+    fn transaction_by_hash_with_meta(
+        &self,
+        hash: TxHash,
+    ) -> Result<Option<(TransactionSigned, TransactionMeta)>> {
+        let static_files = self.static_files.as_deref();
+        self.db.view(|tx| -> Result<_> {
+            let Some(tx_id) = tx.get::<tables::TxHashNumber>(hash)? else { return Ok(None) };
+            let transaction = read_tx_value::<_, tables::Transactions>(
+                tx,
+                static_files,
+                StaticFileSegment::Transactions,
+                tx_id,
+            )?;
+            let Some(transaction) = transaction else { return Ok(None) };
+            let block = canonical_block_by_tx_id(tx, tx_id)?;
+            let Some((block_number, block_hash, body)) = block else { return Ok(None) };
+            let header = read_header(tx, static_files, block_number, block_hash)?
+                .ok_or(Error::BlockHash { block_hash })?;
+            let sender = match tx.get::<tables::TxSenders>(tx_id)? {
+                Some(sender) => sender,
+                None => transaction
+                    .recover_signer()
+                    .ok_or(Error::TransactionSender { id: tx_id })?,
+            };
+
+            let meta = TransactionMeta {
+                tx_id,
+                sender,
+                index: tx_id - body.start_tx_id,
+                block_hash,
+                block_number,
+                base_fee: header.base_fee_per_gas,
+            };
+            Ok(Some((transaction, meta)))
+        })?
+    }
+
+    fn transactions_by_block(&self, block: BlockId) -> Result<Option<Vec<TransactionSigned>>> {
+        let Some(body) = self.canonical_block_body(block)? else { return Ok(None) };
+        self.db.view(|tx| -> Result<_> {
+            let transactions = read_tx_range::<_, tables::Transactions>(
+                tx,
+                self.static_files.as_deref(),
+                StaticFileSegment::Transactions,
+                body.tx_id_range(),
+            )?;
+            Ok(Some(transactions))
+        })?
+    }
+}
+
+impl<DB: Database> ReceiptProvider for ShareableDatabase<DB> {
+    fn receipt(&self, id: TxNumber) -> Result<Option<Receipt>> {
+        self.db.view(|tx| -> Result<_> {
+            ensure_receipt_not_pruned(tx, id)?;
+            read_tx_value::<_, tables::Receipts>(
+                tx,
+                self.static_files.as_deref(),
+                StaticFileSegment::Receipts,
+                id,
+            )
+        })?
+    }
+
+    fn receipt_by_hash(&self, hash: TxHash) -> Result<Option<Receipt>> {
+        match self.transaction_id(hash)? {
+            Some(id) => self.receipt(id),
+            None => Ok(None),
+        }
+    }
+
+    fn receipts_by_block(&self, block: BlockId) -> Result<Option<Vec<Receipt>>> {
+        let Some(body) = self.canonical_block_body(block)? else { return Ok(None) };
+        self.db.view(|tx| -> Result<_> {
+            ensure_receipt_not_pruned(tx, body.start_tx_id)?;
+            let receipts = read_tx_range::<_, tables::Receipts>(
+                tx,
+                self.static_files.as_deref(),
+                StaticFileSegment::Receipts,
+                body.tx_id_range(),
+            )?;
+            Ok(Some(receipts))
+        })?
+    }
+}
+
+impl<DB: Database> LogIndexProvider for ShareableDatabase<DB> {
+    fn blocks_with_log_address(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>> {
+        self.db.view(|tx| indexed_blocks::<_, tables::LogAddressIndex, _>(tx, address, range))?
+    }
+
+    fn blocks_with_log_topic(
+        &self,
+        topic: H256,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>> {
+        self.db.view(|tx| indexed_blocks::<_, tables::LogTopicIndex, _>(tx, topic, range))?
+    }
+}
+
+/// Collects the block numbers in the range from the shards of the key.
+///
+/// Shards are keyed by their highest block number, so the walk starts at the first shard that can
+/// contain the start of the range.
+fn indexed_blocks<'a, TX, T, K>(
+    tx: &TX,
+    key: K,
+    range: RangeInclusive<BlockNumber>,
+) -> Result<Vec<BlockNumber>>
+where
+    TX: DbTx<'a>,
+    T: Table<Key = ShardedKey<K>, Value = BlockNumberList>,
+    K: Clone + PartialEq,
+{
+    let mut cursor = tx.cursor_read::<T>()?;
+    let mut blocks = Vec::new();
+    for entry in cursor.walk(ShardedKey::new(key.clone(), *range.start()))? {
+        let (sharded_key, list) = entry?;
+        if sharded_key.key != key {
+            break
+        }
+        blocks.extend(
+            list.iter(0)
+                .map(|block| block as BlockNumber)
+                .skip_while(|block| block < range.start())
+                .take_while(|block| block <= range.end()),
+        );
+        if sharded_key.highest_transition_id >= *range.end() {
+            break
+        }
+    }
+    Ok(blocks)
+}
+
 impl<DB: Database> StateProviderFactory for ShareableDatabase<DB> {
     type HistorySP<'a> = HistoricalStateProvider<'a,<DB as DatabaseGAT<'a>>::TX> where Self: 'a;
     type LatestSP<'a> = LatestStateProvider<'a,<DB as DatabaseGAT<'a>>::TX> where Self: 'a;
@@ -130,10 +595,27 @@ impl<DB: Database> StateProviderFactory for ShareableDatabase<DB> {
 
 #[cfg(test)]
 mod tests {
-    use crate::StateProviderFactory;
+    use crate::{
+        insert_canonical_block, BlockProvider, ForkchoiceMarker, HeaderProvider, LogIndexProvider,
+        ReceiptProvider, StateProviderFactory, TransactionsProvider, FINISH,
+    };
 
-    use super::ShareableDatabase;
-    use reth_db::mdbx::{test_utils::create_test_db, EnvKind, WriteMap};
+    use super::{canonical_block_by_tx_id, ShareableDatabase};
+    use reth_db::{
+        database::Database,
+        mdbx::{test_utils::create_test_db, EnvKind, WriteMap},
+        models::{ShardedKey, StoredBlockBody},
+        static_file::{StaticFileSegment, StaticFiles},
+        tables,
+        transaction::DbTxMut,
+        BlockNumberList,
+    };
+    use reth_interfaces::test_utils::generators::random_block;
+    use reth_primitives::{
+        rpc::{BlockId, BlockNumber},
+        Address, Header, Receipt, TransactionSigned, H256,
+    };
+    use std::sync::Arc;
 
     #[test]
     fn common_history_provider() {
@@ -141,4 +623,241 @@ mod tests {
         let provider = ShareableDatabase::new(db);
         let _ = provider.latest();
     }
+
+    #[test]
+    fn blocks_with_log_address() {
+        let db = create_test_db::<WriteMap>(EnvKind::RW);
+        let address = Address::from_low_u64_be(1);
+        let other = Address::from_low_u64_be(2);
+        db.update(|tx| {
+            let shards = [
+                (address, 5, vec![1, 3, 5]),
+                (address, u64::MAX, vec![8, 13]),
+                (other, u64::MAX, vec![2, 4]),
+            ];
+            for (key, highest, blocks) in shards {
+                tx.put::<tables::LogAddressIndex>(
+                    ShardedKey::new(key, highest),
+                    BlockNumberList::new(blocks).unwrap(),
+                )
+                .unwrap();
+            }
+        })
+        .unwrap();
+
+        let provider = ShareableDatabase::new(db);
+        assert_eq!(
+            provider.blocks_with_log_address(address, 0..=20).unwrap(),
+            vec![1, 3, 5, 8, 13]
+        );
+        assert_eq!(provider.blocks_with_log_address(address, 2..=8).unwrap(), vec![3, 5, 8]);
+        assert_eq!(provider.blocks_with_log_address(address, 6..=7).unwrap(), Vec::<u64>::new());
+        assert_eq!(provider.blocks_with_log_address(other, 0..=3).unwrap(), vec![2]);
+        assert!(provider.blocks_with_log_address(Address::zero(), 0..=20).unwrap().is_empty());
+    }
+
+    #[test]
+    fn header_and_chain_info() {
+        let db = create_test_db::<WriteMap>(EnvKind::RW);
+        let genesis = Header::default().seal();
+        let header = Header { number: 1, parent_hash: genesis.hash(), ..Default::default() }.seal();
+        db.update(|tx| {
+            for header in [&genesis, &header] {
+                tx.put::<tables::CanonicalHeaders>(header.number, header.hash()).unwrap();
+                tx.put::<tables::HeaderNumbers>(header.hash(), header.number).unwrap();
+                tx.put::<tables::Headers>(
+                    (header.number, header.hash()).into(),
+                    header.clone().unseal(),
+                )
+                .unwrap();
+            }
+        })
+        .unwrap();
+
+        let provider = ShareableDatabase::new(db.clone());
+        assert_eq!(provider.header(&header.hash()).unwrap(), Some(header.clone().unseal()));
+        assert_eq!(provider.header_by_number(1).unwrap(), Some(header.clone().unseal()));
+
+        // the chain tip is the highest block processed by all stages
+        assert_eq!(provider.chain_info().unwrap().best_hash, genesis.hash());
+        db.update(|tx| {
+            FINISH.save_progress(&tx, 1).unwrap();
+        })
+        .unwrap();
+        let chain_info = provider.chain_info().unwrap();
+        assert_eq!(chain_info.best_number, 1);
+        assert_eq!(chain_info.best_hash, header.hash());
+        assert_eq!(chain_info.last_finalized, None);
+        assert_eq!(chain_info.safe_finalized, None);
+
+        // only canonical markers that were processed by all stages are exposed
+        let sidechain = Header { number: 1, timestamp: 1, ..Default::default() }.seal();
+        db.update(|tx| {
+            tx.put::<tables::HeaderNumbers>(sidechain.hash(), sidechain.number).unwrap();
+            ForkchoiceMarker::Finalized.save(tx, genesis.hash()).unwrap();
+            ForkchoiceMarker::Safe.save(tx, sidechain.hash()).unwrap();
+        })
+        .unwrap();
+        let chain_info = provider.chain_info().unwrap();
+        assert_eq!(chain_info.last_finalized, Some(0));
+        assert_eq!(chain_info.safe_finalized, None);
+
+        db.update(|tx| {
+            ForkchoiceMarker::Safe.save(tx, header.hash()).unwrap();
+            FINISH.save_progress(&tx, 0).unwrap();
+        })
+        .unwrap();
+        assert_eq!(provider.chain_info().unwrap().safe_finalized, None);
+        db.update(|tx| {
+            FINISH.save_progress(&tx, 1).unwrap();
+        })
+        .unwrap();
+        assert_eq!(provider.chain_info().unwrap().safe_finalized, Some(1));
+        assert_eq!(provider.convert_block_number(BlockNumber::Finalized).unwrap(), Some(0));
+        assert_eq!(provider.convert_block_number(BlockNumber::Safe).unwrap(), Some(1));
+    }
+
+    #[test]
+    fn read_from_static_files() {
+        let db = create_test_db::<WriteMap>(EnvKind::RW);
+        let dir = tempfile::tempdir().unwrap();
+        let static_files = Arc::new(StaticFiles::open(dir.path()).unwrap());
+
+        let genesis = Header { gas_limit: 1, ..Default::default() }.seal();
+        let transactions: Vec<_> = (0..2)
+            .map(|nonce| TransactionSigned {
+                hash: H256::from_low_u64_be(nonce + 1),
+                ..Default::default()
+            })
+            .collect();
+        let receipts: Vec<_> =
+            (0..2).map(|gas| Receipt { cumulative_gas_used: gas, ..Default::default() }).collect();
+
+        // the header and the first transaction were moved, the second one is still in the database
+        static_files.append(StaticFileSegment::Headers, 0, 0, genesis.clone().unseal()).unwrap();
+        static_files
+            .append(StaticFileSegment::Transactions, 0, 0, transactions[0].clone())
+            .unwrap();
+        static_files.append(StaticFileSegment::Receipts, 0, 0, receipts[0].clone()).unwrap();
+        db.update(|tx| {
+            tx.put::<tables::CanonicalHeaders>(0, genesis.hash()).unwrap();
+            tx.put::<tables::HeaderNumbers>(genesis.hash(), 0).unwrap();
+            tx.put::<tables::BlockBodies>(
+                (0, genesis.hash()).into(),
+                StoredBlockBody { start_tx_id: 0, tx_count: 2 },
+            )
+            .unwrap();
+            tx.put::<tables::Transactions>(1, transactions[1].clone()).unwrap();
+            tx.put::<tables::Receipts>(1, receipts[1].clone()).unwrap();
+        })
+        .unwrap();
+
+        let provider = ShareableDatabase::new(db.clone());
+        assert_eq!(provider.header(&genesis.hash()).unwrap(), None);
+
+        let provider = provider.with_static_files(static_files);
+        let block = BlockId::Hash(genesis.hash().0.into());
+        assert_eq!(provider.header(&genesis.hash()).unwrap(), Some(genesis.clone().unseal()));
+        assert_eq!(provider.transaction_by_id(0).unwrap(), Some(transactions[0].clone()));
+        assert_eq!(provider.transactions_by_block(block).unwrap(), Some(transactions));
+        assert_eq!(provider.receipt(0).unwrap(), Some(receipts[0].clone()));
+        assert_eq!(provider.receipts_by_block(block).unwrap(), Some(receipts));
+
+        // sidechain headers are read from the database
+        let sidechain = Header { timestamp: 1, ..Default::default() }.seal();
+        db.update(|tx| {
+            tx.put::<tables::HeaderNumbers>(sidechain.hash(), 0).unwrap();
+            tx.put::<tables::Headers>((0, sidechain.hash()).into(), sidechain.clone().unseal())
+                .unwrap();
+        })
+        .unwrap();
+        assert_eq!(provider.header(&sidechain.hash()).unwrap(), Some(sidechain.unseal()));
+    }
+
+    #[test]
+    fn read_blocks() {
+        let db = create_test_db::<WriteMap>(EnvKind::RW);
+        let genesis = random_block(0, None, Some(1), Some(0));
+        let block = random_block(1, Some(genesis.hash()), Some(2), Some(1));
+        // the last block is canonical but above the tip, as it is still being synced
+        let syncing = random_block(2, Some(block.hash()), Some(1), Some(0));
+        db.update(|tx| {
+            insert_canonical_block(tx, &genesis, false).unwrap();
+            insert_canonical_block(tx, &block, false).unwrap();
+            insert_canonical_block(tx, &syncing, false).unwrap();
+            FINISH.save_progress(&tx, 1).unwrap();
+            // the sender of the last transaction was not recovered yet
+            tx.delete::<tables::TxSenders>(2, None).unwrap();
+        })
+        .unwrap();
+        let provider = ShareableDatabase::new(db);
+
+        let by_number = |number: u64| BlockId::Number(BlockNumber::Number(number.into()));
+        assert_eq!(provider.block(by_number(0)).unwrap(), Some(genesis.clone().unseal()));
+        assert_eq!(
+            provider.block(BlockId::Hash(block.hash().0.into())).unwrap(),
+            Some(block.clone().unseal())
+        );
+        for tag in [BlockNumber::Latest, BlockNumber::Pending] {
+            assert_eq!(provider.block(BlockId::Number(tag)).unwrap(), Some(block.clone().unseal()));
+        }
+        assert_eq!(provider.block(by_number(2)).unwrap(), None);
+        assert_eq!(provider.block(BlockId::Hash(Default::default())).unwrap(), None);
+
+        let with_senders = provider.block_with_senders(by_number(1)).unwrap().unwrap();
+        assert_eq!(with_senders.block, block.clone().unseal());
+        assert_eq!(
+            with_senders.senders,
+            block.body.iter().map(|tx| tx.recover_signer().unwrap()).collect::<Vec<_>>()
+        );
+
+        let blocks = provider.blocks_range(0..=5).unwrap();
+        assert_eq!(blocks, vec![genesis.unseal(), block.clone().unseal()]);
+        let blocks = provider.blocks_range_with_senders(1..=2).unwrap();
+        assert_eq!(blocks, vec![with_senders]);
+        assert!(provider.blocks_range(2..=2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn transactions_and_receipts_by_block() {
+        let db = create_test_db::<WriteMap>(EnvKind::RW);
+        let genesis = random_block(0, None, Some(1), Some(0));
+        let block = random_block(1, Some(genesis.hash()), Some(2), Some(0));
+        let receipts = (1..=3)
+            .map(|gas| Receipt { cumulative_gas_used: gas * 21_000, ..Default::default() })
+            .collect::<Vec<_>>();
+        db.update(|tx| {
+            insert_canonical_block(tx, &genesis, false).unwrap();
+            insert_canonical_block(tx, &block, false).unwrap();
+            for (id, transaction) in genesis.body.iter().chain(&block.body).enumerate() {
+                tx.put::<tables::TxHashNumber>(transaction.hash(), id as u64).unwrap();
+                tx.put::<tables::Receipts>(id as u64, receipts[id].clone()).unwrap();
+            }
+        })
+        .unwrap();
+
+        db.view(|tx| {
+            let found =
+                |id| canonical_block_by_tx_id(tx, id).unwrap().map(|(n, hash, _)| (n, hash));
+            assert_eq!(found(0), Some((0, genesis.hash())));
+            assert_eq!(found(1), Some((1, block.hash())));
+            assert_eq!(found(2), Some((1, block.hash())));
+            assert_eq!(found(3), None);
+        })
+        .unwrap();
+
+        let provider = ShareableDatabase::new(db);
+        let last = &block.body[1];
+        let (transaction, meta) =
+            provider.transaction_by_hash_with_meta(last.hash()).unwrap().unwrap();
+        assert_eq!(&transaction, last);
+        assert_eq!((meta.tx_id, meta.index, meta.block_number), (2, 1, 1));
+        assert_eq!(meta.block_hash, block.hash());
+        assert_eq!(meta.sender, last.recover_signer().unwrap());
+
+        let by_hash = BlockId::Hash(block.hash().0.into());
+        assert_eq!(provider.transactions_by_block(by_hash).unwrap(), Some(block.body.clone()));
+        assert_eq!(provider.receipts_by_block(by_hash).unwrap(), Some(receipts[1..].to_vec()));
+        assert_eq!(provider.receipt_by_hash(last.hash()).unwrap(), Some(receipts[2].clone()));
+    }
 }
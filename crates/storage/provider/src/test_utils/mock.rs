@@ -1,13 +1,22 @@
-use crate::{AccountProvider, BlockHashProvider, BlockProvider, HeaderProvider, StateProvider};
+use crate::{
+    trie::AccountChanges, AccountProvider, BlockHashProvider, BlockProvider, HeaderProvider,
+    LogIndexProvider, ReceiptProvider, StateProvider, StateProviderFactory, TransactionMeta,
+    TransactionsProvider,
+};
 use parking_lot::Mutex;
-use reth_interfaces::Result;
+use reth_interfaces::{provider::Error as ProviderError, Result};
 use reth_primitives::{
     keccak256,
+    proofs::genesis_state_root,
     rpc::{BlockId, BlockNumber},
-    Account, Address, Block, BlockHash, Bytes, ChainInfo, Header, StorageKey, StorageValue, H256,
-    U256,
+    Account, Address, Block, BlockHash, BlockWithSenders, Bytes, ChainInfo, GenesisAccount, Header,
+    Receipt, StorageKey, StorageValue, TransactionSigned, TxHash, TxNumber, H256, U256,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::RangeInclusive,
+    sync::Arc,
 };
-use std::{collections::HashMap, sync::Arc};
 
 /// A mock implementation for Provider interfaces.
 #[derive(Debug, Clone, Default)]
@@ -18,6 +27,8 @@ pub struct MockEthProvider {
     pub headers: Arc<Mutex<HashMap<H256, Header>>>,
     /// Local account store
     pub accounts: Arc<Mutex<HashMap<Address, ExtendedAccount>>>,
+    /// Local receipt store, by block hash
+    pub receipts: Arc<Mutex<HashMap<H256, Vec<Receipt>>>>,
 }
 
 /// An extended account for local store
@@ -45,6 +56,15 @@ impl ExtendedAccount {
         self.bytecode = Some(bytecode);
         self
     }
+
+    /// Add storage to the extended account. Existing storage slots are overwritten.
+    pub fn extend_storage(
+        mut self,
+        storage: impl IntoIterator<Item = (StorageKey, StorageValue)>,
+    ) -> Self {
+        self.storage.extend(storage);
+        self
+    }
 }
 
 impl MockEthProvider {
@@ -74,6 +94,11 @@ impl MockEthProvider {
         }
     }
 
+    /// Add the receipts of a block to local receipt store
+    pub fn add_receipts(&self, hash: H256, receipts: Vec<Receipt>) {
+        self.receipts.lock().insert(hash, receipts);
+    }
+
     /// Add account to local account store
     pub fn add_account(&self, address: Address, account: ExtendedAccount) {
         self.accounts.lock().insert(address, account);
@@ -128,7 +153,13 @@ impl BlockHashProvider for MockEthProvider {
 
 impl BlockProvider for MockEthProvider {
     fn chain_info(&self) -> Result<ChainInfo> {
-        todo!()
+        let lock = self.headers.lock();
+        let (best_hash, best_number) = lock
+            .iter()
+            .max_by_key(|(_, header)| header.number)
+            .map(|(hash, header)| (*hash, header.number))
+            .unwrap_or_default();
+        Ok(ChainInfo { best_hash, best_number, last_finalized: None, safe_finalized: None })
     }
 
     fn block(&self, id: BlockId) -> Result<Option<Block>> {
@@ -144,6 +175,27 @@ impl BlockProvider for MockEthProvider {
         }
     }
 
+    fn block_with_senders(&self, id: BlockId) -> Result<Option<BlockWithSenders>> {
+        Ok(self.block(id)?.map(with_recovered_senders))
+    }
+
+    fn blocks_range(
+        &self,
+        range: RangeInclusive<reth_primitives::BlockNumber>,
+    ) -> Result<Vec<Block>> {
+        let lock = self.blocks.lock();
+        Ok(range
+            .map_while(|number| lock.values().find(|block| block.number == number).cloned())
+            .collect())
+    }
+
+    fn blocks_range_with_senders(
+        &self,
+        range: RangeInclusive<reth_primitives::BlockNumber>,
+    ) -> Result<Vec<BlockWithSenders>> {
+        Ok(self.blocks_range(range)?.into_iter().map(with_recovered_senders).collect())
+    }
+
     fn block_number(&self, hash: H256) -> Result<Option<reth_primitives::BlockNumber>> {
         let lock = self.blocks.lock();
         let num = lock.iter().find_map(|(h, b)| if *h == hash { Some(b.number) } else { None });
@@ -151,6 +203,83 @@ impl BlockProvider for MockEthProvider {
     }
 }
 
+/// Recovers the senders of the transactions of the block.
+fn with_recovered_senders(block: Block) -> BlockWithSenders {
+    let senders = block.body.iter().map(|tx| tx.recover_signer().unwrap_or_default()).collect();
+    BlockWithSenders { block, senders }
+}
+
+impl TransactionsProvider for MockEthProvider {
+    fn transaction_id(&self, _tx_hash: TxHash) -> Result<Option<TxNumber>> {
+        Ok(None)
+    }
+
+    fn transaction_by_id(&self, _id: TxNumber) -> Result<Option<TransactionSigned>> {
+        Ok(None)
+    }
+
+    fn transaction_by_hash(&self, hash: TxHash) -> Result<Option<TransactionSigned>> {
+        Ok(self.transaction_by_hash_with_meta(hash)?.map(|(tx, _)| tx))
+    }
+
+    fn transaction_by_hash_with_meta(
+        &self,
+        hash: TxHash,
+    ) -> Result<Option<(TransactionSigned, TransactionMeta)>> {
+        let lock = self.blocks.lock();
+        Ok(lock.iter().find_map(|(block_hash, block)| {
+            let index = block.body.iter().position(|tx| tx.hash() == hash)?;
+            let tx = block.body[index].clone();
+            let meta = TransactionMeta {
+                tx_id: 0,
+                sender: tx.recover_signer().unwrap_or_default(),
+                index: index as u64,
+                block_hash: *block_hash,
+                block_number: block.number,
+                base_fee: block.base_fee_per_gas,
+            };
+            Some((tx, meta))
+        }))
+    }
+
+    fn transactions_by_block(&self, block: BlockId) -> Result<Option<Vec<TransactionSigned>>> {
+        Ok(self.block(block)?.map(|block| block.body))
+    }
+}
+
+impl ReceiptProvider for MockEthProvider {
+    fn receipt(&self, _id: TxNumber) -> Result<Option<Receipt>> {
+        Ok(None)
+    }
+
+    fn receipt_by_hash(&self, _hash: TxHash) -> Result<Option<Receipt>> {
+        Ok(None)
+    }
+
+    fn receipts_by_block(&self, block: BlockId) -> Result<Option<Vec<Receipt>>> {
+        let Some(hash) = self.block_hash_for_id(block)? else { return Ok(None) };
+        Ok(self.receipts.lock().get(&hash).cloned())
+    }
+}
+
+impl LogIndexProvider for MockEthProvider {
+    fn blocks_with_log_address(
+        &self,
+        _address: Address,
+        _range: RangeInclusive<reth_primitives::BlockNumber>,
+    ) -> Result<Vec<reth_primitives::BlockNumber>> {
+        Ok(vec![])
+    }
+
+    fn blocks_with_log_topic(
+        &self,
+        _topic: H256,
+        _range: RangeInclusive<reth_primitives::BlockNumber>,
+    ) -> Result<Vec<reth_primitives::BlockNumber>> {
+        Ok(vec![])
+    }
+}
+
 impl AccountProvider for MockEthProvider {
     fn basic_account(&self, address: Address) -> Result<Option<Account>> {
         Ok(self.accounts.lock().get(&address).cloned().map(|a| a.account))
@@ -174,4 +303,69 @@ impl StateProvider for MockEthProvider {
         let lock = self.accounts.lock();
         Ok(lock.get(&account).and_then(|account| account.storage.get(&storage_key)).cloned())
     }
+
+    /// The local account store has no trie to prove against.
+    fn proof(
+        &self,
+        _address: Address,
+        _keys: &[H256],
+    ) -> Result<(Vec<Bytes>, H256, Vec<Vec<Bytes>>)> {
+        Err(ProviderError::ProofUnavailable.into())
+    }
+
+    /// Computes the root of the local account store with the changes applied, see
+    /// [genesis_state_root].
+    fn state_root_with_changes(&self, changes: &BTreeMap<Address, AccountChanges>) -> Result<H256> {
+        let mut accounts = self.accounts.lock().clone();
+        for (address, changes) in changes {
+            let Some(account) = changes.account else {
+                accounts.remove(address);
+                continue
+            };
+            let entry =
+                accounts.entry(*address).or_insert_with(|| ExtendedAccount::new(0, U256::ZERO));
+            entry.account = account;
+            if changes.wipe_storage {
+                entry.storage.clear();
+            }
+            entry.storage.extend(changes.storage.clone());
+        }
+
+        let alloc = accounts
+            .into_iter()
+            .map(|(address, account)| {
+                let storage = account
+                    .storage
+                    .into_iter()
+                    .filter(|(_, value)| *value != U256::ZERO)
+                    .map(|(key, value)| (key, H256(value.to_be_bytes())))
+                    .collect();
+                let account = GenesisAccount {
+                    nonce: Some(account.account.nonce),
+                    balance: account.account.balance,
+                    code: account.bytecode,
+                    storage: Some(storage),
+                };
+                (address, account)
+            })
+            .collect();
+        Ok(genesis_state_root(alloc))
+    }
+}
+
+impl StateProviderFactory for MockEthProvider {
+    type HistorySP<'a> = &'a MockEthProvider where Self: 'a;
+    type LatestSP<'a> = &'a MockEthProvider where Self: 'a;
+
+    fn latest(&self) -> Result<Self::LatestSP<'_>> {
+        Ok(self)
+    }
+
+    fn history_by_block_number(&self, _block: u64) -> Result<Self::HistorySP<'_>> {
+        Ok(self)
+    }
+
+    fn history_by_block_hash(&self, _block: BlockHash) -> Result<Self::HistorySP<'_>> {
+        Ok(self)
+    }
 }
@@ -1,6 +1,9 @@
 use crate::{BlockHashProvider, BlockProvider, HeaderProvider};
 use reth_interfaces::Result;
-use reth_primitives::{rpc::BlockId, Block, BlockHash, BlockNumber, ChainInfo, Header, H256, U256};
+use reth_primitives::{
+    rpc::BlockId, Block, BlockHash, BlockNumber, BlockWithSenders, ChainInfo, Header, H256, U256,
+};
+use std::ops::RangeInclusive;
 
 /// Supports various api interfaces for testing purposes.
 #[derive(Debug, Clone, Default)]
@@ -28,6 +31,21 @@ impl BlockProvider for NoopProvider {
         Ok(None)
     }
 
+    fn block_with_senders(&self, _id: BlockId) -> Result<Option<BlockWithSenders>> {
+        Ok(None)
+    }
+
+    fn blocks_range(&self, _range: RangeInclusive<BlockNumber>) -> Result<Vec<Block>> {
+        Ok(Vec::new())
+    }
+
+    fn blocks_range_with_senders(
+        &self,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockWithSenders>> {
+        Ok(Vec::new())
+    }
+
     fn block_number(&self, _hash: H256) -> Result<Option<BlockNumber>> {
         Ok(None)
     }
@@ -2,8 +2,9 @@ use super::BlockHashProvider;
 use reth_interfaces::Result;
 use reth_primitives::{
     rpc::{BlockId, BlockNumber},
-    Block, ChainInfo, H256, U256,
+    Block, BlockWithSenders, ChainInfo, H256, U256,
 };
+use std::ops::RangeInclusive;
 
 /// Api trait for fetching `Block` related data.
 pub trait BlockProvider: BlockHashProvider + Send + Sync {
@@ -13,6 +14,24 @@ pub trait BlockProvider: BlockHashProvider + Send + Sync {
     /// Returns the block. Returns `None` if block is not found.
     fn block(&self, id: BlockId) -> Result<Option<Block>>;
 
+    /// Returns the block with the senders of its transactions. Returns `None` if block is not
+    /// found.
+    fn block_with_senders(&self, id: BlockId) -> Result<Option<BlockWithSenders>>;
+
+    /// Returns the canonical blocks in the range up to the chain tip, stopping at the first block
+    /// that is not found.
+    fn blocks_range(
+        &self,
+        range: RangeInclusive<reth_primitives::BlockNumber>,
+    ) -> Result<Vec<Block>>;
+
+    /// Returns the canonical blocks in the range up to the chain tip with the senders of their
+    /// transactions, stopping at the first block that is not found.
+    fn blocks_range_with_senders(
+        &self,
+        range: RangeInclusive<reth_primitives::BlockNumber>,
+    ) -> Result<Vec<BlockWithSenders>>;
+
     /// Converts the `BlockNumber` variants.
     fn convert_block_number(
         &self,
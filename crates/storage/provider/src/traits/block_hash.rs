@@ -3,7 +3,7 @@ use reth_interfaces::Result;
 use reth_primitives::{H256, U256};
 
 /// Client trait for fetching block hashes by number.
-#[auto_impl(&)]
+#[auto_impl(&, Box)]
 pub trait BlockHashProvider: Send + Sync {
     /// Get the hash of the block with the given number. Returns `None` if no block with this number
     /// exists.
@@ -0,0 +1,26 @@
+use auto_impl::auto_impl;
+use reth_interfaces::Result;
+use reth_primitives::{Address, BlockNumber, H256};
+use std::ops::RangeInclusive;
+
+/// Client trait for looking up the blocks that contain logs of an address or topic.
+///
+/// The lookups only cover blocks that were already indexed.
+#[auto_impl(&)]
+pub trait LogIndexProvider: Send + Sync {
+    /// Returns the numbers of the blocks in the range that contain a log emitted by the address,
+    /// in ascending order.
+    fn blocks_with_log_address(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>>;
+
+    /// Returns the numbers of the blocks in the range that contain a log with the topic at any
+    /// position, in ascending order.
+    fn blocks_with_log_topic(
+        &self,
+        topic: H256,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>>;
+}
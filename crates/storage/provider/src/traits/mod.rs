@@ -12,5 +12,14 @@ pub use block_hash::BlockHashProvider;
 mod header;
 pub use header::HeaderProvider;
 
+mod logs;
+pub use logs::LogIndexProvider;
+
+mod receipts;
+pub use receipts::ReceiptProvider;
+
 mod state;
-pub use state::{StateProvider, StateProviderFactory};
+pub use state::{StateProvider, StateProviderBox, StateProviderFactory};
+
+mod transactions;
+pub use transactions::{TransactionMeta, TransactionsProvider};
@@ -0,0 +1,18 @@
+use auto_impl::auto_impl;
+use reth_interfaces::Result;
+use reth_primitives::{rpc::BlockId, Receipt, TxHash, TxNumber};
+
+/// Client trait for fetching [Receipt] data.
+#[auto_impl(&)]
+pub trait ReceiptProvider: Send + Sync {
+    /// Get receipt by transaction number.
+    fn receipt(&self, id: TxNumber) -> Result<Option<Receipt>>;
+
+    /// Get receipt by transaction hash.
+    fn receipt_by_hash(&self, hash: TxHash) -> Result<Option<Receipt>>;
+
+    /// Get receipts by block id.
+    ///
+    /// Returns `None` if the block is not found.
+    fn receipts_by_block(&self, block: BlockId) -> Result<Option<Vec<Receipt>>>;
+}
@@ -1,17 +1,32 @@
 use super::AccountProvider;
-use crate::BlockHashProvider;
+use crate::{trie::AccountChanges, BlockHashProvider};
 use auto_impl::auto_impl;
 use reth_interfaces::Result;
 use reth_primitives::{Address, BlockHash, BlockNumber, Bytes, StorageKey, StorageValue, H256};
+use std::collections::BTreeMap;
+
+/// Type alias of boxed [StateProvider].
+pub type StateProviderBox<'a> = Box<dyn StateProvider + 'a>;
 
 /// An abstraction for a type that provides state data.
-#[auto_impl(&)]
+#[auto_impl(&, Box)]
 pub trait StateProvider: BlockHashProvider + AccountProvider + Send + Sync {
     /// Get storage.
     fn storage(&self, account: Address, storage_key: StorageKey) -> Result<Option<StorageValue>>;
 
     /// Get account code by its hash
     fn bytecode_by_hash(&self, code_hash: H256) -> Result<Option<Bytes>>;
+
+    /// Get the [EIP-1186](https://eips.ethereum.org/EIPS/eip-1186) account and storage proofs.
+    ///
+    /// Returns the account proof, the storage root of the account and one storage proof per key.
+    fn proof(&self, address: Address, keys: &[H256])
+        -> Result<(Vec<Bytes>, H256, Vec<Vec<Bytes>>)>;
+
+    /// Returns the state root of this state with the given account changes applied on top of it.
+    ///
+    /// Nothing is written to the database.
+    fn state_root_with_changes(&self, changes: &BTreeMap<Address, AccountChanges>) -> Result<H256>;
 }
 
 /// Light wrapper that returns `StateProvider` implementations that correspond to the given
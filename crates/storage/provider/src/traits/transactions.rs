@@ -0,0 +1,49 @@
+use auto_impl::auto_impl;
+use reth_interfaces::Result;
+use reth_primitives::{
+    rpc::BlockId, Address, BlockHash, BlockNumber, TransactionSigned, TxHash, TxNumber,
+};
+
+/// Client trait for fetching [TransactionSigned] related data.
+#[auto_impl(&)]
+pub trait TransactionsProvider: Send + Sync {
+    /// Get internal transaction identifier by transaction hash.
+    ///
+    /// Returns `None` if the transaction is not found.
+    fn transaction_id(&self, tx_hash: TxHash) -> Result<Option<TxNumber>>;
+
+    /// Get transaction by id.
+    fn transaction_by_id(&self, id: TxNumber) -> Result<Option<TransactionSigned>>;
+
+    /// Get transaction by transaction hash.
+    fn transaction_by_hash(&self, hash: TxHash) -> Result<Option<TransactionSigned>>;
+
+    /// Get transaction by transaction hash and additional metadata of the block the transaction
+    /// was mined in.
+    fn transaction_by_hash_with_meta(
+        &self,
+        hash: TxHash,
+    ) -> Result<Option<(TransactionSigned, TransactionMeta)>>;
+
+    /// Get transactions by block id.
+    ///
+    /// Returns `None` if the block is not found.
+    fn transactions_by_block(&self, block: BlockId) -> Result<Option<Vec<TransactionSigned>>>;
+}
+
+/// Additional metadata of a mined transaction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransactionMeta {
+    /// Internal transaction identifier.
+    pub tx_id: TxNumber,
+    /// The sender of the transaction.
+    pub sender: Address,
+    /// Index of the transaction in the block.
+    pub index: u64,
+    /// Hash of the block.
+    pub block_hash: BlockHash,
+    /// Number of the block.
+    pub block_number: BlockNumber,
+    /// Base fee of the block.
+    pub base_fee: Option<u64>,
+}
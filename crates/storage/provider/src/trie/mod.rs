@@ -0,0 +1,1084 @@
+use crate::{StateProvider, MERKLE_EXECUTION};
+use reth_db::{
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO},
+    models::TransitionIdAddress,
+    table::Table,
+    tables,
+    transaction::{DbTx, DbTxMut},
+    TrieNode, TrieNodePath,
+};
+use reth_interfaces::{provider::Error as ProviderError, Result};
+use reth_primitives::{
+    keccak256, proofs::EMPTY_ROOT, Account, Address, Bytes, TransitionId, H256, KECCAK_EMPTY, U256,
+};
+use reth_rlp::{Decodable, Encodable, RlpDecodable, RlpEncodable};
+use std::{
+    collections::{btree_map::Entry, BTreeMap, BTreeSet},
+    marker::PhantomData,
+    ops::Range,
+};
+
+mod node;
+use node::{common_prefix, extension, to_nibbles, Node};
+
+/// The number of leaves inserted into a trie before its nodes are flushed to the database, this
+/// bounds the amount of nodes that are held in memory.
+const FLUSH_THRESHOLD: usize = 100_000;
+
+/// An Ethereum account as it is stored in the leaves of the state trie.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct TrieAccount {
+    /// Account nonce.
+    pub nonce: u64,
+    /// Account balance.
+    pub balance: U256,
+    /// Root of the account storage trie.
+    pub storage_root: H256,
+    /// Hash of the account bytecode.
+    pub code_hash: H256,
+}
+
+impl TrieAccount {
+    /// Create the trie representation of the account with the given storage root.
+    pub fn new(account: Account, storage_root: H256) -> Self {
+        Self {
+            nonce: account.nonce,
+            balance: account.balance,
+            storage_root,
+            code_hash: account.bytecode_hash.unwrap_or(KECCAK_EMPTY),
+        }
+    }
+
+    /// Returns the RLP encoding of the account.
+    pub fn rlp(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode(&mut out);
+        out
+    }
+}
+
+/// The changes of a single account that are applied on top of a state trie, see
+/// [DBTrieLoader::state_root_with_changes].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountChanges {
+    /// The account after the changes, `None` if it was destroyed.
+    pub account: Option<Account>,
+    /// Whether the storage of the account was wiped before the changed slots were written.
+    pub wipe_storage: bool,
+    /// The new values of the changed storage slots, keyed by the unhashed slot.
+    pub storage: BTreeMap<H256, U256>,
+}
+
+/// Returns the RLP encoding of a storage value as it is stored in the leaves of a storage trie.
+pub fn encode_storage_value(value: U256) -> Vec<u8> {
+    let mut out = Vec::new();
+    value.encode(&mut out);
+    out
+}
+
+/// A Merkle Patricia Trie whose nodes are stored in table `T`, indexed by their path.
+///
+/// A node is stored under its nibble path from the root, prepended with the key prefix of the
+/// trie, so the table only ever holds a single version of every trie. Nodes are loaded lazily
+/// while the trie is walked and checked against the hash their parent refers to them by.
+/// Modified nodes are kept in memory until [PatriciaTrie::commit] writes them to the database and
+/// deletes the stored nodes that they replaced.
+///
+/// Keys are always `keccak256` hashes. If an operation returns an error, the trie is left in an
+/// undefined state and should be discarded.
+pub struct PatriciaTrie<'a, 'tx, TX, T> {
+    /// Database transaction
+    tx: &'a TX,
+    /// Prefix of the database keys of all nodes of the trie
+    prefix: Vec<u8>,
+    /// Root node
+    root: Node,
+    /// Paths of the stored nodes that were loaded to be modified
+    loaded: BTreeSet<Vec<u8>>,
+    /// Phantom data over the transaction lifetime and the table
+    _phantom: PhantomData<(&'tx (), T)>,
+}
+
+impl<'a, 'tx, TX, T> PatriciaTrie<'a, 'tx, TX, T>
+where
+    TX: DbTx<'tx>,
+    T: Table<Key = TrieNodePath, Value = TrieNode>,
+{
+    /// Open the trie with the given root.
+    pub fn new(tx: &'a TX, root: H256) -> Self {
+        Self::with_prefix(tx, Vec::new(), root)
+    }
+
+    /// Open the trie with the given root whose nodes are stored under the given key prefix.
+    pub fn with_prefix(tx: &'a TX, prefix: Vec<u8>, root: H256) -> Self {
+        let root = if root == EMPTY_ROOT { Node::Empty } else { Node::Hash(root) };
+        Self { tx, prefix, root, loaded: BTreeSet::new(), _phantom: PhantomData }
+    }
+
+    /// Returns the root of the trie that is stored in the database without a key prefix.
+    pub fn stored_root(tx: &'a TX) -> Result<H256> {
+        Ok(tx.get::<T>(Vec::new())?.map(|rlp| keccak256(&rlp)).unwrap_or(EMPTY_ROOT))
+    }
+
+    /// Get the value stored under `key`.
+    pub fn get(&self, key: H256) -> Result<Option<Vec<u8>>> {
+        self.get_at(&self.root, &to_nibbles(key.as_bytes()), 0)
+    }
+
+    /// Insert or update the value stored under `key`.
+    pub fn insert(&mut self, key: H256, value: Vec<u8>) -> Result<()> {
+        let root = std::mem::take(&mut self.root);
+        self.root = self.insert_at(root, &to_nibbles(key.as_bytes()), 0, value)?;
+        Ok(())
+    }
+
+    /// Remove the value stored under `key`. Removing a key that does not exist is a no-op.
+    pub fn remove(&mut self, key: H256) -> Result<()> {
+        let root = std::mem::take(&mut self.root);
+        self.root = self.remove_at(root, &to_nibbles(key.as_bytes()), 0)?;
+        Ok(())
+    }
+
+    /// Returns the RLP encoded nodes on the path from the root to `key`, as used by
+    /// [EIP-1186](https://eips.ethereum.org/EIPS/eip-1186) proofs.
+    ///
+    /// If the key does not exist, the returned nodes prove its absence.
+    pub fn proof(&self, key: H256) -> Result<Vec<Bytes>> {
+        let mut proof = Vec::new();
+        if !matches!(self.root, Node::Empty) {
+            self.proof_at(&self.root, &to_nibbles(key.as_bytes()), 0, &mut proof)?;
+        }
+        Ok(proof)
+    }
+
+    /// Returns the root hash of the trie, without writing anything to the database.
+    pub fn root(&self) -> H256 {
+        self.encode_root().0
+    }
+
+    /// Hash the root and collect the paths and encodings of all nodes that need to be persisted.
+    fn encode_root(&self) -> (H256, Vec<(Vec<u8>, Vec<u8>)>) {
+        let mut hashed = Vec::new();
+        let root = match &self.root {
+            Node::Empty => EMPTY_ROOT,
+            Node::Hash(hash) => *hash,
+            node => {
+                // The root is always stored, even if it encodes to less than 32 bytes.
+                let rlp = node.encode(&[], &mut hashed);
+                let hash = keccak256(&rlp);
+                hashed.push((Vec::new(), rlp));
+                hash
+            }
+        };
+        (root, hashed)
+    }
+
+    /// Returns the database key of the node at the given path.
+    fn key(&self, path: &[u8]) -> TrieNodePath {
+        [self.prefix.as_slice(), path].concat()
+    }
+
+    /// Load the node at `path` that is expected to have the given hash, returning its encoding and
+    /// the decoded node.
+    fn load(&self, path: &[u8], hash: H256) -> Result<(Vec<u8>, Node)> {
+        let rlp = self
+            .tx
+            .get::<T>(self.key(path))?
+            .filter(|rlp| keccak256(rlp) == hash)
+            .ok_or(ProviderError::TrieNode { hash })?;
+        let node = Node::decode(&rlp).map_err(|_| ProviderError::TrieNodeDecode { hash })?;
+        Ok((rlp, node))
+    }
+
+    fn resolve(&self, path: &[u8], hash: H256) -> Result<Node> {
+        self.load(path, hash).map(|(_, node)| node)
+    }
+
+    /// Load the node at `path` in order to modify it. The stored node is deleted on commit,
+    /// unless it is written again.
+    fn resolve_mut(&mut self, path: &[u8], hash: H256) -> Result<Node> {
+        let node = self.resolve(path, hash)?;
+        self.loaded.insert(path.to_vec());
+        Ok(node)
+    }
+
+    fn get_at(&self, node: &Node, key: &[u8], depth: usize) -> Result<Option<Vec<u8>>> {
+        match node {
+            Node::Empty => Ok(None),
+            Node::Hash(hash) => self.get_at(&self.resolve(&key[..depth], *hash)?, key, depth),
+            Node::Leaf { path, value } => {
+                Ok((path.as_slice() == &key[depth..]).then(|| value.clone()))
+            }
+            Node::Extension { path, child } => {
+                if key[depth..].starts_with(path) {
+                    self.get_at(child, key, depth + path.len())
+                } else {
+                    Ok(None)
+                }
+            }
+            Node::Branch { children } => match key.get(depth) {
+                Some(nibble) => self.get_at(&children[*nibble as usize], key, depth + 1),
+                None => Ok(None),
+            },
+        }
+    }
+
+    /// Insert `value` under `key` into `node`, which is located at `key[..depth]`.
+    fn insert_at(&mut self, node: Node, key: &[u8], depth: usize, value: Vec<u8>) -> Result<Node> {
+        let path = &key[depth..];
+        match node {
+            Node::Empty => Ok(Node::Leaf { path: path.to_vec(), value }),
+            Node::Hash(hash) => {
+                let node = self.resolve_mut(&key[..depth], hash)?;
+                self.insert_at(node, key, depth, value)
+            }
+            Node::Leaf { path: leaf_path, value: leaf_value } => {
+                if leaf_path == path {
+                    return Ok(Node::Leaf { path: leaf_path, value })
+                }
+                // Keys have a fixed length, so the paths diverge before either of them ends.
+                let common = common_prefix(&leaf_path, path);
+                let mut children: Box<[Node; 16]> = Default::default();
+                children[leaf_path[common] as usize] =
+                    Node::Leaf { path: leaf_path[common + 1..].to_vec(), value: leaf_value };
+                children[path[common] as usize] =
+                    Node::Leaf { path: path[common + 1..].to_vec(), value };
+                Ok(extension(path[..common].to_vec(), Node::Branch { children }))
+            }
+            Node::Extension { path: ext_path, child } => {
+                let common = common_prefix(&ext_path, path);
+                if common == ext_path.len() {
+                    let child = self.insert_at(*child, key, depth + common, value)?;
+                    return Ok(Node::Extension { path: ext_path, child: Box::new(child) })
+                }
+                let mut children: Box<[Node; 16]> = Default::default();
+                children[ext_path[common] as usize] =
+                    extension(ext_path[common + 1..].to_vec(), *child);
+                children[path[common] as usize] =
+                    Node::Leaf { path: path[common + 1..].to_vec(), value };
+                Ok(extension(ext_path[..common].to_vec(), Node::Branch { children }))
+            }
+            Node::Branch { mut children } => {
+                let nibble = path[0] as usize;
+                let child = std::mem::take(&mut children[nibble]);
+                children[nibble] = self.insert_at(child, key, depth + 1, value)?;
+                Ok(Node::Branch { children })
+            }
+        }
+    }
+
+    /// Remove `key` from `node`, which is located at `key[..depth]`.
+    fn remove_at(&mut self, node: Node, key: &[u8], depth: usize) -> Result<Node> {
+        let path = &key[depth..];
+        match node {
+            Node::Empty => Ok(Node::Empty),
+            Node::Hash(hash) => {
+                let node = self.resolve_mut(&key[..depth], hash)?;
+                self.remove_at(node, key, depth)
+            }
+            Node::Leaf { path: leaf_path, value } => {
+                if leaf_path == path {
+                    Ok(Node::Empty)
+                } else {
+                    Ok(Node::Leaf { path: leaf_path, value })
+                }
+            }
+            Node::Extension { path: ext_path, child } => {
+                if !path.starts_with(&ext_path) {
+                    return Ok(Node::Extension { path: ext_path, child })
+                }
+                let child_depth = depth + ext_path.len();
+                let child = self.remove_at(*child, key, child_depth)?;
+                self.prepend_path(ext_path, child, &key[..child_depth])
+            }
+            Node::Branch { mut children } => {
+                let nibble = path[0] as usize;
+                let child = std::mem::take(&mut children[nibble]);
+                children[nibble] = self.remove_at(child, key, depth + 1)?;
+
+                let remaining = children
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, child)| !matches!(child, Node::Empty))
+                    .map(|(nibble, _)| nibble)
+                    .take(2)
+                    .collect::<Vec<_>>();
+                match remaining.as_slice() {
+                    [] => Ok(Node::Empty),
+                    // A branch with a single child is collapsed into that child.
+                    [only] => {
+                        let child = std::mem::take(&mut children[*only]);
+                        let child_path = [&key[..depth], &[*only as u8]].concat();
+                        self.prepend_path(vec![*only as u8], child, &child_path)
+                    }
+                    _ => Ok(Node::Branch { children }),
+                }
+            }
+        }
+    }
+
+    /// Prepend `prefix` to the path of `node`, which is located at `location`, merging it into
+    /// leaves and extensions.
+    fn prepend_path(&mut self, prefix: Vec<u8>, node: Node, location: &[u8]) -> Result<Node> {
+        match node {
+            Node::Empty => Ok(Node::Empty),
+            Node::Hash(hash) => {
+                let node = self.resolve_mut(location, hash)?;
+                self.prepend_path(prefix, node, location)
+            }
+            Node::Leaf { path, value } => Ok(Node::Leaf { path: [prefix, path].concat(), value }),
+            Node::Extension { path, child } => {
+                Ok(Node::Extension { path: [prefix, path].concat(), child })
+            }
+            branch @ Node::Branch { .. } => Ok(extension(prefix, branch)),
+        }
+    }
+
+    /// Collect the proof of `key` starting at `node`, which is located at `key[..depth]`.
+    fn proof_at(
+        &self,
+        node: &Node,
+        key: &[u8],
+        depth: usize,
+        proof: &mut Vec<Bytes>,
+    ) -> Result<()> {
+        let loaded;
+        let (node, rlp) = match node {
+            Node::Hash(hash) => {
+                loaded = self.load(&key[..depth], *hash)?;
+                (&loaded.1, loaded.0.clone())
+            }
+            node => (node, node.encode(&key[..depth], &mut Vec::new())),
+        };
+        // Nodes shorter than 32 bytes are inlined into their parent.
+        if depth == 0 || rlp.len() >= 32 {
+            proof.push(rlp.into());
+        }
+
+        match node {
+            Node::Extension { path, child } if key[depth..].starts_with(path) => {
+                self.proof_at(child, key, depth + path.len(), proof)
+            }
+            Node::Branch { children } => match key.get(depth) {
+                Some(nibble) => self.proof_at(&children[*nibble as usize], key, depth + 1, proof),
+                None => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<'a, 'tx, TX, T> PatriciaTrie<'a, 'tx, TX, T>
+where
+    TX: DbTxMut<'tx> + DbTx<'tx>,
+    T: Table<Key = TrieNodePath, Value = TrieNode>,
+{
+    /// Write all modified nodes to the database, delete the nodes they replaced and return the new
+    /// root.
+    pub fn commit(&mut self) -> Result<H256> {
+        let (root, hashed) = self.encode_root();
+        for (path, rlp) in hashed {
+            self.loaded.remove(&path);
+            self.tx.put::<T>(self.key(&path), rlp)?;
+        }
+        // Nodes that were removed, moved to another path or are now inlined into their parent.
+        for path in std::mem::take(&mut self.loaded) {
+            self.tx.delete::<T>(self.key(&path), None)?;
+        }
+        self.root = if root == EMPTY_ROOT { Node::Empty } else { Node::Hash(root) };
+        Ok(root)
+    }
+}
+
+/// The state trie of [tables::AccountsTrie].
+type AccountsTrie<'a, 'tx, TX> = PatriciaTrie<'a, 'tx, TX, tables::AccountsTrie>;
+
+/// A storage trie of [tables::StoragesTrie].
+type StorageTrie<'a, 'tx, TX> = PatriciaTrie<'a, 'tx, TX, tables::StoragesTrie>;
+
+/// Computes the state root from the [tables::HashedAccount] and [tables::HashedStorage] tables
+/// and persists the trie nodes in [tables::AccountsTrie] and [tables::StoragesTrie].
+///
+/// The stored trie is the trie of the state at the end of the last block the merkle stage was
+/// executed for. Roots and proofs of any other state are computed by applying the accounts and
+/// storage slots that changed in between on top of it, in memory.
+#[derive(Debug, Default)]
+pub struct DBTrieLoader;
+
+impl DBTrieLoader {
+    /// Rebuild the whole state trie from the hashed state and return the state root.
+    pub fn calculate_root<'tx, TX: DbTxMut<'tx> + DbTx<'tx>>(&self, tx: &TX) -> Result<H256> {
+        tx.clear::<tables::AccountsTrie>()?;
+        tx.clear::<tables::StoragesTrie>()?;
+
+        let mut accounts_trie = AccountsTrie::new(tx, EMPTY_ROOT);
+
+        let mut accounts = tx.cursor_read::<tables::HashedAccount>()?;
+        let mut walker = accounts.walk(H256::zero())?;
+        let mut inserted = 0;
+        while let Some((hashed_address, account)) = walker.next().transpose()? {
+            let storage_root = self.calculate_storage_root(tx, hashed_address)?;
+            accounts_trie.insert(hashed_address, TrieAccount::new(account, storage_root).rlp())?;
+
+            inserted += 1;
+            if inserted % FLUSH_THRESHOLD == 0 {
+                accounts_trie.commit()?;
+            }
+        }
+
+        accounts_trie.commit()
+    }
+
+    /// Rebuild the storage trie of a single account and return its root.
+    fn calculate_storage_root<'tx, TX: DbTxMut<'tx> + DbTx<'tx>>(
+        &self,
+        tx: &TX,
+        hashed_address: H256,
+    ) -> Result<H256> {
+        let mut storage_trie = StorageTrie::with_prefix(tx, hashed_address.0.to_vec(), EMPTY_ROOT);
+
+        let mut storage = tx.cursor_dup_read::<tables::HashedStorage>()?;
+        let mut inserted = 0;
+        for entry in storage.walk_dup(hashed_address, H256::zero())? {
+            let (_, entry) = entry?;
+            if entry.value == U256::ZERO {
+                continue
+            }
+            storage_trie.insert(entry.key, encode_storage_value(entry.value))?;
+
+            inserted += 1;
+            if inserted % FLUSH_THRESHOLD == 0 {
+                storage_trie.commit()?;
+            }
+        }
+
+        storage_trie.commit()
+    }
+
+    /// Update the stored state trie by re-reading every account and storage slot that changed in
+    /// the transitions of `range`, and return the new state root.
+    ///
+    /// The hashed state tables are expected to already reflect the state at the end of the range
+    /// (or at its start, when unwinding).
+    pub fn update_root<'tx, TX: DbTxMut<'tx> + DbTx<'tx>>(
+        &self,
+        tx: &TX,
+        range: Range<TransitionId>,
+    ) -> Result<H256> {
+        let changes = self.gather_changes(tx, range)?;
+
+        let mut accounts_trie = AccountsTrie::new(tx, AccountsTrie::stored_root(tx)?);
+        for (address, changed_storage) in changes {
+            let hashed_address = keccak256(address);
+            let Some(account) = tx.get::<tables::HashedAccount>(hashed_address)? else {
+                accounts_trie.remove(hashed_address)?;
+                self.delete_storage_trie(tx, hashed_address)?;
+                continue
+            };
+
+            let storage_root = storage_root_of(&accounts_trie, hashed_address)?;
+            let storage_root =
+                self.update_storage_root(tx, hashed_address, storage_root, changed_storage)?;
+
+            accounts_trie.insert(hashed_address, TrieAccount::new(account, storage_root).rlp())?;
+        }
+
+        accounts_trie.commit()
+    }
+
+    /// Update the storage trie of a single account with the current values of `changed` slots.
+    fn update_storage_root<'tx, TX: DbTxMut<'tx> + DbTx<'tx>>(
+        &self,
+        tx: &TX,
+        hashed_address: H256,
+        root: H256,
+        changed: BTreeSet<H256>,
+    ) -> Result<H256> {
+        let mut storage_trie = StorageTrie::with_prefix(tx, hashed_address.0.to_vec(), root);
+
+        let mut storage = tx.cursor_dup_read::<tables::HashedStorage>()?;
+        for slot in changed {
+            let hashed_slot = keccak256(slot);
+            let entry = storage
+                .seek_by_key_subkey(hashed_address, hashed_slot)?
+                .filter(|entry| entry.key == hashed_slot && entry.value != U256::ZERO);
+            match entry {
+                Some(entry) => {
+                    storage_trie.insert(hashed_slot, encode_storage_value(entry.value))?
+                }
+                None => storage_trie.remove(hashed_slot)?,
+            }
+        }
+
+        storage_trie.commit()
+    }
+
+    /// Delete all stored nodes of the storage trie of the account with the given hashed address.
+    fn delete_storage_trie<'tx, TX: DbTxMut<'tx> + DbTx<'tx>>(
+        &self,
+        tx: &TX,
+        hashed_address: H256,
+    ) -> Result<()> {
+        let mut cursor = tx.cursor_write::<tables::StoragesTrie>()?;
+        let mut entry = cursor.seek(hashed_address.0.to_vec())?;
+        while let Some((key, _)) = entry {
+            if !key.starts_with(hashed_address.as_bytes()) {
+                break
+            }
+            cursor.delete_current()?;
+            entry = cursor.next()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the changes that turn the stored state trie into the trie of `state`, which is the
+    /// state at `transition`, or the latest state if `None`.
+    ///
+    /// Every account and storage slot that changed between the end of the block the stored trie
+    /// was built for and `transition` is read from `state`.
+    pub fn state_changes<'tx, TX: DbTx<'tx>>(
+        &self,
+        tx: &TX,
+        state: &impl StateProvider,
+        transition: Option<TransitionId>,
+    ) -> Result<BTreeMap<Address, AccountChanges>> {
+        let trie_block = MERKLE_EXECUTION.get_progress(tx)?.ok_or(ProviderError::StateTrie)?;
+        let trie_transition = tx
+            .get::<tables::BlockTransitionIndex>(trie_block)?
+            .ok_or(ProviderError::BlockTransition { block_number: trie_block })?;
+        let range = match transition {
+            Some(transition) if transition < trie_transition => transition..trie_transition,
+            Some(transition) => trie_transition..transition,
+            None => trie_transition..TransitionId::MAX,
+        };
+
+        self.gather_changes(tx, range)?
+            .into_iter()
+            .map(|(address, slots)| {
+                let storage = slots
+                    .into_iter()
+                    .map(|slot| Ok((slot, state.storage(address, slot)?.unwrap_or_default())))
+                    .collect::<Result<_>>()?;
+                let changes = AccountChanges {
+                    account: state.basic_account(address)?,
+                    wipe_storage: false,
+                    storage,
+                };
+                Ok((address, changes))
+            })
+            .collect()
+    }
+
+    /// Returns the state root of the stored state trie after applying `changes`.
+    ///
+    /// The modified nodes are only held in memory, nothing is written to the database.
+    pub fn state_root_with_changes<'tx, TX: DbTx<'tx>>(
+        &self,
+        tx: &TX,
+        changes: &BTreeMap<Address, AccountChanges>,
+    ) -> Result<H256> {
+        Ok(self.apply_changes(tx, changes, None)?.0.root())
+    }
+
+    /// Generate the [EIP-1186](https://eips.ethereum.org/EIPS/eip-1186) proof of `address` and
+    /// its storage `keys` in the stored state trie after applying `changes`.
+    ///
+    /// Returns the account proof, the storage root of the account and one storage proof per key.
+    pub fn generate_proof<'tx, TX: DbTx<'tx>>(
+        &self,
+        tx: &TX,
+        address: Address,
+        keys: &[H256],
+        changes: &BTreeMap<Address, AccountChanges>,
+    ) -> Result<(Vec<Bytes>, H256, Vec<Vec<Bytes>>)> {
+        let (accounts_trie, storage_trie) = self.apply_changes(tx, changes, Some(address))?;
+        let hashed_address = keccak256(address);
+        let account_proof = accounts_trie.proof(hashed_address)?;
+        let storage_root = storage_root_of(&accounts_trie, hashed_address)?;
+
+        let storage_trie = match storage_trie {
+            Some(storage_trie) => storage_trie,
+            None => StorageTrie::with_prefix(tx, hashed_address.0.to_vec(), storage_root),
+        };
+        let storage_proofs =
+            keys.iter().map(|key| storage_trie.proof(keccak256(key))).collect::<Result<_>>()?;
+
+        Ok((account_proof, storage_root, storage_proofs))
+    }
+
+    /// Apply `changes` to the stored state trie in memory.
+    ///
+    /// Returns the modified state trie and, if the account `keep` was changed, its modified
+    /// storage trie.
+    fn apply_changes<'a, 'tx, TX: DbTx<'tx>>(
+        &self,
+        tx: &'a TX,
+        changes: &BTreeMap<Address, AccountChanges>,
+        keep: Option<Address>,
+    ) -> Result<(AccountsTrie<'a, 'tx, TX>, Option<StorageTrie<'a, 'tx, TX>>)> {
+        let mut accounts_trie = AccountsTrie::new(tx, AccountsTrie::stored_root(tx)?);
+        let mut kept = None;
+        for (address, changes) in changes {
+            let hashed_address = keccak256(address);
+            let Some(account) = changes.account else {
+                accounts_trie.remove(hashed_address)?;
+                continue
+            };
+
+            let storage_root = if changes.wipe_storage {
+                EMPTY_ROOT
+            } else {
+                storage_root_of(&accounts_trie, hashed_address)?
+            };
+            let mut storage_trie =
+                StorageTrie::with_prefix(tx, hashed_address.0.to_vec(), storage_root);
+            for (slot, value) in changes.storage.iter() {
+                if *value == U256::ZERO {
+                    storage_trie.remove(keccak256(slot))?;
+                } else {
+                    storage_trie.insert(keccak256(slot), encode_storage_value(*value))?;
+                }
+            }
+
+            accounts_trie
+                .insert(hashed_address, TrieAccount::new(account, storage_trie.root()).rlp())?;
+            if keep == Some(*address) {
+                kept = Some(storage_trie);
+            }
+        }
+
+        Ok((accounts_trie, kept))
+    }
+
+    /// Collect the addresses and storage slots that changed in the given range of transitions.
+    fn gather_changes<'tx, TX: DbTx<'tx>>(
+        &self,
+        tx: &TX,
+        range: Range<TransitionId>,
+    ) -> Result<BTreeMap<Address, BTreeSet<H256>>> {
+        let mut changes: BTreeMap<Address, BTreeSet<H256>> = BTreeMap::new();
+
+        let mut account_changes = tx.cursor_read::<tables::AccountChangeSet>()?;
+        for entry in account_changes.walk_range(range.clone())? {
+            let (_, account_before) = entry?;
+            changes.entry(account_before.address).or_default();
+        }
+
+        let mut storage_changes = tx.cursor_read::<tables::StorageChangeSet>()?;
+        let storage_range = TransitionIdAddress((range.start, Address::zero()))..
+            TransitionIdAddress((range.end, Address::zero()));
+        for entry in storage_changes.walk_range(storage_range)? {
+            let (key, storage_entry) = entry?;
+            changes.entry(key.address()).or_default().insert(storage_entry.key);
+        }
+
+        Ok(changes)
+    }
+}
+
+/// Merge the `changes` made on top of a state into the changes that lead to that state.
+pub fn merge_changes(
+    base: &mut BTreeMap<Address, AccountChanges>,
+    changes: &BTreeMap<Address, AccountChanges>,
+) {
+    for (address, changes) in changes {
+        match base.entry(*address) {
+            Entry::Vacant(entry) => {
+                entry.insert(changes.clone());
+            }
+            Entry::Occupied(mut entry) => {
+                let base = entry.get_mut();
+                base.account = changes.account;
+                if changes.wipe_storage {
+                    base.wipe_storage = true;
+                    base.storage = changes.storage.clone();
+                } else {
+                    base.storage.extend(changes.storage.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Returns the storage root of the account with the given hashed address in the state trie, which
+/// is the empty root if the account does not exist.
+fn storage_root_of<'a, 'tx, TX: DbTx<'tx>>(
+    accounts_trie: &AccountsTrie<'a, 'tx, TX>,
+    hashed_address: H256,
+) -> Result<H256> {
+    Ok(accounts_trie
+        .get(hashed_address)?
+        .map(|rlp| {
+            TrieAccount::decode(&mut rlp.as_slice())
+                .map(|account| account.storage_root)
+                .map_err(|_| ProviderError::TrieAccountDecode { hashed_address })
+        })
+        .transpose()?
+        .unwrap_or(EMPTY_ROOT))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HistoricalStateProviderRef, LatestStateProviderRef};
+    use reth_db::{
+        database::Database,
+        mdbx::test_utils::create_test_rw_db,
+        models::{storage_sharded_key::StorageShardedKey, AccountBeforeTx, ShardedKey},
+        TransitionList,
+    };
+    use reth_primitives::{proofs::genesis_state_root, GenesisAccount, StorageEntry};
+    use std::collections::HashMap;
+
+    fn random_accounts(n: u64) -> Vec<(Address, Account)> {
+        (0..n)
+            .map(|i| {
+                let account = Account {
+                    nonce: i,
+                    balance: U256::from(rand::random::<u64>()),
+                    bytecode_hash: None,
+                };
+                (Address::random(), account)
+            })
+            .collect()
+    }
+
+    /// Returns all stored nodes of the state trie and the storage tries.
+    fn trie_nodes<'tx, TX: DbTx<'tx>>(tx: &TX) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let mut nodes = tx
+            .cursor_read::<tables::AccountsTrie>()
+            .unwrap()
+            .walk(Vec::new())
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        nodes.extend(
+            tx.cursor_read::<tables::StoragesTrie>()
+                .unwrap()
+                .walk(Vec::new())
+                .unwrap()
+                .map(|entry| entry.unwrap()),
+        );
+        nodes
+    }
+
+    #[test]
+    fn empty_trie() {
+        let db = create_test_rw_db();
+        let tx = db.tx_mut().unwrap();
+
+        let mut trie = PatriciaTrie::<_, tables::AccountsTrie>::new(&tx, EMPTY_ROOT);
+        assert_eq!(trie.root(), EMPTY_ROOT);
+        assert_eq!(trie.commit(), Ok(EMPTY_ROOT));
+        assert_eq!(DBTrieLoader::default().calculate_root(&tx), Ok(EMPTY_ROOT));
+    }
+
+    #[test]
+    fn insert_remove_get() {
+        let db = create_test_rw_db();
+        let tx = db.tx_mut().unwrap();
+
+        let entries =
+            (0..100u8).map(|i| (H256::random(), vec![i; 1 + i as usize])).collect::<Vec<_>>();
+        let (kept, removed) = entries.split_at(60);
+
+        let mut expected = PatriciaTrie::<_, tables::StoragesTrie>::new(&tx, EMPTY_ROOT);
+        for (key, value) in kept {
+            expected.insert(*key, value.clone()).unwrap();
+        }
+
+        let mut trie = PatriciaTrie::<_, tables::StoragesTrie>::new(&tx, EMPTY_ROOT);
+        for (key, value) in entries.iter() {
+            trie.insert(*key, value.clone()).unwrap();
+        }
+        // reopen the trie from the database
+        let root = trie.commit().unwrap();
+        let mut trie = PatriciaTrie::<_, tables::StoragesTrie>::new(&tx, root);
+        for (key, value) in entries.iter() {
+            assert_eq!(trie.get(*key), Ok(Some(value.clone())));
+        }
+
+        for (key, _) in removed {
+            trie.remove(*key).unwrap();
+            assert_eq!(trie.get(*key), Ok(None));
+        }
+        assert_eq!(trie.root(), expected.root());
+    }
+
+    #[test]
+    fn state_root_matches_genesis_root() {
+        let db = create_test_rw_db();
+        let tx = db.tx_mut().unwrap();
+
+        let accounts = random_accounts(200);
+        for (address, account) in accounts.iter() {
+            tx.put::<tables::HashedAccount>(keccak256(address), *account).unwrap();
+        }
+
+        let alloc = accounts
+            .iter()
+            .map(|(address, account)| {
+                let account = GenesisAccount {
+                    nonce: Some(account.nonce),
+                    balance: account.balance,
+                    ..Default::default()
+                };
+                (*address, account)
+            })
+            .collect::<HashMap<_, _>>();
+
+        assert_eq!(DBTrieLoader::default().calculate_root(&tx), Ok(genesis_state_root(alloc)));
+    }
+
+    #[test]
+    fn incremental_update_matches_full_calculation() {
+        let db = create_test_rw_db();
+        let tx = db.tx_mut().unwrap();
+        let loader = DBTrieLoader::default();
+
+        let accounts = random_accounts(50);
+        for (address, account) in accounts.iter() {
+            let hashed_address = keccak256(address);
+            tx.put::<tables::HashedAccount>(hashed_address, *account).unwrap();
+            for slot in 1..4u64 {
+                let entry = StorageEntry {
+                    key: keccak256(H256::from_low_u64_be(slot)),
+                    value: U256::from(slot),
+                };
+                tx.put::<tables::HashedStorage>(hashed_address, entry).unwrap();
+            }
+        }
+        let root = loader.calculate_root(&tx).unwrap();
+
+        // Change an account, remove an account and change storage of a third one.
+        let (changed, _) = accounts[0];
+        tx.put::<tables::HashedAccount>(
+            keccak256(changed),
+            Account { nonce: 1000, balance: U256::ZERO, bytecode_hash: None },
+        )
+        .unwrap();
+        tx.put::<tables::AccountChangeSet>(1, AccountBeforeTx { address: changed, info: None })
+            .unwrap();
+
+        let (removed, _) = accounts[1];
+        tx.delete::<tables::HashedAccount>(keccak256(removed), None).unwrap();
+        tx.delete::<tables::HashedStorage>(keccak256(removed), None).unwrap();
+        tx.put::<tables::AccountChangeSet>(1, AccountBeforeTx { address: removed, info: None })
+            .unwrap();
+
+        let (storage_changed, _) = accounts[2];
+        let slot = H256::from_low_u64_be(1);
+        let old_entry = StorageEntry { key: keccak256(slot), value: U256::from(1) };
+        tx.delete::<tables::HashedStorage>(keccak256(storage_changed), Some(old_entry)).unwrap();
+        tx.put::<tables::HashedStorage>(
+            keccak256(storage_changed),
+            StorageEntry { key: keccak256(slot), value: U256::from(42) },
+        )
+        .unwrap();
+        tx.put::<tables::StorageChangeSet>(
+            (2, storage_changed).into(),
+            StorageEntry { key: slot, value: U256::from(1) },
+        )
+        .unwrap();
+
+        let updated_root = loader.update_root(&tx, 0..3).unwrap();
+        assert_ne!(updated_root, root);
+
+        // The updated trie holds exactly the nodes of the rebuilt one, without stale leftovers.
+        let updated_nodes = trie_nodes(&tx);
+        assert_eq!(updated_root, loader.calculate_root(&tx).unwrap());
+        assert_eq!(updated_nodes, trie_nodes(&tx));
+    }
+
+    #[test]
+    fn generate_proof() {
+        let db = create_test_rw_db();
+        let tx = db.tx_mut().unwrap();
+        let loader = DBTrieLoader::default();
+
+        let accounts = random_accounts(20);
+        let (address, account) = accounts[0];
+        for (address, account) in accounts.iter() {
+            tx.put::<tables::HashedAccount>(keccak256(address), *account).unwrap();
+        }
+        let slot = H256::from_low_u64_be(1);
+        let entry = StorageEntry { key: keccak256(slot), value: U256::from(1) };
+        tx.put::<tables::HashedStorage>(keccak256(address), entry).unwrap();
+        let root = loader.calculate_root(&tx).unwrap();
+
+        let (account_proof, storage_root, storage_proofs) =
+            loader.generate_proof(&tx, address, &[slot], &BTreeMap::new()).unwrap();
+        assert_eq!(keccak256(&account_proof[0]), root);
+        let leaf = account_proof.last().unwrap();
+        assert!(leaf.ends_with(&TrieAccount::new(account, storage_root).rlp()));
+        assert_eq!(storage_proofs.len(), 1);
+        assert_eq!(storage_proofs[0].last().unwrap().last(), Some(&1));
+
+        // Accounts that do not exist are proven absent and have an empty storage trie.
+        let (account_proof, storage_root, storage_proofs) =
+            loader.generate_proof(&tx, Address::random(), &[slot], &BTreeMap::new()).unwrap();
+        assert_eq!(keccak256(&account_proof[0]), root);
+        assert_eq!(storage_root, EMPTY_ROOT);
+        assert_eq!(storage_proofs, vec![Vec::<Bytes>::new()]);
+    }
+
+    #[test]
+    fn state_root_with_changes_matches_full_calculation() {
+        let db = create_test_rw_db();
+        let tx = db.tx_mut().unwrap();
+        let loader = DBTrieLoader::default();
+
+        let accounts = random_accounts(20);
+        for (address, account) in accounts.iter() {
+            let hashed_address = keccak256(address);
+            tx.put::<tables::HashedAccount>(hashed_address, *account).unwrap();
+            let entry =
+                StorageEntry { key: keccak256(H256::from_low_u64_be(1)), value: U256::from(1) };
+            tx.put::<tables::HashedStorage>(hashed_address, entry).unwrap();
+        }
+        let root = loader.calculate_root(&tx).unwrap();
+
+        // Change an account and its storage, destroy an account and create a new one.
+        let (changed, _) = accounts[0];
+        let changed_account = Account { nonce: 1000, balance: U256::ZERO, bytecode_hash: None };
+        let (destroyed, _) = accounts[1];
+        let created = Address::random();
+        let created_account = Account { nonce: 1, balance: U256::from(1), bytecode_hash: None };
+        let changes = BTreeMap::from([
+            (
+                changed,
+                AccountChanges {
+                    account: Some(changed_account),
+                    wipe_storage: false,
+                    storage: BTreeMap::from([
+                        (H256::from_low_u64_be(1), U256::ZERO),
+                        (H256::from_low_u64_be(2), U256::from(2)),
+                    ]),
+                },
+            ),
+            (destroyed, AccountChanges { account: None, ..Default::default() }),
+            (created, AccountChanges { account: Some(created_account), ..Default::default() }),
+        ]);
+        let post_root = loader.state_root_with_changes(&tx, &changes).unwrap();
+
+        // Apply the same changes to the hashed state and rebuild the trie.
+        tx.put::<tables::HashedAccount>(keccak256(changed), changed_account).unwrap();
+        tx.delete::<tables::HashedStorage>(keccak256(changed), None).unwrap();
+        let entry = StorageEntry { key: keccak256(H256::from_low_u64_be(2)), value: U256::from(2) };
+        tx.put::<tables::HashedStorage>(keccak256(changed), entry).unwrap();
+        tx.delete::<tables::HashedAccount>(keccak256(destroyed), None).unwrap();
+        tx.delete::<tables::HashedStorage>(keccak256(destroyed), None).unwrap();
+        tx.put::<tables::HashedAccount>(keccak256(created), created_account).unwrap();
+
+        assert_ne!(post_root, root);
+        assert_eq!(post_root, loader.calculate_root(&tx).unwrap());
+    }
+
+    #[test]
+    fn state_at_any_transition() {
+        let db = create_test_rw_db();
+        let tx = db.tx_mut().unwrap();
+        let loader = DBTrieLoader::default();
+
+        let (a, b) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2));
+        let slot = H256::from_low_u64_be(1);
+        let account = |nonce| Account { nonce, balance: U256::from(1), bytecode_hash: None };
+        let root_of = |accounts: &[(Address, Account, U256)]| {
+            genesis_state_root(
+                accounts
+                    .iter()
+                    .map(|(address, account, value)| {
+                        let mut storage = HashMap::new();
+                        if *value != U256::ZERO {
+                            storage.insert(slot, H256(value.to_be_bytes()));
+                        }
+                        let account = GenesisAccount {
+                            nonce: Some(account.nonce),
+                            balance: account.balance,
+                            code: None,
+                            storage: Some(storage),
+                        };
+                        (*address, account)
+                    })
+                    .collect(),
+            )
+        };
+
+        // Block 1 (transition 0) bumps the nonce of `b`, block 2 (transition 1) bumps the nonce
+        // of `a` and writes a storage slot of `b`. The trie is only built for block 1.
+        tx.put::<tables::BlockTransitionIndex>(0, 0).unwrap();
+        tx.put::<tables::BlockTransitionIndex>(1, 1).unwrap();
+        tx.put::<tables::BlockTransitionIndex>(2, 2).unwrap();
+        tx.put::<tables::AccountChangeSet>(
+            0,
+            AccountBeforeTx { address: b, info: Some(account(0)) },
+        )
+        .unwrap();
+        tx.put::<tables::AccountChangeSet>(
+            1,
+            AccountBeforeTx { address: a, info: Some(account(1)) },
+        )
+        .unwrap();
+        tx.put::<tables::StorageChangeSet>(
+            (1, b).into(),
+            StorageEntry { key: slot, value: U256::ZERO },
+        )
+        .unwrap();
+        tx.put::<tables::AccountHistory>(
+            ShardedKey::new(a, u64::MAX),
+            TransitionList::new([1]).unwrap(),
+        )
+        .unwrap();
+        tx.put::<tables::AccountHistory>(
+            ShardedKey::new(b, u64::MAX),
+            TransitionList::new([0]).unwrap(),
+        )
+        .unwrap();
+        tx.put::<tables::StorageHistory>(
+            StorageShardedKey::new(b, slot, u64::MAX),
+            TransitionList::new([1]).unwrap(),
+        )
+        .unwrap();
+
+        tx.put::<tables::PlainAccountState>(a, account(2)).unwrap();
+        tx.put::<tables::PlainAccountState>(b, account(1)).unwrap();
+        tx.put::<tables::PlainStorageState>(b, StorageEntry { key: slot, value: U256::from(5) })
+            .unwrap();
+
+        tx.put::<tables::HashedAccount>(keccak256(a), account(1)).unwrap();
+        tx.put::<tables::HashedAccount>(keccak256(b), account(1)).unwrap();
+        let trie_root = loader.calculate_root(&tx).unwrap();
+        MERKLE_EXECUTION.save_progress(&tx, 1).unwrap();
+        assert_eq!(trie_root, root_of(&[(a, account(1), U256::ZERO), (b, account(1), U256::ZERO)]));
+
+        // The latest state is ahead of the trie.
+        let latest = LatestStateProviderRef::new(&tx);
+        let latest_root = root_of(&[(a, account(2), U256::ZERO), (b, account(1), U256::from(5))]);
+        assert_eq!(latest.state_root_with_changes(&BTreeMap::new()), Ok(latest_root));
+        let (account_proof, storage_root, storage_proofs) = latest.proof(b, &[slot]).unwrap();
+        assert_eq!(keccak256(&account_proof[0]), latest_root);
+        assert!(account_proof
+            .last()
+            .unwrap()
+            .ends_with(&TrieAccount::new(account(1), storage_root).rlp()));
+        assert_eq!(storage_proofs[0].last().unwrap().last(), Some(&5));
+
+        // The state at the end of block 0 is behind the trie.
+        let historical = HistoricalStateProviderRef::new(&tx, 0);
+        let historical_root = root_of(&[(a, account(1), U256::ZERO), (b, account(0), U256::ZERO)]);
+        assert_eq!(historical.state_root_with_changes(&BTreeMap::new()), Ok(historical_root));
+        let (account_proof, storage_root, _) = historical.proof(b, &[slot]).unwrap();
+        assert_eq!(keccak256(&account_proof[0]), historical_root);
+        assert_eq!(storage_root, EMPTY_ROOT);
+
+        // Changes are applied on top of the state of the provider.
+        let changes = BTreeMap::from([(
+            a,
+            AccountChanges { account: Some(account(3)), ..Default::default() },
+        )]);
+        assert_eq!(
+            historical.state_root_with_changes(&changes),
+            Ok(root_of(&[(a, account(3), U256::ZERO), (b, account(0), U256::ZERO)]))
+        );
+    }
+}
@@ -0,0 +1,255 @@
+use reth_primitives::{keccak256, H256};
+use reth_rlp::{DecodeError, Encodable, Header, EMPTY_LIST_CODE, EMPTY_STRING_CODE};
+
+/// A node of the Merkle Patricia Trie.
+///
+/// All keys of the trie have the same length (they are `keccak256` hashes), so branch nodes never
+/// carry a value of their own.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) enum Node {
+    /// Empty subtrie.
+    #[default]
+    Empty,
+    /// Leaf node holding the remaining path of the key and the value.
+    Leaf {
+        /// Remaining nibbles of the key.
+        path: Vec<u8>,
+        /// The value stored under the key.
+        value: Vec<u8>,
+    },
+    /// Extension node sharing a common path between all of its descendants.
+    Extension {
+        /// Shared nibbles.
+        path: Vec<u8>,
+        /// The child node, always a branch.
+        child: Box<Node>,
+    },
+    /// Branch node with one child per nibble.
+    Branch {
+        /// Children indexed by nibble.
+        children: Box<[Node; 16]>,
+    },
+    /// Reference to a node that is stored in the database and was not loaded yet.
+    Hash(H256),
+}
+
+impl Node {
+    /// Returns the RLP encoding of the node, which is located at the nibble `path` of the trie.
+    ///
+    /// Descendants that encode to 32 bytes or more are referenced by their hash. Their encodings
+    /// are pushed to `hashed` together with their path, so they can be persisted.
+    pub(crate) fn encode(&self, path: &[u8], hashed: &mut Vec<(Vec<u8>, Vec<u8>)>) -> Vec<u8> {
+        match self {
+            Node::Empty => vec![EMPTY_STRING_CODE],
+            Node::Leaf { path: leaf_path, value } => {
+                encode_list(&[encode_bytes(&hex_prefix(leaf_path, true)), encode_bytes(value)])
+            }
+            Node::Extension { path: ext_path, child } => {
+                let child_path = [path, ext_path].concat();
+                encode_list(&[
+                    encode_bytes(&hex_prefix(ext_path, false)),
+                    child.reference(child_path, hashed),
+                ])
+            }
+            Node::Branch { children } => {
+                let mut items = children
+                    .iter()
+                    .zip(0u8..)
+                    .map(|(child, nibble)| child.reference([path, &[nibble]].concat(), hashed))
+                    .collect::<Vec<_>>();
+                // Branch value, always empty.
+                items.push(vec![EMPTY_STRING_CODE]);
+                encode_list(&items)
+            }
+            // A node that was not loaded can only be encoded as its reference.
+            Node::Hash(hash) => encode_bytes(hash.as_bytes()),
+        }
+    }
+
+    /// Returns the encoding the parent uses to refer to this node: either the node itself if it
+    /// encodes to less than 32 bytes, or the RLP encoded hash of the node.
+    fn reference(&self, path: Vec<u8>, hashed: &mut Vec<(Vec<u8>, Vec<u8>)>) -> Vec<u8> {
+        match self {
+            Node::Hash(hash) => encode_bytes(hash.as_bytes()),
+            node => {
+                let rlp = node.encode(&path, hashed);
+                if rlp.len() < 32 {
+                    return rlp
+                }
+                let hash = keccak256(&rlp);
+                hashed.push((path, rlp));
+                encode_bytes(hash.as_bytes())
+            }
+        }
+    }
+
+    /// Decodes a node from its RLP encoding.
+    pub(crate) fn decode(buf: &[u8]) -> Result<Self, DecodeError> {
+        let items = list_items(buf)?;
+        match items.len() {
+            2 => {
+                let (path, is_leaf) = decode_hex_prefix(string_payload(items[0])?)
+                    .ok_or(DecodeError::Custom("invalid hex prefix encoding"))?;
+                if is_leaf {
+                    Ok(Node::Leaf { path, value: string_payload(items[1])?.to_vec() })
+                } else {
+                    Ok(Node::Extension { path, child: Box::new(Node::decode_reference(items[1])?) })
+                }
+            }
+            17 => {
+                if !string_payload(items[16])?.is_empty() {
+                    return Err(DecodeError::Custom("branch node values are not supported"))
+                }
+                let mut children: Box<[Node; 16]> = Default::default();
+                for (child, item) in children.iter_mut().zip(items) {
+                    *child = Node::decode_reference(item)?;
+                }
+                Ok(Node::Branch { children })
+            }
+            _ => Err(DecodeError::Custom("invalid number of trie node items")),
+        }
+    }
+
+    /// Decodes a child reference, which is either an inlined node, an empty string or a hash.
+    fn decode_reference(item: &[u8]) -> Result<Self, DecodeError> {
+        if item.first().map_or(false, |b| *b >= EMPTY_LIST_CODE) {
+            return Node::decode(item)
+        }
+        let payload = string_payload(item)?;
+        match payload.len() {
+            0 => Ok(Node::Empty),
+            32 => Ok(Node::Hash(H256::from_slice(payload))),
+            _ => Err(DecodeError::Custom("invalid trie node reference")),
+        }
+    }
+}
+
+/// Wraps `node` into an extension with the given path, unless the path is empty.
+pub(crate) fn extension(path: Vec<u8>, node: Node) -> Node {
+    if path.is_empty() {
+        node
+    } else {
+        Node::Extension { path, child: Box::new(node) }
+    }
+}
+
+/// Splits the key into nibbles.
+pub(crate) fn to_nibbles(key: &[u8]) -> Vec<u8> {
+    key.iter().flat_map(|b| [b >> 4, b & 0x0f]).collect()
+}
+
+/// Returns the length of the common prefix of two nibble paths.
+pub(crate) fn common_prefix(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+/// Compact (hex-prefix) encoding of a nibble path, see Appendix C of the yellow paper.
+fn hex_prefix(path: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = if is_leaf { 2 } else { 0 };
+    let mut out = Vec::with_capacity(path.len() / 2 + 1);
+    let rest = if path.len() % 2 == 1 {
+        out.push(((flag + 1) << 4) | path[0]);
+        &path[1..]
+    } else {
+        out.push(flag << 4);
+        path
+    };
+    out.extend(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+    out
+}
+
+/// Decodes a hex-prefix encoded path, returning the nibbles and whether the path belongs to a
+/// leaf.
+fn decode_hex_prefix(bytes: &[u8]) -> Option<(Vec<u8>, bool)> {
+    let first = *bytes.first()?;
+    let flag = first >> 4;
+    if flag > 3 {
+        return None
+    }
+    let mut path = Vec::with_capacity(bytes.len() * 2);
+    if flag & 1 == 1 {
+        path.push(first & 0x0f);
+    }
+    path.extend(bytes[1..].iter().flat_map(|b| [b >> 4, b & 0x0f]));
+    Some((path, flag & 2 == 2))
+}
+
+fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len() + 3);
+    bytes.encode(&mut out);
+    out
+}
+
+fn encode_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload_length = items.iter().map(Vec::len).sum();
+    let mut out = Vec::with_capacity(payload_length + 3);
+    Header { list: true, payload_length }.encode(&mut out);
+    items.iter().for_each(|item| out.extend_from_slice(item));
+    out
+}
+
+/// Splits an RLP list into the raw encodings of its items.
+fn list_items(buf: &[u8]) -> Result<Vec<&[u8]>, DecodeError> {
+    let mut payload = buf;
+    let header = Header::decode(&mut payload)?;
+    if !header.list {
+        return Err(DecodeError::UnexpectedString)
+    }
+    let mut payload = payload.get(..header.payload_length).ok_or(DecodeError::InputTooShort)?;
+
+    let mut items = Vec::with_capacity(17);
+    while !payload.is_empty() {
+        let mut rest = payload;
+        let item_header = Header::decode(&mut rest)?;
+        let len = payload.len() - rest.len() + item_header.payload_length;
+        items.push(payload.get(..len).ok_or(DecodeError::InputTooShort)?);
+        payload = &payload[len..];
+    }
+    Ok(items)
+}
+
+/// Returns the payload of an RLP string.
+fn string_payload(item: &[u8]) -> Result<&[u8], DecodeError> {
+    let mut payload = item;
+    let header = Header::decode(&mut payload)?;
+    if header.list {
+        return Err(DecodeError::UnexpectedList)
+    }
+    payload.get(..header.payload_length).ok_or(DecodeError::InputTooShort)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_prefix_roundtrip() {
+        for (path, is_leaf) in [
+            (vec![], false),
+            (vec![1], true),
+            (vec![1, 2, 3, 4], false),
+            (vec![0, 15, 1, 12, 11, 8], true),
+        ] {
+            let encoded = hex_prefix(&path, is_leaf);
+            assert_eq!(decode_hex_prefix(&encoded), Some((path, is_leaf)));
+        }
+    }
+
+    #[test]
+    fn node_roundtrip() {
+        let leaf = Node::Leaf { path: vec![1, 2, 3], value: vec![0x42; 40] };
+        let mut children: Box<[Node; 16]> = Default::default();
+        children[3] = Node::Hash(H256::repeat_byte(0x11));
+        // Small leaf that is inlined into its parent.
+        children[7] = Node::Leaf { path: vec![5], value: vec![1] };
+        let branch = Node::Branch { children };
+        let ext = extension(vec![4, 5], Node::Hash(H256::repeat_byte(0x22)));
+
+        for node in [leaf, branch, ext] {
+            let mut hashed = Vec::new();
+            let encoded = node.encode(&[], &mut hashed);
+            assert!(hashed.is_empty());
+            assert_eq!(Node::decode(&encoded), Ok(node));
+        }
+    }
+}
@@ -1,10 +1,90 @@
 use reth_db::{
     models::{BlockNumHash, StoredBlockBody, StoredBlockOmmers},
+    static_file::StaticFileSegment,
     tables,
     transaction::{DbTx, DbTxMut},
+    Error as DbError,
 };
 use reth_interfaces::{provider::Error as ProviderError, Result};
-use reth_primitives::SealedBlock;
+use reth_primitives::{BlockHash, BlockNumber, PruneSegment, SealedBlock};
+
+/// A block of the forkchoice state that is persisted in [tables::ForkchoiceMarkers].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ForkchoiceMarker {
+    /// The latest block that is considered safe by the consensus layer.
+    Safe,
+    /// The latest finalized block.
+    Finalized,
+}
+
+impl ForkchoiceMarker {
+    fn key(&self) -> Vec<u8> {
+        match self {
+            ForkchoiceMarker::Safe => b"Safe".to_vec(),
+            ForkchoiceMarker::Finalized => b"Finalized".to_vec(),
+        }
+    }
+
+    /// Get the hash of the block this marker points to.
+    pub fn get<'db>(&self, tx: &impl DbTx<'db>) -> std::result::Result<Option<BlockHash>, DbError> {
+        tx.get::<tables::ForkchoiceMarkers>(self.key())
+    }
+
+    /// Get the number of the block this marker points to.
+    ///
+    /// Returns `None` if the marker is not set or the block is not part of the canonical chain.
+    pub fn block_number<'db>(
+        &self,
+        tx: &impl DbTx<'db>,
+    ) -> std::result::Result<Option<BlockNumber>, DbError> {
+        let Some(hash) = self.get(tx)? else { return Ok(None) };
+        let Some(number) = tx.get::<tables::HeaderNumbers>(hash)? else { return Ok(None) };
+        Ok((tx.get::<tables::CanonicalHeaders>(number)? == Some(hash)).then_some(number))
+    }
+
+    /// Point this marker to the block with the given hash.
+    pub fn save<'db>(
+        &self,
+        tx: &impl DbTxMut<'db>,
+        hash: BlockHash,
+    ) -> std::result::Result<(), DbError> {
+        tx.put::<tables::ForkchoiceMarkers>(self.key(), hash)
+    }
+}
+
+/// Get the highest block of the segment that was pruned.
+pub fn prune_checkpoint<'db>(
+    tx: &impl DbTx<'db>,
+    segment: PruneSegment,
+) -> std::result::Result<Option<BlockNumber>, DbError> {
+    tx.get::<tables::PruneCheckpoints>(segment.as_str().as_bytes().to_vec())
+}
+
+/// Save the highest block of the segment that was pruned.
+pub fn save_prune_checkpoint<'db>(
+    tx: &impl DbTxMut<'db>,
+    segment: PruneSegment,
+    block: BlockNumber,
+) -> std::result::Result<(), DbError> {
+    tx.put::<tables::PruneCheckpoints>(segment.as_str().as_bytes().to_vec(), block)
+}
+
+/// Get the highest block of the static file segment that was moved out of the database.
+pub fn static_file_checkpoint<'db>(
+    tx: &impl DbTx<'db>,
+    segment: StaticFileSegment,
+) -> std::result::Result<Option<BlockNumber>, DbError> {
+    tx.get::<tables::StaticFileCheckpoints>(segment.as_str().as_bytes().to_vec())
+}
+
+/// Save the highest block of the static file segment that was moved out of the database.
+pub fn save_static_file_checkpoint<'db>(
+    tx: &impl DbTxMut<'db>,
+    segment: StaticFileSegment,
+    block: BlockNumber,
+) -> std::result::Result<(), DbError> {
+    tx.put::<tables::StaticFileCheckpoints>(segment.as_str().as_bytes().to_vec(), block)
+}
 
 /// Insert block data into corresponding tables. Used mainly for testing & internal tooling.
 ///
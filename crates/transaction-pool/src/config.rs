@@ -1,6 +1,21 @@
+use std::time::Duration;
+
 /// Guarantees max transactions for one sender, compatible with geth/erigon
 pub(crate) const MAX_ACCOUNT_SLOTS_PER_SENDER: usize = 16;
 
+/// Default price bump (in %) required to replace a transaction, compatible with geth
+pub const DEFAULT_PRICE_BUMP: u128 = 10;
+
+/// Default minimum gas price (in wei) of non-local transactions, compatible with geth
+pub const DEFAULT_MINIMAL_GAS_PRICE: u128 = 1;
+
+/// Default max number of new transactions a single peer can submit to the pool per
+/// [DEFAULT_PEER_IMPORT_WINDOW].
+pub const DEFAULT_MAX_PEER_IMPORTS: usize = 4096;
+
+/// Default window in which the transactions a single peer submits to the pool are counted.
+pub const DEFAULT_PEER_IMPORT_WINDOW: Duration = Duration::from_secs(12);
+
 ///! Configuration options for the Transaction pool.
 #[derive(Debug, Clone)]
 pub struct PoolConfig {
@@ -12,6 +27,19 @@ pub struct PoolConfig {
     pub queued_limit: SubPoolLimit,
     /// Max number of executable transaction slots guaranteed per account
     pub max_account_slots: usize,
+    /// Minimum price bump (in %) a transaction needs to replace an existing transaction with the
+    /// same sender and nonce.
+    ///
+    /// This applies to the gas price of legacy transactions, and to both the max fee and the max
+    /// priority fee of EIP-1559 transactions.
+    pub price_bump: u128,
+    /// Minimum gas price (in wei) of non-local transactions.
+    ///
+    /// This applies to the gas price of legacy transactions, and to the max priority fee of
+    /// EIP-1559 transactions. Local transactions are exempt.
+    pub minimal_gas_price: u128,
+    /// Max number of new transactions a single peer can submit to the pool per time window.
+    pub peer_import_limit: PeerImportLimit,
 }
 
 impl Default for PoolConfig {
@@ -21,6 +49,9 @@ impl Default for PoolConfig {
             basefee_limit: Default::default(),
             queued_limit: Default::default(),
             max_account_slots: MAX_ACCOUNT_SLOTS_PER_SENDER,
+            price_bump: DEFAULT_PRICE_BUMP,
+            minimal_gas_price: DEFAULT_MINIMAL_GAS_PRICE,
+            peer_import_limit: Default::default(),
         }
     }
 }
@@ -48,3 +79,20 @@ impl Default for SubPoolLimit {
         Self { max_txs: 10_000, max_size: 20 * 1024 * 1024 }
     }
 }
+
+/// Rate limit for the new transactions a single peer submits to the pool.
+///
+/// Transactions the peer sends after it reached the limit are dropped until the window ends.
+#[derive(Debug, Clone)]
+pub struct PeerImportLimit {
+    /// Maximum amount of transactions per window.
+    pub max_txs: usize,
+    /// The window in which the transactions are counted.
+    pub window: Duration,
+}
+
+impl Default for PeerImportLimit {
+    fn default() -> Self {
+        Self { max_txs: DEFAULT_MAX_PEER_IMPORTS, window: DEFAULT_PEER_IMPORT_WINDOW }
+    }
+}
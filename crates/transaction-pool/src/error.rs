@@ -1,6 +1,6 @@
 //! Transaction pool errors
 
-use reth_primitives::{Address, TxHash};
+use reth_primitives::{Address, TxHash, U256};
 
 /// Transaction pool result type.
 pub type PoolResult<T> = Result<T, PoolError>;
@@ -8,12 +8,22 @@ pub type PoolResult<T> = Result<T, PoolError>;
 /// All errors the Transaction pool can throw.
 #[derive(Debug, thiserror::Error)]
 pub enum PoolError {
-    /// Thrown if a replacement transaction's gas price is below the already imported transaction
-    #[error("[{0:?}]: insufficient gas price to replace existing transaction.")]
-    ReplacementUnderpriced(TxHash),
+    /// Thrown if a replacement transaction's fees are not bumped enough compared to the already
+    /// imported transaction.
+    ///
+    /// Contains the hash of the rejected transaction, the hash of the existing transaction, and
+    /// the minimum max fee (gas price for legacy transactions) and the minimum max priority fee
+    /// that are required to replace the existing transaction.
+    #[error("[{0:?}]: insufficient gas price to replace existing transaction {1:?}, requires a max fee of at least {2} and a max priority fee of at least {3}.")]
+    ReplacementUnderpriced(TxHash, TxHash, u128, u128),
     /// Encountered a transaction that was already added into the poll
     #[error("[{0:?}] Transaction feeCap {1} below chain minimum.")]
     ProtocolFeeCapTooLow(TxHash, u128),
+    /// Thrown if the gas price of a non-local transaction is below the configured minimum.
+    ///
+    /// For EIP-1559 transactions this is the max priority fee.
+    #[error("[{0:?}] Transaction's gas price {1} is below the pool's minimum {2}.")]
+    GasPriceBelowMinimum(TxHash, u128, u128),
     /// Thrown when the number of unique transactions of a sender exceeded the slot capacity.
     #[error("{0:?} identified as spammer. Transaction {1:?} rejected.")]
     SpammerExceededCapacity(Address, TxHash),
@@ -29,6 +39,27 @@ pub enum PoolError {
     /// respect the max_init_code_size.
     #[error("[{0:?}] Transaction's size {1} exceeds max_init_code_size {2}.")]
     TxExceedsMaxInitCodeSize(TxHash, usize, usize),
+    /// Thrown if the transaction was signed for another chain.
+    #[error("[{0:?}] Transaction's chain id {1} does not match the chain id {2}.")]
+    InvalidChainId(TxHash, u64, u64),
+    /// Thrown if the transaction's gas limit does not cover its intrinsic gas.
+    #[error("[{0:?}] Transaction's gas limit {1} is below the intrinsic gas {2}.")]
+    IntrinsicGasTooLow(TxHash, u64, u64),
+    /// Thrown if the EIP-1559 priority fee of the transaction is higher than its fee cap.
+    #[error("[{0:?}] Transaction's max priority fee {1} exceeds its max fee {2}.")]
+    TipAboveFeeCap(TxHash, u128, u128),
+    /// Thrown if the signature of the transaction does not recover to its sender.
+    #[error("[{0:?}] Transaction's signature does not match the sender {1:?}.")]
+    InvalidSender(TxHash, Address),
+    /// Thrown if the nonce of the transaction is lower than the nonce of the sender's account.
+    #[error("[{0:?}] Transaction's nonce {1} is below the sender's nonce {2}.")]
+    NonceTooLow(TxHash, u64, u64),
+    /// Thrown if the sender's balance does not cover the cost of the transaction.
+    #[error("[{0:?}] Transaction's cost {1} exceeds the sender's balance {2}.")]
+    InsufficientFunds(TxHash, U256, U256),
+    /// Thrown if the state required to validate the transaction could not be read.
+    #[error("[{0:?}] Failed to read the state: {1}")]
+    Provider(TxHash, reth_interfaces::Error),
 }
 
 // === impl PoolError ===
@@ -37,12 +68,20 @@ impl PoolError {
     /// Returns the hash of the transaction that resulted in this error.
     pub fn hash(&self) -> &TxHash {
         match self {
-            PoolError::ReplacementUnderpriced(hash) => hash,
+            PoolError::ReplacementUnderpriced(hash, _, _, _) => hash,
             PoolError::ProtocolFeeCapTooLow(hash, _) => hash,
+            PoolError::GasPriceBelowMinimum(hash, _, _) => hash,
             PoolError::SpammerExceededCapacity(_, hash) => hash,
             PoolError::DiscardedOnInsert(hash) => hash,
             PoolError::TxExceedsGasLimit(hash, _, _) => hash,
             PoolError::TxExceedsMaxInitCodeSize(hash, _, _) => hash,
+            PoolError::InvalidChainId(hash, _, _) => hash,
+            PoolError::IntrinsicGasTooLow(hash, _, _) => hash,
+            PoolError::TipAboveFeeCap(hash, _, _) => hash,
+            PoolError::InvalidSender(hash, _) => hash,
+            PoolError::NonceTooLow(hash, _, _) => hash,
+            PoolError::InsufficientFunds(hash, _, _) => hash,
+            PoolError::Provider(hash, _) => hash,
         }
     }
 }
@@ -0,0 +1,305 @@
+//! Journal of local transactions that keeps them in the pool across restarts.
+
+use crate::{TransactionOrigin, TransactionPool};
+use reth_primitives::{FromRecoveredTransaction, IntoRecoveredTransaction, TransactionSigned};
+use reth_rlp::{Decodable, Encodable};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, ErrorKind, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::time::{interval_at, Instant, MissedTickBehavior};
+use tracing::{debug, info, warn};
+
+/// The default interval at which the journal is rotated.
+pub const DEFAULT_JOURNAL_ROTATION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// An on-disk journal of local transactions.
+///
+/// The journal is a file of concatenated RLP encoded [TransactionSigned]s, in the same format as
+/// the transactions of the `Transactions` p2p message. New transactions are appended to the
+/// journal, and the journal is periodically rewritten with only the transactions that are still in
+/// the pool.
+#[derive(Debug, Clone)]
+pub struct TransactionJournal {
+    /// The path of the journal file.
+    path: PathBuf,
+}
+
+impl TransactionJournal {
+    /// Creates a journal that is stored at the given path.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the path of the journal file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads all transactions of the journal.
+    ///
+    /// A missing journal is treated as empty. If the journal is corrupted, for example because the
+    /// node crashed while appending to it, all transactions up to the corrupted entry are returned.
+    pub fn load(&self) -> io::Result<Vec<TransactionSigned>> {
+        let data = match fs::read(&self.path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+
+        let mut buf = data.as_slice();
+        let mut transactions = Vec::new();
+        while !buf.is_empty() {
+            match TransactionSigned::decode(&mut buf) {
+                Ok(transaction) => transactions.push(transaction),
+                Err(err) => {
+                    warn!(target: "txpool::journal", path = %self.path.display(), ?err, "Dropping corrupted journal entries");
+                    break
+                }
+            }
+        }
+        Ok(transactions)
+    }
+
+    /// Appends the transaction to the journal.
+    pub fn insert(&self, transaction: &TransactionSigned) -> io::Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        let mut buf = Vec::with_capacity(transaction.length());
+        transaction.encode(&mut buf);
+        file.write_all(&buf)
+    }
+
+    /// Replaces the content of the journal with the given transactions.
+    ///
+    /// The new journal is written to a temporary file first, so the old journal stays intact if
+    /// this fails.
+    pub fn rotate<'a>(
+        &self,
+        transactions: impl IntoIterator<Item = &'a TransactionSigned>,
+    ) -> io::Result<()> {
+        let tmp_path = self.path.with_extension("new");
+
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        let mut buf = Vec::new();
+        for transaction in transactions {
+            buf.clear();
+            transaction.encode(&mut buf);
+            writer.write_all(&buf)?;
+        }
+        writer.into_inner().map_err(|err| err.into_error())?.sync_all()?;
+
+        fs::rename(tmp_path, &self.path)
+    }
+}
+
+/// Keeps the local transactions of the pool in the given journal.
+///
+/// On startup, all transactions of the journal are validated and put back into the pool as local
+/// transactions. Afterwards, every new local transaction is appended to the journal, and every
+/// `rotation_interval` the journal is rewritten with the local transactions that are currently in
+/// the pool, dropping those that were mined or discarded. Since the journal is rebuilt from the
+/// pool, this also recovers local transactions that were missed by a lagging
+/// [TransactionPool::transactions_listener].
+///
+/// The journal is accessed on the blocking thread pool, see [tokio::task::spawn_blocking].
+///
+/// Returns once the pool no longer emits new transactions.
+pub async fn journal_local_transactions<Pool>(
+    pool: Pool,
+    journal: TransactionJournal,
+    rotation_interval: Duration,
+) where
+    Pool: TransactionPool,
+{
+    // subscribe first, so no local transaction is missed while the journal is replayed
+    let mut new_transactions = pool.transactions_listener();
+
+    replay_journal(&pool, &journal).await;
+    // the rotation covers all transactions that were added so far, including the replayed ones
+    while new_transactions.try_recv().is_ok() {}
+    // drop the transactions that failed validation
+    rotate_journal(&pool, &journal).await;
+
+    let mut rotation = interval_at(Instant::now() + rotation_interval, rotation_interval);
+    rotation.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            event = new_transactions.recv() => {
+                let Some(event) = event else { return };
+                if !event.transaction.is_local() {
+                    continue
+                }
+                let transaction =
+                    event.transaction.transaction.to_recovered_transaction().into_signed();
+                let journal = journal.clone();
+                if let Err(err) = blocking(move || journal.insert(&transaction)).await {
+                    warn!(target: "txpool::journal", ?err, "Failed to journal local transaction");
+                }
+            }
+            _ = rotation.tick() => rotate_journal(&pool, &journal).await,
+        }
+    }
+}
+
+/// Validates all transactions of the journal and adds them to the pool.
+///
+/// Returns the transactions that were accepted by the pool.
+async fn replay_journal<Pool>(pool: &Pool, journal: &TransactionJournal) -> Vec<TransactionSigned>
+where
+    Pool: TransactionPool,
+{
+    let path = journal.path().to_path_buf();
+    let journal = journal.clone();
+    let journaled = match blocking(move || journal.load()).await {
+        Ok(journaled) => journaled,
+        Err(err) => {
+            warn!(target: "txpool::journal", path = %path.display(), ?err, "Failed to load journal");
+            return Vec::new()
+        }
+    };
+    let total = journaled.len();
+
+    let (signed, transactions): (Vec<_>, Vec<_>) = journaled
+        .into_iter()
+        .filter_map(|tx| tx.into_ecrecovered())
+        .map(|tx| (tx.clone().into_signed(), Pool::Transaction::from_recovered_transaction(tx)))
+        .unzip();
+    if transactions.is_empty() {
+        return Vec::new()
+    }
+
+    let results = match pool.add_transactions(TransactionOrigin::Local, transactions).await {
+        Ok(results) => results,
+        Err(err) => {
+            warn!(target: "txpool::journal", ?err, "Failed to replay journal");
+            return Vec::new()
+        }
+    };
+    let replayed = signed
+        .into_iter()
+        .zip(results)
+        .filter_map(|(tx, result)| result.ok().map(|_| tx))
+        .collect::<Vec<_>>();
+
+    info!(target: "txpool::journal", total, replayed = replayed.len(), "Replayed local transactions");
+    replayed
+}
+
+/// Rewrites the journal with the local transactions that are currently in the pool.
+async fn rotate_journal<Pool>(pool: &Pool, journal: &TransactionJournal)
+where
+    Pool: TransactionPool,
+{
+    let local = local_transactions(pool);
+    let count = local.len();
+    let journal = journal.clone();
+    match blocking(move || journal.rotate(&local)).await {
+        Ok(()) => debug!(target: "txpool::journal", count, "Rotated journal"),
+        Err(err) => warn!(target: "txpool::journal", ?err, "Failed to rotate journal"),
+    }
+}
+
+/// Returns the local transactions of the pool, ordered by sender and nonce.
+fn local_transactions<Pool>(pool: &Pool) -> Vec<TransactionSigned>
+where
+    Pool: TransactionPool,
+{
+    pool.pending_transactions()
+        .into_iter()
+        .chain(pool.queued_transactions())
+        .filter(|tx| tx.is_local())
+        .map(|tx| tx.transaction.to_recovered_transaction().into_signed())
+        .collect()
+}
+
+/// Runs the journal operation on the blocking thread pool.
+async fn blocking<F, R>(f: F) -> io::Result<R>
+where
+    F: FnOnce() -> io::Result<R> + Send + 'static,
+    R: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .unwrap_or_else(|err| Err(io::Error::new(ErrorKind::Other, err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::{testing_pool, NoopTransactionValidator},
+        CostOrdering, Pool, PooledTransaction,
+    };
+    use reth_interfaces::test_utils::generators::random_signed_tx;
+    use std::sync::Arc;
+
+    #[test]
+    fn insert_load_rotate() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = TransactionJournal::new(dir.path().join("transactions.rlp"));
+        assert!(journal.load().unwrap().is_empty());
+
+        let transactions = (0..3).map(|_| random_signed_tx()).collect::<Vec<_>>();
+        for tx in &transactions {
+            journal.insert(tx).unwrap();
+        }
+        assert_eq!(journal.load().unwrap(), transactions);
+
+        journal.rotate(&transactions[1..]).unwrap();
+        assert_eq!(journal.load().unwrap(), transactions[1..]);
+    }
+
+    #[test]
+    fn load_corrupted() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = TransactionJournal::new(dir.path().join("transactions.rlp"));
+
+        let tx = random_signed_tx();
+        journal.insert(&tx).unwrap();
+        OpenOptions::new().append(true).open(journal.path()).unwrap().write_all(&[0xf8]).unwrap();
+
+        assert_eq!(journal.load().unwrap(), vec![tx]);
+    }
+
+    #[tokio::test]
+    async fn replays_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = TransactionJournal::new(dir.path().join("transactions.rlp"));
+        let tx = random_signed_tx();
+        journal.insert(&tx).unwrap();
+
+        let pool = testing_pool();
+        let replayed = replay_journal(&pool, &journal).await;
+
+        assert_eq!(replayed, vec![tx.clone()]);
+        assert!(pool.get(&tx.hash()).unwrap().is_local());
+    }
+
+    #[tokio::test]
+    async fn rotates_to_local_pool_transactions() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = TransactionJournal::new(dir.path().join("transactions.rlp"));
+        let (local, external, mined) = (random_signed_tx(), random_signed_tx(), random_signed_tx());
+        journal.insert(&mined).unwrap();
+
+        let pool = Pool::new(
+            Arc::new(NoopTransactionValidator::<PooledTransaction>::default()),
+            Arc::new(CostOrdering::default()),
+            Default::default(),
+        );
+        for (origin, tx) in
+            [(TransactionOrigin::Local, &local), (TransactionOrigin::External, &external)]
+        {
+            let tx = PooledTransaction::from_recovered_transaction(
+                tx.clone().into_ecrecovered().unwrap(),
+            );
+            pool.add_transaction(origin, tx).await.unwrap();
+        }
+        rotate_journal(&pool, &journal).await;
+
+        assert_eq!(journal.load().unwrap(), vec![local]);
+    }
+}
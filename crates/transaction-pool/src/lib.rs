@@ -45,7 +45,8 @@
 //! ### Validation
 //!
 //! The pool itself does not validate incoming transactions, instead this should be provided by
-//! implementing `TransactionsValidator`. Only transactions that the validator returns as valid are
+//! implementing `TransactionsValidator`. [`EthTransactionValidator`] validates transactions against
+//! the latest state of the chain. Only transactions that the validator returns as valid are
 //! included in the pool. It is assumed that transaction that are in the pool are either valid on
 //! the current state or could become valid after certain state changes. transaction that can never
 //! become valid (e.g. nonce lower than current on chain nonce) will never be added to the pool and
@@ -59,6 +60,19 @@
 //!   - update using account changes: balance changes
 //!   - base fee updates
 //!
+//! [`maintain_transaction_pool`](crate::maintain::maintain_transaction_pool) performs these updates
+//! for every canonical chain event and puts the transactions of unwound blocks back into the pool.
+//!
+//! ### Local transactions
+//!
+//! Local transactions can be persisted across restarts with a
+//! [`TransactionJournal`](crate::journal::TransactionJournal), see
+//! [`journal_local_transactions`](crate::journal::journal_local_transactions).
+//!
+//! Local transactions are also exempt from the spam protection of the pool: they are neither
+//! limited by the per-sender slot capacity nor by the minimal gas price, and they are only evicted
+//! if a sub-pool exceeds its size limits with local transactions alone.
+//!
 //! ## Implementation details
 //!
 //! The `TransactionPool` trait exposes all externally used functionality of the pool, such as
@@ -80,27 +94,34 @@
 //! that provides the `TransactionPool` interface.
 
 pub use crate::{
-    config::PoolConfig,
-    ordering::TransactionOrdering,
+    config::{
+        PeerImportLimit, PoolConfig, DEFAULT_MAX_PEER_IMPORTS, DEFAULT_MINIMAL_GAS_PRICE,
+        DEFAULT_PEER_IMPORT_WINDOW, DEFAULT_PRICE_BUMP,
+    },
+    ordering::{CostOrdering, TransactionOrdering},
     traits::{
-        BestTransactions, OnNewBlockEvent, PoolTransaction, PropagateKind, PropagatedTransactions,
-        TransactionOrigin, TransactionPool,
+        BestTransactions, ChangedAccount, OnNewBlockEvent, PoolTransaction, PooledTransaction,
+        PropagateKind, PropagatedTransactions, TransactionOrigin, TransactionPool,
+    },
+    validate::{
+        EthTransactionValidator, TransactionValidationOutcome, TransactionValidator,
+        ValidPoolTransaction, MAX_INIT_CODE_SIZE,
     },
-    validate::{TransactionValidationOutcome, TransactionValidator},
 };
 use crate::{
     error::PoolResult,
-    pool::PoolInner,
+    pool::{PoolInner, TransactionEvent},
     traits::{NewTransactionEvent, PoolSize},
-    validate::ValidPoolTransaction,
 };
 use reth_primitives::{TxHash, U256};
 use std::{collections::HashMap, sync::Arc};
-use tokio::sync::mpsc::Receiver;
+use tokio::sync::mpsc::{Receiver, UnboundedReceiver};
 
 mod config;
 pub mod error;
 mod identifier;
+pub mod journal;
+pub mod maintain;
 pub mod metrics;
 mod ordering;
 pub mod pool;
@@ -111,6 +132,11 @@ mod validate;
 /// Common test helpers for mocking A pool
 pub mod test_utils;
 
+/// A [Pool] of [PooledTransaction]s that are validated against the state of the chain with the
+/// [EthTransactionValidator] and ordered by their cost.
+pub type EthTransactionPool<Client> =
+    Pool<EthTransactionValidator<Client, PooledTransaction>, CostOrdering<PooledTransaction>>;
+
 /// A shareable, generic, customizable `TransactionPool` implementation.
 #[derive(Debug)]
 pub struct Pool<V: TransactionValidator, T: TransactionOrdering> {
@@ -135,11 +161,6 @@ where
         &self.pool
     }
 
-    /// Get the config the pool was configured with.
-    pub fn config(&self) -> &PoolConfig {
-        self.inner().config()
-    }
-
     /// Returns future that validates all transaction in the given iterator.
     async fn validate_all(
         &self,
@@ -170,6 +191,17 @@ where
         (hash, outcome)
     }
 
+    /// Returns a new stream of [TransactionEvent]s of the given transaction, for example when it is
+    /// mined or replaced by a transaction with the same sender and nonce.
+    ///
+    /// Returns `None` if the transaction is not in the pool.
+    pub fn add_transaction_event_listener(
+        &self,
+        tx_hash: TxHash,
+    ) -> Option<UnboundedReceiver<TransactionEvent>> {
+        self.pool.add_transaction_event_listener(tx_hash)
+    }
+
     /// Number of transactions in the entire pool
     pub fn len(&self) -> usize {
         self.pool.len()
@@ -190,6 +222,10 @@ where
 {
     type Transaction = T::Transaction;
 
+    fn config(&self) -> &PoolConfig {
+        self.inner().config()
+    }
+
     fn status(&self) -> PoolSize {
         self.pool.size()
     }
@@ -235,6 +271,14 @@ where
         Box::new(self.pool.best_transactions())
     }
 
+    fn pending_transactions(&self) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        self.pool.pending_transactions()
+    }
+
+    fn queued_transactions(&self) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        self.pool.queued_transactions()
+    }
+
     fn remove_invalid(
         &self,
         hashes: impl IntoIterator<Item = TxHash>,
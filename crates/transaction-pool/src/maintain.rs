@@ -0,0 +1,310 @@
+//! Support for keeping the pool in sync with the canonical chain.
+
+use crate::{traits::ChangedAccount, OnNewBlockEvent, TransactionOrigin, TransactionPool};
+use reth_interfaces::{events::ChainEvent, provider::Error as ProviderError, Result};
+use reth_primitives::{
+    rpc::{BlockId, BlockNumber as RpcBlockNumber},
+    Address, BlockNumber, FromRecoveredTransaction, TransactionKind, TransactionSignedEcRecovered,
+    TxHash, U256,
+};
+use reth_provider::{
+    AccountProvider, BlockHashProvider, HeaderProvider, StateProviderFactory, TransactionsProvider,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, warn};
+
+/// The number of most recent canonical blocks the pool is updated with, and whose transactions are
+/// put back into the pool if the blocks are unwound.
+pub const MAX_REORG_DEPTH: u64 = 64;
+
+/// Keeps the pool in sync with the [ChainEvent]s of the canonical chain.
+///
+/// For every new canonical block, the mined transactions are removed from the pool, the nonces and
+/// balances of the accounts touched by its transactions are refreshed from the latest state, and
+/// the pool is updated with the base fee of the next block.
+///
+/// If blocks are unwound, their transactions are put back into the pool and validated again, with
+/// the origin they had in the pool before they were mined. The transactions of unwound blocks can
+/// no longer be read from the client, so this only covers the
+/// last [MAX_REORG_DEPTH] blocks. For the same reason, the pool is only updated with the last
+/// [MAX_REORG_DEPTH] blocks of a large extension, for example during sync. Instead of reading the
+/// blocks before those, every sender in the pool is refreshed from the latest state, which removes
+/// the transactions that were mined in them.
+///
+/// Returns once no more events can be received.
+pub async fn maintain_transaction_pool<Client, Pool>(
+    client: Client,
+    pool: Pool,
+    mut events: broadcast::Receiver<ChainEvent>,
+) where
+    Client: HeaderProvider + BlockHashProvider + TransactionsProvider + StateProviderFactory,
+    Pool: TransactionPool,
+{
+    // The transactions of the most recent canonical blocks, with their origin in the pool.
+    let mut recent_blocks =
+        BTreeMap::<BlockNumber, Vec<(TransactionSignedEcRecovered, TransactionOrigin)>>::new();
+
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(skipped)) => {
+                warn!(target: "txpool::maintain", skipped, "Pool maintenance lagged behind the chain");
+                continue
+            }
+            Err(RecvError::Closed) => return,
+        };
+
+        match event {
+            ChainEvent::Extended { first, tip } => {
+                let recent = (tip + 1).saturating_sub(MAX_REORG_DEPTH);
+                if first < recent {
+                    let senders = pool
+                        .pending_transactions()
+                        .into_iter()
+                        .chain(pool.queued_transactions())
+                        .map(|tx| tx.sender());
+                    let number = recent - 1;
+                    if let Err(err) = update_pool(&client, &pool, number, senders, Vec::new()) {
+                        warn!(target: "txpool::maintain", number, ?err, "Failed to update pool");
+                    }
+                }
+                for number in first.max(recent)..=tip {
+                    let transactions = match canonical_block_transactions(&client, number) {
+                        Ok(transactions) => transactions,
+                        Err(err) => {
+                            warn!(target: "txpool::maintain", number, ?err, "Failed to read block");
+                            continue
+                        }
+                    };
+                    let mined = transactions.iter().map(|tx| tx.hash()).collect::<Vec<_>>();
+                    // the origin is looked up before the mined transactions are removed
+                    let origins = pool
+                        .get_all(mined.clone())
+                        .into_iter()
+                        .map(|tx| (*tx.hash(), tx.origin))
+                        .collect::<HashMap<_, _>>();
+                    let accounts = touched_accounts(&transactions);
+                    if let Err(err) = update_pool(&client, &pool, number, accounts, mined) {
+                        warn!(target: "txpool::maintain", number, ?err, "Failed to update pool");
+                    }
+                    let transactions = transactions
+                        .into_iter()
+                        .map(|tx| {
+                            let origin = origins
+                                .get(&tx.hash())
+                                .copied()
+                                .unwrap_or(TransactionOrigin::External);
+                            (tx, origin)
+                        })
+                        .collect();
+                    recent_blocks.insert(number, transactions);
+                }
+                recent_blocks = recent_blocks.split_off(&(tip + 1).saturating_sub(MAX_REORG_DEPTH));
+            }
+            ChainEvent::Unwound { tip } => {
+                let unwound =
+                    recent_blocks.split_off(&(tip + 1)).into_values().flatten().collect::<Vec<_>>();
+
+                // the accounts of the unwound transactions are reverted as well
+                let reverted = touched_accounts(unwound.iter().map(|(tx, _)| tx));
+                if let Err(err) = update_pool(&client, &pool, tip, reverted, Vec::new()) {
+                    warn!(target: "txpool::maintain", tip, ?err, "Failed to update pool");
+                }
+
+                let mut by_origin = HashMap::<TransactionOrigin, Vec<_>>::new();
+                for (tx, origin) in unwound {
+                    by_origin
+                        .entry(origin)
+                        .or_default()
+                        .push(Pool::Transaction::from_recovered_transaction(tx));
+                }
+                for (origin, transactions) in by_origin {
+                    let count = transactions.len();
+                    match pool.add_transactions(origin, transactions).await {
+                        Ok(_) => {
+                            debug!(target: "txpool::maintain", count, ?origin, "Reinjected unwound transactions")
+                        }
+                        Err(err) => {
+                            warn!(target: "txpool::maintain", ?err, "Failed to reinject transactions")
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the transactions of the canonical block with their senders.
+fn canonical_block_transactions<Client>(
+    client: &Client,
+    number: BlockNumber,
+) -> Result<Vec<TransactionSignedEcRecovered>>
+where
+    Client: TransactionsProvider,
+{
+    let id = BlockId::Number(RpcBlockNumber::Number(number.into()));
+    let transactions = client.transactions_by_block(id)?.unwrap_or_default();
+    Ok(transactions.into_iter().filter_map(|tx| tx.into_ecrecovered()).collect())
+}
+
+/// Returns the senders and recipients of the given transactions.
+fn touched_accounts<'a>(
+    transactions: impl IntoIterator<Item = &'a TransactionSignedEcRecovered>,
+) -> HashSet<Address> {
+    let mut addresses = HashSet::new();
+    for tx in transactions {
+        addresses.insert(tx.signer());
+        if let TransactionKind::Call(to) = tx.kind() {
+            addresses.insert(*to);
+        }
+    }
+    addresses
+}
+
+/// Updates the pool with the new canonical block.
+///
+/// This refreshes the given accounts and the block's beneficiary.
+fn update_pool<Client, Pool>(
+    client: &Client,
+    pool: &Pool,
+    number: BlockNumber,
+    accounts: impl IntoIterator<Item = Address>,
+    mined_transactions: Vec<TxHash>,
+) -> Result<()>
+where
+    Client: HeaderProvider + BlockHashProvider + StateProviderFactory,
+    Pool: TransactionPool,
+{
+    let header = client
+        .header_by_number(number)?
+        .ok_or(ProviderError::BlockNumber { block_number: number })?;
+    let hash = client
+        .block_hash(U256::from(number))?
+        .ok_or(ProviderError::BlockNumber { block_number: number })?;
+
+    let mut addresses = HashSet::<Address>::from([header.beneficiary]);
+    addresses.extend(accounts);
+
+    let state = client.latest()?;
+    let changed_accounts = addresses
+        .into_iter()
+        .map(|address| {
+            let account = state.basic_account(address)?.unwrap_or_default();
+            Ok(ChangedAccount { address, nonce: account.nonce, balance: account.balance })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    pool.on_new_block(OnNewBlockEvent {
+        hash,
+        pending_block_base_fee: header.next_block_base_fee().unwrap_or_default() as u128,
+        changed_accounts,
+        mined_transactions,
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{testing_pool, MockTransaction};
+    use reth_interfaces::{
+        events::{ChainEventSender, ChainEventSubscriptions},
+        test_utils::generators::random_signed_tx,
+    };
+    use reth_primitives::{Block, Header, H256};
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+
+    /// Waits until the maintenance task updated the pool to the expected number of transactions.
+    async fn wait_for_len(pool: &impl TransactionPool, len: usize) {
+        for _ in 0..1000 {
+            if pool.pooled_transactions().len() == len {
+                return
+            }
+            tokio::task::yield_now().await;
+        }
+        panic!("pool does not contain {len} transactions");
+    }
+
+    #[tokio::test]
+    async fn follows_canonical_chain() {
+        let tx = random_signed_tx().into_ecrecovered().unwrap();
+        let sender = tx.signer();
+        let nonce = tx.nonce();
+
+        let client = MockEthProvider::default();
+        for number in 0..=1 {
+            let header = Header { number, ..Default::default() };
+            let body = if number == 1 { vec![tx.clone().into_signed()] } else { vec![] };
+            let hash = H256::from_low_u64_be(number);
+            client.add_header(hash, header.clone());
+            client.add_block(hash, Block { header, body, ommers: vec![] });
+        }
+        client.add_account(sender, ExtendedAccount::new(nonce + 1, U256::ZERO));
+
+        let pool = testing_pool();
+        let hash = pool
+            .add_transaction(
+                TransactionOrigin::Local,
+                MockTransaction::from_recovered_transaction(tx),
+            )
+            .await
+            .unwrap();
+        assert_eq!(pool.len(), 1);
+
+        let chain_events = ChainEventSender::default();
+        let events = chain_events.subscribe_chain_events();
+        tokio::spawn(maintain_transaction_pool(client.clone(), pool.clone(), events));
+
+        // the transaction is mined
+        chain_events.notify(ChainEvent::Extended { first: 1, tip: 1 });
+        wait_for_len(&pool, 0).await;
+
+        // the block is unwound, so the transaction is put back with its origin
+        client.add_account(sender, ExtendedAccount::new(nonce, U256::ZERO));
+        chain_events.notify(ChainEvent::Unwound { tip: 0 });
+        wait_for_len(&pool, 1).await;
+        assert_eq!(pool.get(&hash).unwrap().origin, TransactionOrigin::Local);
+    }
+
+    #[tokio::test]
+    async fn refreshes_all_senders_on_deep_extension() {
+        let tx = random_signed_tx().into_ecrecovered().unwrap();
+        let sender = tx.signer();
+        let nonce = tx.nonce();
+
+        // the transaction is mined in a block that is too deep to be read
+        let tip = MAX_REORG_DEPTH + 1;
+        let client = MockEthProvider::default();
+        for number in 0..=tip {
+            let header = Header { number, ..Default::default() };
+            let body = if number == 1 { vec![tx.clone().into_signed()] } else { vec![] };
+            let hash = H256::from_low_u64_be(number);
+            client.add_header(hash, header.clone());
+            client.add_block(hash, Block { header, body, ommers: vec![] });
+        }
+        client.add_account(sender, ExtendedAccount::new(nonce + 1, U256::ZERO));
+
+        let pool = testing_pool();
+        pool.add_transaction(
+            TransactionOrigin::External,
+            MockTransaction::from_recovered_transaction(tx),
+        )
+        .await
+        .unwrap();
+
+        let chain_events = ChainEventSender::default();
+        let events = chain_events.subscribe_chain_events();
+        tokio::spawn(maintain_transaction_pool(client, pool.clone(), events));
+
+        chain_events.notify(ChainEvent::Extended { first: 1, tip });
+        wait_for_len(&pool, 0).await;
+    }
+
+    #[test]
+    fn update_fails_for_unknown_block() {
+        let client = MockEthProvider::default();
+        let pool = testing_pool();
+        assert!(update_pool(&client, &pool, 1, [], Vec::new()).is_err());
+    }
+}
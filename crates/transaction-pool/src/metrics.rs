@@ -13,4 +13,22 @@ pub struct TxPoolMetrics {
     pub(crate) invalid_transactions: Counter,
     /// Number of removed transactions from the pool
     pub(crate) removed_transactions: Counter,
+    /// Number of transactions rejected by the validator
+    pub(crate) failed_validation_transactions: Counter,
+    /// Number of replacement transactions rejected because their fees were not bumped enough
+    pub(crate) underpriced_replacement_transactions: Counter,
+    /// Number of transactions rejected because their fee cap is below the protocol minimum
+    pub(crate) fee_cap_too_low_transactions: Counter,
+    /// Number of transactions rejected because their gas price is below the configured minimum
+    pub(crate) below_minimal_gas_price_transactions: Counter,
+    /// Number of transactions rejected because their sender exhausted its slot capacity
+    pub(crate) spammer_transactions: Counter,
+    /// Number of transactions rejected because their gas limit exceeds the block gas limit
+    pub(crate) exceeded_gas_limit_transactions: Counter,
+    /// Number of transactions evicted to enforce the size limits of the sub-pools
+    pub(crate) evicted_transactions: Counter,
+    /// Number of local transactions evicted to enforce the size limits of the sub-pools
+    pub(crate) evicted_local_transactions: Counter,
+    /// Number of transactions discarded because their nonce was used by a mined transaction
+    pub(crate) outdated_transactions: Counter,
 }
@@ -3,7 +3,7 @@
 use crate::{pool::events::TransactionEvent, traits::PropagateKind};
 use reth_primitives::{TxHash, H256};
 use std::{collections::HashMap, sync::Arc};
-use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 
 type EventBroadcast = UnboundedSender<TransactionEvent>;
 
@@ -35,6 +35,17 @@ impl PoolEventBroadcast {
         }
     }
 
+    /// Returns a new receiver for all events of the given transaction.
+    pub(crate) fn subscribe(&mut self, tx: TxHash) -> UnboundedReceiver<TransactionEvent> {
+        let (sender, receiver) = unbounded_channel();
+        self.broadcasters
+            .entry(tx)
+            .or_insert_with(|| PoolEventBroadcaster { is_done: false, senders: Vec::new() })
+            .senders
+            .push(sender);
+        receiver
+    }
+
     /// Notify listeners about a transaction that was added to the pending queue.
     pub(crate) fn pending(&mut self, tx: &TxHash, replaced: Option<&TxHash>) {
         self.broadcast_with(tx, |notifier| notifier.pending());
@@ -46,8 +57,13 @@ impl PoolEventBroadcast {
     }
 
     /// Notify listeners about a transaction that was added to the queued pool.
-    pub(crate) fn queued(&mut self, tx: &TxHash) {
+    pub(crate) fn queued(&mut self, tx: &TxHash, replaced: Option<&TxHash>) {
         self.broadcast_with(tx, |notifier| notifier.queued());
+
+        if let Some(replaced) = replaced {
+            // notify listeners that this transaction was replaced
+            self.broadcast_with(replaced, |notifier| notifier.replaced(*tx));
+        }
     }
 
     /// Notify listeners about a transaction that was propagated.
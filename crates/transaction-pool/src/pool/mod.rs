@@ -70,15 +70,21 @@
 use crate::{
     error::{PoolError, PoolResult},
     identifier::{SenderId, SenderIdentifiers, TransactionId},
-    pool::{listener::PoolEventBroadcast, state::SubPool, txpool::TxPool},
+    pool::{
+        listener::PoolEventBroadcast,
+        state::SubPool,
+        txpool::{SenderInfo, TxPool},
+    },
     traits::{
-        NewTransactionEvent, PoolSize, PoolTransaction, PropagatedTransactions, TransactionOrigin,
+        ChangedAccount, NewTransactionEvent, PoolSize, PoolTransaction, PropagatedTransactions,
+        TransactionOrigin,
     },
     validate::{TransactionValidationOutcome, ValidPoolTransaction},
     OnNewBlockEvent, PoolConfig, TransactionOrdering, TransactionValidator,
 };
 use best::BestTransactions;
 pub use events::TransactionEvent;
+use fnv::FnvHashMap;
 use parking_lot::{Mutex, RwLock};
 use reth_primitives::{Address, TxHash, H256};
 use std::{collections::HashSet, fmt, sync::Arc, time::Instant};
@@ -171,6 +177,16 @@ where
         rx
     }
 
+    /// Adds a new listener for the events of the given transaction.
+    ///
+    /// Returns `None` if the transaction is not in the pool.
+    pub(crate) fn add_transaction_event_listener(
+        &self,
+        tx_hash: TxHash,
+    ) -> Option<mpsc::UnboundedReceiver<TransactionEvent>> {
+        self.contains(&tx_hash).then(|| self.event_listener.write().subscribe(tx_hash))
+    }
+
     /// Returns hashes of _all_ transactions in the pool.
     pub(crate) fn pooled_transactions(&self) -> Vec<TxHash> {
         let pool = self.pool.read();
@@ -179,10 +195,24 @@ where
 
     /// Updates the entire pool after a new block was executed.
     pub(crate) fn on_new_block(&self, block: OnNewBlockEvent) {
-        let outcome = self.pool.write().on_new_block(block);
+        let changed_senders = self.changed_senders(&block.changed_accounts);
+        let outcome = self.pool.write().on_new_block(block, changed_senders);
         self.notify_on_new_block(outcome);
     }
 
+    /// Returns the new info of all changed accounts that are known senders of the pool.
+    fn changed_senders(&self, accounts: &[ChangedAccount]) -> FnvHashMap<SenderId, SenderInfo> {
+        let identifiers = self.identifiers.read();
+        accounts
+            .iter()
+            .filter_map(|account| {
+                let sender = identifiers.sender_id(&account.address)?;
+                let info = SenderInfo { state_nonce: account.nonce, balance: account.balance };
+                Some((sender, info))
+            })
+            .collect()
+    }
+
     /// Add a single validated transaction into the pool.
     ///
     /// Note: this is only used internally by [`Self::add_transactions()`], all new transaction(s)
@@ -223,6 +253,7 @@ where
                 Ok(hash)
             }
             TransactionValidationOutcome::Invalid(tx, err) => {
+                self.pool.read().metrics().failed_validation_transactions.increment(1);
                 let mut listener = self.event_listener.write();
                 listener.discarded(tx.hash());
                 Err(err)
@@ -317,14 +348,14 @@ where
 
         match tx {
             AddedTransaction::Pending(tx) => {
-                let AddedPendingTransaction { transaction, promoted, discarded, .. } = tx;
+                let AddedPendingTransaction { transaction, promoted, discarded, replaced, .. } = tx;
 
-                listener.pending(transaction.hash(), None);
+                listener.pending(transaction.hash(), replaced.as_ref());
                 promoted.iter().for_each(|tx| listener.pending(tx, None));
                 discarded.iter().for_each(|tx| listener.discarded(tx));
             }
-            AddedTransaction::Parked { transaction, .. } => {
-                listener.queued(transaction.hash());
+            AddedTransaction::Parked { transaction, replaced, .. } => {
+                listener.queued(transaction.hash(), replaced.as_ref());
             }
         }
     }
@@ -334,6 +365,16 @@ where
         self.pool.read().best_transactions()
     }
 
+    /// Returns all transactions of the _pending_ sub-pool.
+    pub(crate) fn pending_transactions(&self) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.pool.read().pending_transactions()
+    }
+
+    /// Returns all transactions of the _basefee_ and _queued_ sub-pools.
+    pub(crate) fn queued_transactions(&self) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.pool.read().queued_transactions()
+    }
+
     /// Removes and returns all matching transactions from the pool.
     pub(crate) fn remove_invalid(
         &self,
@@ -412,6 +453,8 @@ pub struct AddedPendingTransaction<T: PoolTransaction> {
     discarded: Vec<TxHash>,
     /// Transactions removed from the Ready pool
     removed: Vec<Arc<ValidPoolTransaction<T>>>,
+    /// The transaction that was replaced by the inserted transaction.
+    replaced: Option<TxHash>,
 }
 
 impl<T: PoolTransaction> AddedPendingTransaction<T> {
@@ -422,6 +465,7 @@ impl<T: PoolTransaction> AddedPendingTransaction<T> {
             promoted: Default::default(),
             discarded: Default::default(),
             removed: Default::default(),
+            replaced: None,
         }
     }
 }
@@ -438,6 +482,8 @@ pub enum AddedTransaction<T: PoolTransaction> {
         transaction: Arc<ValidPoolTransaction<T>>,
         /// The subpool it was moved to.
         subpool: SubPool,
+        /// The transaction that was replaced by the inserted transaction.
+        replaced: Option<TxHash>,
     },
 }
 
@@ -465,7 +511,7 @@ impl<T: PoolTransaction> AddedTransaction<T> {
             AddedTransaction::Pending(tx) => {
                 NewTransactionEvent { subpool: SubPool::Pending, transaction: tx.transaction }
             }
-            AddedTransaction::Parked { transaction, subpool } => {
+            AddedTransaction::Parked { transaction, subpool, .. } => {
                 NewTransactionEvent { transaction, subpool }
             }
         }
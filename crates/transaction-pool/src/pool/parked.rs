@@ -63,8 +63,15 @@ impl<T: ParkedOrd> ParkedPool<T> {
     }
 
     /// Removes the worst transaction from this pool.
+    ///
+    /// Local transactions are only removed if the pool contains no other transactions.
     pub(crate) fn pop_worst(&mut self) -> Option<Arc<ValidPoolTransaction<T::Transaction>>> {
-        let worst = self.best.iter().next().map(|tx| *tx.transaction.id())?;
+        let worst = self
+            .best
+            .iter()
+            .find(|tx| !tx.transaction.is_local())
+            .or_else(|| self.best.iter().next())
+            .map(|tx| *tx.transaction.id())?;
         self.remove_transaction(&worst)
     }
 
@@ -162,8 +162,16 @@ impl<T: TransactionOrdering> PendingPool<T> {
     }
 
     /// Removes the worst transaction from this pool.
+    ///
+    /// Local transactions are only removed if the pool contains no other transactions.
     pub(crate) fn pop_worst(&mut self) -> Option<Arc<ValidPoolTransaction<T::Transaction>>> {
-        let worst = self.all.iter().next_back().map(|tx| *tx.transaction.id())?;
+        let worst = self
+            .all
+            .iter()
+            .rev()
+            .find(|tx| !tx.transaction.is_local())
+            .or_else(|| self.all.iter().next_back())
+            .map(|tx| *tx.transaction.id())?;
         self.remove_transaction(&worst)
     }
 
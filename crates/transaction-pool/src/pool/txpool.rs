@@ -1,6 +1,6 @@
 //! The internal transaction pool implementation.
 use crate::{
-    config::MAX_ACCOUNT_SLOTS_PER_SENDER,
+    config::{DEFAULT_MINIMAL_GAS_PRICE, DEFAULT_PRICE_BUMP, MAX_ACCOUNT_SLOTS_PER_SENDER},
     error::PoolError,
     identifier::{SenderId, TransactionId},
     metrics::TxPoolMetrics,
@@ -12,7 +12,7 @@ use crate::{
         update::{Destination, PoolUpdate},
         AddedPendingTransaction, AddedTransaction, OnNewBlockOutcome,
     },
-    traits::{PoolSize, StateDiff},
+    traits::PoolSize,
     OnNewBlockEvent, PoolConfig, PoolResult, PoolTransaction, TransactionOrdering,
     ValidPoolTransaction, U256,
 };
@@ -100,12 +100,17 @@ impl<T: TransactionOrdering> TxPool<T> {
             pending_pool: PendingPool::new(ordering),
             queued_pool: Default::default(),
             basefee_pool: Default::default(),
-            all_transactions: AllTransactions::new(config.max_account_slots),
+            all_transactions: AllTransactions::new(&config),
             config,
             metrics: Default::default(),
         }
     }
 
+    /// Returns the metrics of the pool.
+    pub(crate) fn metrics(&self) -> &TxPoolMetrics {
+        &self.metrics
+    }
+
     /// Returns access to the [`AllTransactions`] container.
     pub(crate) fn all(&self) -> &AllTransactions<T::Transaction> {
         &self.all_transactions
@@ -125,10 +130,11 @@ impl<T: TransactionOrdering> TxPool<T> {
 
     /// Updates the pool based on the changed base fee.
     ///
-    /// This enforces the dynamic fee requirement.
-    pub(crate) fn update_base_fee(&mut self, _new_base_fee: U256) {
-        // TODO update according to the changed base_fee
-        todo!()
+    /// This enforces the dynamic fee requirement and rechecks the nonce and balance conditions of
+    /// all transactions against the tracked sender info.
+    pub(crate) fn update_base_fee(&mut self, new_base_fee: u128) -> UpdateOutcome<T::Transaction> {
+        let updates = self.all_transactions.update(new_base_fee, &self.sender_info);
+        self.process_updates(updates)
     }
 
     /// Returns an iterator that yields transactions that are ready to be included in the block.
@@ -136,6 +142,17 @@ impl<T: TransactionOrdering> TxPool<T> {
         self.pending_pool.best()
     }
 
+    /// Returns all transactions of the _pending_ sub-pool, sorted by sender and nonce.
+    pub(crate) fn pending_transactions(&self) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.all_transactions.transactions_by_subpool(|subpool| subpool.is_pending())
+    }
+
+    /// Returns all transactions of the _basefee_ and _queued_ sub-pools, sorted by sender and
+    /// nonce.
+    pub(crate) fn queued_transactions(&self) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.all_transactions.transactions_by_subpool(|subpool| !subpool.is_pending())
+    }
+
     /// Returns if the transaction for the given hash is already included in this pool
     pub(crate) fn contains(&self, tx_hash: &TxHash) -> bool {
         self.all_transactions.contains(tx_hash)
@@ -161,20 +178,44 @@ impl<T: TransactionOrdering> TxPool<T> {
     ///
     /// This removes all mined transactions, updates according to the new base fee and rechecks
     /// sender allowance.
-    pub(crate) fn on_new_block(&mut self, event: OnNewBlockEvent) -> OnNewBlockOutcome {
+    ///
+    /// The `changed_senders` contain the new nonce and balance of all senders that were changed by
+    /// the block. Transactions of these senders with a nonce below the new nonce can no longer be
+    /// included and are discarded.
+    pub(crate) fn on_new_block(
+        &mut self,
+        event: OnNewBlockEvent,
+        changed_senders: FnvHashMap<SenderId, SenderInfo>,
+    ) -> OnNewBlockOutcome {
         // Remove all transaction that were included in the block
         for tx_hash in &event.mined_transactions {
-            self.remove_transaction_by_hash(tx_hash);
-            // Update removed transactions metric
-            self.metrics.removed_transactions.increment(1);
+            if self.remove_transaction_by_hash(tx_hash).is_some() {
+                // Update removed transactions metric
+                self.metrics.removed_transactions.increment(1);
+            }
+        }
+
+        // Update the sender info and remove all transactions that are outdated by the new nonce
+        let mut discarded = Vec::new();
+        for (sender, info) in changed_senders {
+            for id in self.all_transactions.ids_below_nonce(sender, info.state_nonce) {
+                if let Some(tx) = self.remove_transaction(&id) {
+                    self.metrics.outdated_transactions.increment(1);
+                    discarded.push(*tx.hash());
+                }
+            }
+            self.sender_info.insert(sender, info);
         }
 
         // Apply the state changes to the total set of transactions which triggers sub-pool updates.
-        let updates =
-            self.all_transactions.update(event.pending_block_base_fee, &event.state_changes);
+        let UpdateOutcome { promoted, discarded: discarded_by_update, .. } =
+            self.update_base_fee(event.pending_block_base_fee);
+        discarded.extend(discarded_by_update);
 
-        // Process the sub-pool updates
-        let UpdateOutcome { promoted, discarded, .. } = self.process_updates(updates);
+        // The info is only needed for senders with transactions in the pool, it is tracked again
+        // once a sender adds a new transaction.
+        let tx_counter = &self.all_transactions.tx_counter;
+        self.sender_info.retain(|sender, _| tx_counter.contains_key(sender));
 
         OnNewBlockOutcome {
             block_hash: event.hash,
@@ -220,6 +261,7 @@ impl<T: TransactionOrdering> TxPool<T> {
 
         match self.all_transactions.insert_tx(tx, on_chain_balance, on_chain_nonce) {
             Ok(InsertOk { transaction, move_to, replaced_tx, updates, .. }) => {
+                let replaced = replaced_tx.as_ref().map(|(tx, _)| *tx.hash());
                 self.add_new_transaction(transaction.clone(), replaced_tx, move_to);
                 // Update inserted transactions metric
                 self.metrics.inserted_transactions.increment(1);
@@ -232,9 +274,10 @@ impl<T: TransactionOrdering> TxPool<T> {
                         promoted,
                         discarded,
                         removed,
+                        replaced,
                     })
                 } else {
-                    AddedTransaction::Parked { transaction, subpool: move_to }
+                    AddedTransaction::Parked { transaction, subpool: move_to, replaced }
                 };
 
                 Ok(res)
@@ -243,13 +286,33 @@ impl<T: TransactionOrdering> TxPool<T> {
                 // Update invalid transactions metric
                 self.metrics.invalid_transactions.increment(1);
                 match e {
-                    InsertErr::Underpriced { existing, .. } => {
-                        Err(PoolError::ReplacementUnderpriced(existing))
+                    InsertErr::Underpriced { transaction, existing, min_fee, min_priority_fee } => {
+                        self.metrics.underpriced_replacement_transactions.increment(1);
+                        Err(PoolError::ReplacementUnderpriced(
+                            *transaction.hash(),
+                            existing,
+                            min_fee,
+                            min_priority_fee,
+                        ))
                     }
                     InsertErr::ProtocolFeeCapTooLow { transaction, fee_cap } => {
+                        self.metrics.fee_cap_too_low_transactions.increment(1);
                         Err(PoolError::ProtocolFeeCapTooLow(*transaction.hash(), fee_cap))
                     }
+                    InsertErr::GasPriceBelowMinimum {
+                        transaction,
+                        gas_price,
+                        minimal_gas_price,
+                    } => {
+                        self.metrics.below_minimal_gas_price_transactions.increment(1);
+                        Err(PoolError::GasPriceBelowMinimum(
+                            *transaction.hash(),
+                            gas_price,
+                            minimal_gas_price,
+                        ))
+                    }
                     InsertErr::ExceededSenderTransactionsCapacity { transaction } => {
+                        self.metrics.spammer_transactions.increment(1);
                         Err(PoolError::SpammerExceededCapacity(
                             transaction.sender(),
                             *transaction.hash(),
@@ -259,11 +322,14 @@ impl<T: TransactionOrdering> TxPool<T> {
                         transaction,
                         block_gas_limit,
                         tx_gas_limit,
-                    } => Err(PoolError::TxExceedsGasLimit(
-                        *transaction.hash(),
-                        block_gas_limit,
-                        tx_gas_limit,
-                    )),
+                    } => {
+                        self.metrics.exceeded_gas_limit_transactions.increment(1);
+                        Err(PoolError::TxExceedsGasLimit(
+                            *transaction.hash(),
+                            block_gas_limit,
+                            tx_gas_limit,
+                        ))
+                    }
                 }
             }
         }
@@ -286,6 +352,9 @@ impl<T: TransactionOrdering> TxPool<T> {
                 Destination::Pool(move_to) => {
                     debug_assert!(!move_to.eq(&current), "destination must be different");
                     self.move_transaction(current, move_to, &id);
+                    if move_to.is_pending() {
+                        outcome.promoted.push(hash);
+                    }
                 }
             }
         }
@@ -409,7 +478,8 @@ impl<T: TransactionOrdering> TxPool<T> {
     /// Ensures that the transactions in the sub-pools are within the given bounds.
     ///
     /// If the current size exceeds the given bounds, the worst transactions are evicted from the
-    /// pool and returned.
+    /// pool and returned. Non-local transactions are evicted first, so local transactions are only
+    /// evicted if a sub-pool exceeds its limits with local transactions alone.
     pub(crate) fn discard_worst(&mut self) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
         let mut removed = Vec::new();
 
@@ -424,6 +494,11 @@ impl<T: TransactionOrdering> TxPool<T> {
                     {
                         if let Some(tx) = $this.$pool.pop_worst() {
                             let id = tx.transaction_id;
+                            $this.all_transactions.remove_transaction(&id);
+                            $this.metrics.evicted_transactions.increment(1);
+                            if tx.is_local() {
+                                $this.metrics.evicted_local_transactions.increment(1);
+                            }
                             removed.push(tx);
                             $this.remove_descendants(&id, &mut $removed);
                         }
@@ -493,6 +568,10 @@ pub(crate) struct AllTransactions<T: PoolTransaction> {
     block_gas_limit: u64,
     /// Max number of executable transaction slots guaranteed per account
     max_account_slots: usize,
+    /// Minimum price bump (in %) required to replace a transaction
+    price_bump: u128,
+    /// Minimum gas price of non-local transactions
+    minimal_gas_price: u128,
     /// _All_ transactions identified by their hash.
     by_hash: HashMap<TxHash, Arc<ValidPoolTransaction<T>>>,
     /// _All_ transaction in the pool sorted by their sender and nonce pair.
@@ -503,8 +582,25 @@ pub(crate) struct AllTransactions<T: PoolTransaction> {
 
 impl<T: PoolTransaction> AllTransactions<T> {
     /// Create a new instance
-    fn new(max_account_slots: usize) -> Self {
-        Self { max_account_slots, ..Default::default() }
+    fn new(config: &PoolConfig) -> Self {
+        Self {
+            max_account_slots: config.max_account_slots,
+            price_bump: config.price_bump,
+            minimal_gas_price: config.minimal_gas_price,
+            ..Default::default()
+        }
+    }
+
+    /// Returns all transactions of the sub-pools matching the filter, sorted by sender and nonce.
+    fn transactions_by_subpool(
+        &self,
+        filter: impl Fn(SubPool) -> bool,
+    ) -> Vec<Arc<ValidPoolTransaction<T>>> {
+        self.txs
+            .values()
+            .filter(|tx| filter(tx.subpool))
+            .map(|tx| Arc::clone(&tx.transaction))
+            .collect()
     }
 
     /// Returns an iterator over all _unique_ hashes in the pool
@@ -553,90 +649,70 @@ impl<T: PoolTransaction> AllTransactions<T> {
     ///   - increased sender allowance: promote from `queued` to
     ///       - `pending` if basefee condition is met.
     ///       - `basefee` if basefee condition is _not_ met.
+    ///   - changed sender nonce: closes or opens nonce gaps.
     ///
-    /// Additionally, this will also update the `cumulative_gas_used` for transactions of a sender
-    /// that got transaction included in the block.
+    /// The nonce and balance of a sender are taken from the given `sender_info`, which also updates
+    /// the `cumulative_cost` of all transactions.
     pub(crate) fn update(
         &mut self,
         pending_block_base_fee: u128,
-        _state_diffs: &StateDiff,
+        sender_info: &FnvHashMap<SenderId, SenderInfo>,
     ) -> Vec<PoolUpdate> {
         // update new basefee
         self.pending_basefee = pending_block_base_fee;
 
-        // TODO(mattsse): probably good idea to allocate some capacity here.
         let mut updates = Vec::new();
 
-        let mut iter = self.txs.iter_mut().peekable();
-
-        // Loop over all individual senders and update all affected transactions.
-        // One sender may have up to `max_account_slots` transactions here, which means, worst case
-        // `max_accounts_slots` need to be updated, for example if the first transaction is blocked
-        // due to too low base fee.
-        // However, we don't have to necessarily check every transaction of a sender. If no updates
-        // are possible (nonce gap) then we can skip to the next sender.
-
-        // The `unique_sender` loop will process the first transaction of all senders, update its
-        // state and internally update all consecutive transactions
-        'unique_sender: while let Some((id, tx)) = iter.next() {
-            // Advances the iterator to the next sender
-            macro_rules! next_sender {
-                ($iter:ident) => {
-                    'this: while let Some((peek, _)) = iter.peek() {
-                        if peek.sender != id.sender {
-                            break 'this
-                        }
-                        iter.next();
-                    }
-                };
+        // Tracks the state of the sender whose transactions are currently visited: the next nonce
+        // without gaps, the combined cost of its transactions so far and whether one of them is
+        // parked.
+        let mut sender = None;
+        let mut next_nonce = 0;
+        let mut balance = U256::ZERO;
+        let mut cumulative_cost = U256::ZERO;
+        let mut has_parked_ancestor = false;
+
+        // All transactions are sorted by sender and nonce, so this visits the transactions of each
+        // sender in order, starting with the lowest nonce.
+        for (id, tx) in self.txs.iter_mut() {
+            if sender != Some(id.sender) {
+                // first transaction of the next sender
+                let info = sender_info.get(&id.sender).cloned().unwrap_or_default();
+                sender = Some(id.sender);
+                next_nonce = info.state_nonce;
+                balance = info.balance;
+                cumulative_cost = U256::ZERO;
+                has_parked_ancestor = false;
             }
-            // If there's a nonce gap, we can shortcircuit, because there's nothing to update.
-            if tx.state.has_nonce_gap() {
-                next_sender!(iter);
-                continue
+
+            // Update nonce gap condition.
+            if id.nonce == next_nonce {
+                tx.state.insert(TxState::NO_NONCE_GAPS);
+                next_nonce = id.next_nonce();
+            } else {
+                tx.state.remove(TxState::NO_NONCE_GAPS);
             }
 
-            // TODO(mattsse): if account has balance changes or mined transactions the balance needs
-            // to be checked here
+            // Update balance condition.
+            tx.cumulative_cost = cumulative_cost;
+            cumulative_cost = tx.next_cumulative_cost();
+            if cumulative_cost > balance {
+                tx.state.remove(TxState::ENOUGH_BALANCE);
+            } else {
+                tx.state.insert(TxState::ENOUGH_BALANCE);
+            }
 
-            // Since this is the first transaction of the sender, it has no parked ancestors
-            tx.state.insert(TxState::NO_PARKED_ANCESTORS);
+            // Update ancestor condition.
+            if has_parked_ancestor {
+                tx.state.remove(TxState::NO_PARKED_ANCESTORS);
+            } else {
+                tx.state.insert(TxState::NO_PARKED_ANCESTORS);
+            }
 
-            // Update the first transaction of this sender.
+            // Update and record sub-pool changes.
             Self::update_base_fee(&pending_block_base_fee, tx);
-            // Track if the transaction's sub-pool changed.
+            has_parked_ancestor = !tx.state.is_pending();
             Self::record_subpool_update(&mut updates, tx);
-
-            // Track blocking transactions.
-            let mut has_parked_ancestor = !tx.state.is_pending();
-
-            // Update all consecutive transaction of this sender
-            while let Some((peek, ref mut tx)) = iter.peek_mut() {
-                if peek.sender != id.sender {
-                    // Found the next sender
-                    continue 'unique_sender
-                }
-
-                if tx.state.has_nonce_gap() {
-                    next_sender!(iter);
-                    continue 'unique_sender
-                }
-
-                // Update ancestor condition.
-                if has_parked_ancestor {
-                    tx.state.remove(TxState::NO_PARKED_ANCESTORS);
-                } else {
-                    tx.state.insert(TxState::NO_PARKED_ANCESTORS);
-                }
-                has_parked_ancestor = !tx.state.is_pending();
-
-                // Update and record sub-pool changes.
-                Self::update_base_fee(&pending_block_base_fee, tx);
-                Self::record_subpool_update(&mut updates, tx);
-
-                // Advance iterator
-                iter.next();
-            }
         }
 
         updates
@@ -744,6 +820,14 @@ impl<T: PoolTransaction> AllTransactions<T> {
         self.txs.range_mut(id..).take_while(|(other, _)| id.sender == other.sender)
     }
 
+    /// Returns the ids of all transactions of the sender with a nonce below the given nonce.
+    pub(crate) fn ids_below_nonce(&self, sender: SenderId, nonce: u64) -> Vec<TransactionId> {
+        self.txs
+            .range(TransactionId::new(sender, 0)..TransactionId::new(sender, nonce))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
     /// Removes a transaction from the set using its hash.
     pub(crate) fn remove_transaction_by_hash(
         &mut self,
@@ -778,6 +862,7 @@ impl<T: PoolTransaction> AllTransactions<T> {
     /// This will enforce all additional rules in the context of this pool, such as:
     ///   - Spam protection: reject new non-local transaction from a sender that exhausted its slot
     ///     capacity.
+    ///   - Gas price floor: reject new non-local transactions below the minimal gas price.
     ///   - Gas limit: reject transactions if they exceed a block's maximum gas.
     fn ensure_valid(
         &self,
@@ -791,6 +876,14 @@ impl<T: PoolTransaction> AllTransactions<T> {
                     transaction: Arc::new(transaction),
                 })
             }
+            let gas_price = transaction.max_priority_fee();
+            if gas_price < self.minimal_gas_price {
+                return Err(InsertErr::GasPriceBelowMinimum {
+                    transaction: Arc::new(transaction),
+                    gas_price,
+                    minimal_gas_price: self.minimal_gas_price,
+                })
+            }
         }
         if transaction.gas_limit() > self.block_gas_limit {
             return Err(InsertErr::TxGasLimitMoreThanAvailableBlockGas {
@@ -872,10 +965,14 @@ impl<T: PoolTransaction> AllTransactions<T> {
             Entry::Occupied(mut entry) => {
                 // Transaction already exists
                 // Ensure the new transaction is not underpriced
-                if transaction.is_underpriced(entry.get().transaction.as_ref()) {
+                let existing = entry.get().transaction.as_ref();
+                if transaction.is_underpriced(existing, self.price_bump) {
+                    let (min_fee, min_priority_fee) = existing.replacement_fees(self.price_bump);
                     return Err(InsertErr::Underpriced {
                         transaction: pool_tx.transaction,
-                        existing: *entry.get().transaction.hash(),
+                        existing: *existing.hash(),
+                        min_fee,
+                        min_priority_fee,
                     })
                 }
                 let new_hash = *pool_tx.transaction.hash();
@@ -996,6 +1093,8 @@ impl<T: PoolTransaction> Default for AllTransactions<T> {
     fn default() -> Self {
         Self {
             max_account_slots: MAX_ACCOUNT_SLOTS_PER_SENDER,
+            price_bump: DEFAULT_PRICE_BUMP,
+            minimal_gas_price: DEFAULT_MINIMAL_GAS_PRICE,
             pending_basefee: Default::default(),
             minimal_protocol_basefee: MIN_PROTOCOL_BASE_FEE,
             block_gas_limit: 30_000_000,
@@ -1013,11 +1112,22 @@ pub(crate) type InsertResult<T> = Result<InsertOk<T>, InsertErr<T>>;
 #[derive(Debug)]
 pub(crate) enum InsertErr<T: PoolTransaction> {
     /// Attempted to replace existing transaction, but was underpriced
-    Underpriced { transaction: Arc<ValidPoolTransaction<T>>, existing: TxHash },
+    Underpriced {
+        transaction: Arc<ValidPoolTransaction<T>>,
+        existing: TxHash,
+        min_fee: u128,
+        min_priority_fee: u128,
+    },
     /// The transactions feeCap is lower than the chain's minimum fee requirement.
     ///
     /// See also [`MIN_PROTOCOL_BASE_FEE`]
     ProtocolFeeCapTooLow { transaction: Arc<ValidPoolTransaction<T>>, fee_cap: u128 },
+    /// The gas price of a non-local transaction is lower than the configured minimum.
+    GasPriceBelowMinimum {
+        transaction: Arc<ValidPoolTransaction<T>>,
+        gas_price: u128,
+        minimal_gas_price: u128,
+    },
     /// Sender currently exceeds the configured limit for max account slots.
     ///
     /// The sender can be considered a spammer at this point.
@@ -1118,11 +1228,11 @@ impl<T: PoolTransaction> fmt::Debug for PruneResult<T> {
 
 /// Stores relevant context about a sender.
 #[derive(Debug, Clone, Default)]
-struct SenderInfo {
+pub(crate) struct SenderInfo {
     /// current nonce of the sender.
-    state_nonce: u64,
+    pub(crate) state_nonce: u64,
     /// Balance of the sender at the current point.
-    balance: U256,
+    pub(crate) balance: U256,
 }
 
 // === impl SenderInfo ===
@@ -1143,7 +1253,8 @@ impl SenderInfo {
 mod tests {
     use super::*;
     use crate::{
-        test_utils::{MockTransaction, MockTransactionFactory},
+        config::SubPoolLimit,
+        test_utils::{MockOrdering, MockTransaction, MockTransactionFactory},
         traits::TransactionOrigin,
     };
 
@@ -1211,6 +1322,63 @@ mod tests {
         assert_eq!(pool.len(), 1);
     }
 
+    #[test]
+    fn insert_underpriced_replacement() {
+        let on_chain_balance = U256::ZERO;
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = AllTransactions::default();
+        let tx = MockTransaction::eip1559().with_gas_price(100).inc_limit();
+        let first = f.validated(tx.clone());
+        pool.insert_tx(first.clone(), on_chain_balance, on_chain_nonce).unwrap();
+
+        // the max fee is bumped enough, but the max priority fee is not
+        let replacement =
+            f.validated(tx.clone().rng_hash().with_max_fee(110).with_priority_fee(109));
+        match pool.insert_tx(replacement, on_chain_balance, on_chain_nonce) {
+            Err(InsertErr::Underpriced { existing, min_fee, min_priority_fee, .. }) => {
+                assert_eq!(existing, *first.hash());
+                assert_eq!((min_fee, min_priority_fee), (110, 110));
+            }
+            _ => unreachable!(),
+        }
+        assert!(pool.contains(first.hash()));
+
+        let replacement = f.validated(tx.rng_hash().with_gas_price(110));
+        let InsertOk { replaced_tx, .. } =
+            pool.insert_tx(replacement, on_chain_balance, on_chain_nonce).unwrap();
+        assert_eq!(replaced_tx.unwrap().0.hash(), first.hash());
+    }
+
+    #[test]
+    fn underpriced_replacement_error() {
+        let on_chain_balance = U256::ZERO;
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(Arc::new(MockOrdering::default()), Default::default());
+        let tx = MockTransaction::eip1559().with_gas_price(100);
+        let first = f.validated(tx.clone());
+        pool.add_transaction(first.clone(), on_chain_balance, on_chain_nonce).unwrap();
+
+        let replacement = f.validated(tx.rng_hash().with_gas_price(101));
+        let rejected = *replacement.hash();
+        match pool.add_transaction(replacement, on_chain_balance, on_chain_nonce) {
+            Err(err @ PoolError::ReplacementUnderpriced(_, existing, _, _)) => {
+                assert_eq!(*err.hash(), rejected);
+                assert_eq!(existing, *first.hash());
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn replacement_fees_saturate() {
+        let mut f = MockTransactionFactory::default();
+        let tx = f.validated(MockTransaction::eip1559().with_gas_price(100));
+        assert_eq!(tx.replacement_fees(10), (110, 110));
+        assert_eq!(tx.replacement_fees(u128::MAX), (u128::MAX / 100, u128::MAX / 100));
+    }
+
     // insert nonce then nonce - 1
     #[test]
     fn insert_previous() {
@@ -1376,4 +1544,87 @@ mod tests {
             Err(InsertErr::TxGasLimitMoreThanAvailableBlockGas { .. })
         ));
     }
+
+    #[test]
+    fn reject_tx_below_minimal_gas_price() {
+        let on_chain_balance = U256::from(1_000);
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = AllTransactions::default();
+
+        let tx = MockTransaction::eip1559().with_priority_fee(pool.minimal_gas_price - 1);
+        assert!(matches!(
+            pool.insert_tx(f.validated(tx.clone()), on_chain_balance, on_chain_nonce),
+            Err(InsertErr::GasPriceBelowMinimum { .. })
+        ));
+
+        // local transactions are exempt
+        pool.insert_tx(
+            f.validated_with_origin(TransactionOrigin::Local, tx),
+            on_chain_balance,
+            on_chain_nonce,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn discard_worst_keeps_local_transactions() {
+        let on_chain_balance = U256::ZERO;
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let config = PoolConfig {
+            queued_limit: SubPoolLimit { max_txs: 1, ..Default::default() },
+            ..Default::default()
+        };
+        let mut pool = TxPool::new(Arc::new(MockOrdering::default()), config);
+
+        let local = f.validated_with_origin(
+            TransactionOrigin::Local,
+            MockTransaction::eip1559().inc_nonce(),
+        );
+        let external = f.validated(MockTransaction::eip1559().inc_nonce());
+        let (local_hash, external_hash) = (*local.hash(), *external.hash());
+        pool.add_transaction(local, on_chain_balance, on_chain_nonce).unwrap();
+        pool.add_transaction(external, on_chain_balance, on_chain_nonce).unwrap();
+        assert_eq!(pool.size().queued, 2);
+
+        let discarded = pool.discard_worst();
+        assert_eq!(discarded.len(), 1);
+        assert_eq!(*discarded[0].hash(), external_hash);
+        assert!(pool.contains(&local_hash));
+        assert!(!pool.contains(&external_hash));
+        assert_eq!(pool.len(), 1);
+    }
+
+    #[test]
+    fn on_new_block_prunes_sender_info() {
+        let on_chain_balance = U256::from(1_000_000);
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(Arc::new(MockOrdering::default()), Default::default());
+
+        let mined = f.validated(MockTransaction::eip1559());
+        let pending = f.validated(MockTransaction::eip1559());
+        let (mined_hash, mined_sender) = (*mined.hash(), mined.sender_id());
+        let pending_sender = pending.sender_id();
+        pool.add_transaction(mined, on_chain_balance, on_chain_nonce).unwrap();
+        pool.add_transaction(pending, on_chain_balance, on_chain_nonce).unwrap();
+
+        let info = SenderInfo { state_nonce: 1, balance: on_chain_balance };
+        let changed_senders = FnvHashMap::from_iter([
+            (mined_sender, info.clone()),
+            // a sender without transactions in the pool
+            (SenderId::from(u64::MAX), info),
+        ]);
+        let event = OnNewBlockEvent {
+            hash: H256::random(),
+            pending_block_base_fee: 0,
+            changed_accounts: vec![],
+            mined_transactions: vec![mined_hash],
+        };
+        pool.on_new_block(event, changed_senders);
+
+        assert_eq!(pool.len(), 1);
+        assert_eq!(pool.sender_info.keys().collect::<Vec<_>>(), vec![&pending_sender]);
+    }
 }
@@ -12,8 +12,9 @@ use rand::{
     prelude::Distribution,
 };
 use reth_primitives::{
-    Address, FromRecoveredTransaction, IntoRecoveredTransaction, Transaction, TransactionKind,
-    TransactionSignedEcRecovered, TxEip1559, TxHash, TxLegacy, H256, U128, U256,
+    Address, FromRecoveredTransaction, IntoRecoveredTransaction, Signature, Transaction,
+    TransactionKind, TransactionSigned, TransactionSignedEcRecovered, TxEip1559, TxHash, TxLegacy,
+    H256, U128, U256,
 };
 use std::{ops::Range, sync::Arc, time::Instant};
 
@@ -398,8 +399,46 @@ impl FromRecoveredTransaction for MockTransaction {
 }
 
 impl IntoRecoveredTransaction for MockTransaction {
+    /// The signature of the returned transaction is empty, it keeps the hash of the mock.
     fn to_recovered_transaction(&self) -> TransactionSignedEcRecovered {
-        todo!()
+        let transaction = match self.clone() {
+            MockTransaction::Legacy { nonce, gas_price, gas_limit, to, value, .. } => {
+                Transaction::Legacy(TxLegacy {
+                    chain_id: None,
+                    nonce,
+                    gas_price,
+                    gas_limit,
+                    to,
+                    value: value.to(),
+                    input: Default::default(),
+                })
+            }
+            MockTransaction::Eip1559 {
+                nonce,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                gas_limit,
+                to,
+                value,
+                ..
+            } => Transaction::Eip1559(TxEip1559 {
+                chain_id: 1,
+                nonce,
+                gas_limit,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                to,
+                value: value.to(),
+                input: Default::default(),
+                access_list: Default::default(),
+            }),
+        };
+        let signed = TransactionSigned {
+            hash: self.get_hash(),
+            signature: Signature::default(),
+            transaction,
+        };
+        TransactionSignedEcRecovered::from_signed_transaction(signed, self.get_sender())
     }
 }
 
@@ -499,3 +538,12 @@ fn test_mock_priority() {
     let hi = lo.next().inc_value();
     assert!(o.priority(&hi) > o.priority(&lo));
 }
+
+#[test]
+fn test_mock_recovered_roundtrip() {
+    for tx in [MockTransaction::legacy(), MockTransaction::eip1559()] {
+        let recovered = tx.to_recovered_transaction();
+        assert_eq!(recovered.signer(), tx.get_sender());
+        assert_eq!(MockTransaction::from_recovered_transaction(recovered), tx);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{error::PoolResult, pool::state::SubPool, validate::ValidPoolTransaction};
+use crate::{error::PoolResult, pool::state::SubPool, validate::ValidPoolTransaction, PoolConfig};
 use reth_primitives::{
     Address, FromRecoveredTransaction, IntoRecoveredTransaction, PeerId, Transaction,
     TransactionKind, TransactionSignedEcRecovered, TxHash, H256, U256,
@@ -20,6 +20,9 @@ pub trait TransactionPool: Send + Sync + Clone {
     /// The transaction type of the pool
     type Transaction: PoolTransaction;
 
+    /// Returns the config the pool was configured with.
+    fn config(&self) -> &PoolConfig;
+
     /// Returns stats about the pool.
     fn status(&self) -> PoolSize;
 
@@ -82,6 +85,19 @@ pub trait TransactionPool: Send + Sync + Clone {
         &self,
     ) -> Box<dyn BestTransactions<Item = Arc<ValidPoolTransaction<Self::Transaction>>>>;
 
+    /// Returns all transactions that can be included in the next block, which are all transactions
+    /// of the _pending_ sub-pool.
+    ///
+    /// Consumer: RPC
+    fn pending_transactions(&self) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
+
+    /// Returns all transactions that can't be included in the next block yet, because of nonce
+    /// gaps, insufficient balance or a fee cap below the base fee. These are all transactions of
+    /// the _basefee_ and _queued_ sub-pools.
+    ///
+    /// Consumer: RPC
+    fn queued_transactions(&self) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
+
     /// Removes all transactions corresponding to the given hashes.
     ///
     /// Also removes all dependent transactions.
@@ -178,7 +194,7 @@ impl<T: PoolTransaction> Clone for NewTransactionEvent<T> {
 ///
 /// Depending on where the transaction was picked up, it affects how the transaction is handled
 /// internally, e.g. limits for simultaneous transaction of one sender.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum TransactionOrigin {
     /// Transaction is coming from a local source.
     Local,
@@ -207,16 +223,21 @@ pub struct OnNewBlockEvent {
     ///
     /// The base fee of a block depends on the utilization of the last block and its base fee.
     pub pending_block_base_fee: u128,
-    /// Provides a set of state changes that affected the accounts.
-    pub state_changes: StateDiff,
+    /// The current nonce and balance of all accounts that were changed by the block.
+    pub changed_accounts: Vec<ChangedAccount>,
     /// All mined transactions in the block
     pub mined_transactions: Vec<H256>,
 }
 
-/// Contains a list of changed state
-#[derive(Debug, Clone)]
-pub struct StateDiff {
-    // TODO(mattsse) this could be an `Arc<revm::State>>`
+/// The state of an account after it was changed by a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChangedAccount {
+    /// The address of the account.
+    pub address: Address,
+    /// The nonce of the account.
+    pub nonce: u64,
+    /// The balance of the account.
+    pub balance: U256,
 }
 
 /// An `Iterator` that only returns transactions that are ready to be executed.
@@ -238,7 +259,9 @@ impl<T> BestTransactions for std::iter::Empty<T> {
 }
 
 /// Trait for transaction types used inside the pool
-pub trait PoolTransaction: fmt::Debug + Send + Sync + FromRecoveredTransaction {
+pub trait PoolTransaction:
+    fmt::Debug + Send + Sync + FromRecoveredTransaction + IntoRecoveredTransaction
+{
     /// Hash of the transaction.
     fn hash(&self) -> &TxHash;
 
@@ -284,7 +307,7 @@ pub trait PoolTransaction: fmt::Debug + Send + Sync + FromRecoveredTransaction {
 /// This type is essentially a wrapper around [TransactionSignedEcRecovered] with additional fields
 /// derived from the transaction that are frequently used by the pools for ordering.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub(crate) struct PooledTransaction {
+pub struct PooledTransaction {
     /// EcRecovered transaction info
     pub(crate) transaction: TransactionSignedEcRecovered,
 
@@ -5,8 +5,29 @@ use crate::{
     identifier::{SenderId, TransactionId},
     traits::{PoolTransaction, TransactionOrigin},
 };
-use reth_primitives::{Address, TransactionKind, TxHash, U256};
-use std::{fmt, time::Instant};
+use reth_interfaces::provider::Error as ProviderError;
+use reth_primitives::{Address, ChainSpec, Hardfork, Transaction, TransactionKind, TxHash, U256};
+use reth_provider::{AccountProvider, BlockProvider, HeaderProvider, StateProviderFactory};
+use std::{fmt, marker::PhantomData, sync::Arc, time::Instant};
+
+/// The maximum size of the init code of a contract creation transaction, as defined in
+/// [EIP-3860](https://eips.ethereum.org/EIPS/eip-3860).
+pub const MAX_INIT_CODE_SIZE: usize = 2 * 24576;
+
+/// Gas paid by every transaction.
+const TX_BASE_GAS: u64 = 21_000;
+/// Additional gas paid by contract creation transactions.
+const TX_CREATE_GAS: u64 = 32_000;
+/// Gas paid for every zero byte of the input.
+const TX_DATA_ZERO_GAS: u64 = 4;
+/// Gas paid for every non-zero byte of the input.
+const TX_DATA_NON_ZERO_GAS: u64 = 16;
+/// Gas paid for every address of the access list.
+const TX_ACCESS_LIST_ADDRESS_GAS: u64 = 2_400;
+/// Gas paid for every storage key of the access list.
+const TX_ACCESS_LIST_STORAGE_KEY_GAS: u64 = 1_900;
+/// Gas paid for every 32 byte word of the init code, since Shanghai.
+const TX_INIT_CODE_WORD_GAS: u64 = 2;
 
 /// A Result type returned after checking a transaction's validity.
 #[derive(Debug)]
@@ -57,16 +78,14 @@ pub trait TransactionValidator: Send + Sync {
 
     /// Ensure that the code size is not greater than `max_init_code_size`.
     /// `max_init_code_size` should be configurable so this will take it as an argument.
+    ///
+    /// The limit was introduced with Shanghai, so callers should only enforce it once the fork is
+    /// active.
     fn ensure_max_init_code_size(
         &self,
-        transaction: Self::Transaction,
+        transaction: &Self::Transaction,
         max_init_code_size: usize,
     ) -> Result<(), PoolError> {
-        // TODO check whether we are in the Shanghai stage.
-        // if !self.shanghai {
-        //     return Ok(())
-        // }
-
         if *transaction.kind() == TransactionKind::Create && transaction.size() > max_init_code_size
         {
             Err(PoolError::TxExceedsMaxInitCodeSize(
@@ -80,6 +99,159 @@ pub trait TransactionValidator: Send + Sync {
     }
 }
 
+/// A [TransactionValidator] that validates ethereum transactions against the latest state of the
+/// chain.
+///
+/// A transaction is valid if
+///
+///    * it is signed for the chain, or not bound to any chain
+///    * its signature recovers to its sender
+///    * its gas limit lies between its intrinsic gas and the gas limit of the latest block
+///    * its EIP-1559 priority fee does not exceed its fee cap
+///    * its init code does not exceed [`MAX_INIT_CODE_SIZE`] once Shanghai is active
+///    * its nonce is not below the nonce of the sender's account
+///    * the sender's balance covers its cost
+#[derive(Debug)]
+pub struct EthTransactionValidator<Client, T> {
+    /// Provides the latest state and the current block.
+    client: Arc<Client>,
+    /// The chain the transactions are validated for.
+    chain_spec: Arc<ChainSpec>,
+    /// The maximum size of the init code of contract creation transactions.
+    max_init_code_size: usize,
+    /// Marker for the transaction type.
+    _marker: PhantomData<T>,
+}
+
+// === impl EthTransactionValidator ===
+
+impl<Client, T> EthTransactionValidator<Client, T> {
+    /// Creates a new validator for the given chain that reads the state from the client.
+    pub fn new(client: Arc<Client>, chain_spec: Arc<ChainSpec>) -> Self {
+        Self { client, chain_spec, max_init_code_size: MAX_INIT_CODE_SIZE, _marker: PhantomData }
+    }
+
+    /// Sets the maximum size of the init code of contract creation transactions.
+    pub fn with_max_init_code_size(mut self, max_init_code_size: usize) -> Self {
+        self.max_init_code_size = max_init_code_size;
+        self
+    }
+}
+
+impl<Client, T> EthTransactionValidator<Client, T>
+where
+    Client: StateProviderFactory + BlockProvider + HeaderProvider,
+    T: PoolTransaction,
+{
+    /// Checks the transaction against the chain and the latest state.
+    fn validate(&self, transaction: &T) -> Result<(U256, u64), PoolError> {
+        let hash = *transaction.hash();
+        let sender = transaction.sender();
+        let recovered = transaction.to_recovered_transaction();
+
+        let chain_id = self.chain_spec.chain().id();
+        if let Some(tx_chain_id) = recovered.chain_id() {
+            if *tx_chain_id != chain_id {
+                return Err(PoolError::InvalidChainId(hash, *tx_chain_id, chain_id))
+            }
+        }
+
+        if recovered.recover_signer() != Some(sender) {
+            return Err(PoolError::InvalidSender(hash, sender))
+        }
+
+        if let Some(tip) = transaction.max_priority_fee_per_gas() {
+            let fee_cap = transaction.max_fee_per_gas().unwrap_or_default();
+            if tip > fee_cap {
+                return Err(PoolError::TipAboveFeeCap(hash, tip, fee_cap))
+            }
+        }
+
+        let provider_err = |err| PoolError::Provider(hash, err);
+        // the transaction is validated for inclusion in the block on top of the canonical tip, so
+        // it is rejected if the tip is unknown
+        let tip = self.client.chain_info().map_err(provider_err)?;
+        let block = self.client.header(&tip.best_hash).map_err(provider_err)?.ok_or_else(|| {
+            provider_err(ProviderError::BlockHash { block_hash: tip.best_hash }.into())
+        })?;
+        let is_shanghai = self.chain_spec.fork_active(Hardfork::Shanghai, block.number + 1);
+
+        let intrinsic_gas = intrinsic_gas(&recovered, is_shanghai);
+        if transaction.gas_limit() < intrinsic_gas {
+            return Err(PoolError::IntrinsicGasTooLow(hash, transaction.gas_limit(), intrinsic_gas))
+        }
+        if transaction.gas_limit() > block.gas_limit {
+            return Err(PoolError::TxExceedsGasLimit(hash, transaction.gas_limit(), block.gas_limit))
+        }
+
+        if is_shanghai {
+            self.ensure_max_init_code_size(transaction, self.max_init_code_size)?;
+        }
+
+        let account = self
+            .client
+            .latest()
+            .and_then(|state| state.basic_account(sender))
+            .map_err(provider_err)?
+            .unwrap_or_default();
+        if transaction.nonce() < account.nonce {
+            return Err(PoolError::NonceTooLow(hash, transaction.nonce(), account.nonce))
+        }
+        if transaction.cost() > account.balance {
+            return Err(PoolError::InsufficientFunds(hash, transaction.cost(), account.balance))
+        }
+
+        Ok((account.balance, account.nonce))
+    }
+}
+
+#[async_trait::async_trait]
+impl<Client, T> TransactionValidator for EthTransactionValidator<Client, T>
+where
+    Client: StateProviderFactory + BlockProvider + HeaderProvider,
+    T: PoolTransaction,
+{
+    type Transaction = T;
+
+    async fn validate_transaction(
+        &self,
+        _origin: TransactionOrigin,
+        transaction: Self::Transaction,
+    ) -> TransactionValidationOutcome<Self::Transaction> {
+        match self.validate(&transaction) {
+            Ok((balance, state_nonce)) => {
+                TransactionValidationOutcome::Valid { balance, state_nonce, transaction }
+            }
+            Err(err) => TransactionValidationOutcome::Invalid(transaction, err),
+        }
+    }
+}
+
+/// Returns the gas a transaction has to pay before its execution starts.
+fn intrinsic_gas(transaction: &Transaction, is_shanghai: bool) -> u64 {
+    let input = transaction.input();
+    let zero_bytes = input.iter().filter(|byte| **byte == 0).count() as u64;
+    let non_zero_bytes = input.len() as u64 - zero_bytes;
+    let mut gas =
+        TX_BASE_GAS + zero_bytes * TX_DATA_ZERO_GAS + non_zero_bytes * TX_DATA_NON_ZERO_GAS;
+
+    if *transaction.kind() == TransactionKind::Create {
+        gas += TX_CREATE_GAS;
+        if is_shanghai {
+            gas += (input.len() as u64 + 31) / 32 * TX_INIT_CODE_WORD_GAS;
+        }
+    }
+
+    if let Some(access_list) = transaction.access_list() {
+        for item in access_list.0.iter() {
+            gas += TX_ACCESS_LIST_ADDRESS_GAS +
+                item.storage_keys.len() as u64 * TX_ACCESS_LIST_STORAGE_KEY_GAS;
+        }
+    }
+
+    gas
+}
+
 /// A valid transaction in the pool.
 pub struct ValidPoolTransaction<T: PoolTransaction> {
     /// The transaction
@@ -134,9 +306,34 @@ impl<T: PoolTransaction> ValidPoolTransaction<T> {
         self.transaction.gas_limit()
     }
 
-    /// Returns true if this transaction is underpriced compared to the other.
-    pub(crate) fn is_underpriced(&self, other: &Self) -> bool {
-        self.transaction.effective_gas_price() <= other.transaction.effective_gas_price()
+    /// Returns the max fee of the transaction, which is the gas price for legacy transactions.
+    pub(crate) fn max_fee(&self) -> u128 {
+        self.transaction.max_fee_per_gas().unwrap_or_else(|| self.transaction.effective_gas_price())
+    }
+
+    /// Returns the max priority fee of the transaction, which is the gas price for legacy
+    /// transactions.
+    pub(crate) fn max_priority_fee(&self) -> u128 {
+        self.transaction
+            .max_priority_fee_per_gas()
+            .unwrap_or_else(|| self.transaction.effective_gas_price())
+    }
+
+    /// Returns the minimum max fee and max priority fee that a transaction needs to replace this
+    /// transaction.
+    ///
+    /// Both fees must be bumped by `price_bump` percent, and must be strictly higher.
+    pub(crate) fn replacement_fees(&self, price_bump: u128) -> (u128, u128) {
+        let factor = price_bump.saturating_add(100);
+        let bump = |fee: u128| (fee.saturating_mul(factor) / 100).max(fee.saturating_add(1));
+        (bump(self.max_fee()), bump(self.max_priority_fee()))
+    }
+
+    /// Returns true if this transaction is underpriced to replace the other transaction with the
+    /// given price bump.
+    pub(crate) fn is_underpriced(&self, other: &Self, price_bump: u128) -> bool {
+        let (min_fee, min_priority_fee) = other.replacement_fees(price_bump);
+        self.max_fee() < min_fee || self.max_priority_fee() < min_priority_fee
     }
 
     /// Whether the transaction originated locally.
@@ -174,3 +371,133 @@ impl<T: PoolTransaction> fmt::Debug for ValidPoolTransaction<T> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::PooledTransaction;
+    use reth_interfaces::test_utils::generators::sign_message;
+    use reth_primitives::{
+        AccessList, AccessListItem, FromRecoveredTransaction, Header, IntoRecoveredTransaction,
+        TransactionSigned, TransactionSignedEcRecovered, TxEip1559, H256, MAINNET,
+    };
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+
+    const GAS_LIMIT: u64 = 30_000_000;
+
+    fn signed(tx: TxEip1559) -> PooledTransaction {
+        let tx = Transaction::Eip1559(tx);
+        let signature = sign_message(H256::from_low_u64_be(1), tx.signature_hash()).unwrap();
+        let signed = TransactionSigned::from_transaction_and_signature(tx, signature);
+        PooledTransaction::from_recovered_transaction(signed.into_ecrecovered().unwrap())
+    }
+
+    fn transfer() -> TxEip1559 {
+        TxEip1559 {
+            chain_id: 1,
+            nonce: 1,
+            gas_limit: TX_BASE_GAS,
+            max_fee_per_gas: 10,
+            max_priority_fee_per_gas: 1,
+            to: TransactionKind::Call(Address::random()),
+            value: 1,
+            ..Default::default()
+        }
+    }
+
+    fn validator(
+        sender: Address,
+        balance: U256,
+    ) -> EthTransactionValidator<MockEthProvider, PooledTransaction> {
+        let client = MockEthProvider::default();
+        client.add_header(H256::random(), Header { gas_limit: GAS_LIMIT, ..Default::default() });
+        client.add_account(sender, ExtendedAccount::new(1, balance));
+        EthTransactionValidator::new(Arc::new(client), Arc::new(MAINNET.clone()))
+    }
+
+    async fn validate(
+        validator: &EthTransactionValidator<MockEthProvider, PooledTransaction>,
+        tx: TxEip1559,
+    ) -> Result<(U256, u64), PoolError> {
+        match validator.validate_transaction(TransactionOrigin::External, signed(tx)).await {
+            TransactionValidationOutcome::Valid { balance, state_nonce, .. } => {
+                Ok((balance, state_nonce))
+            }
+            TransactionValidationOutcome::Invalid(_, err) => Err(err),
+        }
+    }
+
+    #[tokio::test]
+    async fn validates_against_latest_state() {
+        let sender = signed(transfer()).sender();
+        let balance = U256::from(1_000_000u64);
+        let validator = validator(sender, balance);
+
+        assert_eq!(validate(&validator, transfer()).await.unwrap(), (balance, 1));
+
+        let res = validate(&validator, TxEip1559 { chain_id: 5, ..transfer() }).await;
+        assert!(matches!(res, Err(PoolError::InvalidChainId(_, 5, 1))));
+
+        let res = validate(&validator, TxEip1559 { gas_limit: 20_999, ..transfer() }).await;
+        assert!(matches!(res, Err(PoolError::IntrinsicGasTooLow(_, 20_999, 21_000))));
+
+        let res = validate(&validator, TxEip1559 { gas_limit: GAS_LIMIT + 1, ..transfer() }).await;
+        assert!(matches!(res, Err(PoolError::TxExceedsGasLimit(..))));
+
+        let res =
+            validate(&validator, TxEip1559 { max_priority_fee_per_gas: 11, ..transfer() }).await;
+        assert!(matches!(res, Err(PoolError::TipAboveFeeCap(_, 11, 10))));
+
+        let res = validate(&validator, TxEip1559 { nonce: 0, ..transfer() }).await;
+        assert!(matches!(res, Err(PoolError::NonceTooLow(_, 0, 1))));
+
+        let res = validate(&validator, TxEip1559 { value: 1_000_000, ..transfer() }).await;
+        assert!(matches!(res, Err(PoolError::InsufficientFunds(..))));
+
+        // signature does not match the claimed sender
+        let tx = signed(transfer()).to_recovered_transaction().into_signed();
+        let tx = TransactionSignedEcRecovered::from_signed_transaction(tx, Address::random());
+        let outcome = validator
+            .validate_transaction(
+                TransactionOrigin::External,
+                PooledTransaction::from_recovered_transaction(tx),
+            )
+            .await;
+        assert!(matches!(
+            outcome,
+            TransactionValidationOutcome::Invalid(_, PoolError::InvalidSender(..))
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_without_canonical_tip() {
+        let sender = signed(transfer()).sender();
+        let client = MockEthProvider::default();
+        client.add_account(sender, ExtendedAccount::new(1, U256::from(1_000_000u64)));
+        let validator = EthTransactionValidator::new(Arc::new(client), Arc::new(MAINNET.clone()));
+
+        let res = validate(&validator, transfer()).await;
+        assert!(matches!(res, Err(PoolError::Provider(..))));
+    }
+
+    #[test]
+    fn calculates_intrinsic_gas() {
+        let tx = Transaction::Eip1559(TxEip1559 {
+            input: vec![0, 1, 0, 2].into(),
+            access_list: AccessList(vec![AccessListItem {
+                address: Address::random(),
+                storage_keys: vec![H256::random(), H256::random()],
+            }]),
+            ..transfer()
+        });
+        assert_eq!(intrinsic_gas(&tx, false), 21_000 + 2 * 4 + 2 * 16 + 2_400 + 2 * 1_900);
+
+        let create = Transaction::Eip1559(TxEip1559 {
+            to: TransactionKind::Create,
+            input: vec![1; 33].into(),
+            ..transfer()
+        });
+        assert_eq!(intrinsic_gas(&create, false), 21_000 + 33 * 16 + 32_000);
+        assert_eq!(intrinsic_gas(&create, true), 21_000 + 33 * 16 + 32_000 + 2 * 2);
+    }
+}